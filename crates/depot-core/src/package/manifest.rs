@@ -27,6 +27,18 @@ pub struct PackageManifest {
     pub binary_urls: std::collections::HashMap<String, String>, // target -> URL
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resolution_strategy: Option<String>,
+    /// Minimum age of a release before it can be resolved (e.g. "7d"), overrides the global config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_release_age: Option<String>,
+    /// Per-package minimum release age overrides (package -> age)
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub min_release_age_overrides: std::collections::HashMap<String, String>,
+    /// Security audit settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit: Option<AuditConfig>,
+    /// License policy enforced across the resolved dependency graph
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_policy: Option<LicensePolicy>,
 }
 
 fn default_lua_version() -> String {
@@ -56,6 +68,51 @@ pub struct BuildConfig {
     pub profile: Option<String>,
 }
 
+/// Security audit settings (`audit:` in package.yaml or `.depot/audit.yaml`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditConfig {
+    #[serde(default)]
+    pub ignore: Vec<AuditIgnoreEntry>,
+}
+
+/// An advisory that `depot audit` should not fail on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditIgnoreEntry {
+    /// Advisory ID, CVE or alias
+    pub id: String,
+    /// Only ignore the advisory for this package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Why the advisory does not apply
+    pub reason: String,
+    /// Date (YYYY-MM-DD) after which the advisory is reported again
+    pub expires: String,
+}
+
+/// Allowed and denied SPDX license identifiers for dependencies
+///
+/// An empty `allow` list permits any license that is not denied.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LicensePolicy {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exceptions: Vec<LicenseException>,
+}
+
+/// Licenses accepted for one package regardless of the allow/deny lists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseException {
+    pub package: String,
+    /// Accepted licenses; empty accepts the package whatever its license
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 impl PackageManifest {
     /// Load package.yaml from a directory
     pub fn load(dir: &Path) -> DepotResult<Self> {
@@ -160,6 +217,10 @@ impl PackageManifest {
             build: None,
             binary_urls: std::collections::HashMap::new(),
             resolution_strategy: None,
+            min_release_age: None,
+            min_release_age_overrides: std::collections::HashMap::new(),
+            audit: None,
+            license_policy: None,
         }
     }
 
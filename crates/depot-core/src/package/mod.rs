@@ -1,3 +1,5 @@
 pub mod manifest;
 
-pub use manifest::PackageManifest;
+pub use manifest::{
+    AuditConfig, AuditIgnoreEntry, LicenseException, LicensePolicy, PackageManifest,
+};
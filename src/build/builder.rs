@@ -2,12 +2,86 @@ use crate::build::prebuilt::PrebuiltBinaryManager;
 use crate::build::sandbox::BuildSandbox;
 use crate::build::targets::Target;
 use crate::cache::Cache;
-use crate::core::path::cache_dir;
+use crate::core::path::{cache_dir, depot_home};
 use crate::core::{DepotError, DepotResult};
+use crate::lua_manager::switcher::{project_version, VersionSwitcher};
+use crate::lua_manager::{headers, source};
 use crate::lua_version::detector::{LuaVersion, LuaVersionDetector};
 use crate::package::manifest::{BuildConfig, PackageManifest};
+use crate::security::signature::SignaturePolicy;
+use std::env;
 use std::path::{Path, PathBuf};
 
+/// The Lua a native module is built against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LuaTarget {
+    pub version: LuaVersion,
+    /// Depot-managed install whose headers the build compiles against
+    pub install_dir: Option<PathBuf>,
+}
+
+impl LuaTarget {
+    fn managed(switcher: &VersionSwitcher, version: &str) -> DepotResult<Self> {
+        let dir = switcher.version_dir(version);
+        let install_dir = if headers::has_headers(&dir) {
+            Some(dir)
+        } else {
+            eprintln!(
+                "⚠ Lua {} has no managed headers; falling back to the system headers.\n  \
+                 Install them with: depot lua install {}",
+                version, version
+            );
+            None
+        };
+        Ok(Self {
+            version: LuaVersion::parse(version)?,
+            install_dir,
+        })
+    }
+
+    /// Environment that points mlua and C compilers at the managed headers
+    ///
+    /// mlua reads `LUA_INC`, `cc`-based build scripts pick up `CFLAGS`, and
+    /// `LUA_INCDIR` follows the LuaRocks convention for Makefile builds.
+    /// Versions built from source also provide `liblua.a` (or LuaJIT's
+    /// `libluajit-5.1.a`) through `LUA_LIB` and `LUA_LIB_NAME` for crates
+    /// that link Lua instead of loading into it.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let Some(dir) = &self.install_dir else {
+            return Vec::new();
+        };
+        let include = headers::include_dir(dir).display().to_string();
+        let cflags = match env::var("CFLAGS") {
+            Ok(existing) if !existing.trim().is_empty() => format!("{} -I{}", existing, include),
+            _ => format!("-I{}", include),
+        };
+        let mut vars = vec![
+            ("LUA_INC".to_string(), include.clone()),
+            ("LUA_INCDIR".to_string(), include),
+            ("CFLAGS".to_string(), cflags),
+        ];
+
+        if let Some(name) = source::static_library(dir) {
+            let lib_dir = source::lib_dir(dir).display().to_string();
+            vars.push(("LUA_LIB".to_string(), lib_dir.clone()));
+            vars.push(("LUA_LIB_NAME".to_string(), name.to_string()));
+            vars.push(("LUA_LIBDIR".to_string(), lib_dir));
+        }
+        vars
+    }
+}
+
+/// `owner/repo` of a package directory installed at `lua_modules/<owner>/<repo>`
+fn installed_repository(package_dir: &Path) -> Option<String> {
+    let owner_dir = package_dir.parent()?;
+    if owner_dir.parent()?.file_name()? != "lua_modules" {
+        return None;
+    }
+    let owner = owner_dir.file_name()?.to_str()?;
+    let repo = package_dir.file_name()?.to_str()?;
+    Some(format!("{}/{}", owner, repo))
+}
+
 /// Builder for Rust code compiled into Lua native modules
 ///
 /// This builds Rust code as dynamic libraries (.so/.dylib/.dll) that can be
@@ -16,6 +90,10 @@ use std::path::{Path, PathBuf};
 pub struct RustBuilder {
     project_root: PathBuf,
     build_config: BuildConfig,
+    lua_version: String,
+    /// `owner/repo` when building a package installed under `lua_modules`
+    repository: Option<String>,
+    signatures: SignaturePolicy,
 }
 
 impl RustBuilder {
@@ -39,32 +117,55 @@ impl RustBuilder {
         Ok(Self {
             project_root: project_root.to_path_buf(),
             build_config,
+            lua_version: manifest.lua_version.clone(),
+            repository: installed_repository(project_root),
+            signatures: SignaturePolicy::default(),
         })
     }
 
+    /// Verify pre-built binaries against publisher signatures
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signatures = policy;
+        self
+    }
+
     /// Build for a specific target
     ///
     /// This will:
-    /// 1. Check for pre-built binaries (local cache or download)
+    /// 1. Use the pre-built binary from `binary_urls` (cached or downloaded),
+    ///    verified against the signature policy
     /// 2. Check for locally cached builds
     /// 3. Build from source if neither is available
     pub async fn build(&self, target: Option<&Target>) -> DepotResult<PathBuf> {
         let default_target = Target::default_target();
         let target = target.unwrap_or(&default_target);
 
-        // Detect Lua version for caching
-        let lua_version = Self::detect_lua_version()?;
+        // Resolve the target Lua version for caching
+        let lua_version = self.lua_target()?.version;
         let lua_version_str = lua_version.major_minor();
 
         let manifest = PackageManifest::load(&self.project_root)?;
 
-        // First, check for pre-built binaries
-        let prebuilt_manager = PrebuiltBinaryManager::new()?;
-        if let Some(prebuilt_path) =
-            prebuilt_manager.get_prebuilt(&manifest.name, &manifest.version, &lua_version, target)
-        {
-            eprintln!("Using pre-built binary for Lua {}", lua_version_str);
-            return Ok(prebuilt_path);
+        // First, use the pre-built binary from package.yaml, checked on every use
+        let prebuilt_manager =
+            PrebuiltBinaryManager::new()?.with_signature_policy(self.signatures.clone());
+        // Build target key: "5.4-x86_64-unknown-linux-gnu"
+        let target_key = format!("{}-{}", lua_version_str, target.triple);
+        if let Some(url) = manifest.binary_urls.get(&target_key) {
+            if let Some(prebuilt_path) = prebuilt_manager
+                .get_or_download(
+                    &manifest.name,
+                    self.repository.as_deref(),
+                    &manifest.version,
+                    &lua_version,
+                    target,
+                    Some(url),
+                )
+                .await?
+            {
+                eprintln!("Using pre-built binary for Lua {}", lua_version_str);
+                return Ok(prebuilt_path);
+            }
         }
 
         // Check local cache
@@ -79,39 +180,6 @@ impl RustBuilder {
             return Ok(cached_path);
         }
 
-        // Check if package.yaml specifies pre-built binary URLs
-        let binary_url: Option<String> = {
-            // First check package.yaml
-            let manifest = PackageManifest::load(&self.project_root).ok();
-            let url_from_manifest = manifest.as_ref().and_then(|m| {
-                // Build target key: "5.4-x86_64-unknown-linux-gnu"
-                let target_key = format!("{}-{}", lua_version_str, target.triple);
-                m.binary_urls.get(&target_key).cloned()
-            });
-
-            url_from_manifest
-        };
-
-        // Try to download pre-built binary if URL is available
-        if let Some(url) = binary_url {
-            if let Some(downloaded) = prebuilt_manager
-                .get_or_download(
-                    &manifest.name,
-                    &manifest.version,
-                    &lua_version,
-                    target,
-                    Some(&url),
-                )
-                .await?
-            {
-                eprintln!(
-                    "Using downloaded pre-built binary for Lua {}",
-                    lua_version_str
-                );
-                return Ok(downloaded);
-            }
-        }
-
         // Ensure cargo-zigbuild is installed
         BuildSandbox::ensure_cargo_zigbuild()?;
 
@@ -157,13 +225,19 @@ impl RustBuilder {
             )));
         }
 
-        // Detect Lua version and add mlua feature
-        let lua_version = Self::detect_lua_version()?;
-        let mlua_feature = lua_version.mlua_feature();
+        // Resolve the target Lua and add its mlua feature
+        let lua = self.lua_target()?;
+        let mlua_feature = lua.version.mlua_feature();
         eprintln!(
             "Building for Lua {} (mlua feature: {})",
-            lua_version, mlua_feature
+            lua.version, mlua_feature
         );
+        if let Some(dir) = &lua.install_dir {
+            eprintln!(
+                "Using Lua headers from {}",
+                headers::include_dir(dir).display()
+            );
+        }
 
         // Build with cargo-zigbuild
         let target_triple = target.triple.clone();
@@ -197,7 +271,12 @@ impl RustBuilder {
 
         // Convert to &str for execute_cargo
         let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        BuildSandbox::execute_cargo(&self.project_root, &args_str, &[])?;
+        let env_vars = lua.env_vars();
+        let env_str: Vec<(&str, &str)> = env_vars
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        BuildSandbox::execute_cargo(&self.project_root, &args_str, &env_str)?;
 
         Ok(())
     }
@@ -218,13 +297,19 @@ impl RustBuilder {
             )));
         }
 
-        // Detect Lua version and add mlua feature
-        let lua_version = Self::detect_lua_version()?;
-        let mlua_feature = lua_version.mlua_feature();
+        // Resolve the target Lua and add its mlua feature
+        let lua = self.lua_target()?;
+        let mlua_feature = lua.version.mlua_feature();
         eprintln!(
             "Building for Lua {} (mlua feature: {})",
-            lua_version, mlua_feature
+            lua.version, mlua_feature
         );
+        if let Some(dir) = &lua.install_dir {
+            eprintln!(
+                "Using Lua headers from {}",
+                headers::include_dir(dir).display()
+            );
+        }
 
         // Build with cargo
         let mut args: Vec<String> = vec!["build".to_string()];
@@ -252,7 +337,12 @@ impl RustBuilder {
 
         // Convert to &str for execute_cargo
         let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        BuildSandbox::execute_cargo(&self.project_root, &args_str, &[])?;
+        let env_vars = lua.env_vars();
+        let env_str: Vec<(&str, &str)> = env_vars
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        BuildSandbox::execute_cargo(&self.project_root, &args_str, &env_str)?;
 
         Ok(())
     }
@@ -325,10 +415,43 @@ impl RustBuilder {
         Ok(results)
     }
 
+    /// Resolve the Lua to build against
+    ///
+    /// `DEPOT_LUA_VERSION` or the project's `.lua-version`/`.tool-versions`,
+    /// or else the manifest's `lua_version`, selects a depot-managed install
+    /// and its headers. Without a matching install the `lua` on PATH decides
+    /// the version.
+    pub fn lua_target(&self) -> DepotResult<LuaTarget> {
+        self.lua_target_in(&depot_home()?)
+    }
+
+    fn lua_target_in(&self, depot_home: &Path) -> DepotResult<LuaTarget> {
+        let switcher = VersionSwitcher::new(depot_home);
+
+        if let Some((pinned, source)) = project_version(&self.project_root) {
+            let installed = switcher.find_installed(&pinned)?.ok_or_else(|| {
+                DepotError::Package(format!(
+                    "Lua {} (from {}) is not installed. Run: depot lua install {}",
+                    pinned, source, pinned
+                ))
+            })?;
+            return LuaTarget::managed(&switcher, &installed);
+        }
+
+        if let Some(installed) = switcher.find_installed(&self.lua_version)? {
+            return LuaTarget::managed(&switcher, &installed);
+        }
+
+        Ok(LuaTarget {
+            version: Self::detect_lua_version()?,
+            install_dir: None,
+        })
+    }
+
     /// Detect the target Lua version for building
     ///
     /// This detects the installed Lua version and ensures mlua is built
-    /// with the correct feature flags (lua51, lua53, or lua54)
+    /// with the correct feature flags (lua51, lua53, lua54, or luajit)
     pub fn detect_lua_version() -> DepotResult<LuaVersion> {
         LuaVersionDetector::detect()
     }
@@ -874,4 +997,117 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), module_path);
     }
+
+    fn install_managed_lua(depot_home: &Path, version: &str, with_headers: bool) {
+        let version_dir = depot_home.join("versions").join(version);
+        std::fs::create_dir_all(version_dir.join("bin")).unwrap();
+        std::fs::write(version_dir.join("bin").join("lua"), "mock binary").unwrap();
+        if with_headers {
+            let include = headers::include_dir(&version_dir);
+            std::fs::create_dir_all(&include).unwrap();
+            for header in headers::REQUIRED_HEADERS {
+                std::fs::write(include.join(header), "/* header */").unwrap();
+            }
+        }
+    }
+
+    fn rust_manifest(lua_version: &str) -> PackageManifest {
+        let mut manifest = PackageManifest::default("test".to_string());
+        manifest.lua_version = lua_version.to_string();
+        manifest.build = Some(BuildConfig {
+            build_type: "rust".to_string(),
+            manifest: None,
+            modules: std::collections::HashMap::new(),
+            features: vec![],
+            profile: None,
+        });
+        manifest
+    }
+
+    #[test]
+    fn test_installed_repository() {
+        assert_eq!(
+            installed_repository(Path::new("/work/app/lua_modules/owner/lib")),
+            Some("owner/lib".to_string())
+        );
+        assert_eq!(installed_repository(Path::new("/work/owner/lib")), None);
+        assert_eq!(installed_repository(Path::new("/work/app")), None);
+    }
+
+    #[test]
+    fn test_lua_target_from_lua_version_file() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        install_managed_lua(home.path(), "5.1.5", true);
+        install_managed_lua(home.path(), "5.4.8", true);
+        std::fs::write(project.path().join(".lua-version"), "5.1.5\n").unwrap();
+
+        let builder = RustBuilder::new(project.path(), &rust_manifest("5.4")).unwrap();
+        let lua = builder.lua_target_in(home.path()).unwrap();
+        assert_eq!(lua.version, LuaVersion::new(5, 1, 5));
+        assert_eq!(lua.version.mlua_feature(), "lua51");
+
+        let include = home.path().join("versions").join("5.1.5").join("include");
+        let env_vars = lua.env_vars();
+        assert!(env_vars.contains(&("LUA_INC".to_string(), include.display().to_string())));
+        assert!(env_vars
+            .iter()
+            .any(|(k, v)| k == "CFLAGS" && v.ends_with(&format!("-I{}", include.display()))));
+        assert!(!env_vars.iter().any(|(k, _)| k == "LUA_LIB"));
+
+        // A version built from source also links its liblua
+        let lib_dir = home.path().join("versions").join("5.1.5").join("lib");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+        std::fs::write(lib_dir.join("liblua.a"), "archive").unwrap();
+        let env_vars = lua.env_vars();
+        assert!(env_vars.contains(&("LUA_LIB".to_string(), lib_dir.display().to_string())));
+        assert!(env_vars.contains(&("LUA_LIB_NAME".to_string(), "lua".to_string())));
+    }
+
+    #[test]
+    fn test_lua_target_from_manifest() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        install_managed_lua(home.path(), "5.3.6", false);
+
+        let builder = RustBuilder::new(project.path(), &rust_manifest("5.3")).unwrap();
+        let lua = builder.lua_target_in(home.path()).unwrap();
+        assert_eq!(lua.version, LuaVersion::new(5, 3, 6));
+        // Without managed headers the build keeps the system ones
+        assert_eq!(lua.install_dir, None);
+        assert!(lua.env_vars().is_empty());
+    }
+
+    #[test]
+    fn test_lua_target_luajit() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        install_managed_lua(home.path(), "5.1.5", true);
+        install_managed_lua(home.path(), "luajit-2.1", true);
+        let lib_dir = home.path().join("versions").join("luajit-2.1").join("lib");
+        std::fs::create_dir_all(&lib_dir).unwrap();
+        std::fs::write(lib_dir.join("libluajit-5.1.a"), "archive").unwrap();
+        std::fs::write(project.path().join(".lua-version"), "luajit\n").unwrap();
+
+        let builder = RustBuilder::new(project.path(), &rust_manifest("5.1")).unwrap();
+        let lua = builder.lua_target_in(home.path()).unwrap();
+        assert_eq!(lua.version, LuaVersion::luajit("2.1"));
+        assert_eq!(lua.version.mlua_feature(), "luajit");
+        let env_vars = lua.env_vars();
+        assert!(env_vars.contains(&("LUA_LIB".to_string(), lib_dir.display().to_string())));
+        assert!(env_vars.contains(&("LUA_LIB_NAME".to_string(), "luajit-5.1".to_string())));
+    }
+
+    #[test]
+    fn test_lua_target_pinned_version_not_installed() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        std::fs::write(project.path().join(".lua-version"), "5.2.4\n").unwrap();
+
+        let builder = RustBuilder::new(project.path(), &rust_manifest("5.4")).unwrap();
+        let err = builder.lua_target_in(home.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Lua 5.2.4 (from .lua-version) is not installed"));
+    }
 }
@@ -3,8 +3,9 @@ use crate::cache::Cache;
 use crate::core::path::cache_dir;
 use crate::core::{DepotError, DepotResult};
 use crate::lua_version::detector::LuaVersion;
+use crate::security::signature::{SignaturePolicy, SignatureStatus, SIGNATURE_EXTENSION};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Manages pre-built binary downloads for Rust-compiled Lua native modules
 ///
@@ -12,13 +13,23 @@ use std::path::PathBuf;
 /// from Rust code and are part of Lua module packages. NOT standalone Rust libraries.
 pub struct PrebuiltBinaryManager {
     cache: Cache,
+    signatures: SignaturePolicy,
 }
 
 impl PrebuiltBinaryManager {
     /// Create a new pre-built binary manager
     pub fn new() -> DepotResult<Self> {
         let cache = Cache::new(cache_dir()?)?;
-        Ok(Self { cache })
+        Ok(Self {
+            cache,
+            signatures: SignaturePolicy::default(),
+        })
+    }
+
+    /// Verify downloaded binaries against publisher signatures
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signatures = policy;
+        self
     }
 
     /// Check if a pre-built native module binary is available for a package
@@ -42,25 +53,91 @@ impl PrebuiltBinaryManager {
     }
 
     /// Get the path to a pre-built binary if available
+    ///
+    /// When a signature policy is configured, the cached binary is checked
+    /// against the signature stored with it on every use.
     pub fn get_prebuilt(
         &self,
         package: &str,
+        repository: Option<&str>,
         version: &str,
         lua_version: &LuaVersion,
         target: &Target,
-    ) -> Option<PathBuf> {
+    ) -> DepotResult<Option<PathBuf>> {
         let lua_version_str = lua_version.major_minor();
-        self.cache
-            .get_rust_build(package, version, &lua_version_str, &target.triple)
+        let Some(path) =
+            self.cache
+                .get_rust_build(package, version, &lua_version_str, &target.triple)
+        else {
+            return Ok(None);
+        };
+
+        if self.signatures.is_enabled() {
+            let bytes = fs::read(&path)?;
+            let signature = fs::read_to_string(Self::signature_path(&path)).ok();
+            self.check_signature(
+                package,
+                repository,
+                "cached pre-built binary",
+                &bytes,
+                signature.as_deref(),
+            )?;
+        }
+        Ok(Some(path))
+    }
+
+    /// Where the signature of a cached binary is kept
+    fn signature_path(cache_path: &Path) -> PathBuf {
+        let mut name = cache_path.file_name().unwrap_or_default().to_os_string();
+        name.push(SIGNATURE_EXTENSION);
+        cache_path.with_file_name(name)
+    }
+
+    /// Check a binary against the keys trusted for its `owner/repo`
+    ///
+    /// Without a known repository no publisher key applies, so only strict
+    /// mode can refuse the binary.
+    fn check_signature(
+        &self,
+        package: &str,
+        repository: Option<&str>,
+        artifact: &str,
+        data: &[u8],
+        signature: Option<&str>,
+    ) -> DepotResult<()> {
+        let keys = repository
+            .map(|repo| self.signatures.keys_for(repo))
+            .unwrap_or_default();
+        let name = repository.unwrap_or(package);
+        match self
+            .signatures
+            .check_with_keys(name, &keys, artifact, data, signature)?
+        {
+            SignatureStatus::Verified(comment) => {
+                eprintln!("✓ Signature verified ({})", comment)
+            }
+            SignatureStatus::Untrusted => eprintln!(
+                "Warning: {} for {} is signed, but no trusted key is configured for it",
+                artifact, name
+            ),
+            SignatureStatus::Unsigned => {
+                eprintln!("Warning: {} for {} is not signed", artifact, name)
+            }
+        }
+        Ok(())
     }
 
     /// Download a pre-built native module binary from a URL
     ///
     /// Downloads a compiled Rust dynamic library (.so/.dylib/.dll) that is
-    /// part of a Lua module package and stores it in the cache.
+    /// part of a Lua module package and stores it in the cache. When a
+    /// signature policy is configured, the detached signature at
+    /// `<url>.minisig` is checked before the binary is written, and kept
+    /// with it so the cached copy can be re-verified.
     pub async fn download_prebuilt(
         &self,
         package: &str,
+        repository: Option<&str>,
         version: &str,
         lua_version: &LuaVersion,
         target: &Target,
@@ -98,6 +175,20 @@ impl PrebuiltBinaryManager {
             .await
             .map_err(|e| DepotError::Package(format!("Failed to read binary data: {}", e)))?;
 
+        let signature = if self.signatures.is_enabled() {
+            let signature = Self::download_signature(url).await?;
+            self.check_signature(
+                package,
+                repository,
+                "pre-built binary",
+                &bytes,
+                signature.as_deref(),
+            )?;
+            signature
+        } else {
+            None
+        };
+
         // Write to cache
         let mut file = File::create(&cache_path)
             .await
@@ -111,11 +202,44 @@ impl PrebuiltBinaryManager {
             .await
             .map_err(|e| DepotError::Cache(format!("Failed to sync cache file: {}", e)))?;
 
+        let signature_path = Self::signature_path(&cache_path);
+        match signature {
+            Some(signature) => fs::write(&signature_path, signature)?,
+            None if signature_path.exists() => fs::remove_file(&signature_path)?,
+            None => {}
+        }
+
         eprintln!("✓ Downloaded pre-built binary: {}", cache_path.display());
 
         Ok(cache_path)
     }
 
+    /// Fetch the detached signature published next to a binary URL
+    ///
+    /// A 404 means the binary is unsigned; other failures are errors.
+    async fn download_signature(url: &str) -> DepotResult<Option<String>> {
+        let signature_url = format!("{}{}", url, SIGNATURE_EXTENSION);
+        let response = reqwest::get(&signature_url).await.map_err(|e| {
+            DepotError::Package(format!("Failed to download binary signature: {}", e))
+        })?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(DepotError::Package(format!(
+                "Failed to download binary signature: HTTP {}",
+                response.status()
+            )));
+        }
+
+        let signature = response
+            .text()
+            .await
+            .map_err(|e| DepotError::Package(format!("Failed to read binary signature: {}", e)))?;
+        Ok(Some(signature))
+    }
+
     /// Find binary URL from a package's binary_urls table
     ///
     /// Looks for a binary URL matching the current Lua version and target.
@@ -132,34 +256,32 @@ impl PrebuiltBinaryManager {
 
     /// Try to get or download a pre-built binary
     ///
-    /// Returns the path to the binary if available, or None if not available
+    /// Returns the path to the binary if available, or None if not available.
+    /// A binary that fails to download or verify is an error, so a tampered
+    /// artifact never falls back to another build silently.
     pub async fn get_or_download(
         &self,
         package: &str,
+        repository: Option<&str>,
         version: &str,
         lua_version: &LuaVersion,
         target: &Target,
         binary_url: Option<&str>,
     ) -> DepotResult<Option<PathBuf>> {
         // First, check if we already have it cached
-        if let Some(cached) = self.get_prebuilt(package, version, lua_version, target) {
+        if let Some(cached) =
+            self.get_prebuilt(package, repository, version, lua_version, target)?
+        {
             return Ok(Some(cached));
         }
 
-        // If a binary URL is provided, try to download it
-        if let Some(url) = binary_url {
-            match self
-                .download_prebuilt(package, version, lua_version, target, url)
+        // If a binary URL is provided, download it
+        match binary_url {
+            Some(url) => self
+                .download_prebuilt(package, repository, version, lua_version, target, url)
                 .await
-            {
-                Ok(path) => Ok(Some(path)),
-                Err(e) => {
-                    eprintln!("Warning: Failed to download pre-built binary: {}", e);
-                    Ok(None)
-                }
-            }
-        } else {
-            Ok(None)
+                .map(Some),
+            None => Ok(None),
         }
     }
 }
@@ -173,7 +295,10 @@ mod tests {
     async fn test_prebuilt_manager() {
         let temp = TempDir::new().unwrap();
         let cache = Cache::new(temp.path().to_path_buf()).unwrap();
-        let manager = PrebuiltBinaryManager { cache };
+        let manager = PrebuiltBinaryManager {
+            cache,
+            signatures: SignaturePolicy::default(),
+        };
 
         let lua_version = LuaVersion::new(5, 4, 0);
         let target = Target::default_target();
@@ -239,7 +364,10 @@ mod tests {
     fn test_has_prebuilt_false() {
         let temp = TempDir::new().unwrap();
         let cache = Cache::new(temp.path().to_path_buf()).unwrap();
-        let manager = PrebuiltBinaryManager { cache };
+        let manager = PrebuiltBinaryManager {
+            cache,
+            signatures: SignaturePolicy::default(),
+        };
 
         let lua_version = LuaVersion::new(5, 4, 0);
         let target = Target::default_target();
@@ -251,12 +379,17 @@ mod tests {
     fn test_get_prebuilt_none() {
         let temp = TempDir::new().unwrap();
         let cache = Cache::new(temp.path().to_path_buf()).unwrap();
-        let manager = PrebuiltBinaryManager { cache };
+        let manager = PrebuiltBinaryManager {
+            cache,
+            signatures: SignaturePolicy::default(),
+        };
 
         let lua_version = LuaVersion::new(5, 4, 0);
         let target = Target::default_target();
 
-        let path = manager.get_prebuilt("nonexistent", "1.0.0", &lua_version, &target);
+        let path = manager
+            .get_prebuilt("nonexistent", None, "1.0.0", &lua_version, &target)
+            .unwrap();
         assert!(path.is_none());
     }
 
@@ -264,13 +397,16 @@ mod tests {
     async fn test_get_or_download_no_url() {
         let temp = TempDir::new().unwrap();
         let cache = Cache::new(temp.path().to_path_buf()).unwrap();
-        let manager = PrebuiltBinaryManager { cache };
+        let manager = PrebuiltBinaryManager {
+            cache,
+            signatures: SignaturePolicy::default(),
+        };
 
         let lua_version = LuaVersion::new(5, 4, 0);
         let target = Target::default_target();
 
         let result = manager
-            .get_or_download("test-pkg", "1.0.0", &lua_version, &target, None)
+            .get_or_download("test-pkg", None, "1.0.0", &lua_version, &target, None)
             .await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
@@ -288,7 +424,10 @@ mod tests {
     async fn test_get_or_download_with_cached() {
         let temp = TempDir::new().unwrap();
         let cache = Cache::new(temp.path().to_path_buf()).unwrap();
-        let manager = PrebuiltBinaryManager { cache };
+        let manager = PrebuiltBinaryManager {
+            cache,
+            signatures: SignaturePolicy::default(),
+        };
 
         let lua_version = LuaVersion::new(5, 4, 0);
         let target = Target::default_target();
@@ -308,6 +447,7 @@ mod tests {
         let result = manager
             .get_or_download(
                 "test-pkg",
+                None,
                 "1.0.0",
                 &lua_version,
                 &target,
@@ -320,6 +460,171 @@ mod tests {
         assert_eq!(path_opt.unwrap(), cache_path);
     }
 
+    async fn serve_binary(signature: Option<String>) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/lib.so"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"binary".to_vec()))
+            .mount(&server)
+            .await;
+        let signature_response = match signature {
+            Some(signature) => ResponseTemplate::new(200).set_body_string(signature),
+            None => ResponseTemplate::new(404),
+        };
+        Mock::given(method("GET"))
+            .and(path("/lib.so.minisig"))
+            .respond_with(signature_response)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn signed_manager(temp: &TempDir, key: &str, require: bool) -> PrebuiltBinaryManager {
+        PrebuiltBinaryManager {
+            cache: Cache::new(temp.path().to_path_buf()).unwrap(),
+            signatures: SignaturePolicy {
+                trusted_keys: std::collections::HashMap::from([(
+                    "owner".to_string(),
+                    vec![key.to_string()],
+                )]),
+                require,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_prebuilt_verifies_signature() {
+        use crate::security::signature::sign_for_tests;
+
+        let (signature, key) = sign_for_tests(5, b"binary");
+        let server = serve_binary(Some(signature)).await;
+        let temp = TempDir::new().unwrap();
+        let manager = signed_manager(&temp, &key, true);
+
+        let path = manager
+            .download_prebuilt(
+                "test-pkg",
+                Some("owner/test-pkg"),
+                "1.0.0",
+                &LuaVersion::new(5, 4, 0),
+                &Target::default_target(),
+                &format!("{}/lib.so", server.uri()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), b"binary");
+    }
+
+    #[tokio::test]
+    async fn test_download_prebuilt_rejects_bad_signature() {
+        use crate::security::signature::sign_for_tests;
+
+        let (signature, key) = sign_for_tests(5, b"something else");
+        let server = serve_binary(Some(signature)).await;
+        let temp = TempDir::new().unwrap();
+        let manager = signed_manager(&temp, &key, false);
+        let lua_version = LuaVersion::new(5, 4, 0);
+        let target = Target::default_target();
+
+        let err = manager
+            .download_prebuilt(
+                "test-pkg",
+                Some("owner/test-pkg"),
+                "1.0.0",
+                &lua_version,
+                &target,
+                &format!("{}/lib.so", server.uri()),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Signature verification failed"));
+        assert!(!manager.has_prebuilt("test-pkg", "1.0.0", &lua_version, &target));
+    }
+
+    #[tokio::test]
+    async fn test_download_prebuilt_unsigned_in_strict_mode() {
+        use crate::security::signature::sign_for_tests;
+
+        let (_, key) = sign_for_tests(5, b"");
+        let server = serve_binary(None).await;
+        let temp = TempDir::new().unwrap();
+        let lua_version = LuaVersion::new(5, 4, 0);
+        let target = Target::default_target();
+        let url = format!("{}/lib.so", server.uri());
+
+        let repository = Some("owner/test-pkg");
+
+        let err = signed_manager(&temp, &key, true)
+            .download_prebuilt("test-pkg", repository, "1.0.0", &lua_version, &target, &url)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is not signed"));
+
+        // A trusted key for the owner refuses it without strict mode too
+        let err = signed_manager(&temp, &key, false)
+            .download_prebuilt("test-pkg", repository, "1.0.0", &lua_version, &target, &url)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("a trusted key is configured"));
+
+        // With no repository no key applies, and the unsigned binary is accepted
+        assert!(signed_manager(&temp, &key, false)
+            .download_prebuilt("test-pkg", None, "1.0.0", &lua_version, &target, &url)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cached_prebuilt_is_reverified() {
+        use crate::security::signature::sign_for_tests;
+
+        let (signature, key) = sign_for_tests(5, b"binary");
+        let server = serve_binary(Some(signature)).await;
+        let temp = TempDir::new().unwrap();
+        let manager = signed_manager(&temp, &key, false);
+        let lua_version = LuaVersion::new(5, 4, 0);
+        let target = Target::default_target();
+        let url = format!("{}/lib.so", server.uri());
+        let repository = Some("owner/test-pkg");
+
+        let path = manager
+            .get_or_download(
+                "test-pkg",
+                repository,
+                "1.0.0",
+                &lua_version,
+                &target,
+                Some(&url),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            manager
+                .get_prebuilt("test-pkg", repository, "1.0.0", &lua_version, &target)
+                .unwrap(),
+            Some(path.clone())
+        );
+
+        // A cached binary that was swapped out is refused instead of used
+        std::fs::write(&path, b"swapped").unwrap();
+        let err = manager
+            .get_or_download(
+                "test-pkg",
+                repository,
+                "1.0.0",
+                &lua_version,
+                &target,
+                Some(&url),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Signature verification failed"));
+    }
+
     #[test]
     fn test_find_binary_url_with_multiple_targets() {
         let mut binary_urls = std::collections::HashMap::new();
@@ -1,26 +1,104 @@
+use clap::Subcommand;
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
-use depot::security::audit::{format_report, SecurityAuditor};
+use depot::di::traits::{ConfigProvider, GitHubProvider};
+use depot::di::ServiceContainer;
+use depot::package::installer::PackageInstaller;
+use depot::package::license;
+use depot::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use depot::package::lockfile_builder::LockfileBuilder;
+use depot::package::manifest::PackageManifest;
+use depot::package::update_diff::UpdateDiff;
+use depot::path_setup::PathSetup;
+use depot::security::audit::SecurityAuditor;
+use depot::security::fix::{plan_fixes, FixAction};
+use depot::security::health::{self, DEFAULT_STALE_MONTHS};
+use depot::security::ignore::AuditIgnoreList;
+use depot::security::report::{collect_findings, render_report, AuditFormat};
+use depot::security::vulnerability::{Severity, VulnerabilityReport};
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 
-pub async fn run() -> DepotResult<()> {
+#[derive(Subcommand, Debug, Clone)]
+pub enum AuditCommands {
+    /// Upgrade vulnerable packages to the smallest patched release
+    Fix {
+        /// Rewrite constraints when the fix needs a new major version
+        #[arg(long)]
+        allow_major: bool,
+    },
+}
+
+/// Options for `depot audit`
+#[derive(Debug, Clone)]
+pub struct AuditOptions {
+    /// Use only the local advisory database, without network access
+    pub offline: bool,
+    /// Advisory database directory or zip (overrides the configured `advisory_db`)
+    pub db: Option<String>,
+    /// Output format: text, json or sarif
+    pub format: String,
+    /// Report unmaintained dependencies instead of vulnerabilities
+    pub health: bool,
+    /// Months without commits or releases before `--health` reports a repository
+    pub stale_months: u32,
+}
+
+impl Default for AuditOptions {
+    fn default() -> Self {
+        Self {
+            offline: false,
+            db: None,
+            format: "text".to_string(),
+            health: false,
+            stale_months: DEFAULT_STALE_MONTHS,
+        }
+    }
+}
+
+pub async fn run(options: AuditOptions) -> DepotResult<()> {
     let current_dir = env::current_dir()
         .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
-    run_in_dir(&current_dir).await
+    run_in_dir(&current_dir, options).await
 }
 
-pub async fn run_in_dir(dir: &Path) -> DepotResult<()> {
+pub async fn run_in_dir(dir: &Path, options: AuditOptions) -> DepotResult<()> {
+    let container = ServiceContainer::new()?;
+    if options.health {
+        return health_in_dir(dir, container.github.as_ref(), &options).await;
+    }
+
+    let format = AuditFormat::parse(&options.format)?;
     let project_root = find_project_root(dir)?;
+    let manifest = PackageManifest::load(&project_root).ok();
 
-    println!("Running security audit...");
-    println!("  Querying OSV (Open Source Vulnerabilities) database...");
-    println!();
+    // Keep stdout clean for machine-readable output
+    let progress = |line: &str| {
+        if format == AuditFormat::Text {
+            println!("{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    };
 
-    let report = SecurityAuditor::audit_project_with_osv(&project_root).await?;
+    let report = audit_report(
+        &project_root,
+        manifest.as_ref(),
+        &options,
+        container.config.as_ref(),
+        &progress,
+    )
+    .await?;
 
     // Display results
-    let output = format_report(&report);
+    let findings = if format == AuditFormat::Text {
+        Vec::new()
+    } else {
+        let lockfile = Lockfile::load(&project_root)?;
+        collect_findings(&report, manifest.as_ref(), lockfile.as_ref())
+    };
+    let output = render_report(format, &report, &findings)?;
     print!("{}", output);
 
     // Return error if critical/high vulnerabilities found
@@ -39,6 +117,365 @@ pub async fn run_in_dir(dir: &Path) -> DepotResult<()> {
     Ok(())
 }
 
+/// `depot audit --health`: report locked packages whose repositories look unmaintained
+pub async fn health_in_dir(
+    dir: &Path,
+    github: &dyn GitHubProvider,
+    options: &AuditOptions,
+) -> DepotResult<()> {
+    let format = AuditFormat::parse(&options.format)?;
+    if format == AuditFormat::Sarif {
+        return Err(DepotError::Config(
+            "SARIF output is not supported with --health; use text or json".to_string(),
+        ));
+    }
+    let project_root = find_project_root(dir)?;
+    let lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+
+    if format == AuditFormat::Text {
+        println!(
+            "Checking repository health of {} package(s)...",
+            lockfile.packages.len()
+        );
+    }
+    let results = health::check_lockfile(github, &lockfile, options.stale_months).await?;
+
+    if format == AuditFormat::Json {
+        let json = serde_json::to_string_pretty(&results)
+            .map_err(|e| DepotError::Package(format!("Failed to serialize report: {}", e)))?;
+        println!("{}", json);
+    } else {
+        let unhealthy: Vec<_> = results.iter().filter(|r| !r.issues.is_empty()).collect();
+        for result in &unhealthy {
+            println!("\n⚠️  {}", result.package);
+            for issue in &result.issues {
+                println!("    - {}", issue);
+            }
+        }
+        if unhealthy.is_empty() {
+            println!("✓ No unmaintained dependencies found");
+        } else {
+            println!(
+                "\n{} of {} package(s) may be unmaintained",
+                unhealthy.len(),
+                results.len()
+            );
+        }
+    }
+
+    // Missing, archived and disabled repositories will not get fixes, so fail like a high severity finding
+    let abandoned = results
+        .iter()
+        .filter(|r| r.issues.iter().any(|i| i.is_abandoned()))
+        .count();
+    if abandoned > 0 {
+        return Err(DepotError::AuditFailed(format!(
+            "{} dependency(ies) are missing, archived or disabled",
+            abandoned
+        )));
+    }
+
+    Ok(())
+}
+
+/// `depot audit fix`: upgrade vulnerable packages to patched releases
+pub async fn fix(options: AuditOptions, allow_major: bool) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    fix_in_dir(&current_dir, options, allow_major).await
+}
+
+pub async fn fix_in_dir(dir: &Path, options: AuditOptions, allow_major: bool) -> DepotResult<()> {
+    let project_root = find_project_root(dir)?;
+    let mut manifest = PackageManifest::load(&project_root)?;
+    let lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+
+    let container = ServiceContainer::new()?.with_release_cooldown(Some(&manifest), false)?;
+    let report = audit_report(
+        &project_root,
+        Some(&manifest),
+        &options,
+        container.config.as_ref(),
+        &|line| println!("{}", line),
+    )
+    .await?;
+
+    if report.is_empty() {
+        println!("✓ No vulnerable packages to fix");
+        return Ok(());
+    }
+
+    println!("Looking for patched releases...");
+    let actions = plan_fixes(
+        container.github.as_ref(),
+        &report,
+        &manifest,
+        &lockfile,
+        allow_major,
+    )
+    .await;
+    let (fixes, unfixable): (Vec<&FixAction>, Vec<&FixAction>) =
+        actions.iter().partition(|a| a.is_fixable());
+
+    if !fixes.is_empty() {
+        println!();
+        println!("Applying fixes:");
+        for action in &fixes {
+            print_fix(action, &manifest);
+        }
+        println!();
+
+        apply_fixes(&project_root, &mut manifest, &lockfile, &fixes, &container).await?;
+        println!("\n✓ Fixed {} package(s)", fixes.len());
+    }
+
+    if unfixable.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("Could not fix {} package(s):", unfixable.len());
+    for action in &unfixable {
+        if let FixAction::Unfixable {
+            package,
+            from,
+            reason,
+            advisories,
+        } = action
+        {
+            println!(
+                "  ✗ {}@{} ({}): {}",
+                package,
+                from,
+                advisories.join(", "),
+                reason
+            );
+        }
+    }
+
+    // Fail like `depot audit` when critical/high findings remain
+    let blocking = report.vulnerabilities.iter().any(|v| {
+        v.severity >= Severity::High && unfixable.iter().any(|a| a.package() == v.package)
+    });
+    if blocking {
+        return Err(DepotError::AuditFailed(format!(
+            "{} vulnerable package(s) could not be fixed",
+            unfixable.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Run the audit and apply the project's ignore list
+pub async fn audit_report(
+    project_root: &Path,
+    manifest: Option<&PackageManifest>,
+    options: &AuditOptions,
+    config: &dyn ConfigProvider,
+    progress: &dyn Fn(&str),
+) -> DepotResult<VulnerabilityReport> {
+    let lockfile = Lockfile::load(project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+    audit_lockfile_report(project_root, manifest, &lockfile, options, config, progress).await
+}
+
+/// Audit a lockfile, which may not be saved yet, and apply the project's ignore list
+pub async fn audit_lockfile_report(
+    project_root: &Path,
+    manifest: Option<&PackageManifest>,
+    lockfile: &Lockfile,
+    options: &AuditOptions,
+    config: &dyn ConfigProvider,
+    progress: &dyn Fn(&str),
+) -> DepotResult<VulnerabilityReport> {
+    let ignore_list = AuditIgnoreList::load(project_root, manifest)?;
+
+    progress("Running security audit...");
+    let mut report = if options.offline {
+        progress("  Using local advisory database (offline)...");
+        progress("");
+        SecurityAuditor::audit_lockfile_offline(
+            lockfile,
+            options.db.as_deref().map(Path::new),
+            config,
+        )?
+    } else {
+        progress("  Querying OSV (Open Source Vulnerabilities) database...");
+        progress("");
+        SecurityAuditor::audit_lockfile_with_osv(lockfile, config).await?
+    };
+
+    // Suppress ignored advisories; expired entries fail the audit again
+    for rule in ignore_list.apply(&mut report, chrono::Local::now().date_naive()) {
+        let package = rule
+            .package
+            .map(|p| format!(" for {}", p))
+            .unwrap_or_default();
+        eprintln!(
+            "⚠ Audit ignore entry {}{} expired on {}; reporting it again",
+            rule.id, package, rule.expires
+        );
+    }
+
+    Ok(report)
+}
+
+fn print_fix(action: &FixAction, manifest: &PackageManifest) {
+    match action {
+        FixAction::Upgrade {
+            package,
+            dev,
+            from,
+            to,
+            constraint,
+            major,
+            advisories,
+            ..
+        } => {
+            let deps = if *dev {
+                &manifest.dev_dependencies
+            } else {
+                &manifest.dependencies
+            };
+            let major_label = if *major { " [major]" } else { "" };
+            println!(
+                "  {} {} → {}{} ({})",
+                package,
+                from,
+                to,
+                major_label,
+                advisories.join(", ")
+            );
+            if let Some(old) = deps.get(package).filter(|old| *old != constraint) {
+                println!("    constraint {} → {}", old, constraint);
+            }
+        }
+        FixAction::Reresolve {
+            package,
+            from,
+            to,
+            advisories,
+            ..
+        } => {
+            println!(
+                "  {} {} → {} (transitive, {})",
+                package,
+                from,
+                to,
+                advisories.join(", ")
+            );
+        }
+        FixAction::Unfixable { .. } => {}
+    }
+}
+
+/// Rewrite constraints, reinstall and re-lock the fixed packages
+///
+/// Direct dependencies are reinstalled at the fixed release. Transitive
+/// ones are only re-locked: the new lockfile pins every fix to its tag.
+/// Like `depot install`, the new lockfile must pass the license and
+/// dependency policies before package.yaml and depot.lock are written.
+async fn apply_fixes(
+    project_root: &Path,
+    manifest: &mut PackageManifest,
+    lockfile: &Lockfile,
+    fixes: &[&FixAction],
+    container: &ServiceContainer,
+) -> DepotResult<()> {
+    for action in fixes {
+        if let FixAction::Upgrade {
+            package,
+            dev,
+            constraint,
+            ..
+        } = action
+        {
+            let deps = if *dev {
+                &mut manifest.dev_dependencies
+            } else {
+                &mut manifest.dependencies
+            };
+            deps.insert(package.clone(), constraint.clone());
+        }
+    }
+
+    let installer = PackageInstaller::new(
+        project_root,
+        container.cache.clone(),
+        container.github.clone(),
+        container.config.github_fallback_chain().to_vec(),
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
+    installer.init()?;
+
+    let mut pins = HashMap::new();
+    for action in fixes {
+        match action {
+            FixAction::Upgrade { package, tag, .. } => {
+                println!("  Updating {}...", package);
+                if installer.is_installed(package) {
+                    installer.remove_package(package)?;
+                }
+                installer.install_package(package, Some(tag)).await?;
+                pins.insert(package.clone(), tag.clone());
+            }
+            FixAction::Reresolve { package, tag, .. } => {
+                pins.insert(package.clone(), tag.clone());
+            }
+            FixAction::Unfixable { .. } => {}
+        }
+    }
+
+    // Build the new lockfile before writing anything so package.yaml and
+    // depot.lock are only rewritten together
+    let builder = LockfileBuilder::new(
+        project_root,
+        container.cache.clone(),
+        container.github.clone(),
+        container.config.github_fallback_chain().to_vec(),
+    )
+    .with_pins(pins);
+    let new_lockfile = builder.build(manifest).await?;
+
+    if let Some(policy) =
+        license::effective_policy(Some(manifest), container.config.license_policy())
+    {
+        let checks = license::check_lockfile(policy, project_root, &new_lockfile);
+        license::enforce(policy, &checks)?;
+    }
+    crate::cli::policy::enforce_on_lockfile(
+        project_root,
+        manifest,
+        &new_lockfile,
+        container.config.as_ref(),
+    )
+    .await?;
+
+    manifest.save(project_root)?;
+    new_lockfile.save(project_root)?;
+
+    PathSetup::install_loader(project_root)?;
+
+    UpdateDiff::from_lockfiles(&Some(lockfile.clone()), &new_lockfile).display();
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,7 +491,7 @@ mod tests {
         )
         .unwrap();
 
-        let result = run_in_dir(temp.path()).await;
+        let result = run_in_dir(temp.path(), AuditOptions::default()).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No depot.lock"));
     }
@@ -65,7 +502,7 @@ mod tests {
         let subdir = temp.path().join("subdir");
         std::fs::create_dir_all(&subdir).unwrap();
 
-        let result = run_in_dir(&subdir).await;
+        let result = run_in_dir(&subdir, AuditOptions::default()).await;
         assert!(result.is_err());
     }
 
@@ -81,11 +518,100 @@ mod tests {
         let lockfile = Lockfile::new();
         lockfile.save(temp.path()).unwrap();
 
-        let result = run_in_dir(temp.path()).await;
+        let result = run_in_dir(temp.path(), AuditOptions::default()).await;
         // Should succeed with empty lockfile (no packages to check)
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_run_offline_with_db() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        Lockfile::new().save(temp.path()).unwrap();
+        let db = temp.path().join("advisories");
+        std::fs::create_dir_all(&db).unwrap();
+
+        let options = AuditOptions {
+            offline: true,
+            db: Some(db.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(run_in_dir(temp.path(), options).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_offline_missing_db() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        Lockfile::new().save(temp.path()).unwrap();
+
+        let options = AuditOptions {
+            offline: true,
+            db: Some(temp.path().join("missing").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let result = run_in_dir(temp.path(), options).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Advisory database not found"));
+    }
+
+    #[tokio::test]
+    async fn test_run_invalid_format() {
+        let temp = TempDir::new().unwrap();
+        let options = AuditOptions {
+            format: "xml".to_string(),
+            ..Default::default()
+        };
+        let result = run_in_dir(temp.path(), options).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid audit format"));
+    }
+
+    #[tokio::test]
+    async fn test_fix_without_vulnerabilities() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        Lockfile::new().save(temp.path()).unwrap();
+        let db = temp.path().join("advisories");
+        std::fs::create_dir_all(&db).unwrap();
+
+        let options = AuditOptions {
+            offline: true,
+            db: Some(db.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        assert!(fix_in_dir(temp.path(), options, false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fix_requires_lockfile() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+
+        let result = fix_in_dir(temp.path(), AuditOptions::default(), false).await;
+        assert!(result.unwrap_err().to_string().contains("No depot.lock"));
+    }
+
     #[tokio::test]
     async fn test_run_function_exists() {
         let _ = run;
@@ -102,7 +628,88 @@ mod tests {
         let subdir = temp.path().join("not-a-project");
         std::fs::create_dir_all(&subdir).unwrap();
 
-        let result = run_in_dir(&subdir).await;
+        let result = run_in_dir(&subdir, AuditOptions::default()).await;
         assert!(result.is_err());
     }
+
+    fn write_locked_project(dir: &Path) {
+        std::fs::write(dir.join("package.yaml"), "name: test\nversion: 1.0.0\n").unwrap();
+        std::fs::write(
+            dir.join("depot.lock"),
+            r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/lib:
+    version: main
+    repository: owner/lib
+    ref_type: Branch
+    ref_value: main
+    commit_sha: ""
+    tarball_url: https://api.github.com/repos/owner/lib/tarball/main
+    checksum: blake3:bbbb
+    size: 512
+"#,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_health_fails_on_archived_dependency() {
+        use depot::di::mocks::MockGitHubProvider;
+        use depot::github::GitHubRepo;
+
+        let temp = TempDir::new().unwrap();
+        write_locked_project(temp.path());
+        let github = MockGitHubProvider::new();
+
+        // A branch pin alone is reported without failing
+        let options = AuditOptions {
+            health: true,
+            ..Default::default()
+        };
+        health_in_dir(temp.path(), &github, &options).await.unwrap();
+
+        github.add_repo(
+            "owner",
+            "lib",
+            GitHubRepo {
+                id: 1,
+                name: "lib".to_string(),
+                full_name: "owner/lib".to_string(),
+                default_branch: "main".to_string(),
+                description: None,
+                created_at: None,
+                pushed_at: None,
+                archived: true,
+                disabled: false,
+                fork: false,
+                stargazers_count: 0,
+                parent: None,
+            },
+        );
+        let err = health_in_dir(temp.path(), &github, &options)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1 dependency(ies) are missing, archived or disabled"));
+    }
+
+    #[tokio::test]
+    async fn test_health_rejects_sarif() {
+        use depot::di::mocks::MockGitHubProvider;
+
+        let temp = TempDir::new().unwrap();
+        write_locked_project(temp.path());
+        let options = AuditOptions {
+            health: true,
+            format: "sarif".to_string(),
+            ..Default::default()
+        };
+        let err = health_in_dir(temp.path(), &MockGitHubProvider::new(), &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not supported with --health"));
+    }
 }
@@ -2,6 +2,7 @@ use depot::build::builder::RustBuilder;
 use depot::build::targets::Target;
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
+use depot::di::ServiceContainer;
 use depot::package::manifest::PackageManifest;
 use depot::workspace::{Workspace, WorkspaceFilter};
 use std::env;
@@ -39,7 +40,9 @@ pub fn run_in_dir(dir: &Path, target: Option<String>, all_targets: bool) -> Depo
         ));
     }
 
-    let builder = RustBuilder::new(&project_root, &manifest)?;
+    let container = ServiceContainer::new()?;
+    let builder = RustBuilder::new(&project_root, &manifest)?
+        .with_signature_policy(container.config.signature_policy().clone());
 
     if all_targets {
         // Build for all supported targets
@@ -124,6 +127,7 @@ fn build_workspace_filtered(
     }
     println!();
 
+    let container = ServiceContainer::new()?;
     let mut any_failed = false;
 
     // Build each filtered package
@@ -146,7 +150,7 @@ fn build_workspace_filtered(
 
         // Create builder
         let builder = match RustBuilder::new(&pkg_dir, &manifest) {
-            Ok(b) => b,
+            Ok(b) => b.with_signature_policy(container.config.signature_policy().clone()),
             Err(e) => {
                 println!("  ✗ Failed to create builder for {}: {}\n", pkg.name, e);
                 any_failed = true;
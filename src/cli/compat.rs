@@ -1,6 +1,7 @@
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
 use depot::lua_analysis::{report, scanner};
+use depot::lua_manager::switcher::project_version;
 use depot::PackageManifest;
 use std::env;
 use std::path::Path;
@@ -19,8 +20,12 @@ pub fn run_in_dir(dir: &Path, quiet: bool, json: bool) -> DepotResult<()> {
         println!("Scanning Lua files for version compatibility...");
     }
 
+    // The interpreter pinned for the project is what its code actually runs on
+    let (target, source) = project_version(&project_root)
+        .unwrap_or_else(|| (manifest.lua_version.clone(), "lua_version"));
+
     let file_results = scanner::scan_project(&project_root)?;
-    let report = report::build_report(file_results, Some(&manifest.lua_version));
+    let report = report::build_report(file_results, Some(&target));
 
     if json {
         let json_report = report::to_json_report(&report);
@@ -35,8 +40,8 @@ pub fn run_in_dir(dir: &Path, quiet: bool, json: bool) -> DepotResult<()> {
     // Return error if configured version is incompatible
     if report.config_compatible == Some(false) {
         return Err(DepotError::Package(format!(
-            "Configured lua_version \"{}\" is incompatible with detected code features",
-            manifest.lua_version
+            "Configured {} \"{}\" is incompatible with detected code features",
+            source, target
         )));
     }
 
@@ -109,6 +114,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_run_targets_lua_version_file() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\nlua_version: \"5.4\"\n",
+        )
+        .unwrap();
+        std::fs::write(temp.path().join(".lua-version"), "luajit-2.1\n").unwrap();
+
+        let src_dir = temp.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(src_dir.join("main.lua"), "local ffi = require(\"ffi\")\n").unwrap();
+
+        // FFI code is fine for the pinned LuaJIT even though lua_version says 5.4
+        let result = run_in_dir(temp.path(), false, false);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_run_json_output() {
         let temp = TempDir::new().unwrap();
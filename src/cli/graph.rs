@@ -0,0 +1,142 @@
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::di::ServiceContainer;
+use depot::package::lockfile::Lockfile;
+use depot::package::manifest::PackageManifest;
+use depot::resolver::graph_export::{ExportGraph, GraphExportOptions, GraphFormat};
+use depot::security::audit::SecurityAuditor;
+use depot::security::vulnerability::Severity;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Options for `depot graph`
+#[derive(Debug, Clone, Default)]
+pub struct GraphOptions {
+    pub format: String,
+    pub dev: bool,
+    pub depth: Option<usize>,
+    pub highlight_native: bool,
+    pub audit: bool,
+    pub output: Option<String>,
+}
+
+pub async fn run(options: GraphOptions) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    run_in_dir(&current_dir, options).await
+}
+
+pub async fn run_in_dir(dir: &Path, args: GraphOptions) -> DepotResult<()> {
+    let format = GraphFormat::parse(&args.format)?;
+    let project_root = find_project_root(dir)?;
+    let manifest = PackageManifest::load(&project_root)?;
+    let lockfile = Lockfile::load(&project_root)?;
+
+    let vulnerabilities = if args.audit {
+        eprintln!("Querying OSV for known vulnerabilities...");
+        let container = ServiceContainer::new()?;
+        let report =
+            SecurityAuditor::audit_project_with_osv(&project_root, container.config.as_ref())
+                .await?;
+        highest_severities(
+            report
+                .vulnerabilities
+                .iter()
+                .map(|v| (v.package.as_str(), v.severity)),
+        )
+    } else {
+        HashMap::new()
+    };
+
+    let options = GraphExportOptions {
+        include_dev: args.dev,
+        max_depth: args.depth,
+        highlight_native: args.highlight_native,
+        vulnerabilities,
+    };
+
+    let graph = ExportGraph::build(&manifest, lockfile.as_ref(), &options);
+    let rendered = graph.render(format)?;
+
+    match args.output {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            eprintln!("✓ Wrote dependency graph to {}", path);
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Keep only the highest severity reported for each package
+fn highest_severities<'a>(
+    findings: impl Iterator<Item = (&'a str, Severity)>,
+) -> HashMap<String, Severity> {
+    let mut highest: HashMap<String, Severity> = HashMap::new();
+    for (package, severity) in findings {
+        let entry = highest.entry(package.to_string()).or_insert(severity);
+        if severity > *entry {
+            *entry = severity;
+        }
+    }
+    highest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_run_writes_output_file() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: app\nversion: 1.0.0\ndependencies:\n  owner/json: ^1.0.0\n",
+        )
+        .unwrap();
+
+        let output = temp.path().join("deps.mmd");
+        let args = GraphOptions {
+            format: "mermaid".to_string(),
+            output: Some(output.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        run_in_dir(temp.path(), args).await.unwrap();
+
+        let content = fs::read_to_string(output).unwrap();
+        assert!(content.starts_with("graph LR"));
+        assert!(content.contains("owner/json"));
+    }
+
+    #[tokio::test]
+    async fn test_run_invalid_format() {
+        let temp = TempDir::new().unwrap();
+        let args = GraphOptions {
+            format: "svg".to_string(),
+            ..Default::default()
+        };
+        let result = run_in_dir(temp.path(), args).await;
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid graph format"));
+    }
+
+    #[test]
+    fn test_highest_severities() {
+        let findings = vec![
+            ("owner/a", Severity::Low),
+            ("owner/a", Severity::Critical),
+            ("owner/a", Severity::Medium),
+            ("owner/b", Severity::High),
+        ];
+        let highest = highest_severities(findings.into_iter());
+        assert_eq!(highest["owner/a"], Severity::Critical);
+        assert_eq!(highest["owner/b"], Severity::High);
+    }
+}
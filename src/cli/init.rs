@@ -1,57 +1,11 @@
+use crate::cli::input::{DialoguerInput, UserInput};
 use crate::cli::install::run_interactive;
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
 use depot::package::manifest::PackageManifest;
-use dialoguer::{Confirm, Input, MultiSelect, Select};
 use std::env;
 use std::path::Path;
 
-// Trait for user input (for dependency injection in tests)
-pub trait UserInput {
-    fn prompt_string(&self, prompt: &str, default: &str) -> DepotResult<String>;
-    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool>;
-    fn prompt_select(&self, prompt: &str, items: &[String], default: usize) -> DepotResult<usize>;
-    fn prompt_multiselect(&self, prompt: &str, items: &[String]) -> DepotResult<Vec<usize>>;
-}
-
-// Real implementation using dialoguer
-pub struct DialoguerInput;
-
-impl UserInput for DialoguerInput {
-    fn prompt_string(&self, prompt: &str, default: &str) -> DepotResult<String> {
-        Input::new()
-            .with_prompt(prompt)
-            .default(default.to_string())
-            .interact_text()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-
-    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool> {
-        Confirm::new()
-            .with_prompt(prompt)
-            .default(default)
-            .interact()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-
-    fn prompt_select(&self, prompt: &str, items: &[String], default: usize) -> DepotResult<usize> {
-        Select::new()
-            .with_prompt(prompt)
-            .items(items)
-            .default(default)
-            .interact()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-
-    fn prompt_multiselect(&self, prompt: &str, items: &[String]) -> DepotResult<Vec<usize>> {
-        MultiSelect::new()
-            .with_prompt(prompt)
-            .items(items)
-            .interact()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-}
-
 pub async fn run(yes: bool) -> DepotResult<()> {
     let current_dir = env::current_dir()
         .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
@@ -280,7 +234,7 @@ async fn run_wizard_with_input(
     // Add initial dependencies if requested
     if add_dependencies {
         println!("\n📦 Adding initial dependencies...");
-        run_interactive(current_dir, false, &mut manifest).await?;
+        run_interactive(current_dir, false, &mut manifest, false).await?;
     }
 
     println!("\nNext steps:");
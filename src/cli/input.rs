@@ -0,0 +1,50 @@
+use depot::core::{DepotError, DepotResult};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+
+// Trait for user input (for dependency injection in tests)
+pub trait UserInput {
+    /// Read a line of text; an empty answer yields `default`
+    fn prompt_string(&self, prompt: &str, default: &str) -> DepotResult<String>;
+    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool>;
+    fn prompt_select(&self, prompt: &str, items: &[String], default: usize) -> DepotResult<usize>;
+    fn prompt_multiselect(&self, prompt: &str, items: &[String]) -> DepotResult<Vec<usize>>;
+}
+
+// Real implementation using dialoguer
+pub struct DialoguerInput;
+
+impl UserInput for DialoguerInput {
+    fn prompt_string(&self, prompt: &str, default: &str) -> DepotResult<String> {
+        Input::new()
+            .with_prompt(prompt)
+            .default(default.to_string())
+            .show_default(!default.is_empty())
+            .interact_text()
+            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
+    }
+
+    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool> {
+        Confirm::new()
+            .with_prompt(prompt)
+            .default(default)
+            .interact()
+            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
+    }
+
+    fn prompt_select(&self, prompt: &str, items: &[String], default: usize) -> DepotResult<usize> {
+        Select::new()
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact()
+            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
+    }
+
+    fn prompt_multiselect(&self, prompt: &str, items: &[String]) -> DepotResult<Vec<usize>> {
+        MultiSelect::new()
+            .with_prompt(prompt)
+            .items(items)
+            .interact()
+            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
+    }
+}
@@ -1,46 +1,24 @@
+use crate::cli::input::{DialoguerInput, UserInput};
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
 use depot::di::ServiceContainer;
+use depot::lua_analysis::capabilities;
 use depot::lua_version::detector::LuaVersionDetector;
 use depot::package::conflict_checker::ConflictChecker;
 use depot::package::installer::PackageInstaller;
+use depot::package::license;
+use depot::package::lockfile::Lockfile;
 use depot::package::lockfile_builder::LockfileBuilder;
 use depot::package::manifest::PackageManifest;
 use depot::package::rollback::with_rollback_async;
 use depot::path_setup::loader::PathSetup;
+use depot::security::suspicious::{self, SuspiciousPackagePolicy};
 use depot::workspace::{Workspace, WorkspaceFilter};
-use dialoguer::{Confirm, Input};
 use std::collections::HashMap;
 use std::env;
+use std::io::IsTerminal;
 use std::path::Path;
 
-// Trait for user input (for dependency injection in tests)
-pub trait UserInput {
-    fn prompt_string(&self, prompt: &str) -> DepotResult<String>;
-    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool>;
-}
-
-// Real implementation using dialoguer
-pub struct DialoguerInput;
-
-impl UserInput for DialoguerInput {
-    fn prompt_string(&self, prompt: &str) -> DepotResult<String> {
-        Input::new()
-            .with_prompt(prompt)
-            .allow_empty(false)
-            .interact_text()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-
-    fn prompt_confirm(&self, prompt: &str, default: bool) -> DepotResult<bool> {
-        Confirm::new()
-            .with_prompt(prompt)
-            .default(default)
-            .interact()
-            .map_err(|e| DepotError::Config(format!("Failed to read input: {}", e)))
-    }
-}
-
 pub struct InstallOptions {
     pub package: Option<String>,
     pub dev: bool,
@@ -53,6 +31,7 @@ pub struct InstallOptions {
     pub branch: Option<String>,
     pub commit: Option<String>,
     pub release: Option<String>,
+    pub ignore_cooldown: bool,
 }
 
 /// Parse package specification from either owner/repo[@version] or full GitHub URL
@@ -138,6 +117,7 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
         branch,
         commit,
         release,
+        ignore_cooldown,
     } = options;
     // Validate conflicting flags early, before any other operations.
     if no_dev && dev_only {
@@ -162,7 +142,7 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
         }
 
         let pkg_spec = package.unwrap();
-        return install_global(&pkg_spec, branch, commit, release).await;
+        return install_global(&pkg_spec, branch, commit, release, ignore_cooldown).await;
     }
 
     let current_dir = env::current_dir()
@@ -189,8 +169,16 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
         // Handle --filter flag (workspace only)
         if !filter.is_empty() {
             if let Some(ref ws) = workspace {
-                return install_workspace_filtered(ws, &filter, dev, no_dev, dev_only, package)
-                    .await;
+                return install_workspace_filtered(
+                    ws,
+                    &filter,
+                    dev,
+                    no_dev,
+                    dev_only,
+                    package,
+                    ignore_cooldown,
+                )
+                .await;
             } else {
                 return Err(DepotError::Package(
                     "--filter can only be used in workspace mode".to_string(),
@@ -236,7 +224,7 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
 
         // Handle interactive mode
         if interactive {
-            return run_interactive(&project_root, dev, &mut manifest).await;
+            return run_interactive(&project_root, dev, &mut manifest, ignore_cooldown).await;
         }
 
         match package {
@@ -248,6 +236,31 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
                 // Build version spec from flags and parsed version
                 let version_str = build_version_spec(parsed_version, branch, commit, release);
 
+                let container = ServiceContainer::new()?
+                    .with_release_cooldown(Some(&manifest), ignore_cooldown)?;
+
+                // Refuse dependencies the organisation policy does not allow
+                crate::cli::policy::enforce_on_new_dependency(
+                    &project_root,
+                    &repository,
+                    container.config.policy_file(),
+                )?;
+
+                // Warn about typosquats and other suspicious new dependencies
+                let input = is_interactive_session().then_some(&DialoguerInput as &dyn UserInput);
+                if !check_new_dependency(
+                    &container,
+                    &project_root,
+                    Some(&manifest),
+                    &repository,
+                    input,
+                )
+                .await?
+                {
+                    println!("Cancelled: {} was not added", repository);
+                    return Ok(());
+                }
+
                 // Add to manifest
                 if dev {
                     manifest
@@ -265,13 +278,14 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
                 manifest.save(&project_root)?;
 
                 // Initialize installer
-                let container = ServiceContainer::new()?;
                 let installer = PackageInstaller::new(
                     &project_root,
                     container.cache.clone(),
                     container.github.clone(),
                     container.config.github_fallback_chain().to_vec(),
-                )?;
+                )?
+                .with_archive_limits(container.config.archive_limits())
+                .with_signature_policy(container.config.signature_policy().clone());
                 installer.init()?;
 
                 // Install the package
@@ -285,7 +299,7 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
                 PathSetup::install_loader(&project_root)?;
 
                 // Generate lockfile
-                generate_lockfile(&project_root, &manifest, no_dev).await?;
+                generate_lockfile(&project_root, &manifest, no_dev, ignore_cooldown).await?;
 
                 return Ok(());
             }
@@ -293,11 +307,24 @@ pub async fn run(options: InstallOptions) -> DepotResult<()> {
             None => {
                 if let Some(ref ws) = workspace {
                     // Install workspace dependencies (shared + all packages)
-                    install_workspace_dependencies(install_root, ws, no_dev, dev_only).await?;
+                    install_workspace_dependencies(
+                        install_root,
+                        ws,
+                        no_dev,
+                        dev_only,
+                        ignore_cooldown,
+                    )
+                    .await?;
                 } else {
                     // Install single package dependencies
-                    install_package_dependencies(&project_root, &manifest, no_dev, dev_only)
-                        .await?;
+                    install_package_dependencies(
+                        &project_root,
+                        &manifest,
+                        no_dev,
+                        dev_only,
+                        ignore_cooldown,
+                    )
+                    .await?;
                 }
             }
         }
@@ -313,6 +340,7 @@ async fn install_package_dependencies(
     manifest: &PackageManifest,
     no_dev: bool,
     dev_only: bool,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
     // Determine which dependencies to install
     let mut deps_to_install = HashMap::new();
@@ -330,14 +358,20 @@ async fn install_package_dependencies(
         return Ok(());
     }
 
+    // Check package.yaml and the committed depot.lock against the organisation policy
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(manifest), ignore_cooldown)?;
+    crate::cli::policy::enforce_on_project(project_root, manifest, container.config.policy_file())?;
+
     // Initialize installer
-    let container = ServiceContainer::new()?;
     let installer = PackageInstaller::new(
         project_root,
         container.cache.clone(),
         container.github.clone(),
         container.config.github_fallback_chain().to_vec(),
-    )?;
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
     installer.init()?;
 
     println!("Installing {} dependency(ies)...", deps_to_install.len());
@@ -353,7 +387,7 @@ async fn install_package_dependencies(
     PathSetup::install_loader(project_root)?;
 
     // Generate lockfile
-    generate_lockfile(project_root, manifest, no_dev).await?;
+    generate_lockfile(project_root, manifest, no_dev, ignore_cooldown).await?;
 
     println!("\n✓ Installed {} package(s)", deps_to_install.len());
 
@@ -365,17 +399,22 @@ async fn install_workspace_dependencies(
     workspace: &Workspace,
     no_dev: bool,
     dev_only: bool,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
     println!("Installing workspace dependencies...");
 
     // Initialize service container and package installer
-    let container = ServiceContainer::new()?;
+    let root_manifest = PackageManifest::load(install_root).ok();
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(root_manifest.as_ref(), ignore_cooldown)?;
     let installer = PackageInstaller::new(
         install_root,
         container.cache.clone(),
         container.github.clone(),
         container.config.github_fallback_chain().to_vec(),
-    )?;
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
     installer.init()?;
 
     // Collect all dependencies from workspace packages
@@ -450,9 +489,11 @@ async fn generate_lockfile(
     project_root: &Path,
     manifest: &PackageManifest,
     no_dev: bool,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
     // Load service container
-    let container = ServiceContainer::new()?;
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(manifest), ignore_cooldown)?;
 
     // Create lockfile builder with GitHub dependencies
     let builder = LockfileBuilder::new(
@@ -465,7 +506,26 @@ async fn generate_lockfile(
     // Build lockfile from manifest
     let lockfile = builder.build(manifest).await?;
 
-    // Save lockfile
+    // Enforce the license policy across the resolved graph
+    if let Some(policy) =
+        license::effective_policy(Some(manifest), container.config.license_policy())
+    {
+        let checks = license::check_lockfile(policy, project_root, &lockfile);
+        license::enforce(policy, &checks)?;
+        println!("✓ Licenses comply with policy ({} packages)", checks.len());
+    }
+
+    // Enforce the dependency policies, advisories included, before writing the lockfile
+    crate::cli::policy::enforce_on_lockfile(
+        project_root,
+        manifest,
+        &lockfile,
+        container.config.as_ref(),
+    )
+    .await?;
+
+    // Save lockfile, keeping the previous one to diff capabilities against
+    let previous = Lockfile::load(project_root).ok().flatten();
     lockfile.save(project_root)?;
 
     println!("✓ Generated {}", depot::package::lockfile::LOCKFILE_NAME);
@@ -473,16 +533,115 @@ async fn generate_lockfile(
         println!("  (dev dependencies excluded)");
     }
 
+    report_capabilities(previous.as_ref(), &lockfile);
+
     Ok(())
 }
 
+/// Warn about locked packages that use sensitive APIs, highlighting new ones
+fn report_capabilities(previous: Option<&Lockfile>, lockfile: &Lockfile) {
+    let mut flagged: Vec<_> = lockfile
+        .packages
+        .iter()
+        .filter(|(_, pkg)| !pkg.capabilities.is_empty())
+        .collect();
+    if flagged.is_empty() {
+        return;
+    }
+    flagged.sort_by(|a, b| a.0.cmp(b.0));
+
+    println!("\n⚠️  {} package(s) use sensitive APIs:", flagged.len());
+    for (name, pkg) in flagged {
+        println!(
+            "    {} {}: {}",
+            name,
+            pkg.version,
+            pkg.capabilities.join(", ")
+        );
+    }
+
+    if let Some(previous) = previous {
+        let changes = capabilities::diff_lockfiles(previous, lockfile);
+        if !changes.is_empty() {
+            println!(
+                "  New since the previous {}:",
+                depot::package::lockfile::LOCKFILE_NAME
+            );
+            print!("{}", capabilities::format_changes(&changes));
+        }
+    }
+    println!("  Run 'depot scan-capabilities' for details");
+}
+
+/// Whether a user is present to confirm prompts (a terminal, and not CI)
+fn is_interactive_session() -> bool {
+    std::io::stdin().is_terminal() && env::var_os("CI").is_none()
+}
+
+/// Check a dependency that is not yet in package.yaml for typosquatting and
+/// other warning signs
+///
+/// Returns whether to go ahead. With `input` the user confirms; without it
+/// the `suspicious_packages` policy decides. Global installs have no manifest.
+async fn check_new_dependency(
+    container: &ServiceContainer,
+    project_root: &Path,
+    manifest: Option<&PackageManifest>,
+    repository: &str,
+    input: Option<&dyn UserInput>,
+) -> DepotResult<bool> {
+    let policy = container.config.suspicious_package_policy();
+    if policy == SuspiciousPackagePolicy::Allow
+        || manifest.is_some_and(|m| {
+            m.dependencies.contains_key(repository) || m.dev_dependencies.contains_key(repository)
+        })
+    {
+        return Ok(true);
+    }
+
+    let mut known: Vec<String> = manifest
+        .map(|m| {
+            m.dependencies
+                .keys()
+                .chain(m.dev_dependencies.keys())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(lockfile) = Lockfile::load(project_root).ok().flatten() {
+        known.extend(lockfile.packages.into_keys());
+    }
+
+    let suspicions = suspicious::inspect(container.github.as_ref(), repository, &known).await?;
+    if suspicions.is_empty() {
+        return Ok(true);
+    }
+
+    eprint!("{}", suspicious::format_suspicions(repository, &suspicions));
+    match input {
+        Some(input) => input.prompt_confirm(&format!("Add {} anyway?", repository), false),
+        None => {
+            suspicious::enforce(policy, repository, &suspicions)?;
+            Ok(true)
+        }
+    }
+}
+
 /// Interactive package installation
 pub async fn run_interactive(
     project_root: &Path,
     dev: bool,
     manifest: &mut PackageManifest,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
-    run_interactive_with_input(project_root, dev, manifest, &DialoguerInput).await
+    run_interactive_with_input(
+        project_root,
+        dev,
+        manifest,
+        ignore_cooldown,
+        &DialoguerInput,
+    )
+    .await
 }
 
 /// Interactive package installation with dependency injection
@@ -490,6 +649,7 @@ pub async fn run_interactive_with_input(
     project_root: &Path,
     dev: bool,
     manifest: &mut PackageManifest,
+    ignore_cooldown: bool,
     input: &dyn UserInput,
 ) -> DepotResult<()> {
     println!("🔍 Interactive package installation");
@@ -500,11 +660,35 @@ pub async fn run_interactive_with_input(
     println!();
 
     // Get package repository from user
-    let package_spec = input.prompt_string("Package repository")?;
+    let package_spec = input.prompt_string("Package repository", "")?;
 
     // Parse owner/repo[@version] or full GitHub URL
     let (repository, version) = parse_package_spec(&package_spec)?;
 
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(manifest), ignore_cooldown)?;
+
+    // Refuse dependencies the organisation policy does not allow
+    crate::cli::policy::enforce_on_new_dependency(
+        project_root,
+        &repository,
+        container.config.policy_file(),
+    )?;
+
+    // Warn about typosquats and other suspicious new dependencies
+    if !check_new_dependency(
+        &container,
+        project_root,
+        Some(manifest),
+        &repository,
+        Some(input),
+    )
+    .await?
+    {
+        println!("Cancelled: {} was not added", repository);
+        return Ok(());
+    }
+
     // Add to manifest
     let version_str = version.unwrap_or_else(|| "*".to_string());
     if dev {
@@ -524,13 +708,14 @@ pub async fn run_interactive_with_input(
 
     if install_now {
         // Initialize installer
-        let container = ServiceContainer::new()?;
         let installer = PackageInstaller::new(
             project_root,
             container.cache.clone(),
             container.github.clone(),
             container.config.github_fallback_chain().to_vec(),
-        )?;
+        )?
+        .with_archive_limits(container.config.archive_limits())
+        .with_signature_policy(container.config.signature_policy().clone());
         installer.init()?;
 
         // Install the package
@@ -544,7 +729,7 @@ pub async fn run_interactive_with_input(
         PathSetup::install_loader(project_root)?;
 
         // Generate lockfile
-        generate_lockfile(project_root, manifest, false).await?;
+        generate_lockfile(project_root, manifest, false, ignore_cooldown).await?;
     }
 
     Ok(())
@@ -557,6 +742,7 @@ async fn install_workspace_filtered(
     no_dev: bool,
     dev_only: bool,
     package: Option<String>,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
     // Create filter
     let filter = WorkspaceFilter::new(filter_patterns.to_vec());
@@ -578,6 +764,16 @@ async fn install_workspace_filtered(
     }
     println!();
 
+    // A package to add is checked like any other new dependency
+    let new_dependency = match package {
+        Some(ref pkg_spec) => {
+            let (repository, version) = parse_package_spec(pkg_spec)?;
+            Some((repository, version.unwrap_or_else(|| "*".to_string())))
+        }
+        None => None,
+    };
+    let mut confirmed = false;
+
     // Install for each filtered package
     for pkg in filtered_packages {
         let pkg_dir = workspace.root.join(&pkg.path);
@@ -601,24 +797,38 @@ async fn install_workspace_filtered(
             }
         }
 
-        // If a specific package is requested, add it to this workspace package
-        if let Some(ref pkg_spec) = package {
-            // Parse package name and version
-            let (pkg_name, version) = if let Some(at_pos) = pkg_spec.find('@') {
-                (
-                    pkg_spec[..at_pos].to_string(),
-                    Some(pkg_spec[at_pos + 1..].to_string()),
-                )
-            } else {
-                (pkg_spec.clone(), None)
-            };
+        let container =
+            ServiceContainer::new()?.with_release_cooldown(Some(&manifest), ignore_cooldown)?;
 
-            let version_str = version.unwrap_or_else(|| "*".to_string());
+        // If a specific package is requested, add it to this workspace package
+        if let Some((ref repository, ref version_str)) = new_dependency {
+            // Refuse dependencies the organisation policy does not allow
+            crate::cli::policy::enforce_on_new_dependency(
+                &pkg_dir,
+                repository,
+                container.config.policy_file(),
+            )?;
+
+            // Warn about typosquats once, not for every workspace package
+            if !confirmed {
+                let input = is_interactive_session().then_some(&DialoguerInput as &dyn UserInput);
+                if !check_new_dependency(&container, &pkg_dir, Some(&manifest), repository, input)
+                    .await?
+                {
+                    println!("Cancelled: {} was not added", repository);
+                    return Ok(());
+                }
+                confirmed = true;
+            }
 
             if dev {
-                manifest.dev_dependencies.insert(pkg_name, version_str);
+                manifest
+                    .dev_dependencies
+                    .insert(repository.clone(), version_str.clone());
             } else {
-                manifest.dependencies.insert(pkg_name, version_str);
+                manifest
+                    .dependencies
+                    .insert(repository.clone(), version_str.clone());
             }
 
             manifest.save(&pkg_dir)?;
@@ -645,13 +855,14 @@ async fn install_workspace_filtered(
         }
 
         // Initialize installer for this package
-        let container = ServiceContainer::new()?;
         let installer = PackageInstaller::new(
             &pkg_dir,
             container.cache.clone(),
             container.github.clone(),
             container.config.github_fallback_chain().to_vec(),
-        )?;
+        )?
+        .with_archive_limits(container.config.archive_limits())
+        .with_signature_policy(container.config.signature_policy().clone());
         installer.init()?;
 
         // Install all dependencies
@@ -667,7 +878,7 @@ async fn install_workspace_filtered(
         PathSetup::install_loader(&pkg_dir)?;
 
         // Generate lockfile for this package
-        generate_lockfile(&pkg_dir, &manifest, false).await?;
+        generate_lockfile(&pkg_dir, &manifest, false, ignore_cooldown).await?;
 
         println!("✓ Installed dependencies for {}\n", pkg.name);
     }
@@ -683,6 +894,7 @@ async fn install_global(
     branch: Option<String>,
     commit: Option<String>,
     release: Option<String>,
+    ignore_cooldown: bool,
 ) -> DepotResult<()> {
     use depot::config::Config;
     use depot::core::path::global_dir;
@@ -713,14 +925,31 @@ async fn install_global(
     fs::create_dir_all(&global_bin)?;
     fs::create_dir_all(&metadata_dir)?;
 
+    let container = ServiceContainer::new()?.with_release_cooldown(None, ignore_cooldown)?;
+
+    // Refuse packages the organisation policy does not allow
+    crate::cli::policy::enforce_on_new_dependency(
+        &base_global_dir,
+        &repository,
+        container.config.policy_file(),
+    )?;
+
+    // Warn about typosquats and other suspicious packages
+    let input = is_interactive_session().then_some(&DialoguerInput as &dyn UserInput);
+    if !check_new_dependency(&container, &base_global_dir, None, &repository, input).await? {
+        println!("Cancelled: {} was not installed", repository);
+        return Ok(());
+    }
+
     // Initialize installer with global directories
-    let container = ServiceContainer::new()?;
     let installer = PackageInstaller::new(
         global_lua_modules.parent().unwrap(),
         container.cache.clone(),
         container.github.clone(),
         container.config.github_fallback_chain().to_vec(),
-    )?;
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
     installer.init()?;
 
     // Install the package
@@ -0,0 +1,200 @@
+use clap::Subcommand;
+use depot::config::Config;
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::package::license::{self, LicenseStatus};
+use depot::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use depot::package::manifest::{LicensePolicy, PackageManifest};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default file written by `depot licenses notice`
+pub const NOTICES_FILE: &str = "THIRD_PARTY_NOTICES";
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum LicensesCommands {
+    /// Check every locked package against the license policy
+    Check,
+    /// Generate a third-party notices file with each package's license text
+    Notice {
+        /// Output file (defaults to THIRD_PARTY_NOTICES in the project root)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+pub fn run(command: LicensesCommands) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    let config_policy = Config::load().unwrap_or_default().license_policy;
+    match command {
+        LicensesCommands::Check => check_in_dir(&current_dir, config_policy.as_ref()),
+        LicensesCommands::Notice { output } => notice_in_dir(&current_dir, output),
+    }
+}
+
+fn load_project(dir: &Path) -> DepotResult<(PathBuf, PackageManifest, Lockfile)> {
+    let project_root = find_project_root(dir)?;
+    let manifest = PackageManifest::load(&project_root)?;
+    let lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+    Ok((project_root, manifest, lockfile))
+}
+
+/// Check the project's locked packages, using `config_policy` when package.yaml has no policy
+pub fn check_in_dir(dir: &Path, config_policy: Option<&LicensePolicy>) -> DepotResult<()> {
+    let (project_root, manifest, lockfile) = load_project(dir)?;
+    let configured = license::effective_policy(Some(&manifest), config_policy);
+    let policy = configured.cloned().unwrap_or_default();
+
+    let checks = license::check_lockfile(&policy, &project_root, &lockfile);
+    if checks.is_empty() {
+        println!("No packages in {}", LOCKFILE_NAME);
+        return Ok(());
+    }
+
+    println!("Licenses for {} package(s):", checks.len());
+    for check in &checks {
+        let marker = if check.is_violation(&policy) {
+            "✗"
+        } else if check.status == LicenseStatus::Unknown {
+            "?"
+        } else {
+            "✓"
+        };
+        let note = match check.status {
+            LicenseStatus::Excepted => " (exception)",
+            LicenseStatus::Denied => " (denied)",
+            LicenseStatus::NotAllowed => " (not allowed)",
+            _ => "",
+        };
+        println!(
+            "  {} {} {}  {}{}",
+            marker,
+            check.package,
+            check.version,
+            check.license.as_deref().unwrap_or("UNKNOWN"),
+            note
+        );
+    }
+
+    if configured.is_none() {
+        println!("\nNo license_policy configured; nothing to enforce");
+        return Ok(());
+    }
+
+    license::enforce(&policy, &checks)?;
+    println!("\n✓ All licenses comply with the policy");
+    Ok(())
+}
+
+/// Write third-party notices for the project's locked packages
+pub fn notice_in_dir(dir: &Path, output: Option<String>) -> DepotResult<()> {
+    let (project_root, _manifest, lockfile) = load_project(dir)?;
+    let notices = license::render_notices(&project_root, &lockfile)?;
+
+    let path = output
+        .map(PathBuf::from)
+        .unwrap_or_else(|| project_root.join(NOTICES_FILE));
+    fs::write(&path, notices)?;
+    println!(
+        "✓ Wrote notices for {} package(s) to {}",
+        lockfile.packages.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path, manifest: &str, license: Option<&str>) {
+        fs::write(dir.join("package.yaml"), manifest).unwrap();
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/lib".to_string(),
+            LockedPackage {
+                version: "1.2.0".to_string(),
+                repository: "owner/lib".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.2.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: "https://github.com/owner/lib/archive/v1.2.0.tar.gz".to_string(),
+                checksum: "blake3:00".to_string(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: license.map(String::from),
+                capabilities: Vec::new(),
+                repository_id: None,
+            },
+        );
+        lockfile.save(dir).unwrap();
+    }
+
+    #[test]
+    fn test_check_requires_lockfile() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: t\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        let err = check_in_dir(temp.path(), None).unwrap_err();
+        assert!(err.to_string().contains("No depot.lock"));
+    }
+
+    #[test]
+    fn test_check_manifest_policy() {
+        let temp = TempDir::new().unwrap();
+        write_project(
+            temp.path(),
+            "name: t\nversion: 1.0.0\nlicense_policy:\n  deny: [GPL-3.0-only]\n",
+            Some("GPL-3.0-only"),
+        );
+        let err = check_in_dir(temp.path(), None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("owner/lib (GPL-3.0-only) is denied"));
+    }
+
+    #[test]
+    fn test_check_config_policy_and_override() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path(), "name: t\nversion: 1.0.0\n", Some("MIT"));
+        let config_policy = LicensePolicy {
+            allow: vec!["Apache-2.0".to_string()],
+            ..Default::default()
+        };
+        assert!(check_in_dir(temp.path(), Some(&config_policy)).is_err());
+        assert!(check_in_dir(temp.path(), None).is_ok());
+
+        write_project(
+            temp.path(),
+            "name: t\nversion: 1.0.0\nlicense_policy:\n  allow: [MIT]\n",
+            Some("MIT"),
+        );
+        assert!(check_in_dir(temp.path(), Some(&config_policy)).is_ok());
+    }
+
+    #[test]
+    fn test_notice_writes_default_file() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path(), "name: t\nversion: 1.0.0\n", Some("MIT"));
+        notice_in_dir(temp.path(), None).unwrap();
+
+        let notices = fs::read_to_string(temp.path().join(NOTICES_FILE)).unwrap();
+        assert!(notices.contains("owner/lib 1.2.0"));
+        assert!(notices.contains("License: MIT"));
+    }
+}
@@ -342,6 +342,9 @@ mod tests {
                 dependencies: HashMap::new(),
                 build: None,
                 native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: None,
             },
         );
 
@@ -394,6 +397,9 @@ mod tests {
                 dependencies: deps,
                 build: None,
                 native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: None,
             },
         );
 
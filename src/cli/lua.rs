@@ -1,9 +1,12 @@
 use clap::Subcommand;
 use depot::core::path::depot_home;
 use depot::core::{DepotError, DepotResult};
-use depot::lua_manager::{LuaDownloader, VersionSwitcher, WrapperGenerator};
+use depot::lua_manager::downloader::is_luajit;
+use depot::lua_manager::source::SourceBuilder;
+use depot::lua_manager::switcher::project_version;
+use depot::lua_manager::{headers, LuaDownloader, VersionSwitcher, WrapperGenerator};
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 #[derive(Subcommand)]
@@ -16,8 +19,11 @@ pub enum LuaCommands {
     ListRemote,
     /// Install a Lua version
     Install {
-        /// Version to install (e.g., "5.4.8", "latest", "5.4")
+        /// Version to install (e.g., "5.4.8", "latest", "5.4", "luajit", "openresty-luajit")
         version: String,
+        /// Compile the official source release instead of downloading binaries
+        #[arg(long)]
+        from_source: bool,
     },
     /// Switch to a Lua version globally
     Use {
@@ -54,7 +60,10 @@ pub async fn run(command: LuaCommands) -> DepotResult<()> {
     match command {
         LuaCommands::List => list_installed(&depot_home),
         LuaCommands::ListRemote => list_remote(&cache_dir),
-        LuaCommands::Install { version } => install(&depot_home, &cache_dir, &version).await,
+        LuaCommands::Install {
+            version,
+            from_source,
+        } => install(&depot_home, &cache_dir, &version, from_source).await,
         LuaCommands::Use { version } => use_version(&depot_home, &version),
         LuaCommands::Local { version } => set_local(&depot_home, &version),
         LuaCommands::Current => show_current(&depot_home),
@@ -86,20 +95,35 @@ fn list_installed(depot_home: &Path) -> DepotResult<()> {
     Ok(())
 }
 
-fn list_remote(_cache_dir: &Path) -> DepotResult<()> {
+fn list_remote(cache_dir: &Path) -> DepotResult<()> {
+    let downloader = LuaDownloader::new(cache_dir.to_path_buf())?;
+
     // For now, just show the known versions
     // In the future, we could fetch from GitHub releases API
     println!("Available Lua versions:");
-    println!("  5.1.5");
-    println!("  5.3.6");
-    println!("  5.4.8");
+    for version in downloader.list_available_versions() {
+        println!("  {}", version);
+    }
+    println!();
+    println!("Built from source (depot lua install <version> --from-source):");
+    for version in downloader.list_source_versions() {
+        println!("  {}", version);
+    }
+    println!("  ...and any other release published at https://www.lua.org/ftp/");
+    println!("  luajit (LuaJIT 2.1 branch)");
+    println!("  openresty-luajit (OpenResty's LuaJIT fork)");
     println!();
     println!("Note: Other versions may be available with custom sources.");
     println!("Configure with: depot config set lua_binary_sources.<version> <url>");
     Ok(())
 }
 
-async fn install(depot_home: &Path, cache_dir: &Path, version: &str) -> DepotResult<()> {
+async fn install(
+    depot_home: &Path,
+    cache_dir: &Path,
+    version: &str,
+    from_source: bool,
+) -> DepotResult<()> {
     let downloader = LuaDownloader::new(cache_dir.to_path_buf())?;
     let switcher = VersionSwitcher::new(depot_home);
     let resolved_version = downloader.resolve_version(version);
@@ -108,35 +132,55 @@ async fn install(depot_home: &Path, cache_dir: &Path, version: &str) -> DepotRes
     let installed = switcher.list_installed()?;
     if installed.contains(&resolved_version) {
         println!("Lua {} is already installed.", resolved_version);
+        // Versions installed before headers were managed get them now
+        let version_dir = switcher.version_dir(&resolved_version);
+        if !headers::has_headers(&version_dir) && !is_luajit(&resolved_version) {
+            install_headers(&downloader, &resolved_version, &version_dir).await;
+        }
         return Ok(());
     }
 
     println!("Installing Lua {}...", resolved_version);
+    let install_dir = depot_home.join("versions").join(&resolved_version);
 
-    // Download binaries
-    let lua_binary = downloader.download_binary(&resolved_version, "lua").await?;
-    let luac_binary = downloader
-        .download_binary(&resolved_version, "luac")
-        .await?;
+    // Download binaries, building from source when there are none.
+    // LuaJIT has no binary releases and is always built from source.
+    let from_source = from_source || is_luajit(&resolved_version);
+    let binaries = if from_source {
+        None
+    } else {
+        download_binaries(&downloader, &resolved_version).await?
+    };
+
+    if let Some((lua_binary, luac_binary)) = binaries {
+        // Install to versions directory
+        let bin_dir = install_dir.join("bin");
+        std::fs::create_dir_all(&bin_dir)?;
+
+        std::fs::copy(&lua_binary, bin_dir.join("lua"))?;
+        std::fs::copy(&luac_binary, bin_dir.join("luac"))?;
+
+        // Make executable on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(bin_dir.join("lua"))?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(bin_dir.join("lua"), perms)?;
+            let mut perms = std::fs::metadata(bin_dir.join("luac"))?.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(bin_dir.join("luac"), perms)?;
+        }
 
-    // Install to versions directory
-    let install_dir = depot_home.join("versions").join(&resolved_version);
-    let bin_dir = install_dir.join("bin");
-    std::fs::create_dir_all(&bin_dir)?;
-
-    std::fs::copy(&lua_binary, bin_dir.join("lua"))?;
-    std::fs::copy(&luac_binary, bin_dir.join("luac"))?;
-
-    // Make executable on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(bin_dir.join("lua"))?.permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(bin_dir.join("lua"), perms)?;
-        let mut perms = std::fs::metadata(bin_dir.join("luac"))?.permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(bin_dir.join("luac"), perms)?;
+        install_headers(&downloader, &resolved_version, &install_dir).await;
+    } else {
+        if !from_source {
+            println!(
+                "No verified pre-built binaries of Lua {} for this platform; building from source",
+                resolved_version
+            );
+        }
+        build_from_source(&downloader, cache_dir, &resolved_version, &install_dir).await?;
     }
 
     // Generate wrappers (if first install)
@@ -155,6 +199,57 @@ async fn install(depot_home: &Path, cache_dir: &Path, version: &str) -> DepotRes
     Ok(())
 }
 
+/// Download `lua` and `luac`, or `None` if the source does not provide both
+async fn download_binaries(
+    downloader: &LuaDownloader,
+    version: &str,
+) -> DepotResult<Option<(PathBuf, PathBuf)>> {
+    let Some(lua) = downloader.try_download_binary(version, "lua").await? else {
+        return Ok(None);
+    };
+    let Some(luac) = downloader.try_download_binary(version, "luac").await? else {
+        return Ok(None);
+    };
+    Ok(Some((lua, luac)))
+}
+
+/// Compile the verified source release into `install_dir`
+///
+/// A failed build is removed so it is not mistaken for an installed version.
+async fn build_from_source(
+    downloader: &LuaDownloader,
+    cache_dir: &Path,
+    version: &str,
+    install_dir: &Path,
+) -> DepotResult<()> {
+    let tarball = downloader.download_source(version).await?;
+    let work_dir = cache_dir.join(version).join("build");
+    let result = SourceBuilder::new().build(version, &tarball, install_dir, &work_dir);
+    if result.is_err() && install_dir.exists() {
+        std::fs::remove_dir_all(install_dir)?;
+    }
+    result
+}
+
+/// Install the C headers native module builds compile against
+///
+/// A failure only costs native builds their managed headers, so it is
+/// reported without failing the install.
+async fn install_headers(downloader: &LuaDownloader, version: &str, version_dir: &Path) {
+    let result = downloader
+        .download_source(version)
+        .await
+        .and_then(|tarball| headers::extract_headers(&tarball, version_dir));
+    match result {
+        Ok(include) => println!("✓ Installed headers to {}", include.display()),
+        Err(e) => eprintln!(
+            "⚠ Could not install headers for Lua {}: {}\n  \
+             Native modules will be built against the system Lua headers.",
+            version, e
+        ),
+    }
+}
+
 fn use_version(depot_home: &Path, version: &str) -> DepotResult<()> {
     let switcher = VersionSwitcher::new(depot_home);
     switcher.switch(version)
@@ -175,27 +270,9 @@ fn show_current(depot_home: &Path) -> DepotResult<()> {
 
 fn show_which(depot_home: &Path) -> DepotResult<()> {
     let current_dir = env::current_dir()?;
-    let mut dir = current_dir.clone();
-    let mut project_version = None;
-
-    // Walk up directory tree looking for .lua-version
-    loop {
-        let version_file = dir.join(".lua-version");
-        if version_file.exists() {
-            if let Ok(content) = std::fs::read_to_string(&version_file) {
-                project_version = Some(content.trim().to_string());
-                break;
-            }
-        }
-        if let Some(parent) = dir.parent() {
-            dir = parent.to_path_buf();
-        } else {
-            break;
-        }
-    }
 
-    if let Some(ver) = project_version {
-        println!("{} (from .lua-version)", ver);
+    if let Some((ver, source)) = project_version(&current_dir) {
+        println!("{} (from {})", ver, source);
     } else {
         let switcher = VersionSwitcher::new(depot_home);
         let version = switcher.current()?;
@@ -3,15 +3,24 @@ pub mod build;
 pub mod clean;
 pub mod compat;
 pub mod exec;
+pub mod graph;
 pub mod init;
+pub mod input;
 pub mod install;
+pub mod licenses;
 pub mod list;
 pub mod lua;
 pub mod new;
 pub mod package;
 pub mod plugin;
+pub mod policy;
 pub mod remove;
 pub mod run;
+pub mod sbom;
+pub mod scan_capabilities;
+pub mod shim;
+pub mod trust;
 pub mod update;
 pub mod verify;
+pub mod why;
 pub mod workspace;
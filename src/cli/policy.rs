@@ -0,0 +1,190 @@
+use crate::cli::audit::{audit_lockfile_report, AuditOptions};
+use clap::Subcommand;
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::di::traits::ConfigProvider;
+use depot::di::ServiceContainer;
+use depot::package::lockfile::Lockfile;
+use depot::package::manifest::PackageManifest;
+use depot::security::policy::{self, DependencyPolicy, PolicyViolation, POLICY_FILE};
+use std::env;
+use std::path::Path;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum PolicyCommands {
+    /// Check package.yaml, depot.lock and known advisories against the dependency policy
+    Check,
+}
+
+pub async fn run(command: PolicyCommands) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    let container = ServiceContainer::new()?;
+    match command {
+        PolicyCommands::Check => check_in_dir(&current_dir, container.config.as_ref()).await,
+    }
+}
+
+/// Check the project in `dir` against its policy and the configured `policy_file`
+pub async fn check_in_dir(dir: &Path, config: &dyn ConfigProvider) -> DepotResult<()> {
+    let project_root = find_project_root(dir)?;
+    let policies = DependencyPolicy::discover(&project_root, config.policy_file())?;
+    if policies.is_empty() {
+        println!(
+            "No dependency policy found ({} or policy_file in the config)",
+            POLICY_FILE
+        );
+        return Ok(());
+    }
+
+    let manifest = PackageManifest::load(&project_root)?;
+    let lockfile = Lockfile::load(&project_root)?;
+    for (path, policy) in &policies {
+        println!("Checking dependency policy {}...", path.display());
+        let mut violations = policy.check_project(&project_root, &manifest, lockfile.as_ref());
+        if let Some(lockfile) = &lockfile {
+            violations.extend(
+                advisory_violations(&project_root, &manifest, lockfile, policy, config).await?,
+            );
+        }
+        policy::enforce(path, &violations)?;
+    }
+
+    println!("✓ Dependencies comply with the policy");
+    Ok(())
+}
+
+/// Enforce the policy on a dependency before it is added to package.yaml
+pub fn enforce_on_new_dependency(
+    project_root: &Path,
+    repository: &str,
+    configured: Option<&Path>,
+) -> DepotResult<()> {
+    for (path, policy) in DependencyPolicy::discover(project_root, configured)? {
+        policy::enforce(&path, &policy.check_dependency(repository))?;
+    }
+    Ok(())
+}
+
+/// Enforce the policy on package.yaml and the existing depot.lock
+pub fn enforce_on_project(
+    project_root: &Path,
+    manifest: &PackageManifest,
+    configured: Option<&Path>,
+) -> DepotResult<()> {
+    let policies = DependencyPolicy::discover(project_root, configured)?;
+    if policies.is_empty() {
+        return Ok(());
+    }
+    let lockfile = Lockfile::load(project_root)?;
+    for (path, policy) in &policies {
+        policy::enforce(
+            path,
+            &policy.check_project(project_root, manifest, lockfile.as_ref()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Enforce the policy, advisories included, on a lockfile before it is written
+pub async fn enforce_on_lockfile(
+    project_root: &Path,
+    manifest: &PackageManifest,
+    lockfile: &Lockfile,
+    config: &dyn ConfigProvider,
+) -> DepotResult<()> {
+    for (path, policy) in DependencyPolicy::discover(project_root, config.policy_file())? {
+        let mut violations = policy.check_lockfile(project_root, manifest, lockfile);
+        violations
+            .extend(advisory_violations(project_root, manifest, lockfile, &policy, config).await?);
+        policy::enforce(&path, &violations)?;
+        println!("✓ Dependencies comply with policy {}", path.display());
+    }
+    Ok(())
+}
+
+/// Audit the locked packages when the policy limits advisory severity
+///
+/// Uses the configured local advisory database when there is one, otherwise OSV.
+async fn advisory_violations(
+    project_root: &Path,
+    manifest: &PackageManifest,
+    lockfile: &Lockfile,
+    policy: &DependencyPolicy,
+    config: &dyn ConfigProvider,
+) -> DepotResult<Vec<PolicyViolation>> {
+    if policy.max_advisory_severity.is_none() {
+        return Ok(Vec::new());
+    }
+    let options = AuditOptions {
+        offline: config.advisory_db().is_some(),
+        ..Default::default()
+    };
+    let report = audit_lockfile_report(
+        project_root,
+        Some(manifest),
+        lockfile,
+        &options,
+        config,
+        &|_| {},
+    )
+    .await?;
+    Ok(policy.check_advisories(&report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::di::mocks::MockConfigProvider;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path, policy: &str) {
+        fs::write(
+            dir.join("package.yaml"),
+            "name: t\nversion: 1.0.0\ndependencies:\n  evil/lib: ^1.0.0\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join(".depot")).unwrap();
+        fs::write(dir.join(POLICY_FILE), policy).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_violations() {
+        let temp = TempDir::new().unwrap();
+        write_project(
+            temp.path(),
+            "allowed_owners: [myorg]\nrequire_lockfile: true\n",
+        );
+
+        let err = check_in_dir(temp.path(), &MockConfigProvider::default())
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("2 dependency policy violation(s)"));
+        assert!(message.contains("evil/lib [allowed_owners]"));
+        assert!(message.contains("[require_lockfile]: depot.lock is missing"));
+    }
+
+    #[tokio::test]
+    async fn test_check_passes() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path(), "allowed_owners: [evil]\n");
+        check_in_dir(temp.path(), &MockConfigProvider::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_check_without_policy() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: t\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        check_in_dir(temp.path(), &MockConfigProvider::default())
+            .await
+            .unwrap();
+    }
+}
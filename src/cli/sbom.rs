@@ -0,0 +1,107 @@
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use depot::package::manifest::PackageManifest;
+use depot::package::sbom::{Sbom, SbomFormat};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Options for `depot sbom`
+#[derive(Debug, Clone)]
+pub struct SbomOptions {
+    /// Output format: cyclonedx-json or spdx-json
+    pub format: String,
+    /// Write the SBOM to a file instead of stdout
+    pub output: Option<String>,
+}
+
+impl Default for SbomOptions {
+    fn default() -> Self {
+        Self {
+            format: "cyclonedx-json".to_string(),
+            output: None,
+        }
+    }
+}
+
+pub fn run(options: SbomOptions) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    run_in_dir(&current_dir, options)
+}
+
+pub fn run_in_dir(dir: &Path, options: SbomOptions) -> DepotResult<()> {
+    let format = SbomFormat::parse(&options.format)?;
+    let project_root = find_project_root(dir)?;
+    let manifest = PackageManifest::load(&project_root)?;
+    let lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+
+    let rendered = Sbom::new(&manifest, &lockfile).render(format)?;
+
+    match options.output {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            eprintln!("✓ Wrote SBOM to {}", path);
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_manifest(dir: &Path) {
+        fs::write(dir.join("package.yaml"), "name: test\nversion: 1.0.0\n").unwrap();
+    }
+
+    #[test]
+    fn test_run_requires_lockfile() {
+        let temp = TempDir::new().unwrap();
+        write_manifest(temp.path());
+
+        let result = run_in_dir(temp.path(), SbomOptions::default());
+        assert!(result.unwrap_err().to_string().contains("No depot.lock"));
+    }
+
+    #[test]
+    fn test_run_invalid_format() {
+        let temp = TempDir::new().unwrap();
+        let options = SbomOptions {
+            format: "xml".to_string(),
+            output: None,
+        };
+        let result = run_in_dir(temp.path(), options);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid SBOM format"));
+    }
+
+    #[test]
+    fn test_run_writes_output_file() {
+        let temp = TempDir::new().unwrap();
+        write_manifest(temp.path());
+        Lockfile::new().save(temp.path()).unwrap();
+        let out = temp.path().join("sbom.spdx.json");
+
+        let options = SbomOptions {
+            format: "spdx-json".to_string(),
+            output: Some(out.to_string_lossy().to_string()),
+        };
+        run_in_dir(temp.path(), options).unwrap();
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    }
+}
@@ -0,0 +1,244 @@
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::lua_analysis::capabilities::{self, CapabilityUse, PackageCapabilities};
+use depot::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use serde::Serialize;
+use std::env;
+use std::path::Path;
+
+/// Options for `depot scan-capabilities`
+#[derive(Debug, Clone, Default)]
+pub struct ScanCapabilitiesOptions {
+    /// Only scan this package
+    pub package: Option<String>,
+    /// Output as JSON
+    pub json: bool,
+}
+
+pub fn run(options: ScanCapabilitiesOptions) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    run_in_dir(&current_dir, options)
+}
+
+pub fn run_in_dir(dir: &Path, options: ScanCapabilitiesOptions) -> DepotResult<()> {
+    let project_root = find_project_root(dir)?;
+    let mut lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+
+    if let Some(ref package) = options.package {
+        if lockfile.get_package(package).is_none() {
+            return Err(DepotError::Package(format!(
+                "Package '{}' not found in {}",
+                package, LOCKFILE_NAME
+            )));
+        }
+        lockfile.packages.retain(|name, _| name == package);
+    }
+
+    let results = capabilities::scan_lockfile(&project_root, &lockfile);
+
+    if options.json {
+        let report = JsonReport {
+            packages: results.iter().map(JsonPackage::from).collect(),
+        };
+        let output = serde_json::to_string_pretty(&report)
+            .map_err(|e| DepotError::Package(format!("Failed to serialize report: {}", e)))?;
+        println!("{}", output);
+    } else {
+        print!("{}", format_report(&results));
+    }
+
+    Ok(())
+}
+
+/// Render scan results grouped by package, then by capability
+fn format_report(results: &[PackageCapabilities]) -> String {
+    if results.is_empty() {
+        return "No installed packages to scan\n".to_string();
+    }
+
+    let mut output = String::new();
+    for result in results {
+        let caps = result.capabilities();
+        if caps.is_empty() {
+            output.push_str(&format!("{} {}: none\n", result.package, result.version));
+            continue;
+        }
+
+        output.push_str(&format!("{} {}\n", result.package, result.version));
+        for cap in caps {
+            output.push_str(&format!("  {} — {}\n", cap, cap.description()));
+            for use_ in result.uses.iter().filter(|u| u.capability == cap) {
+                let location = if use_.line == 0 {
+                    use_.file.display().to_string()
+                } else {
+                    format!("{}:{}", use_.file.display(), use_.line)
+                };
+                output.push_str(&format!("    {}  {}\n", location, use_.detail));
+            }
+        }
+    }
+
+    let flagged = results.iter().filter(|r| !r.uses.is_empty()).count();
+    if flagged == 0 {
+        output.push_str(&format!(
+            "\n✓ No sensitive APIs found in {} package(s)\n",
+            results.len()
+        ));
+    } else {
+        output.push_str(&format!(
+            "\n⚠️  {} of {} package(s) use sensitive APIs\n",
+            flagged,
+            results.len()
+        ));
+    }
+    output
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    packages: Vec<JsonPackage<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonPackage<'a> {
+    name: &'a str,
+    version: &'a str,
+    capabilities: Vec<&'static str>,
+    uses: &'a [CapabilityUse],
+}
+
+impl<'a> From<&'a PackageCapabilities> for JsonPackage<'a> {
+    fn from(result: &'a PackageCapabilities) -> Self {
+        Self {
+            name: &result.package,
+            version: &result.version,
+            capabilities: result.capabilities().iter().map(|c| c.as_str()).collect(),
+            uses: &result.uses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::lua_analysis::capabilities::Capability;
+    use depot::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path) {
+        fs::write(dir.join("package.yaml"), "name: t\nversion: 1.0.0\n").unwrap();
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/lib".to_string(),
+            LockedPackage {
+                version: "1.2.0".to_string(),
+                repository: "owner/lib".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.2.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: "https://github.com/owner/lib/archive/v1.2.0.tar.gz".to_string(),
+                checksum: "blake3:00".to_string(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: None,
+            },
+        );
+        lockfile.save(dir).unwrap();
+
+        let pkg_dir = dir.join("lua_modules/owner/lib");
+        fs::create_dir_all(&pkg_dir).unwrap();
+        fs::write(pkg_dir.join("init.lua"), "return {}\n").unwrap();
+    }
+
+    #[test]
+    fn test_scan_requires_lockfile() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: t\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        let err = run_in_dir(temp.path(), ScanCapabilitiesOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("No depot.lock"));
+    }
+
+    #[test]
+    fn test_scan_unknown_package() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path());
+        let options = ScanCapabilitiesOptions {
+            package: Some("owner/other".to_string()),
+            json: false,
+        };
+        let err = run_in_dir(temp.path(), options).unwrap_err();
+        assert!(err.to_string().contains("'owner/other' not found"));
+    }
+
+    #[test]
+    fn test_scan_json_output() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path());
+        let options = ScanCapabilitiesOptions {
+            package: Some("owner/lib".to_string()),
+            json: true,
+        };
+        assert!(run_in_dir(temp.path(), options).is_ok());
+    }
+
+    #[test]
+    fn test_format_report_groups_by_capability() {
+        let results = vec![
+            PackageCapabilities {
+                package: "owner/a".to_string(),
+                version: "1.0.0".to_string(),
+                uses: vec![
+                    CapabilityUse {
+                        capability: Capability::Network,
+                        file: PathBuf::from("src/http.lua"),
+                        line: 1,
+                        detail: "require(\"socket.http\")".to_string(),
+                    },
+                    CapabilityUse {
+                        capability: Capability::OsExecute,
+                        file: PathBuf::from("src/init.lua"),
+                        line: 12,
+                        detail: "os.execute".to_string(),
+                    },
+                ],
+            },
+            PackageCapabilities {
+                package: "owner/b".to_string(),
+                version: "0.3.0".to_string(),
+                uses: Vec::new(),
+            },
+        ];
+
+        let report = format_report(&results);
+        assert!(report.contains(
+            "owner/a 1.0.0\n  os.execute — runs shell commands\n    src/init.lua:12  os.execute\n"
+        ));
+        assert!(report.contains("  network — requires network modules\n"));
+        assert!(report.contains("owner/b 0.3.0: none\n"));
+        assert!(report.contains("1 of 2 package(s) use sensitive APIs"));
+
+        let json = serde_json::to_value(JsonPackage::from(&results[0])).unwrap();
+        assert_eq!(
+            json["capabilities"],
+            serde_json::json!(["os.execute", "network"])
+        );
+        assert_eq!(json["uses"][0]["capability"], "network");
+    }
+}
@@ -0,0 +1,39 @@
+use depot::core::path::depot_home;
+use depot::core::{DepotError, DepotResult};
+use depot::lua_manager::shim;
+use std::env;
+use std::ffi::OsString;
+
+/// Run the managed interpreter depot was invoked as (`lua`, `luac` or `luajit`)
+///
+/// All arguments are passed through untouched. On Unix the interpreter
+/// replaces the depot process; elsewhere its exit code is forwarded.
+pub fn run(binary: &str) -> DepotResult<()> {
+    let args: Vec<OsString> = env::args_os().skip(1).collect();
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+
+    let target = shim::resolve(&depot_home()?, binary, &current_dir)?;
+    let mut command = shim::command(&target, binary, &current_dir)?;
+    command.args(&args);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let error = command.arg0(binary).exec();
+        Err(DepotError::Package(format!(
+            "Failed to run {}: {}",
+            target.path.display(),
+            error
+        )))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let status = command.status()?;
+        match status.code() {
+            Some(0) => Ok(()),
+            code => Err(DepotError::SubprocessExit(code.unwrap_or(1))),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use clap::Subcommand;
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::di::traits::GitHubProvider;
+use depot::di::ServiceContainer;
+use depot::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use depot::security::trust;
+use std::env;
+use std::path::Path;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TrustCommands {
+    /// Accept a package's current GitHub repository ID and pin it in depot.lock
+    Update {
+        /// Package name (owner/repo)
+        package: String,
+    },
+}
+
+pub async fn run(command: TrustCommands) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    let container = ServiceContainer::new()?;
+    match command {
+        TrustCommands::Update { package } => {
+            update_in_dir(&current_dir, container.github.as_ref(), &package).await
+        }
+    }
+}
+
+/// Re-pin `package` in the project's lockfile to its repository's current ID
+pub async fn update_in_dir(
+    dir: &Path,
+    github: &dyn GitHubProvider,
+    package: &str,
+) -> DepotResult<()> {
+    let project_root = find_project_root(dir)?;
+    let mut lockfile = Lockfile::load(&project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            LOCKFILE_NAME
+        ))
+    })?;
+
+    let (previous, current) = trust::update_pin(github, &mut lockfile, package).await?;
+    lockfile.save(&project_root)?;
+
+    match previous {
+        Some(previous) if previous != current => println!(
+            "✓ Trusted {}: repository ID {} → {}",
+            package, previous, current
+        ),
+        Some(_) => println!(
+            "✓ {} is already pinned to repository ID {}",
+            package, current
+        ),
+        None => println!("✓ Pinned {} to repository ID {}", package, current),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::di::mocks::MockGitHubProvider;
+    use depot::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_project(dir: &Path, repository_id: Option<u64>) {
+        fs::write(dir.join("package.yaml"), "name: t\nversion: 1.0.0\n").unwrap();
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/lib".to_string(),
+            LockedPackage {
+                version: "1.0.0".to_string(),
+                repository: "owner/lib".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.0.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: "https://github.com/owner/lib/archive/v1.0.0.tar.gz".to_string(),
+                checksum: "blake3:00".to_string(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id,
+            },
+        );
+        lockfile.save(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_repins_package() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path(), Some(1));
+        let github = MockGitHubProvider::new();
+        github.set_repo_id("owner", "lib", 2);
+
+        update_in_dir(temp.path(), &github, "owner/lib")
+            .await
+            .unwrap();
+
+        let lockfile = Lockfile::load(temp.path()).unwrap().unwrap();
+        assert_eq!(
+            lockfile.get_package("owner/lib").unwrap().repository_id,
+            Some(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_unknown_package() {
+        let temp = TempDir::new().unwrap();
+        write_project(temp.path(), None);
+        let github = MockGitHubProvider::new();
+
+        let err = update_in_dir(temp.path(), &github, "owner/other")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'owner/other' not found"));
+    }
+}
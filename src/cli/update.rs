@@ -1,26 +1,58 @@
+use crate::cli::input::{DialoguerInput, UserInput};
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
-use depot::di::ServiceContainer;
+use depot::di::{GitHubProvider, ServiceContainer};
 use depot::package::installer::PackageInstaller;
 use depot::package::lockfile::Lockfile;
 use depot::package::lockfile_builder::LockfileBuilder;
 use depot::package::manifest::PackageManifest;
+use depot::package::update_diff::{PackageChange, UpdateDiff};
+use depot::package::upgrade::{
+    find_upgrades, notes_excerpt, rewrite_constraint, UpgradeCandidate, UpgradeChoice,
+};
 use depot::path_setup::PathSetup;
 use depot::workspace::{Workspace, WorkspaceFilter};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Offer a fuzzy filter before the per-package prompts above this many candidates
+const FUZZY_FILTER_THRESHOLD: usize = 8;
+
+pub struct UpdateOptions {
+    pub package: Option<String>,
+    pub filter: Vec<String>,
+    pub interactive: bool,
+    pub no_notes: bool,
+    pub json: Option<String>,
+    pub ignore_cooldown: bool,
+}
 
-pub async fn run(package: Option<String>, filter: Vec<String>) -> DepotResult<()> {
+pub async fn run(options: UpdateOptions) -> DepotResult<()> {
     let current_dir = env::current_dir()
         .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
 
     let project_root = find_project_root(&current_dir)?;
+    let package = options.package.clone();
+    let filter = &options.filter;
+
+    if options.interactive {
+        if !filter.is_empty() || package.is_some() {
+            return Err(DepotError::Package(
+                "--interactive cannot be combined with a package name or --filter".to_string(),
+            ));
+        }
+        return run_interactive(&project_root, &options).await;
+    }
 
     // Check if we're in a workspace and filtering is requested
     if !filter.is_empty() {
         if Workspace::is_workspace(&project_root) {
             let workspace = Workspace::load(&project_root)?;
-            return update_workspace_filtered(&workspace, &filter, package).await;
+            return update_workspace_filtered(&workspace, filter, package, &options).await;
         } else {
             return Err(DepotError::Package(
                 "--filter can only be used in workspace mode".to_string(),
@@ -34,10 +66,10 @@ pub async fn run(package: Option<String>, filter: Vec<String>) -> DepotResult<()
 
     if let Some(ref pkg_name) = package {
         // Update specific package
-        update_single_package(&project_root, &manifest, &lockfile, pkg_name).await?;
+        update_single_package(&project_root, &manifest, &lockfile, pkg_name, &options).await?;
     } else {
         // Update all packages
-        update_all_packages(&project_root, &manifest, &lockfile).await?;
+        update_all_packages(&project_root, &manifest, &lockfile, &options).await?;
     }
 
     Ok(())
@@ -48,6 +80,7 @@ async fn update_single_package(
     manifest: &PackageManifest,
     lockfile: &Option<Lockfile>,
     package_name: &str,
+    options: &UpdateOptions,
 ) -> DepotResult<()> {
     // Check if package exists in dependencies
     let _version_constraint = manifest
@@ -76,13 +109,16 @@ async fn update_single_package(
     }
 
     // Initialize installer
-    let container = ServiceContainer::new()?;
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(manifest), options.ignore_cooldown)?;
     let installer = PackageInstaller::new(
         project_root,
         container.cache.clone(),
         container.github.clone(),
         container.config.github_fallback_chain().to_vec(),
-    )?;
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
     installer.init()?;
 
     // Remove old version if it exists
@@ -112,29 +148,41 @@ async fn update_single_package(
         container.config.github_fallback_chain().to_vec(),
     );
     let new_lockfile = builder.build(manifest).await?;
+    crate::cli::policy::enforce_on_lockfile(
+        project_root,
+        manifest,
+        &new_lockfile,
+        container.config.as_ref(),
+    )
+    .await?;
     new_lockfile.save(project_root)?;
 
     // Install loader
     PathSetup::install_loader(project_root)?;
 
-    Ok(())
+    let mut diff = UpdateDiff::from_lockfiles(lockfile, &new_lockfile);
+    report_diff(&mut diff, container.github.as_ref(), options).await
 }
 
 async fn update_all_packages(
     project_root: &std::path::Path,
     manifest: &PackageManifest,
     lockfile: &Option<Lockfile>,
+    options: &UpdateOptions,
 ) -> DepotResult<()> {
     println!("Updating all packages...");
 
     // Initialize installer
-    let container = ServiceContainer::new()?;
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(manifest), options.ignore_cooldown)?;
     let installer = PackageInstaller::new(
         project_root,
         container.cache.clone(),
         container.github.clone(),
         container.config.github_fallback_chain().to_vec(),
-    )?;
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
     installer.init()?;
 
     let mut updated_count = 0;
@@ -179,18 +227,297 @@ async fn update_all_packages(
         container.config.github_fallback_chain().to_vec(),
     );
     let new_lockfile = builder.build(manifest).await?;
+    crate::cli::policy::enforce_on_lockfile(
+        project_root,
+        manifest,
+        &new_lockfile,
+        container.config.as_ref(),
+    )
+    .await?;
     new_lockfile.save(project_root)?;
 
     // Install loader
     PathSetup::install_loader(project_root)?;
 
+    let mut diff = UpdateDiff::from_lockfiles(lockfile, &new_lockfile);
+    report_diff(&mut diff, container.github.as_ref(), options).await
+}
+
+/// Interactive upgrade: pick a target version per outdated package
+async fn run_interactive(project_root: &Path, options: &UpdateOptions) -> DepotResult<()> {
+    let mut manifest = PackageManifest::load(project_root)?;
+    let lockfile = Lockfile::load(project_root)?;
+
+    if lockfile.is_none() {
+        return Err(DepotError::Package(format!(
+            "No {} found. Run 'depot install' first.",
+            depot::package::lockfile::LOCKFILE_NAME
+        )));
+    }
+
+    let container =
+        ServiceContainer::new()?.with_release_cooldown(Some(&manifest), options.ignore_cooldown)?;
+
+    println!("Checking for newer releases...");
+    let candidates = find_upgrades(container.github.as_ref(), &manifest, lockfile.as_ref()).await;
+
+    if candidates.is_empty() {
+        println!("✓ All dependencies are up to date");
+        return Ok(());
+    }
+
+    let selections = select_upgrades(&candidates, &DialoguerInput)?;
+    let selections = apply_selections(&mut manifest, &candidates, &selections);
+    if selections.is_empty() {
+        println!("No packages selected for upgrade");
+        return Ok(());
+    }
+
+    let mut diff = build_diff(&candidates, &selections);
+    diff.calculate_file_changes(project_root);
+    if !options.no_notes {
+        diff.fetch_release_notes(container.github.as_ref()).await;
+    }
+    diff.display();
+    println!();
+
+    if !DialoguerInput.prompt_confirm("Apply these upgrades?", true)? {
+        println!("Upgrade cancelled");
+        return Ok(());
+    }
+
+    let installer = PackageInstaller::new(
+        project_root,
+        container.cache.clone(),
+        container.github.clone(),
+        container.config.github_fallback_chain().to_vec(),
+    )?
+    .with_archive_limits(container.config.archive_limits())
+    .with_signature_policy(container.config.signature_policy().clone());
+    installer.init()?;
+
+    for (index, _) in &selections {
+        let candidate = &candidates[*index];
+        let constraint = manifest
+            .dependencies
+            .get(&candidate.name)
+            .or_else(|| manifest.dev_dependencies.get(&candidate.name))
+            .cloned();
+
+        println!("  Updating {}...", candidate.name);
+        if installer.is_installed(&candidate.name) {
+            installer.remove_package(&candidate.name)?;
+        }
+        installer
+            .install_package(&candidate.name, constraint.as_deref())
+            .await?;
+    }
+
+    // Build the new lockfile before writing anything so package.yaml and
+    // depot.lock are only rewritten together
+    let builder = LockfileBuilder::new(
+        project_root,
+        container.cache.clone(),
+        container.github.clone(),
+        container.config.github_fallback_chain().to_vec(),
+    );
+    let new_lockfile = builder.build(&manifest).await?;
+    crate::cli::policy::enforce_on_lockfile(
+        project_root,
+        &manifest,
+        &new_lockfile,
+        container.config.as_ref(),
+    )
+    .await?;
+    manifest.save(project_root)?;
+    new_lockfile.save(project_root)?;
+
+    PathSetup::install_loader(project_root)?;
+
+    println!("\n✓ Upgraded {} package(s)", selections.len());
+
+    if let Some(ref path) = options.json {
+        write_json(&diff, path)?;
+    }
+
+    Ok(())
+}
+
+/// Print the update summary, with upstream release notes unless disabled
+async fn report_diff(
+    diff: &mut UpdateDiff,
+    github: &dyn GitHubProvider,
+    options: &UpdateOptions,
+) -> DepotResult<()> {
+    if !options.no_notes {
+        diff.fetch_release_notes(github).await;
+    }
+    diff.display();
+
+    if let Some(ref path) = options.json {
+        write_json(diff, path)?;
+    }
+
+    Ok(())
+}
+
+fn write_json(diff: &UpdateDiff, path: &str) -> DepotResult<()> {
+    fs::write(path, diff.to_json()? + "\n")?;
+    println!("✓ Wrote update summary to {}", path);
     Ok(())
 }
 
+/// Ask which version each candidate should move to
+///
+/// Returns `(candidate index, choice)` pairs for every package that is not
+/// staying on its current version.
+fn select_upgrades(
+    candidates: &[UpgradeCandidate],
+    input: &dyn UserInput,
+) -> DepotResult<Vec<(usize, UpgradeChoice)>> {
+    let mut indices: Vec<usize> = (0..candidates.len()).collect();
+
+    if candidates.len() > FUZZY_FILTER_THRESHOLD {
+        let query = input.prompt_string("Filter packages (leave empty for all)", "")?;
+        indices = fuzzy_filter(candidates, query.trim());
+        if indices.is_empty() {
+            println!("No outdated packages match '{}'", query.trim());
+        }
+    }
+
+    let mut selections = Vec::new();
+
+    for index in indices {
+        let candidate = &candidates[index];
+        let choices = candidate.choices();
+        let dev_label = if candidate.dev { " (dev)" } else { "" };
+
+        println!(
+            "\n📦 {}{} {} (locked {})",
+            candidate.name, dev_label, candidate.constraint, candidate.current
+        );
+        if let Some(body) = candidate.latest().and_then(|r| r.body.as_deref()) {
+            let excerpt = notes_excerpt(body);
+            if !excerpt.is_empty() {
+                for line in excerpt.lines() {
+                    println!("    │ {}", line);
+                }
+            }
+        }
+
+        let items: Vec<String> = choices
+            .iter()
+            .map(|choice| match candidate.target(*choice) {
+                Some(release) => format!("{} → {}", choice.as_str(), release.version),
+                None => format!("{} ({})", choice.as_str(), candidate.current),
+            })
+            .collect();
+
+        let selected = input.prompt_select(&candidate.name, &items, 0)?;
+        match choices.get(selected) {
+            Some(UpgradeChoice::Stay) | None => {}
+            Some(choice) => selections.push((index, *choice)),
+        }
+    }
+
+    Ok(selections)
+}
+
+/// Indices of candidates whose name fuzzily matches `query`, best match first
+fn fuzzy_filter(candidates: &[UpgradeCandidate], query: &str) -> Vec<usize> {
+    if query.is_empty() {
+        return (0..candidates.len()).collect();
+    }
+
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| matcher.fuzzy_match(&c.name, query).map(|score| (score, i)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Summarise the selected upgrades in the same form as a regular update
+fn build_diff(
+    candidates: &[UpgradeCandidate],
+    selections: &[(usize, UpgradeChoice)],
+) -> UpdateDiff {
+    let mut diff = UpdateDiff::new();
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        let name = if candidate.dev {
+            format!("{} (dev)", candidate.name)
+        } else {
+            candidate.name.clone()
+        };
+
+        let target = selections
+            .iter()
+            .find(|(i, _)| *i == index)
+            .and_then(|(_, choice)| candidate.target(*choice));
+
+        match target {
+            Some(release) => diff.package_changes.push(PackageChange::Updated {
+                name,
+                current_version: candidate.current.clone(),
+                new_version: release.version.clone(),
+            }),
+            None => diff.package_changes.push(PackageChange::UpToDate {
+                name,
+                version: candidate.current.clone(),
+            }),
+        }
+    }
+
+    diff
+}
+
+/// Rewrite manifest constraints for the selected upgrades
+///
+/// Returns the selections that were applied; those whose constraint cannot
+/// be rewritten are reported and left out.
+fn apply_selections(
+    manifest: &mut PackageManifest,
+    candidates: &[UpgradeCandidate],
+    selections: &[(usize, UpgradeChoice)],
+) -> Vec<(usize, UpgradeChoice)> {
+    let mut applied = Vec::new();
+    for (index, choice) in selections {
+        let candidate = &candidates[*index];
+        let Some(release) = candidate.target(*choice) else {
+            continue;
+        };
+
+        let deps = if candidate.dev {
+            &mut manifest.dev_dependencies
+        } else {
+            &mut manifest.dependencies
+        };
+        let Some(constraint) = deps.get_mut(&candidate.name) else {
+            continue;
+        };
+        match rewrite_constraint(constraint, release) {
+            Some(rewritten) => {
+                *constraint = rewritten;
+                applied.push((*index, *choice));
+            }
+            None => eprintln!(
+                "⚠ Not upgrading {} to {}: '{}' cannot be rewritten without losing its bounds; \
+                 edit package.yaml by hand",
+                candidate.name, release.version, constraint
+            ),
+        }
+    }
+    applied
+}
+
 async fn update_workspace_filtered(
     workspace: &Workspace,
     filter_patterns: &[String],
     package: Option<String>,
+    options: &UpdateOptions,
 ) -> DepotResult<()> {
     // Create filter
     let filter = WorkspaceFilter::new(filter_patterns.to_vec());
@@ -224,10 +551,10 @@ async fn update_workspace_filtered(
 
         if let Some(ref pkg_name) = package {
             // Update specific package in this workspace package
-            update_single_package(&pkg_dir, &manifest, &lockfile, pkg_name).await?;
+            update_single_package(&pkg_dir, &manifest, &lockfile, pkg_name, options).await?;
         } else {
             // Update all packages in this workspace package
-            update_all_packages(&pkg_dir, &manifest, &lockfile).await?;
+            update_all_packages(&pkg_dir, &manifest, &lockfile, options).await?;
         }
 
         println!("✓ Updated dependencies for {}\n", pkg.name);
@@ -237,3 +564,125 @@ async fn update_workspace_filtered(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::core::version::Version;
+    use depot::package::upgrade::AvailableRelease;
+
+    struct MockInput {
+        filter: String,
+        selects: HashMap<String, usize>,
+    }
+
+    impl UserInput for MockInput {
+        fn prompt_string(&self, _prompt: &str, _default: &str) -> DepotResult<String> {
+            Ok(self.filter.clone())
+        }
+
+        fn prompt_confirm(&self, _prompt: &str, default: bool) -> DepotResult<bool> {
+            Ok(default)
+        }
+
+        fn prompt_select(
+            &self,
+            prompt: &str,
+            _items: &[String],
+            default: usize,
+        ) -> DepotResult<usize> {
+            Ok(*self.selects.get(prompt).unwrap_or(&default))
+        }
+
+        fn prompt_multiselect(&self, _prompt: &str, _items: &[String]) -> DepotResult<Vec<usize>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn candidate(name: &str, dev: bool, versions: &[(u64, u64, u64)]) -> UpgradeCandidate {
+        UpgradeCandidate {
+            name: name.to_string(),
+            dev,
+            constraint: "^1.0.0".to_string(),
+            current: Version::new(1, 0, 0),
+            releases: versions
+                .iter()
+                .map(|&(major, minor, patch)| AvailableRelease {
+                    version: Version::new(major, minor, patch),
+                    tag: format!("v{}.{}.{}", major, minor, patch),
+                    body: Some("- fixed things".to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_select_upgrades_and_apply() {
+        let candidates = vec![
+            candidate("owner/json", false, &[(2, 0, 0), (1, 1, 0), (1, 0, 1)]),
+            candidate("owner/busted", true, &[(1, 0, 2)]),
+            candidate("owner/log", false, &[(1, 0, 5)]),
+        ];
+
+        // choices for owner/json: stay, patch, minor, latest
+        let input = MockInput {
+            filter: String::new(),
+            selects: HashMap::from([
+                ("owner/json".to_string(), 2),
+                ("owner/busted".to_string(), 1),
+            ]),
+        };
+
+        let selections = select_upgrades(&candidates, &input).unwrap();
+        assert_eq!(
+            selections,
+            vec![(0, UpgradeChoice::Minor), (1, UpgradeChoice::Patch)]
+        );
+
+        let diff = build_diff(&candidates, &selections);
+        assert!(diff.has_changes());
+        assert_eq!(diff.package_changes.len(), 3);
+
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/json".to_string(), "^1.0.0".to_string());
+        manifest
+            .dependencies
+            .insert("owner/log".to_string(), "~1.0.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("owner/busted".to_string(), "1.0.0".to_string());
+
+        let applied = apply_selections(&mut manifest, &candidates, &selections);
+        assert_eq!(applied, selections);
+        assert_eq!(manifest.dependencies["owner/json"], "v1.1.0");
+        assert_eq!(manifest.dependencies["owner/log"], "~1.0.0");
+        assert_eq!(manifest.dev_dependencies["owner/busted"], "v1.0.2");
+
+        // A constraint that would lose its bounds is left out of the upgrade
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/json".to_string(), ">=1.0.0, <1.1.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("owner/busted".to_string(), "v1.0.0".to_string());
+        let applied = apply_selections(&mut manifest, &candidates, &selections);
+        assert_eq!(applied, vec![(1, UpgradeChoice::Patch)]);
+        assert_eq!(manifest.dependencies["owner/json"], ">=1.0.0, <1.1.0");
+        assert_eq!(manifest.dev_dependencies["owner/busted"], "v1.0.2");
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let candidates: Vec<UpgradeCandidate> = ["owner/json", "owner/luasocket", "other/lpeg"]
+            .iter()
+            .map(|name| candidate(name, false, &[(1, 0, 1)]))
+            .collect();
+
+        assert_eq!(fuzzy_filter(&candidates, ""), vec![0, 1, 2]);
+        assert_eq!(fuzzy_filter(&candidates, "sock"), vec![1]);
+        assert!(fuzzy_filter(&candidates, "zzz").is_empty());
+    }
+}
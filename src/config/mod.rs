@@ -27,10 +27,18 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lua_binary_source_url: Option<String>,
 
-    /// Per-version Lua binary source URLs
+    /// Per-version Lua binary source URLs, optionally with SHA-256 hashes per binary
     /// Example: { "5.4.8": "https://custom-source.com/binaries" }
+    /// Example: { "5.4.8": { url: "https://...", sha256: { lua54: "<hash>", luac54: "<hash>" } } }
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub lua_binary_sources: Option<std::collections::HashMap<String, String>>,
+    pub lua_binary_sources: Option<std::collections::HashMap<String, LuaBinarySource>>,
+
+    /// Refuse Lua source releases that have no configured or pinned checksum (default: true)
+    /// When disabled, such sources are built with a warning and their hash is
+    /// recorded so the cached copy can still be re-verified. Binaries without a
+    /// known checksum are never installed
+    #[serde(default = "default_true")]
+    pub strict_lua_checksums: bool,
 
     /// Resolution strategy for selecting package versions
     /// - "highest": Select the highest compatible version (default)
@@ -73,6 +81,78 @@ pub struct Config {
     /// When set, global packages will be installed to this directory instead of the system default
     #[serde(skip_serializing_if = "Option::is_none")]
     pub global_install_path: Option<std::path::PathBuf>,
+
+    /// Minimum age of a release or tag before it can be resolved (e.g. "7d", "12h")
+    /// Can be overridden per project with `min_release_age` in package.yaml
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_release_age: Option<String>,
+
+    /// Local advisory database used by `depot audit --offline`
+    /// A directory of OSV JSON advisories or a zip export (e.g. an OSV ecosystem dump)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advisory_db: Option<std::path::PathBuf>,
+
+    /// Hours that cached OSV results stay fresh before `depot audit` queries OSV again
+    #[serde(default = "default_osv_cache_ttl_hours")]
+    pub osv_cache_ttl_hours: i64,
+
+    /// Default license policy for dependencies
+    /// A `license_policy` in package.yaml replaces it for that project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license_policy: Option<crate::package::manifest::LicensePolicy>,
+
+    /// Limits on the size and entry count of extracted package archives
+    #[serde(default)]
+    pub archive_limits: crate::package::extractor::ArchiveLimits,
+
+    /// Publisher keys trusted per owner (or owner/repo) for package and prebuilt
+    /// binary signatures, and whether unsigned artifacts are refused
+    #[serde(default)]
+    pub signatures: crate::security::signature::SignaturePolicy,
+
+    /// What `depot install` does with a suspicious new dependency when it
+    /// cannot ask for confirmation: warn (default), deny or allow
+    #[serde(default)]
+    pub suspicious_packages: crate::security::suspicious::SuspiciousPackagePolicy,
+
+    /// Organisation dependency policy applied when a project has no
+    /// `.depot/policy.yaml` of its own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_file: Option<std::path::PathBuf>,
+}
+
+/// Source of the binaries for one Lua version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LuaBinarySource {
+    /// Download URL prefix
+    Url(String),
+    /// Download URL prefix (default source when omitted) and expected hashes
+    Detailed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        /// SHA-256 per binary file name (e.g. `lua54`, `luac54.exe`)
+        #[serde(default)]
+        sha256: std::collections::HashMap<String, String>,
+    },
+}
+
+impl LuaBinarySource {
+    /// The URL prefix, if this source overrides it
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::Url(url) => Some(url),
+            Self::Detailed { url, .. } => url.as_deref(),
+        }
+    }
+
+    /// The configured SHA-256 for a binary file name
+    pub fn sha256(&self, asset: &str) -> Option<&str> {
+        match self {
+            Self::Url(_) => None,
+            Self::Detailed { sha256, .. } => sha256.get(asset).map(|h| h.as_str()),
+        }
+    }
 }
 
 /// GitHub configuration
@@ -107,6 +187,10 @@ fn default_checksum_algorithm() -> String {
     "blake3".to_string()
 }
 
+fn default_osv_cache_ttl_hours() -> i64 {
+    crate::security::osv_cache::DEFAULT_OSV_CACHE_TTL_HOURS
+}
+
 fn default_resolution_strategy() -> String {
     "highest".to_string()
 }
@@ -135,6 +219,7 @@ impl Default for Config {
             show_diffs_on_update: true,
             lua_binary_source_url: None,
             lua_binary_sources: None,
+            strict_lua_checksums: true,
             resolution_strategy: default_resolution_strategy(),
             checksum_algorithm: default_checksum_algorithm(),
             supported_lua_versions: None,
@@ -142,6 +227,14 @@ impl Default for Config {
             github: GitHubConfig::default(),
             strict_native_code: true,
             global_install_path: None,
+            min_release_age: None,
+            advisory_db: None,
+            osv_cache_ttl_hours: default_osv_cache_ttl_hours(),
+            license_policy: None,
+            archive_limits: Default::default(),
+            signatures: Default::default(),
+            suspicious_packages: Default::default(),
+            policy_file: None,
         }
     }
 }
@@ -254,6 +347,38 @@ impl ConfigProvider for Config {
     fn strict_native_code(&self) -> bool {
         self.strict_native_code
     }
+
+    fn min_release_age(&self) -> Option<&str> {
+        self.min_release_age.as_deref()
+    }
+
+    fn license_policy(&self) -> Option<&crate::package::manifest::LicensePolicy> {
+        self.license_policy.as_ref()
+    }
+
+    fn archive_limits(&self) -> crate::package::extractor::ArchiveLimits {
+        self.archive_limits
+    }
+
+    fn signature_policy(&self) -> &crate::security::signature::SignaturePolicy {
+        &self.signatures
+    }
+
+    fn suspicious_package_policy(&self) -> crate::security::suspicious::SuspiciousPackagePolicy {
+        self.suspicious_packages
+    }
+
+    fn policy_file(&self) -> Option<&std::path::Path> {
+        self.policy_file.as_deref()
+    }
+
+    fn advisory_db(&self) -> Option<&std::path::Path> {
+        self.advisory_db.as_deref()
+    }
+
+    fn osv_cache_ttl_hours(&self) -> i64 {
+        self.osv_cache_ttl_hours
+    }
 }
 
 #[cfg(test)]
@@ -354,6 +479,10 @@ cache_dir: /test/cache
         assert!(config.strict_native_code); // default is true
         assert_eq!(config.github.api_url, "https://api.github.com");
         assert_eq!(config.cache_dir, Some("/test/cache".to_string()));
+        assert_eq!(config.osv_cache_ttl_hours, 24);
+
+        let config: Config = serde_yaml::from_str("osv_cache_ttl_hours: 2\n").unwrap();
+        assert_eq!(config.osv_cache_ttl_hours(), 2);
     }
 
     #[test]
@@ -361,7 +490,7 @@ cache_dir: /test/cache
         let mut sources = std::collections::HashMap::new();
         sources.insert(
             "5.4.8".to_string(),
-            "https://custom-source.com/5.4.8".to_string(),
+            LuaBinarySource::Url("https://custom-source.com/5.4.8".to_string()),
         );
         let config = Config {
             lua_binary_sources: Some(sources),
@@ -372,6 +501,28 @@ cache_dir: /test/cache
         assert!(yaml.contains("5.4.8"));
     }
 
+    #[test]
+    fn test_config_lua_binary_sources_with_hashes() {
+        let yaml = r#"
+lua_binary_sources:
+  "5.4.8": https://mirror.example.com/lua
+  "5.4.7":
+    sha256:
+      lua54: abc123
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let sources = config.lua_binary_sources.unwrap();
+
+        assert_eq!(
+            sources["5.4.8"].url(),
+            Some("https://mirror.example.com/lua")
+        );
+        assert_eq!(sources["5.4.8"].sha256("lua54"), None);
+        assert_eq!(sources["5.4.7"].url(), None);
+        assert_eq!(sources["5.4.7"].sha256("lua54"), Some("abc123"));
+        assert!(config.strict_lua_checksums); // default is true
+    }
+
     #[test]
     fn test_config_with_lua_binary_source_url() {
         let config = Config {
@@ -592,7 +743,10 @@ checksum_algorithm: sha256
     #[test]
     fn test_config_serialization_with_all_fields() {
         let mut lua_sources = std::collections::HashMap::new();
-        lua_sources.insert("5.4".to_string(), "https://example.com".to_string());
+        lua_sources.insert(
+            "5.4".to_string(),
+            LuaBinarySource::Url("https://example.com".to_string()),
+        );
 
         let config = Config {
             cache_dir: Some("/cache".to_string()),
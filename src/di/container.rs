@@ -4,7 +4,8 @@ use super::traits::{CacheProvider, ConfigProvider, GitHubProvider};
 use crate::cache::Cache;
 use crate::config::Config;
 use crate::core::DepotResult;
-use crate::github::GitHubClient;
+use crate::github::{CooldownGitHubProvider, CooldownPolicy, GitHubClient};
+use crate::package::manifest::PackageManifest;
 use std::sync::Arc;
 
 /// Service container for dependency injection
@@ -113,6 +114,26 @@ impl ServiceContainer {
         }
     }
 
+    /// Apply the `min_release_age` cooldown to version resolution
+    ///
+    /// Combines the config setting with the project manifest. Returns the
+    /// container unchanged when no cooldown applies or `ignore` is set.
+    pub fn with_release_cooldown(
+        mut self,
+        manifest: Option<&PackageManifest>,
+        ignore: bool,
+    ) -> DepotResult<Self> {
+        if ignore {
+            return Ok(self);
+        }
+
+        let policy = CooldownPolicy::from_settings(self.config.min_release_age(), manifest)?;
+        if policy.is_active() {
+            self.github = Arc::new(CooldownGitHubProvider::new(self.github, policy));
+        }
+        Ok(self)
+    }
+
     /// Get the configuration provider
     pub fn config(&self) -> &dyn ConfigProvider {
         self.config.as_ref()
@@ -2,7 +2,13 @@
 
 use super::traits::{CacheProvider, ConfigProvider, GitHubProvider};
 use crate::core::{DepotError, DepotResult};
-use crate::github::{GitHubRelease, GitHubTag, ResolvedVersion};
+use crate::github::{
+    GitHubCommit, GitHubComparison, GitHubRelease, GitHubRepo, GitHubTag, ResolvedVersion,
+};
+use crate::package::extractor::ArchiveLimits;
+use crate::package::manifest::LicensePolicy;
+use crate::security::signature::SignaturePolicy;
+use crate::security::suspicious::SuspiciousPackagePolicy;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -37,6 +43,14 @@ pub struct MockConfigProvider {
     pub github_token: Option<String>,
     pub github_fallback_chain: Vec<String>,
     pub strict_native_code: bool,
+    pub min_release_age: Option<String>,
+    pub license_policy: Option<LicensePolicy>,
+    pub archive_limits: ArchiveLimits,
+    pub signature_policy: SignaturePolicy,
+    pub suspicious_package_policy: SuspiciousPackagePolicy,
+    pub policy_file: Option<PathBuf>,
+    pub advisory_db: Option<PathBuf>,
+    pub osv_cache_ttl_hours: i64,
 }
 
 impl Default for MockConfigProvider {
@@ -58,6 +72,14 @@ impl Default for MockConfigProvider {
                 "branch".to_string(),
             ],
             strict_native_code: true,
+            min_release_age: None,
+            license_policy: None,
+            archive_limits: ArchiveLimits::default(),
+            signature_policy: SignaturePolicy::default(),
+            suspicious_package_policy: SuspiciousPackagePolicy::default(),
+            policy_file: None,
+            advisory_db: None,
+            osv_cache_ttl_hours: 24,
         }
     }
 }
@@ -110,6 +132,38 @@ impl ConfigProvider for MockConfigProvider {
     fn strict_native_code(&self) -> bool {
         self.strict_native_code
     }
+
+    fn min_release_age(&self) -> Option<&str> {
+        self.min_release_age.as_deref()
+    }
+
+    fn license_policy(&self) -> Option<&LicensePolicy> {
+        self.license_policy.as_ref()
+    }
+
+    fn archive_limits(&self) -> ArchiveLimits {
+        self.archive_limits
+    }
+
+    fn signature_policy(&self) -> &SignaturePolicy {
+        &self.signature_policy
+    }
+
+    fn suspicious_package_policy(&self) -> SuspiciousPackagePolicy {
+        self.suspicious_package_policy
+    }
+
+    fn policy_file(&self) -> Option<&Path> {
+        self.policy_file.as_deref()
+    }
+
+    fn advisory_db(&self) -> Option<&Path> {
+        self.advisory_db.as_deref()
+    }
+
+    fn osv_cache_ttl_hours(&self) -> i64 {
+        self.osv_cache_ttl_hours
+    }
 }
 
 /// Mock cache provider for testing
@@ -361,8 +415,12 @@ pub struct MockGitHubProvider {
     releases: Arc<Mutex<HashMap<String, Vec<GitHubRelease>>>>,
     tags: Arc<Mutex<HashMap<String, Vec<GitHubTag>>>>,
     default_branches: Arc<Mutex<HashMap<String, String>>>,
+    repos: Arc<Mutex<HashMap<String, GitHubRepo>>>,
     tarballs: Arc<Mutex<HashMap<String, PathBuf>>>,
+    assets: Arc<Mutex<HashMap<String, Vec<u8>>>>,
     file_contents: Arc<Mutex<HashMap<String, String>>>,
+    comparisons: Arc<Mutex<HashMap<String, GitHubComparison>>>,
+    commits: Arc<Mutex<HashMap<String, GitHubCommit>>>,
     /// Simulate API rate limit errors
     pub simulate_rate_limit: bool,
     /// Repositories that should return 404
@@ -376,8 +434,12 @@ impl MockGitHubProvider {
             releases: Arc::new(Mutex::new(HashMap::new())),
             tags: Arc::new(Mutex::new(HashMap::new())),
             default_branches: Arc::new(Mutex::new(HashMap::new())),
+            repos: Arc::new(Mutex::new(HashMap::new())),
             tarballs: Arc::new(Mutex::new(HashMap::new())),
+            assets: Arc::new(Mutex::new(HashMap::new())),
             file_contents: Arc::new(Mutex::new(HashMap::new())),
+            comparisons: Arc::new(Mutex::new(HashMap::new())),
+            commits: Arc::new(Mutex::new(HashMap::new())),
             simulate_rate_limit: false,
             missing_repos: Arc::new(Mutex::new(std::collections::HashSet::new())),
         }
@@ -414,6 +476,43 @@ impl MockGitHubProvider {
             .insert(key, branch);
     }
 
+    /// Set the metadata returned for a repository
+    pub fn add_repo(&self, owner: &str, repo: &str, info: GitHubRepo) {
+        let key = format!("{}/{}", owner, repo);
+        self.repos
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, info);
+    }
+
+    /// Set the numeric ID reported for a repository (defaults to 1)
+    pub fn set_repo_id(&self, owner: &str, repo: &str, id: u64) {
+        let key = format!("{}/{}", owner, repo);
+        self.repos
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key.clone())
+            .or_insert_with(|| Self::default_repo(repo, &key))
+            .id = id;
+    }
+
+    fn default_repo(repo: &str, full_name: &str) -> GitHubRepo {
+        GitHubRepo {
+            id: 1,
+            name: repo.to_string(),
+            full_name: full_name.to_string(),
+            default_branch: "main".to_string(),
+            description: None,
+            created_at: None,
+            pushed_at: None,
+            archived: false,
+            disabled: false,
+            fork: false,
+            stargazers_count: 0,
+            parent: None,
+        }
+    }
+
     /// Add a tarball download path
     pub fn add_tarball(&self, owner: &str, repo: &str, ref_: &str, path: PathBuf) {
         let key = format!("{}/{}/{}", owner, repo, ref_);
@@ -423,6 +522,14 @@ impl MockGitHubProvider {
             .insert(key, path);
     }
 
+    /// Add the content served for a release asset download URL
+    pub fn add_asset(&self, url: &str, content: Vec<u8>) {
+        self.assets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(url.to_string(), content);
+    }
+
     /// Add file content for a repository file
     pub fn add_file_content(
         &self,
@@ -439,6 +546,31 @@ impl MockGitHubProvider {
             .insert(key, content);
     }
 
+    /// Add a comparison between two refs
+    pub fn add_comparison(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+        comparison: GitHubComparison,
+    ) {
+        let key = format!("{}/{}/{}...{}", owner, repo, base, head);
+        self.comparisons
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, comparison);
+    }
+
+    /// Add a commit for a repository
+    pub fn add_commit(&self, owner: &str, repo: &str, commit: GitHubCommit) {
+        let key = format!("{}/{}/{}", owner, repo, commit.sha);
+        self.commits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, commit);
+    }
+
     /// Mark a repository as missing (returns 404)
     pub fn add_missing_repo(&self, owner: &str, repo: &str) {
         let key = format!("{}/{}", owner, repo);
@@ -475,10 +607,9 @@ impl GitHubProvider for MockGitHubProvider {
             .unwrap_or_else(|e| e.into_inner())
             .contains(&key)
         {
-            return Err(DepotError::Package(format!(
-                "Repository not found: {}",
-                key
-            )));
+            return Err(DepotError::Package(
+                "GitHub API error: HTTP 404 Not Found".to_string(),
+            ));
         }
 
         Ok(self
@@ -510,10 +641,9 @@ impl GitHubProvider for MockGitHubProvider {
             .unwrap_or_else(|e| e.into_inner())
             .contains(&key)
         {
-            return Err(DepotError::Package(format!(
-                "Repository not found: {}",
-                key
-            )));
+            return Err(DepotError::Package(
+                "GitHub API error: HTTP 404 Not Found".to_string(),
+            ));
         }
 
         Ok(self
@@ -525,6 +655,22 @@ impl GitHubProvider for MockGitHubProvider {
             .unwrap_or_default())
     }
 
+    async fn get_repo(&self, owner: &str, repo: &str) -> DepotResult<GitHubRepo> {
+        let default_branch = self.get_default_branch(owner, repo).await?;
+        let key = format!("{}/{}", owner, repo);
+        let registered = self
+            .repos
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned();
+
+        Ok(registered.unwrap_or_else(|| GitHubRepo {
+            default_branch,
+            ..Self::default_repo(repo, &key)
+        }))
+    }
+
     async fn get_default_branch(&self, owner: &str, repo: &str) -> DepotResult<String> {
         if self.simulate_rate_limit {
             return Err(DepotError::Package("API rate limit exceeded".to_string()));
@@ -537,10 +683,9 @@ impl GitHubProvider for MockGitHubProvider {
             .unwrap_or_else(|e| e.into_inner())
             .contains(&key)
         {
-            return Err(DepotError::Package(format!(
-                "Repository not found: {}",
-                key
-            )));
+            return Err(DepotError::Package(
+                "GitHub API error: HTTP 404 Not Found".to_string(),
+            ));
         }
 
         Ok(self
@@ -552,6 +697,40 @@ impl GitHubProvider for MockGitHubProvider {
             .unwrap_or_else(|| "main".to_string()))
     }
 
+    async fn get_commit(&self, owner: &str, repo: &str, sha: &str) -> DepotResult<GitHubCommit> {
+        if self.simulate_rate_limit {
+            return Err(DepotError::Package("API rate limit exceeded".to_string()));
+        }
+
+        let key = format!("{}/{}/{}", owner, repo, sha);
+        self.commits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| DepotError::Package(format!("Commit not found: {}", sha)))
+    }
+
+    async fn compare_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> DepotResult<GitHubComparison> {
+        if self.simulate_rate_limit {
+            return Err(DepotError::Package("API rate limit exceeded".to_string()));
+        }
+
+        let key = format!("{}/{}/{}...{}", owner, repo, base, head);
+        self.comparisons
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| DepotError::Package(format!("Comparison not found: {}", key)))
+    }
+
     async fn get_file_content(
         &self,
         owner: &str,
@@ -586,6 +765,19 @@ impl GitHubProvider for MockGitHubProvider {
             .ok_or_else(|| DepotError::Package(format!("Tarball not found for ref: {}", ref_)))
     }
 
+    async fn download_asset(&self, url: &str) -> DepotResult<Vec<u8>> {
+        if self.simulate_rate_limit {
+            return Err(DepotError::Package("API rate limit exceeded".to_string()));
+        }
+
+        self.assets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(url)
+            .cloned()
+            .ok_or_else(|| DepotError::Package(format!("Asset not found: {}", url)))
+    }
+
     async fn resolve_version(
         &self,
         owner: &str,
@@ -708,6 +900,14 @@ mod tests {
             github_token: Some("ghp_test123".to_string()),
             github_fallback_chain: vec!["tag".to_string()],
             strict_native_code: false,
+            min_release_age: Some("7d".to_string()),
+            license_policy: None,
+            archive_limits: ArchiveLimits::default(),
+            signature_policy: SignaturePolicy::default(),
+            suspicious_package_policy: SuspiciousPackagePolicy::default(),
+            policy_file: None,
+            advisory_db: None,
+            osv_cache_ttl_hours: 24,
         };
 
         assert_eq!(config.github_api_url(), "https://github.enterprise.com/api");
@@ -1,6 +1,10 @@
 //! Trait definitions for dependency injection
 
 use crate::core::DepotResult;
+use crate::package::extractor::ArchiveLimits;
+use crate::package::manifest::LicensePolicy;
+use crate::security::signature::SignaturePolicy;
+use crate::security::suspicious::SuspiciousPackagePolicy;
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 
@@ -44,6 +48,30 @@ pub trait ConfigProvider: Send + Sync {
 
     /// Check if strict native code warnings are enabled
     fn strict_native_code(&self) -> bool;
+
+    /// Get the minimum release age for version resolution (e.g. "7d")
+    fn min_release_age(&self) -> Option<&str>;
+
+    /// Get the default dependency license policy (optional)
+    fn license_policy(&self) -> Option<&LicensePolicy>;
+
+    /// Get the limits applied when extracting package archives
+    fn archive_limits(&self) -> ArchiveLimits;
+
+    /// Get the trusted publisher keys and signature requirement
+    fn signature_policy(&self) -> &SignaturePolicy;
+
+    /// Get the action for suspicious new dependencies outside interactive sessions
+    fn suspicious_package_policy(&self) -> SuspiciousPackagePolicy;
+
+    /// Get the dependency policy file named in the global config
+    fn policy_file(&self) -> Option<&Path>;
+
+    /// Get the local advisory database used by offline audits
+    fn advisory_db(&self) -> Option<&Path>;
+
+    /// Get how many hours cached OSV results stay fresh
+    fn osv_cache_ttl_hours(&self) -> i64;
 }
 
 /// Trait for cache operations
@@ -129,9 +157,29 @@ pub trait GitHubProvider: Send + Sync {
     async fn get_tags(&self, owner: &str, repo: &str)
         -> DepotResult<Vec<crate::github::GitHubTag>>;
 
+    /// Get repository information, including its stable numeric ID
+    async fn get_repo(&self, owner: &str, repo: &str) -> DepotResult<crate::github::GitHubRepo>;
+
     /// Get the default branch for a repository
     async fn get_default_branch(&self, owner: &str, repo: &str) -> DepotResult<String>;
 
+    /// Get a single commit by SHA
+    async fn get_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> DepotResult<crate::github::GitHubCommit>;
+
+    /// Compare two refs and list the commits between them
+    async fn compare_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> DepotResult<crate::github::GitHubComparison>;
+
     /// Get file content from a repository at a specific ref
     async fn get_file_content(
         &self,
@@ -144,6 +192,9 @@ pub trait GitHubProvider: Send + Sync {
     /// Download a tarball for a specific ref
     async fn download_tarball(&self, owner: &str, repo: &str, ref_: &str) -> DepotResult<PathBuf>;
 
+    /// Download a release asset by its `browser_download_url`
+    async fn download_asset(&self, url: &str) -> DepotResult<Vec<u8>>;
+
     /// Resolve a version using the fallback chain
     async fn resolve_version(
         &self,
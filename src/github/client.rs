@@ -3,7 +3,9 @@
 use crate::config::Config;
 use crate::core::{DepotError, DepotResult};
 use crate::di::traits::{CacheProvider, GitHubProvider};
-use crate::github::types::{GitHubRelease, GitHubRepo, GitHubTag, RefType, ResolvedVersion};
+use crate::github::types::{
+    GitHubCommit, GitHubComparison, GitHubRelease, GitHubRepo, GitHubTag, RefType, ResolvedVersion,
+};
 use async_trait::async_trait;
 use reqwest::{header, Client as HttpClient};
 use std::path::PathBuf;
@@ -88,6 +90,32 @@ impl GitHubClient {
         self.api_get(&url).await
     }
 
+    /// Get a single commit by SHA
+    pub async fn get_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> DepotResult<GitHubCommit> {
+        let url = format!("{}/repos/{}/{}/commits/{}", self.api_url, owner, repo, sha);
+        self.api_get(&url).await
+    }
+
+    /// Compare two refs and list the commits between them
+    pub async fn compare_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> DepotResult<GitHubComparison> {
+        let url = format!(
+            "{}/repos/{}/{}/compare/{}...{}",
+            self.api_url, owner, repo, base, head
+        );
+        self.api_get(&url).await
+    }
+
     /// Get repository information
     pub async fn get_repo(&self, owner: &str, repo: &str) -> DepotResult<GitHubRepo> {
         let url = format!("{}/repos/{}/{}", self.api_url, owner, repo);
@@ -185,6 +213,31 @@ impl GitHubClient {
         Ok(cache_path)
     }
 
+    /// Download a release asset (not cached; assets are small and fetched per install)
+    pub async fn download_asset(&self, url: &str) -> DepotResult<Vec<u8>> {
+        let response = self
+            .http_client
+            .get(url)
+            .header(header::ACCEPT, "application/octet-stream")
+            .send()
+            .await
+            .map_err(|e| DepotError::Package(format!("Failed to download {}: {}", url, e)))?;
+
+        if !response.status().is_success() {
+            return Err(DepotError::Package(format!(
+                "Failed to download {}: HTTP {}",
+                url,
+                response.status()
+            )));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| DepotError::Package(format!("Failed to read {}: {}", url, e)))?;
+        Ok(bytes.to_vec())
+    }
+
     /// Resolve a version using the fallback chain
     pub async fn resolve_version(
         &self,
@@ -385,10 +438,28 @@ impl GitHubProvider for GitHubClient {
         Self::get_tags(self, owner, repo).await
     }
 
+    async fn get_repo(&self, owner: &str, repo: &str) -> DepotResult<GitHubRepo> {
+        Self::get_repo(self, owner, repo).await
+    }
+
     async fn get_default_branch(&self, owner: &str, repo: &str) -> DepotResult<String> {
         Self::get_default_branch(self, owner, repo).await
     }
 
+    async fn get_commit(&self, owner: &str, repo: &str, sha: &str) -> DepotResult<GitHubCommit> {
+        Self::get_commit(self, owner, repo, sha).await
+    }
+
+    async fn compare_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> DepotResult<GitHubComparison> {
+        Self::compare_commits(self, owner, repo, base, head).await
+    }
+
     async fn get_file_content(
         &self,
         owner: &str,
@@ -403,6 +474,10 @@ impl GitHubProvider for GitHubClient {
         Self::download_tarball(self, owner, repo, ref_).await
     }
 
+    async fn download_asset(&self, url: &str) -> DepotResult<Vec<u8>> {
+        Self::download_asset(self, url).await
+    }
+
     async fn resolve_version(
         &self,
         owner: &str,
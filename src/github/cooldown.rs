@@ -0,0 +1,664 @@
+//! Release age cooldown
+//!
+//! Wraps a [`GitHubProvider`] so that releases and tags younger than the
+//! configured `min_release_age` are never resolved. This gives the community
+//! time to notice a compromised release before it lands in a project.
+//! A release whose age cannot be determined is held back as well.
+
+use crate::core::{DepotError, DepotResult};
+use crate::di::GitHubProvider;
+use crate::github::types::{
+    GitHubCommit, GitHubComparison, GitHubRelease, GitHubRepo, GitHubTag, RefType, ResolvedVersion,
+};
+use crate::package::manifest::PackageManifest;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Maximum number of tags whose commit date is looked up during resolution
+const MAX_TAG_LOOKUPS: usize = 10;
+
+/// A minimum release age, keeping the original spelling for messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseAge {
+    pub spec: String,
+    pub duration: Duration,
+}
+
+impl ReleaseAge {
+    /// Parse an age such as `7d`, `12h`, `30m`, `2w` or `0`
+    pub fn parse(spec: &str) -> DepotResult<Self> {
+        let trimmed = spec.trim();
+        let invalid = || {
+            DepotError::Config(format!(
+                "Invalid min_release_age '{}': expected a number followed by m, h, d or w (e.g. 7d)",
+                spec
+            ))
+        };
+
+        if trimmed == "0" {
+            return Ok(Self {
+                spec: trimmed.to_string(),
+                duration: Duration::zero(),
+            });
+        }
+
+        let split = trimmed
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (amount, unit) = trimmed.split_at(split);
+        let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+        let duration = match unit {
+            "m" => Duration::minutes(amount),
+            "h" => Duration::hours(amount),
+            "d" => Duration::days(amount),
+            "w" => Duration::weeks(amount),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            spec: trimmed.to_string(),
+            duration,
+        })
+    }
+
+    fn is_zero(&self) -> bool {
+        self.duration <= Duration::zero()
+    }
+}
+
+/// Which release ages apply to which packages
+#[derive(Debug, Clone)]
+pub struct CooldownPolicy {
+    default: Option<ReleaseAge>,
+    overrides: HashMap<String, ReleaseAge>,
+    now: DateTime<Utc>,
+}
+
+impl CooldownPolicy {
+    /// Build the policy from the global config and the project manifest
+    ///
+    /// The manifest's `min_release_age` takes precedence over the config value,
+    /// and `min_release_age_overrides` take precedence over both.
+    pub fn from_settings(
+        config_age: Option<&str>,
+        manifest: Option<&PackageManifest>,
+    ) -> DepotResult<Self> {
+        let default = manifest
+            .and_then(|m| m.min_release_age.as_deref())
+            .or(config_age)
+            .map(ReleaseAge::parse)
+            .transpose()?;
+
+        let mut overrides = HashMap::new();
+        if let Some(manifest) = manifest {
+            for (package, age) in &manifest.min_release_age_overrides {
+                overrides.insert(package.clone(), ReleaseAge::parse(age)?);
+            }
+        }
+
+        Ok(Self {
+            default,
+            overrides,
+            now: Utc::now(),
+        })
+    }
+
+    /// Evaluate ages relative to a fixed point in time
+    pub fn with_now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Minimum age for a package, or `None` if it has no cooldown
+    pub fn min_age_for(&self, package: &str) -> Option<&ReleaseAge> {
+        self.overrides
+            .get(package)
+            .or(self.default.as_ref())
+            .filter(|age| !age.is_zero())
+    }
+
+    /// Whether any package is subject to a cooldown
+    pub fn is_active(&self) -> bool {
+        self.default.iter().any(|age| !age.is_zero())
+            || self.overrides.values().any(|age| !age.is_zero())
+    }
+
+    /// Why something published at `published_at` is still cooling down, if it is
+    ///
+    /// Missing or unparseable timestamps count as too young, so the cooldown
+    /// never lets a release through just because its age is unknown.
+    fn too_young(&self, published_at: Option<&str>, min_age: &ReleaseAge) -> Option<Cooling> {
+        let Some(published) = published_at.and_then(|p| DateTime::parse_from_rfc3339(p).ok())
+        else {
+            return Some(Cooling::Undated);
+        };
+        let age = self
+            .now
+            .signed_duration_since(published.with_timezone(&Utc));
+        (age < min_age.duration).then_some(Cooling::Young(age))
+    }
+}
+
+/// Why a release is held back by the cooldown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cooling {
+    /// Published this long ago, less than the minimum age
+    Young(Duration),
+    /// No usable publish date
+    Undated,
+}
+
+impl fmt::Display for Cooling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cooling::Young(age) => write!(f, "published {} ago", format_age(*age)),
+            Cooling::Undated => write!(f, "publish date unknown"),
+        }
+    }
+}
+
+/// Human-readable age such as `3d` or `5h`
+pub fn format_age(age: Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
+
+/// GitHub provider that hides releases younger than the cooldown
+pub struct CooldownGitHubProvider {
+    inner: Arc<dyn GitHubProvider>,
+    policy: CooldownPolicy,
+    reported: Mutex<HashSet<String>>,
+}
+
+impl CooldownGitHubProvider {
+    pub fn new(inner: Arc<dyn GitHubProvider>, policy: CooldownPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            reported: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Print why a version was skipped, once per package and version
+    fn report_skip(&self, package: &str, version: &str, cooling: Cooling, min_age: &ReleaseAge) {
+        let key = format!("{}@{}", package, version);
+        let first = self
+            .reported
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key);
+        if first {
+            println!(
+                "⏳ Skipping {} {}: {} (min_release_age {})",
+                package, version, cooling, min_age.spec
+            );
+        }
+    }
+
+    async fn commit_date(&self, owner: &str, repo: &str, sha: &str) -> DepotResult<Option<String>> {
+        let commit = self.inner.get_commit(owner, repo, sha).await?;
+        Ok(commit.commit.committer.map(|c| c.date))
+    }
+
+    /// Releases old enough to be resolved, reporting the ones that are not
+    async fn mature_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        min_age: &ReleaseAge,
+    ) -> DepotResult<Vec<GitHubRelease>> {
+        let package = format!("{}/{}", owner, repo);
+        let releases = self.inner.get_releases(owner, repo).await?;
+
+        Ok(releases
+            .into_iter()
+            .filter(|release| {
+                match self
+                    .policy
+                    .too_young(release.published_at.as_deref(), min_age)
+                {
+                    Some(cooling) => {
+                        if !release.draft {
+                            self.report_skip(&package, &release.tag_name, cooling, min_age);
+                        }
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect())
+    }
+
+    /// Why an explicitly requested version is still cooling down, if it is
+    async fn resolved_too_young(
+        &self,
+        owner: &str,
+        repo: &str,
+        resolved: &ResolvedVersion,
+        min_age: &ReleaseAge,
+    ) -> DepotResult<Option<Cooling>> {
+        let published_at = match resolved.ref_type {
+            RefType::Release => self
+                .inner
+                .get_releases(owner, repo)
+                .await?
+                .into_iter()
+                .find(|r| r.tag_name == resolved.ref_value)
+                .and_then(|r| r.published_at),
+            RefType::Tag => self.commit_date(owner, repo, &resolved.commit_sha).await?,
+            RefType::Branch | RefType::Commit => return Ok(None),
+        };
+
+        Ok(self.policy.too_young(published_at.as_deref(), min_age))
+    }
+
+    async fn resolve_with_fallback(
+        &self,
+        owner: &str,
+        repo: &str,
+        fallback_chain: &[String],
+        min_age: &ReleaseAge,
+    ) -> DepotResult<ResolvedVersion> {
+        let package = format!("{}/{}", owner, repo);
+
+        for strategy in fallback_chain {
+            match strategy.as_str() {
+                "release" => {
+                    if let Ok(releases) = self.mature_releases(owner, repo, min_age).await {
+                        if let Some(release) = releases.iter().find(|r| !r.draft && !r.prerelease) {
+                            return Ok(ResolvedVersion {
+                                ref_type: RefType::Release,
+                                ref_value: release.tag_name.clone(),
+                                commit_sha: String::new(),
+                                tarball_url: release.tarball_url.clone(),
+                            });
+                        }
+                    }
+                }
+                "tag" => {
+                    if let Ok(tags) = self.inner.get_tags(owner, repo).await {
+                        for tag in tags.iter().take(MAX_TAG_LOOKUPS) {
+                            let date = self.commit_date(owner, repo, &tag.commit.sha).await?;
+                            if let Some(cooling) = self.policy.too_young(date.as_deref(), min_age) {
+                                self.report_skip(&package, &tag.name, cooling, min_age);
+                                continue;
+                            }
+                            return Ok(ResolvedVersion {
+                                ref_type: RefType::Tag,
+                                ref_value: tag.name.clone(),
+                                commit_sha: tag.commit.sha.clone(),
+                                tarball_url: tag.tarball_url.clone(),
+                            });
+                        }
+                    }
+                }
+                "branch" => {
+                    return self
+                        .inner
+                        .resolve_version(owner, repo, None, &["branch".to_string()])
+                        .await;
+                }
+                _ => continue,
+            }
+        }
+
+        Err(DepotError::Package(format!(
+            "Could not resolve version for {}: no release or tag is older than min_release_age {} (use --ignore-cooldown to override)",
+            package, min_age.spec
+        )))
+    }
+}
+
+#[async_trait]
+impl GitHubProvider for CooldownGitHubProvider {
+    async fn get_releases(&self, owner: &str, repo: &str) -> DepotResult<Vec<GitHubRelease>> {
+        match self.policy.min_age_for(&format!("{}/{}", owner, repo)) {
+            Some(min_age) => self.mature_releases(owner, repo, min_age).await,
+            None => self.inner.get_releases(owner, repo).await,
+        }
+    }
+
+    async fn get_latest_release(&self, owner: &str, repo: &str) -> DepotResult<GitHubRelease> {
+        let Some(min_age) = self.policy.min_age_for(&format!("{}/{}", owner, repo)) else {
+            return self.inner.get_latest_release(owner, repo).await;
+        };
+
+        self.mature_releases(owner, repo, min_age)
+            .await?
+            .into_iter()
+            .find(|r| !r.draft && !r.prerelease)
+            .ok_or_else(|| {
+                DepotError::Package(format!(
+                    "No releases older than min_release_age {} for {}/{}",
+                    min_age.spec, owner, repo
+                ))
+            })
+    }
+
+    async fn get_tags(&self, owner: &str, repo: &str) -> DepotResult<Vec<GitHubTag>> {
+        self.inner.get_tags(owner, repo).await
+    }
+
+    async fn get_repo(&self, owner: &str, repo: &str) -> DepotResult<GitHubRepo> {
+        self.inner.get_repo(owner, repo).await
+    }
+
+    async fn get_default_branch(&self, owner: &str, repo: &str) -> DepotResult<String> {
+        self.inner.get_default_branch(owner, repo).await
+    }
+
+    async fn get_commit(&self, owner: &str, repo: &str, sha: &str) -> DepotResult<GitHubCommit> {
+        self.inner.get_commit(owner, repo, sha).await
+    }
+
+    async fn compare_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> DepotResult<GitHubComparison> {
+        self.inner.compare_commits(owner, repo, base, head).await
+    }
+
+    async fn get_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        ref_: &str,
+    ) -> DepotResult<String> {
+        self.inner.get_file_content(owner, repo, path, ref_).await
+    }
+
+    async fn download_tarball(&self, owner: &str, repo: &str, ref_: &str) -> DepotResult<PathBuf> {
+        self.inner.download_tarball(owner, repo, ref_).await
+    }
+
+    async fn download_asset(&self, url: &str) -> DepotResult<Vec<u8>> {
+        self.inner.download_asset(url).await
+    }
+
+    async fn resolve_version(
+        &self,
+        owner: &str,
+        repo: &str,
+        version_spec: Option<&str>,
+        fallback_chain: &[String],
+    ) -> DepotResult<ResolvedVersion> {
+        let package = format!("{}/{}", owner, repo);
+        let Some(min_age) = self.policy.min_age_for(&package) else {
+            return self
+                .inner
+                .resolve_version(owner, repo, version_spec, fallback_chain)
+                .await;
+        };
+
+        let Some(version) = version_spec else {
+            return self
+                .resolve_with_fallback(owner, repo, fallback_chain, min_age)
+                .await;
+        };
+
+        let resolved = self
+            .inner
+            .resolve_version(owner, repo, Some(version), fallback_chain)
+            .await?;
+        if let Some(cooling) = self
+            .resolved_too_young(owner, repo, &resolved, min_age)
+            .await?
+        {
+            return Err(DepotError::Package(format!(
+                "{} {} is not older than min_release_age {}: {} (use --ignore-cooldown to install it anyway)",
+                package, version, min_age.spec, cooling
+            )));
+        }
+
+        Ok(resolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::types::{CommitDetail, CommitSignature, TagCommit};
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn release(tag: &str, published_at: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: format!("https://example.com/{}.tar.gz", tag),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: None,
+            published_at: Some(published_at.to_string()),
+        }
+    }
+
+    fn provider(github: MockGitHubProvider, manifest: &PackageManifest) -> CooldownGitHubProvider {
+        let policy = CooldownPolicy::from_settings(None, Some(manifest))
+            .unwrap()
+            .with_now(now());
+        CooldownGitHubProvider::new(Arc::new(github), policy)
+    }
+
+    fn manifest(age: &str) -> PackageManifest {
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest.min_release_age = Some(age.to_string());
+        manifest
+    }
+
+    fn chain() -> Vec<String> {
+        vec!["release".to_string(), "tag".to_string()]
+    }
+
+    #[test]
+    fn test_parse_release_age() {
+        assert_eq!(ReleaseAge::parse("7d").unwrap().duration, Duration::days(7));
+        assert_eq!(
+            ReleaseAge::parse("12h").unwrap().duration,
+            Duration::hours(12)
+        );
+        assert_eq!(
+            ReleaseAge::parse("30m").unwrap().duration,
+            Duration::minutes(30)
+        );
+        assert_eq!(
+            ReleaseAge::parse("2w").unwrap().duration,
+            Duration::weeks(2)
+        );
+        assert!(ReleaseAge::parse("0").unwrap().is_zero());
+        assert!(ReleaseAge::parse("7").is_err());
+        assert!(ReleaseAge::parse("d").is_err());
+        assert!(ReleaseAge::parse("7 days").is_err());
+    }
+
+    #[test]
+    fn test_policy_precedence() {
+        let mut manifest = manifest("3d");
+        manifest
+            .min_release_age_overrides
+            .insert("owner/trusted".to_string(), "0".to_string());
+
+        let policy = CooldownPolicy::from_settings(Some("7d"), Some(&manifest)).unwrap();
+        assert!(policy.is_active());
+        assert_eq!(policy.min_age_for("owner/json").unwrap().spec, "3d");
+        assert!(policy.min_age_for("owner/trusted").is_none());
+
+        let policy = CooldownPolicy::from_settings(Some("7d"), None).unwrap();
+        assert_eq!(policy.min_age_for("owner/json").unwrap().spec, "7d");
+
+        let policy = CooldownPolicy::from_settings(None, None).unwrap();
+        assert!(!policy.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skips_young_releases() {
+        let github = MockGitHubProvider::new();
+        github.add_release("owner", "json", release("v1.1.0", "2024-06-14T00:00:00Z"));
+        github.add_release("owner", "json", release("v1.0.0", "2024-05-01T00:00:00Z"));
+
+        let provider = provider(github, &manifest("7d"));
+        let resolved = provider
+            .resolve_version("owner", "json", None, &chain())
+            .await
+            .unwrap();
+        assert_eq!(resolved.ref_value, "v1.0.0");
+
+        let releases = provider.get_releases("owner", "json").await.unwrap();
+        assert_eq!(releases.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_explicit_young_version_fails() {
+        let github = MockGitHubProvider::new();
+        github.add_release("owner", "json", release("v1.1.0", "2024-06-14T00:00:00Z"));
+
+        let provider = provider(github, &manifest("7d"));
+        let err = provider
+            .resolve_version("owner", "json", Some("v1.1.0"), &chain())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("published 1d ago"));
+        assert!(err.contains("--ignore-cooldown"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skips_young_tags() {
+        let github = MockGitHubProvider::new();
+        for (name, sha, date) in [
+            ("v2.0.0", "new", "2024-06-13T00:00:00Z"),
+            ("v1.0.0", "old", "2024-01-01T00:00:00Z"),
+        ] {
+            github.add_tag(
+                "owner",
+                "json",
+                GitHubTag {
+                    name: name.to_string(),
+                    commit: TagCommit {
+                        sha: sha.to_string(),
+                        url: String::new(),
+                    },
+                    tarball_url: String::new(),
+                    zipball_url: String::new(),
+                },
+            );
+            github.add_commit(
+                "owner",
+                "json",
+                GitHubCommit {
+                    sha: sha.to_string(),
+                    commit: CommitDetail {
+                        message: String::new(),
+                        committer: Some(CommitSignature {
+                            name: None,
+                            date: date.to_string(),
+                        }),
+                    },
+                },
+            );
+        }
+
+        let provider = provider(github, &manifest("1w"));
+        let resolved = provider
+            .resolve_version("owner", "json", None, &chain())
+            .await
+            .unwrap();
+        assert_eq!(resolved.ref_value, "v1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_override_disables_cooldown() {
+        let github = MockGitHubProvider::new();
+        github.add_release("owner", "json", release("v1.1.0", "2024-06-14T00:00:00Z"));
+
+        let mut manifest = manifest("7d");
+        manifest
+            .min_release_age_overrides
+            .insert("owner/json".to_string(), "0".to_string());
+
+        let provider = provider(github, &manifest);
+        let resolved = provider
+            .resolve_version("owner", "json", None, &chain())
+            .await
+            .unwrap();
+        assert_eq!(resolved.ref_value, "v1.1.0");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_age_is_too_young() {
+        let github = MockGitHubProvider::new();
+        let mut undated = release("v1.1.0", "");
+        undated.published_at = None;
+        github.add_release("owner", "json", undated);
+        github.add_release("owner", "json", release("v1.0.0", "not a date"));
+
+        let provider = provider(github, &manifest("7d"));
+        assert!(provider
+            .get_releases("owner", "json")
+            .await
+            .unwrap()
+            .is_empty());
+        let err = provider
+            .resolve_version("owner", "json", Some("v1.1.0"), &chain())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("publish date unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_lookups_are_errors() {
+        // A tag whose commit cannot be fetched is not resolved
+        let github = MockGitHubProvider::new();
+        github.add_tag(
+            "owner",
+            "json",
+            GitHubTag {
+                name: "v1.0.0".to_string(),
+                commit: TagCommit {
+                    sha: "missing".to_string(),
+                    url: String::new(),
+                },
+                tarball_url: String::new(),
+                zipball_url: String::new(),
+            },
+        );
+        let provider = provider(github, &manifest("7d"));
+        assert!(provider
+            .resolve_version("owner", "json", None, &chain())
+            .await
+            .is_err());
+        assert!(provider
+            .resolve_version("owner", "json", Some("v1.0.0"), &chain())
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::days(3)), "3d");
+        assert_eq!(format_age(Duration::hours(5)), "5h");
+        assert_eq!(format_age(Duration::minutes(12)), "12m");
+    }
+}
@@ -7,7 +7,11 @@
 //! - Resolve package versions using a fallback chain
 
 pub mod client;
+pub mod cooldown;
 pub mod types;
 
 pub use client::GitHubClient;
-pub use types::{GitHubRelease, GitHubTag, RefType, ResolvedVersion};
+pub use cooldown::{CooldownGitHubProvider, CooldownPolicy};
+pub use types::{
+    GitHubCommit, GitHubComparison, GitHubRelease, GitHubRepo, GitHubTag, RefType, ResolvedVersion,
+};
@@ -62,13 +62,67 @@ pub struct TagCommit {
     pub url: String,
 }
 
+/// Result of comparing two refs (`GET /repos/{owner}/{repo}/compare/{base}...{head}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubComparison {
+    pub html_url: String,
+    pub status: String,
+    pub ahead_by: u64,
+    pub total_commits: u64,
+    #[serde(default)]
+    pub commits: Vec<GitHubCommit>,
+}
+
+/// A commit, as returned by the commits and compare endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommit {
+    pub sha: String,
+    pub commit: CommitDetail,
+}
+
+/// Git commit metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitDetail {
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committer: Option<CommitSignature>,
+}
+
+/// Author or committer of a commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitSignature {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// ISO 8601 timestamp
+    pub date: String,
+}
+
 /// GitHub repository information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRepo {
+    /// Stable numeric ID; survives renames and transfers
+    pub id: u64,
     pub name: String,
     pub full_name: String,
     pub default_branch: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// ISO 8601 timestamp of the last push to any branch
+    #[serde(default)]
+    pub pushed_at: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    /// Disabled by GitHub, e.g. for a terms of service violation
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default)]
+    pub fork: bool,
+    #[serde(default)]
+    pub stargazers_count: u64,
+    /// Repository this one was forked from
+    #[serde(default)]
+    pub parent: Option<Box<GitHubRepo>>,
 }
 
 /// Resolved version information
@@ -0,0 +1,524 @@
+//! Static scan of dependency code for sensitive Lua APIs
+//!
+//! Reuses the compatibility scanner's AST walker to find calls that reach
+//! outside the Lua sandbox: shell and process execution, dynamic code
+//! loading, the debug library, native libraries and network modules.
+
+use crate::core::path::lua_modules_dir;
+use crate::core::DepotResult;
+use crate::lua_analysis::scanner::{extract_require_arg, walk_source, AstVisitor};
+use crate::package::lockfile::Lockfile;
+use luanext_parser::prelude::*;
+use luanext_parser::StringInterner;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Modules (and their submodules) that open network connections
+const NETWORK_MODULES: &[&str] = &[
+    "socket",
+    "ssl",
+    "http",
+    "resty.http",
+    "resty.websocket",
+    "copas",
+    "cURL",
+    "lcurl",
+    "websocket",
+    "mqtt",
+];
+
+/// A sensitive API family a package may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Capability {
+    OsExecute,
+    IoPopen,
+    DynamicLoad,
+    Debug,
+    LoadLib,
+    Ffi,
+    Network,
+    /// A file the scanner could not read or parse, so its uses are unknown
+    Unscanned,
+}
+
+impl Capability {
+    /// Name recorded in depot.lock and shown in reports
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Capability::OsExecute => "os.execute",
+            Capability::IoPopen => "io.popen",
+            Capability::DynamicLoad => "load",
+            Capability::Debug => "debug",
+            Capability::LoadLib => "package.loadlib",
+            Capability::Ffi => "ffi",
+            Capability::Network => "network",
+            Capability::Unscanned => "unscanned",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Capability::OsExecute => "runs shell commands",
+            Capability::IoPopen => "spawns processes",
+            Capability::DynamicLoad => "compiles code from non-literal strings",
+            Capability::Debug => "uses the debug library",
+            Capability::LoadLib => "loads native libraries",
+            Capability::Ffi => "calls C through the LuaJIT FFI",
+            Capability::Network => "requires network modules",
+            Capability::Unscanned => "has files that could not be scanned",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One place a capability is used
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityUse {
+    #[serde(serialize_with = "serialize_capability")]
+    pub capability: Capability,
+    /// File path relative to the package directory
+    pub file: PathBuf,
+    pub line: u32,
+    /// The matched expression, e.g. `os.execute` or `require("socket.http")`
+    pub detail: String,
+}
+
+fn serialize_capability<S: serde::Serializer>(
+    capability: &Capability,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(capability.as_str())
+}
+
+/// Scan results for one installed package
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageCapabilities {
+    pub package: String,
+    pub version: String,
+    pub uses: Vec<CapabilityUse>,
+}
+
+impl PackageCapabilities {
+    /// Distinct capabilities used, in a stable order
+    pub fn capabilities(&self) -> Vec<Capability> {
+        let mut caps: Vec<Capability> = self.uses.iter().map(|u| u.capability).collect();
+        caps.sort();
+        caps.dedup();
+        caps
+    }
+}
+
+/// Capabilities a package gained between two lockfiles
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityChange {
+    pub package: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub added: Vec<String>,
+}
+
+/// Whether `module` is a network module or one of its submodules
+pub fn is_network_module(module: &str) -> bool {
+    NETWORK_MODULES.iter().any(|m| {
+        module == *m
+            || module
+                .strip_prefix(m)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+/// Capability of a dotted member reference such as `os.execute`
+fn member_capability(name: &str) -> Option<Capability> {
+    match name {
+        "os.execute" => Some(Capability::OsExecute),
+        "io.popen" => Some(Capability::IoPopen),
+        "package.loadlib" => Some(Capability::LoadLib),
+        _ if name.starts_with("debug.") => Some(Capability::Debug),
+        _ => None,
+    }
+}
+
+struct CapabilityVisitor {
+    found: Vec<(Capability, String, u32)>,
+    seen: HashSet<(Capability, String, u32)>,
+}
+
+impl CapabilityVisitor {
+    fn record(&mut self, capability: Capability, detail: String, line: u32) {
+        if self.seen.insert((capability, detail.clone(), line)) {
+            self.found.push((capability, detail, line));
+        }
+    }
+}
+
+/// Dotted name of a member or string-keyed index reference
+///
+/// `os["execute"]` and `_G.os.execute` both resolve to `os.execute`.
+fn member_name(expr: &Expression<'_>, interner: &StringInterner) -> Option<String> {
+    let name = match &expr.kind {
+        ExpressionKind::Identifier(id) => interner.resolve(*id),
+        ExpressionKind::Member(obj, ident) => {
+            format!(
+                "{}.{}",
+                member_name(obj, interner)?,
+                interner.resolve(ident.node)
+            )
+        }
+        ExpressionKind::Index(obj, key) => match &key.kind {
+            ExpressionKind::Literal(Literal::String(key)) => {
+                format!("{}.{}", member_name(obj, interner)?, key)
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name.strip_prefix("_G.").map(str::to_string).unwrap_or(name))
+}
+
+impl AstVisitor for CapabilityVisitor {
+    fn visit_expression(&mut self, expr: &Expression<'_>, interner: &StringInterner) {
+        match &expr.kind {
+            ExpressionKind::Call(callee, args, _) => {
+                if let Some(module) = extract_require_arg(callee, args, interner) {
+                    let detail = format!("require(\"{}\")", module);
+                    if module == "ffi" {
+                        self.record(Capability::Ffi, detail, expr.span.line);
+                    } else if is_network_module(&module) {
+                        self.record(Capability::Network, detail, expr.span.line);
+                    }
+                }
+
+                // load/loadstring are only flagged when the chunk isn't a literal
+                if let ExpressionKind::Identifier(id) = &callee.kind {
+                    let name = interner.resolve(*id);
+                    if name == "load" || name == "loadstring" {
+                        let dynamic = args.first().is_some_and(|arg| {
+                            !matches!(arg.value.kind, ExpressionKind::Literal(Literal::String(_)))
+                        });
+                        if dynamic {
+                            self.record(Capability::DynamicLoad, name, expr.span.line);
+                        }
+                    }
+                }
+            }
+            // Member references catch both calls and aliases like `local run = os.execute`
+            ExpressionKind::Member(..) | ExpressionKind::Index(..) => {
+                if let Some(name) = member_name(expr, interner) {
+                    if let Some(capability) = member_capability(&name) {
+                        self.record(capability, name, expr.span.line);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Scan a source string, returning (capability, detail, line) triples.
+fn scan_source(source: &str) -> DepotResult<Vec<(Capability, String, u32)>> {
+    let mut visitor = CapabilityVisitor {
+        found: Vec::new(),
+        seen: HashSet::new(),
+    };
+    walk_source(source, &mut visitor)?;
+    Ok(visitor.found)
+}
+
+/// Scan every `.lua` file under a package directory
+///
+/// Hidden directories are skipped. A file that can't be read or parsed is
+/// reported as [`Capability::Unscanned`] instead of being passed over.
+pub fn scan_dir(dir: &Path) -> Vec<CapabilityUse> {
+    let mut uses = Vec::new();
+    for entry in WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "lua") {
+            continue;
+        }
+        let file = path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+        let found = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| scan_source(&source).map_err(|e| e.to_string()));
+        let found = match found {
+            Ok(found) => found,
+            Err(e) => vec![(Capability::Unscanned, e, 0)],
+        };
+        uses.extend(
+            found
+                .into_iter()
+                .map(|(capability, detail, line)| CapabilityUse {
+                    capability,
+                    file: file.clone(),
+                    line,
+                    detail,
+                }),
+        );
+    }
+    uses
+}
+
+/// Distinct capability names for the lockfile, in a stable order
+pub fn capability_names(uses: &[CapabilityUse]) -> Vec<String> {
+    let mut caps: Vec<Capability> = uses.iter().map(|u| u.capability).collect();
+    caps.sort();
+    caps.dedup();
+    caps.into_iter().map(|c| c.as_str().to_string()).collect()
+}
+
+/// Scan each locked package installed under lua_modules, sorted by name
+///
+/// Packages that aren't installed are left out.
+pub fn scan_lockfile(project_root: &Path, lockfile: &Lockfile) -> Vec<PackageCapabilities> {
+    let lua_modules = lua_modules_dir(project_root);
+    let mut names: Vec<&String> = lockfile.packages.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let dir = lua_modules.join(name);
+            dir.is_dir().then(|| PackageCapabilities {
+                package: name.clone(),
+                version: lockfile.packages[name].version.clone(),
+                uses: scan_dir(&dir),
+            })
+        })
+        .collect()
+}
+
+/// Capabilities each package gained since the previous lockfile
+///
+/// Only packages present in both lockfiles are compared; a newly added
+/// dependency has no baseline to diff against.
+pub fn diff_lockfiles(previous: &Lockfile, current: &Lockfile) -> Vec<CapabilityChange> {
+    let mut changes: Vec<CapabilityChange> = current
+        .packages
+        .iter()
+        .filter_map(|(name, pkg)| {
+            let old = previous.get_package(name)?;
+            let added: Vec<String> = pkg
+                .capabilities
+                .iter()
+                .filter(|c| !old.capabilities.contains(c))
+                .cloned()
+                .collect();
+            (!added.is_empty()).then(|| CapabilityChange {
+                package: name.clone(),
+                from_version: old.version.clone(),
+                to_version: pkg.version.clone(),
+                added,
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.package.cmp(&b.package));
+    changes
+}
+
+/// Human-readable lines warning about newly gained capabilities
+pub fn format_changes(changes: &[CapabilityChange]) -> String {
+    let mut output = String::new();
+    for change in changes {
+        let versions = if change.from_version == change.to_version {
+            change.to_version.clone()
+        } else {
+            format!("{} → {}", change.from_version, change.to_version)
+        };
+        output.push_str(&format!(
+            "  ⚠️  {} ({}) now uses: {}\n",
+            change.package,
+            versions,
+            change.added.join(", ")
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn capabilities(source: &str) -> Vec<Capability> {
+        let mut caps: Vec<Capability> = scan_source(source)
+            .unwrap()
+            .into_iter()
+            .map(|(c, _, _)| c)
+            .collect();
+        caps.sort();
+        caps.dedup();
+        caps
+    }
+
+    fn locked(version: &str, capabilities: &[&str]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/lib".to_string(),
+            ref_type: "Release".to_string(),
+            ref_value: format!("v{}", version),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:00".to_string(),
+            size: 1,
+            dependencies: HashMap::new(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+            repository_id: None,
+        }
+    }
+
+    #[test]
+    fn test_detect_process_apis() {
+        assert_eq!(
+            capabilities("os.execute('ls')\nlocal f = io.popen(cmd)"),
+            vec![Capability::OsExecute, Capability::IoPopen]
+        );
+    }
+
+    #[test]
+    fn test_detect_aliased_member() {
+        assert_eq!(
+            capabilities("local run = os.execute\nrun('ls')"),
+            vec![Capability::OsExecute]
+        );
+    }
+
+    #[test]
+    fn test_detect_indexed_member() {
+        assert_eq!(
+            capabilities("os[\"execute\"]('ls')\nlocal p = _G.io['popen']"),
+            vec![Capability::OsExecute, Capability::IoPopen]
+        );
+        assert_eq!(
+            capabilities("_G[\"debug\"].sethook(f)"),
+            vec![Capability::Debug]
+        );
+        assert!(capabilities("local f = os[name]").is_empty());
+    }
+
+    #[test]
+    fn test_load_only_flagged_for_non_literals() {
+        assert!(capabilities("local f = load('return 1')").is_empty());
+        assert!(capabilities("local f = loadstring(\"return 1\")").is_empty());
+        assert_eq!(
+            capabilities("local f = load(code)"),
+            vec![Capability::DynamicLoad]
+        );
+        assert_eq!(
+            capabilities("local f = loadstring('return ' .. expr)"),
+            vec![Capability::DynamicLoad]
+        );
+    }
+
+    #[test]
+    fn test_detect_debug_loadlib_ffi() {
+        assert_eq!(
+            capabilities(
+                "local info = debug.getinfo(1)\n\
+                 local open = package.loadlib('./lib.so', 'luaopen_lib')\n\
+                 local ffi = require(\"ffi\")"
+            ),
+            vec![Capability::Debug, Capability::LoadLib, Capability::Ffi]
+        );
+    }
+
+    #[test]
+    fn test_detect_network_modules() {
+        assert_eq!(
+            capabilities("local http = require(\"socket.http\")"),
+            vec![Capability::Network]
+        );
+        assert!(is_network_module("ssl"));
+        assert!(is_network_module("http.request"));
+        assert!(!is_network_module("httpclient"));
+        assert!(capabilities("local json = require(\"cjson\")").is_empty());
+    }
+
+    #[test]
+    fn test_no_detection_in_strings_or_comments() {
+        assert!(capabilities("-- os.execute('rm -rf /')\nlocal s = 'io.popen'").is_empty());
+    }
+
+    #[test]
+    fn test_scan_lockfile_groups_by_package() {
+        let temp = TempDir::new().unwrap();
+        let pkg_dir = lua_modules_dir(temp.path()).join("owner/lib");
+        fs::create_dir_all(pkg_dir.join("src")).unwrap();
+        fs::write(
+            pkg_dir.join("src/init.lua"),
+            "local M = {}\nfunction M.run(c)\n  return os.execute(c)\nend\nreturn M\n",
+        )
+        .unwrap();
+        fs::write(pkg_dir.join("src/broken.lua"), "local = = =").unwrap();
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("owner/lib".to_string(), locked("1.0.0", &[]));
+        lockfile.add_package("owner/missing".to_string(), locked("1.0.0", &[]));
+
+        let results = scan_lockfile(temp.path(), &lockfile);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].package, "owner/lib");
+        assert_eq!(
+            results[0].capabilities(),
+            vec![Capability::OsExecute, Capability::Unscanned]
+        );
+        assert_eq!(results[0].uses[0].capability, Capability::Unscanned);
+        assert_eq!(results[0].uses[0].file, PathBuf::from("src/broken.lua"));
+        assert_eq!(results[0].uses[1].file, PathBuf::from("src/init.lua"));
+        assert_eq!(results[0].uses[1].line, 3);
+        assert_eq!(
+            capability_names(&results[0].uses),
+            vec!["os.execute", "unscanned"]
+        );
+    }
+
+    #[test]
+    fn test_scan_dir_reports_unreadable_files() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("latin1.lua"), b"return '\xe9'").unwrap();
+
+        let uses = scan_dir(temp.path());
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].capability, Capability::Unscanned);
+        assert_eq!(uses[0].file, PathBuf::from("latin1.lua"));
+        assert_eq!(capability_names(&uses), vec!["unscanned"]);
+    }
+
+    #[test]
+    fn test_diff_lockfiles_reports_new_capabilities() {
+        let mut previous = Lockfile::new();
+        previous.add_package("owner/lib".to_string(), locked("1.0.0", &["network"]));
+
+        let mut current = Lockfile::new();
+        current.add_package(
+            "owner/lib".to_string(),
+            locked("1.1.0", &["network", "os.execute"]),
+        );
+        current.add_package("owner/new".to_string(), locked("1.0.0", &["io.popen"]));
+
+        let changes = diff_lockfiles(&previous, &current);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].package, "owner/lib");
+        assert_eq!(changes[0].added, vec!["os.execute"]);
+        assert!(format_changes(&changes).contains("owner/lib (1.0.0 → 1.1.0) now uses: os.execute"));
+
+        assert!(diff_lockfiles(&current, &previous).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod capabilities;
 pub mod compat_db;
 pub mod report;
 pub mod scanner;
@@ -80,7 +81,13 @@ impl LuaVersionSet {
     }
 
     /// Checks if a specific Lua version string (e.g., "5.4") is in this set.
+    ///
+    /// LuaJIT names such as "luajit-2.1" or "openresty-luajit-2.1-20231117"
+    /// select LuaJIT rather than 5.1.
     pub fn contains_version_str(&self, version: &str) -> bool {
+        if version.to_ascii_lowercase().contains("luajit") {
+            return self.contains(Self::LUAJIT);
+        }
         match version {
             "5.1" => self.contains(Self::LUA_5_1),
             "5.2" => self.contains(Self::LUA_5_2),
@@ -181,6 +188,12 @@ mod tests {
         assert!(set.contains_version_str("5.5"));
         assert!(!set.contains_version_str("5.3"));
         assert!(!set.contains_version_str("5.1"));
+        assert!(!set.contains_version_str("luajit-2.1"));
+
+        let jit = LuaVersionSet::from_bits(LuaVersionSet::LUAJIT);
+        assert!(jit.contains_version_str("luajit-2.1"));
+        assert!(jit.contains_version_str("openresty-luajit-2.1-20231117"));
+        assert!(!jit.contains_version_str("5.1"));
     }
 
     #[test]
@@ -91,6 +91,16 @@ pub fn scan_file(path: &Path) -> DepotResult<FileResult> {
 
 /// Scan a source string for version-specific features.
 fn scan_source(source: &str) -> DepotResult<Vec<DetectedFeature>> {
+    let mut visitor = FeatureVisitor {
+        detected: Vec::new(),
+        seen: std::collections::HashSet::new(),
+    };
+    walk_source(source, &mut visitor)?;
+    Ok(visitor.detected)
+}
+
+/// Parse a source string and walk every statement with `visitor`.
+pub(crate) fn walk_source(source: &str, visitor: &mut dyn AstVisitor) -> DepotResult<()> {
     let arena = Bump::new();
     let mut container = DiContainer::new();
     container.register(
@@ -114,18 +124,18 @@ fn scan_source(source: &str) -> DepotResult<Vec<DetectedFeature>> {
         .parse()
         .map_err(|e| DepotError::Package(format!("Parse error: {}", e)))?;
 
-    let mut detected = Vec::new();
-    let mut seen = std::collections::HashSet::new();
-
     for stmt in program.statements {
-        walk_statement(stmt, &interner, &mut detected, &mut seen);
+        walk_statement(stmt, &interner, visitor);
     }
 
-    Ok(detected)
+    Ok(())
 }
 
 /// Resolve a callee expression to a dotted name like "table.move" or "setfenv".
-fn resolve_dotted_name(expr: &Expression<'_>, interner: &StringInterner) -> Option<String> {
+pub(crate) fn resolve_dotted_name(
+    expr: &Expression<'_>,
+    interner: &StringInterner,
+) -> Option<String> {
     match &expr.kind {
         ExpressionKind::Identifier(id) => Some(interner.resolve(*id)),
         ExpressionKind::Member(obj, ident) => {
@@ -138,7 +148,7 @@ fn resolve_dotted_name(expr: &Expression<'_>, interner: &StringInterner) -> Opti
 }
 
 /// Check if a call expression is `require("module_name")` and return the module name.
-fn extract_require_arg<'a>(
+pub(crate) fn extract_require_arg<'a>(
     callee: &Expression<'a>,
     args: &[Argument<'a>],
     interner: &StringInterner,
@@ -157,116 +167,190 @@ fn extract_require_arg<'a>(
     None
 }
 
-/// Record a detected feature, deduplicating by (name, line).
-fn record_feature(
-    info: FeatureInfo,
-    span: &luanext_parser::Span,
-    detected: &mut Vec<DetectedFeature>,
-    seen: &mut std::collections::HashSet<(String, u32)>,
-) {
-    let key = (info.name.to_string(), span.line);
-    if seen.insert(key) {
-        detected.push(DetectedFeature {
-            info,
-            line: span.line,
-            column: span.column,
-        });
+// ===== Feature Detection =====
+
+/// Collects version-specific features, deduplicated by (name, line).
+struct FeatureVisitor {
+    detected: Vec<DetectedFeature>,
+    seen: std::collections::HashSet<(String, u32)>,
+}
+
+impl FeatureVisitor {
+    fn record(&mut self, info: FeatureInfo, span: &luanext_parser::Span) {
+        let key = (info.name.to_string(), span.line);
+        if self.seen.insert(key) {
+            self.detected.push(DetectedFeature {
+                info,
+                line: span.line,
+                column: span.column,
+            });
+        }
+    }
+}
+
+impl AstVisitor for FeatureVisitor {
+    fn visit_statement(&mut self, stmt: &Statement<'_>, _interner: &StringInterner) {
+        match stmt {
+            Statement::Goto(goto) => self.record(compat_db::SYNTAX_GOTO, &goto.span),
+            Statement::Label(label) => self.record(compat_db::SYNTAX_LABEL, &label.span),
+            _ => {}
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'_>, interner: &StringInterner) {
+        match &expr.kind {
+            // Function calls — the primary detection mechanism
+            ExpressionKind::Call(callee, args, _) => {
+                // Check for require("module") calls
+                if let Some(module_name) = extract_require_arg(callee, args, interner) {
+                    if let Some(info) = compat_db::lookup_require(&module_name) {
+                        self.record(info, &expr.span);
+                    }
+                }
+
+                // Check callee for version-specific function
+                if let Some(name) = resolve_dotted_name(callee, interner) {
+                    if let Some(info) = compat_db::lookup_function(&name) {
+                        self.record(info, &expr.span);
+                    }
+                }
+            }
+
+            // Member access: check for version-specific constants (e.g., math.maxinteger)
+            ExpressionKind::Member(..) => {
+                if let Some(name) = resolve_dotted_name(expr, interner) {
+                    if let Some(info) = compat_db::lookup_function(&name) {
+                        // Only record constants (not function calls — those are caught by Call)
+                        if info.category == FeatureCategory::StdlibAdded
+                            || info.category == FeatureCategory::LuaJitExtension
+                        {
+                            self.record(info, &expr.span);
+                        }
+                    }
+                }
+            }
+
+            // Binary operators — detect version-specific operators
+            ExpressionKind::Binary(op, ..) => {
+                let syntax_feature = match op {
+                    BinaryOp::IntegerDivide => Some(compat_db::SYNTAX_INTEGER_DIVIDE),
+                    BinaryOp::BitwiseAnd => Some(compat_db::SYNTAX_BITWISE_AND),
+                    BinaryOp::BitwiseOr => Some(compat_db::SYNTAX_BITWISE_OR),
+                    BinaryOp::BitwiseXor => Some(compat_db::SYNTAX_BITWISE_XOR),
+                    BinaryOp::ShiftLeft => Some(compat_db::SYNTAX_SHIFT_LEFT),
+                    BinaryOp::ShiftRight => Some(compat_db::SYNTAX_SHIFT_RIGHT),
+                    _ => None,
+                };
+                if let Some(info) = syntax_feature {
+                    self.record(info, &expr.span);
+                }
+            }
+
+            // Unary operators
+            ExpressionKind::Unary(op, _) => {
+                if *op == UnaryOp::BitwiseNot {
+                    self.record(compat_db::SYNTAX_BITWISE_NOT, &expr.span);
+                }
+            }
+
+            _ => {}
+        }
     }
 }
 
 // ===== AST Walking =====
 
-fn walk_statement(
-    stmt: &Statement<'_>,
-    interner: &StringInterner,
-    detected: &mut Vec<DetectedFeature>,
-    seen: &mut std::collections::HashSet<(String, u32)>,
-) {
+/// Callbacks invoked for every statement and expression in a program.
+///
+/// The walker visits a node before descending into its children.
+pub(crate) trait AstVisitor {
+    fn visit_statement(&mut self, _stmt: &Statement<'_>, _interner: &StringInterner) {}
+    fn visit_expression(&mut self, _expr: &Expression<'_>, _interner: &StringInterner) {}
+}
+
+fn walk_statement(stmt: &Statement<'_>, interner: &StringInterner, visitor: &mut dyn AstVisitor) {
+    visitor.visit_statement(stmt, interner);
     match stmt {
-        Statement::Goto(goto) => {
-            record_feature(compat_db::SYNTAX_GOTO, &goto.span, detected, seen);
-        }
-        Statement::Label(label) => {
-            record_feature(compat_db::SYNTAX_LABEL, &label.span, detected, seen);
-        }
         Statement::Variable(decl) => {
-            walk_expression(&decl.initializer, interner, detected, seen);
+            walk_expression(&decl.initializer, interner, visitor);
         }
         Statement::Function(decl) => {
-            walk_block(&decl.body, interner, detected, seen);
+            walk_block(&decl.body, interner, visitor);
         }
         Statement::If(stmt) => {
-            walk_expression(&stmt.condition, interner, detected, seen);
-            walk_block(&stmt.then_block, interner, detected, seen);
+            walk_expression(&stmt.condition, interner, visitor);
+            walk_block(&stmt.then_block, interner, visitor);
             for else_if in stmt.else_ifs.iter() {
-                walk_expression(&else_if.condition, interner, detected, seen);
-                walk_block(&else_if.block, interner, detected, seen);
+                walk_expression(&else_if.condition, interner, visitor);
+                walk_block(&else_if.block, interner, visitor);
             }
             if let Some(else_block) = &stmt.else_block {
-                walk_block(else_block, interner, detected, seen);
+                walk_block(else_block, interner, visitor);
             }
         }
         Statement::While(stmt) => {
-            walk_expression(&stmt.condition, interner, detected, seen);
-            walk_block(&stmt.body, interner, detected, seen);
+            walk_expression(&stmt.condition, interner, visitor);
+            walk_block(&stmt.body, interner, visitor);
         }
         Statement::For(for_stmt) => match for_stmt {
             ForStatement::Numeric(num) => {
-                walk_expression(&num.start, interner, detected, seen);
-                walk_expression(&num.end, interner, detected, seen);
+                walk_expression(&num.start, interner, visitor);
+                walk_expression(&num.end, interner, visitor);
                 if let Some(step) = &num.step {
-                    walk_expression(step, interner, detected, seen);
+                    walk_expression(step, interner, visitor);
                 }
-                walk_block(&num.body, interner, detected, seen);
+                walk_block(&num.body, interner, visitor);
             }
             ForStatement::Generic(gen) => {
                 for iter_expr in gen.iterators.iter() {
-                    walk_expression(iter_expr, interner, detected, seen);
+                    walk_expression(iter_expr, interner, visitor);
                 }
-                walk_block(&gen.body, interner, detected, seen);
+                walk_block(&gen.body, interner, visitor);
             }
         },
         Statement::Repeat(stmt) => {
-            walk_block(&stmt.body, interner, detected, seen);
-            walk_expression(&stmt.until, interner, detected, seen);
+            walk_block(&stmt.body, interner, visitor);
+            walk_expression(&stmt.until, interner, visitor);
         }
         Statement::Return(stmt) => {
             for value in stmt.values.iter() {
-                walk_expression(value, interner, detected, seen);
+                walk_expression(value, interner, visitor);
             }
         }
         Statement::Expression(expr) => {
-            walk_expression(expr, interner, detected, seen);
+            walk_expression(expr, interner, visitor);
         }
         Statement::Block(block) => {
-            walk_block(block, interner, detected, seen);
+            walk_block(block, interner, visitor);
         }
         Statement::Class(decl) => {
             for member in decl.members.iter() {
-                walk_class_member(member, interner, detected, seen);
+                walk_class_member(member, interner, visitor);
             }
         }
         Statement::Try(try_stmt) => {
-            walk_block(&try_stmt.try_block, interner, detected, seen);
+            walk_block(&try_stmt.try_block, interner, visitor);
             for clause in try_stmt.catch_clauses.iter() {
-                walk_block(&clause.body, interner, detected, seen);
+                walk_block(&clause.body, interner, visitor);
             }
             if let Some(finally) = &try_stmt.finally_block {
-                walk_block(finally, interner, detected, seen);
+                walk_block(finally, interner, visitor);
             }
         }
         Statement::Throw(throw) => {
-            walk_expression(&throw.expression, interner, detected, seen);
+            walk_expression(&throw.expression, interner, visitor);
         }
         Statement::Export(export) => {
             if let ExportKind::Declaration(stmt) = &export.kind {
-                walk_statement(stmt, interner, detected, seen);
+                walk_statement(stmt, interner, visitor);
             } else if let ExportKind::Default(expr) = &export.kind {
-                walk_expression(expr, interner, detected, seen);
+                walk_expression(expr, interner, visitor);
             }
         }
         // Statements that don't contain expressions or sub-statements we need to check
-        Statement::Break(_)
+        Statement::Goto(_)
+        | Statement::Label(_)
+        | Statement::Break(_)
         | Statement::Continue(_)
         | Statement::Rethrow(_)
         | Statement::Import(_)
@@ -282,138 +366,88 @@ fn walk_statement(
     }
 }
 
-fn walk_block(
-    block: &Block<'_>,
-    interner: &StringInterner,
-    detected: &mut Vec<DetectedFeature>,
-    seen: &mut std::collections::HashSet<(String, u32)>,
-) {
+fn walk_block(block: &Block<'_>, interner: &StringInterner, visitor: &mut dyn AstVisitor) {
     for stmt in block.statements.iter() {
-        walk_statement(stmt, interner, detected, seen);
+        walk_statement(stmt, interner, visitor);
     }
 }
 
 fn walk_class_member(
     member: &ClassMember<'_>,
     interner: &StringInterner,
-    detected: &mut Vec<DetectedFeature>,
-    seen: &mut std::collections::HashSet<(String, u32)>,
+    visitor: &mut dyn AstVisitor,
 ) {
     match member {
         ClassMember::Method(method) => {
             if let Some(body) = &method.body {
-                walk_block(body, interner, detected, seen);
+                walk_block(body, interner, visitor);
             }
         }
         ClassMember::Constructor(ctor) => {
-            walk_block(&ctor.body, interner, detected, seen);
+            walk_block(&ctor.body, interner, visitor);
         }
         ClassMember::Property(prop) => {
             if let Some(init) = &prop.initializer {
-                walk_expression(init, interner, detected, seen);
+                walk_expression(init, interner, visitor);
             }
         }
         ClassMember::Getter(getter) => {
-            walk_block(&getter.body, interner, detected, seen);
+            walk_block(&getter.body, interner, visitor);
         }
         ClassMember::Setter(setter) => {
-            walk_block(&setter.body, interner, detected, seen);
+            walk_block(&setter.body, interner, visitor);
         }
         ClassMember::Operator(op) => {
-            walk_block(&op.body, interner, detected, seen);
+            walk_block(&op.body, interner, visitor);
         }
     }
 }
 
-fn walk_expression(
-    expr: &Expression<'_>,
-    interner: &StringInterner,
-    detected: &mut Vec<DetectedFeature>,
-    seen: &mut std::collections::HashSet<(String, u32)>,
-) {
+fn walk_expression(expr: &Expression<'_>, interner: &StringInterner, visitor: &mut dyn AstVisitor) {
+    visitor.visit_expression(expr, interner);
     match &expr.kind {
-        // Function calls — the primary detection mechanism
+        // Function calls
         ExpressionKind::Call(callee, args, _) => {
-            // Check for require("module") calls
-            if let Some(module_name) = extract_require_arg(callee, args, interner) {
-                if let Some(info) = compat_db::lookup_require(&module_name) {
-                    record_feature(info, &expr.span, detected, seen);
-                }
-            }
-
-            // Check callee for version-specific function
-            if let Some(name) = resolve_dotted_name(callee, interner) {
-                if let Some(info) = compat_db::lookup_function(&name) {
-                    record_feature(info, &expr.span, detected, seen);
-                }
-            }
-
-            // Walk callee and args recursively
-            walk_expression(callee, interner, detected, seen);
+            walk_expression(callee, interner, visitor);
             for arg in args.iter() {
-                walk_expression(&arg.value, interner, detected, seen);
+                walk_expression(&arg.value, interner, visitor);
             }
         }
 
         // Method calls: obj:method(...)
         ExpressionKind::MethodCall(receiver, _method, args, _) => {
-            walk_expression(receiver, interner, detected, seen);
+            walk_expression(receiver, interner, visitor);
             for arg in args.iter() {
-                walk_expression(&arg.value, interner, detected, seen);
+                walk_expression(&arg.value, interner, visitor);
             }
         }
 
-        // Member access: check for version-specific constants (e.g., math.maxinteger)
+        // Member access
         ExpressionKind::Member(obj, _ident) => {
-            if let Some(name) = resolve_dotted_name(expr, interner) {
-                if let Some(info) = compat_db::lookup_function(&name) {
-                    // Only record constants (not function calls — those are caught by Call)
-                    if info.category == FeatureCategory::StdlibAdded
-                        || info.category == FeatureCategory::LuaJitExtension
-                    {
-                        record_feature(info, &expr.span, detected, seen);
-                    }
-                }
-            }
-            walk_expression(obj, interner, detected, seen);
-        }
-
-        // Binary operators — detect version-specific operators
-        ExpressionKind::Binary(op, left, right) => {
-            let syntax_feature = match op {
-                BinaryOp::IntegerDivide => Some(compat_db::SYNTAX_INTEGER_DIVIDE),
-                BinaryOp::BitwiseAnd => Some(compat_db::SYNTAX_BITWISE_AND),
-                BinaryOp::BitwiseOr => Some(compat_db::SYNTAX_BITWISE_OR),
-                BinaryOp::BitwiseXor => Some(compat_db::SYNTAX_BITWISE_XOR),
-                BinaryOp::ShiftLeft => Some(compat_db::SYNTAX_SHIFT_LEFT),
-                BinaryOp::ShiftRight => Some(compat_db::SYNTAX_SHIFT_RIGHT),
-                _ => None,
-            };
-            if let Some(info) = syntax_feature {
-                record_feature(info, &expr.span, detected, seen);
-            }
-            walk_expression(left, interner, detected, seen);
-            walk_expression(right, interner, detected, seen);
+            walk_expression(obj, interner, visitor);
+        }
+
+        // Binary operators
+        ExpressionKind::Binary(_op, left, right) => {
+            walk_expression(left, interner, visitor);
+            walk_expression(right, interner, visitor);
         }
 
         // Unary operators
-        ExpressionKind::Unary(op, operand) => {
-            if *op == UnaryOp::BitwiseNot {
-                record_feature(compat_db::SYNTAX_BITWISE_NOT, &expr.span, detected, seen);
-            }
-            walk_expression(operand, interner, detected, seen);
+        ExpressionKind::Unary(_op, operand) => {
+            walk_expression(operand, interner, visitor);
         }
 
         // Assignment — walk both sides
         ExpressionKind::Assignment(target, _op, value) => {
-            walk_expression(target, interner, detected, seen);
-            walk_expression(value, interner, detected, seen);
+            walk_expression(target, interner, visitor);
+            walk_expression(value, interner, visitor);
         }
 
         // Index: table[key]
         ExpressionKind::Index(obj, key) => {
-            walk_expression(obj, interner, detected, seen);
-            walk_expression(key, interner, detected, seen);
+            walk_expression(obj, interner, visitor);
+            walk_expression(key, interner, visitor);
         }
 
         // Array literal
@@ -421,7 +455,7 @@ fn walk_expression(
             for elem in elements.iter() {
                 match elem {
                     ArrayElement::Expression(e) | ArrayElement::Spread(e) => {
-                        walk_expression(e, interner, detected, seen);
+                        walk_expression(e, interner, visitor);
                     }
                 }
             }
@@ -432,14 +466,14 @@ fn walk_expression(
             for prop in props.iter() {
                 match prop {
                     ObjectProperty::Property { value, .. } => {
-                        walk_expression(value, interner, detected, seen);
+                        walk_expression(value, interner, visitor);
                     }
                     ObjectProperty::Computed { key, value, .. } => {
-                        walk_expression(key, interner, detected, seen);
-                        walk_expression(value, interner, detected, seen);
+                        walk_expression(key, interner, visitor);
+                        walk_expression(value, interner, visitor);
                     }
                     ObjectProperty::Spread { value, .. } => {
-                        walk_expression(value, interner, detected, seen);
+                        walk_expression(value, interner, visitor);
                     }
                 }
             }
@@ -447,89 +481,89 @@ fn walk_expression(
 
         // Function expression
         ExpressionKind::Function(func) => {
-            walk_block(&func.body, interner, detected, seen);
+            walk_block(&func.body, interner, visitor);
         }
 
         // Arrow function
         ExpressionKind::Arrow(arrow) => match &arrow.body {
-            ArrowBody::Expression(e) => walk_expression(e, interner, detected, seen),
-            ArrowBody::Block(block) => walk_block(block, interner, detected, seen),
+            ArrowBody::Expression(e) => walk_expression(e, interner, visitor),
+            ArrowBody::Block(block) => walk_block(block, interner, visitor),
         },
 
         // Conditional (ternary)
         ExpressionKind::Conditional(cond, then, else_) => {
-            walk_expression(cond, interner, detected, seen);
-            walk_expression(then, interner, detected, seen);
-            walk_expression(else_, interner, detected, seen);
+            walk_expression(cond, interner, visitor);
+            walk_expression(then, interner, visitor);
+            walk_expression(else_, interner, visitor);
         }
 
         // Parenthesized
         ExpressionKind::Parenthesized(inner) => {
-            walk_expression(inner, interner, detected, seen);
+            walk_expression(inner, interner, visitor);
         }
 
         // Pipe operator
         ExpressionKind::Pipe(left, right) => {
-            walk_expression(left, interner, detected, seen);
-            walk_expression(right, interner, detected, seen);
+            walk_expression(left, interner, visitor);
+            walk_expression(right, interner, visitor);
         }
 
         // Match expression
         ExpressionKind::Match(match_expr) => {
-            walk_expression(match_expr.value, interner, detected, seen);
+            walk_expression(match_expr.value, interner, visitor);
             for arm in match_expr.arms.iter() {
                 if let Some(guard) = &arm.guard {
-                    walk_expression(guard, interner, detected, seen);
+                    walk_expression(guard, interner, visitor);
                 }
                 match &arm.body {
-                    MatchArmBody::Expression(e) => walk_expression(e, interner, detected, seen),
-                    MatchArmBody::Block(block) => walk_block(block, interner, detected, seen),
+                    MatchArmBody::Expression(e) => walk_expression(e, interner, visitor),
+                    MatchArmBody::Block(block) => walk_block(block, interner, visitor),
                 }
             }
         }
 
         // Type assertion
         ExpressionKind::TypeAssertion(inner, _) => {
-            walk_expression(inner, interner, detected, seen);
+            walk_expression(inner, interner, visitor);
         }
 
         // New expression
         ExpressionKind::New(callee, args, _) => {
-            walk_expression(callee, interner, detected, seen);
+            walk_expression(callee, interner, visitor);
             for arg in args.iter() {
-                walk_expression(&arg.value, interner, detected, seen);
+                walk_expression(&arg.value, interner, visitor);
             }
         }
 
         // Optional chaining variants
         ExpressionKind::OptionalMember(obj, _) | ExpressionKind::OptionalIndex(obj, _) => {
-            walk_expression(obj, interner, detected, seen);
+            walk_expression(obj, interner, visitor);
         }
         ExpressionKind::OptionalCall(callee, args, _)
         | ExpressionKind::OptionalMethodCall(callee, _, args, _) => {
-            walk_expression(callee, interner, detected, seen);
+            walk_expression(callee, interner, visitor);
             for arg in args.iter() {
-                walk_expression(&arg.value, interner, detected, seen);
+                walk_expression(&arg.value, interner, visitor);
             }
         }
 
         // Try expression
         ExpressionKind::Try(try_expr) => {
-            walk_expression(try_expr.expression, interner, detected, seen);
-            walk_expression(try_expr.catch_expression, interner, detected, seen);
+            walk_expression(try_expr.expression, interner, visitor);
+            walk_expression(try_expr.catch_expression, interner, visitor);
         }
 
         // Error chain
         ExpressionKind::ErrorChain(left, right) => {
-            walk_expression(left, interner, detected, seen);
-            walk_expression(right, interner, detected, seen);
+            walk_expression(left, interner, visitor);
+            walk_expression(right, interner, visitor);
         }
 
         // Template literal
         ExpressionKind::Template(template) => {
             for part in template.parts.iter() {
                 if let luanext_parser::ast::expression::TemplatePart::Expression(e) = part {
-                    walk_expression(e, interner, detected, seen);
+                    walk_expression(e, interner, visitor);
                 }
             }
         }
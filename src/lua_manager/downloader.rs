@@ -1,14 +1,49 @@
+use crate::config::LuaBinarySource;
 use crate::core::{DepotError, DepotResult};
+use crate::lua_manager::checksums::{self, CHECKSUM_FILE};
 use crate::lua_manager::versions;
 use reqwest::Client;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where official Lua source releases are published
+pub const LUA_SOURCE_URL: &str = "https://www.lua.org/ftp";
+
+/// Where LuaJIT commit snapshots are downloaded
+///
+/// LuaJIT 2.1 is a rolling release without tags, so only a commit names a
+/// fixed source tree: `luajit-<commit>` downloads `<commit>.tar.gz` from here.
+pub const LUAJIT_SOURCE_URL: &str = "https://github.com/LuaJIT/LuaJIT/archive";
+
+/// Where OpenResty tags the releases of its LuaJIT fork
+pub const OPENRESTY_LUAJIT_SOURCE_URL: &str =
+    "https://github.com/openresty/luajit2/archive/refs/tags";
+
+/// OpenResty LuaJIT release installed for `openresty-luajit`
+pub const OPENRESTY_LUAJIT_RELEASE: &str = "2.1-20231117";
+
+/// Whether a managed version is LuaJIT (`luajit-2.1`, `openresty-luajit-2.1-<date>`)
+pub fn is_luajit(version: &str) -> bool {
+    version.starts_with("luajit-") || version.starts_with("openresty-luajit-")
+}
+
+/// File name of the source release of a version in the download cache
+pub fn source_tarball_name(version: &str) -> String {
+    if is_luajit(version) {
+        format!("{}.tar.gz", version)
+    } else {
+        format!("lua-{}.tar.gz", version)
+    }
+}
 
 pub struct LuaDownloader {
     client: Client,
     cache_dir: PathBuf,
     default_source_url: String,
-    version_sources: HashMap<String, String>,
+    version_sources: HashMap<String, LuaBinarySource>,
+    source_tarball_url: String,
+    strict_checksums: bool,
 }
 
 impl LuaDownloader {
@@ -25,17 +60,140 @@ impl LuaDownloader {
             cache_dir,
             default_source_url,
             version_sources,
+            source_tarball_url: LUA_SOURCE_URL.to_string(),
+            strict_checksums: config.strict_lua_checksums,
         })
     }
 
+    /// Create a downloader with explicit sources instead of the global config
+    pub fn with_sources(
+        cache_dir: PathBuf,
+        default_source_url: String,
+        version_sources: HashMap<String, LuaBinarySource>,
+        strict_checksums: bool,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            cache_dir,
+            default_source_url,
+            version_sources,
+            source_tarball_url: LUA_SOURCE_URL.to_string(),
+            strict_checksums,
+        }
+    }
+
+    /// Fetch source releases from a mirror instead of lua.org
+    pub fn with_source_tarball_url(mut self, url: String) -> Self {
+        self.source_tarball_url = url;
+        self
+    }
+
+    /// URL of the source release of a version
+    ///
+    /// LuaJIT comes from a commit snapshot, OpenResty's release tag or the
+    /// `url` configured for the version; everything else from lua.org or the
+    /// configured mirror.
+    fn source_release_url(&self, version: &str) -> DepotResult<String> {
+        if is_luajit(version) {
+            if let Some(url) = self.version_sources.get(version).and_then(|s| s.url()) {
+                return Ok(url.to_string());
+            }
+        }
+
+        if let Some(release) = version.strip_prefix("openresty-luajit-") {
+            Ok(format!(
+                "{}/v{}.tar.gz",
+                OPENRESTY_LUAJIT_SOURCE_URL, release
+            ))
+        } else if let Some(commit) = version.strip_prefix("luajit-") {
+            if commit.len() != 40 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(DepotError::Package(format!(
+                    "LuaJIT {} is a moving branch. Install a commit with \
+                     'depot lua install luajit-<commit>', or set lua_binary_sources.{}.url \
+                     to a commit tarball such as {}/<commit>.tar.gz",
+                    commit, version, LUAJIT_SOURCE_URL
+                )));
+            }
+            Ok(format!("{}/{}.tar.gz", LUAJIT_SOURCE_URL, commit))
+        } else {
+            Ok(format!(
+                "{}/{}",
+                self.source_tarball_url,
+                source_tarball_name(version)
+            ))
+        }
+    }
+
     /// Get the source URL for a specific version
     fn get_source_url(&self, version: &str) -> &str {
         self.version_sources
             .get(version)
-            .map(|s| s.as_str())
+            .and_then(|s| s.url())
             .unwrap_or(&self.default_source_url)
     }
 
+    /// Expected SHA-256 from the config or the pinned table, with where it came from
+    fn known_checksum(&self, version: &str, filename: &str) -> Option<(String, &'static str)> {
+        if let Some(hash) = self
+            .version_sources
+            .get(version)
+            .and_then(|s| s.sha256(filename))
+        {
+            return Some((hash.to_lowercase(), "lua_binary_sources"));
+        }
+        checksums::pinned_checksum(version, filename).map(|h| (h.to_string(), "pinned checksum"))
+    }
+
+    /// Expected SHA-256 for a download, falling back to the source's SHA256SUMS
+    async fn expected_checksum(
+        &self,
+        version: &str,
+        filename: &str,
+    ) -> DepotResult<Option<(String, &'static str)>> {
+        if let Some(known) = self.known_checksum(version, filename) {
+            return Ok(Some(known));
+        }
+
+        let url = format!("{}/{}", self.get_source_url(version), CHECKSUM_FILE);
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+        let content = response.text().await?;
+        Ok(checksums::parse_checksum_file(&content, filename).map(|h| (h, CHECKSUM_FILE)))
+    }
+
+    /// Sidecar file recording the verified hash of a cached binary
+    fn hash_record(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".sha256");
+        path.with_file_name(name)
+    }
+
+    /// Re-verify a cached binary against its expected or recorded hash
+    fn verify_cached(&self, version: &str, filename: &str, path: &Path) -> DepotResult<()> {
+        let expected = match self.known_checksum(version, filename) {
+            Some((hash, _)) => hash,
+            None => fs::read_to_string(Self::hash_record(path))
+                .map(|h| h.trim().to_lowercase())
+                .map_err(|_| {
+                    DepotError::Package(format!(
+                        "Cached {} for Lua {} has no recorded checksum",
+                        filename, version
+                    ))
+                })?,
+        };
+
+        let actual = checksums::sha256_file(path)?;
+        if actual != expected {
+            return Err(DepotError::Package(format!(
+                "Cached {} for Lua {} does not match its checksum (expected {}, got {})",
+                filename, version, expected, actual
+            )));
+        }
+        Ok(())
+    }
+
     /// Get the binary filename for current platform
     ///
     /// Extracts major.minor from version (e.g., "5.4.8" -> "54")
@@ -84,46 +242,181 @@ impl LuaDownloader {
     }
 
     /// Download Lua binary from configured source
+    ///
+    /// The download is checked against the expected SHA-256 before it is
+    /// written to the cache or made executable, and a cached binary is
+    /// re-verified each time it is used.
     pub async fn download_binary(&self, version: &str, binary: &str) -> DepotResult<PathBuf> {
-        let filename = self.get_binary_name(version, binary)?;
+        self.try_download_binary(version, binary)
+            .await?
+            .ok_or_else(|| {
+                DepotError::Package(format!(
+                    "No verified Lua {} binary is available for this platform from source: {}\n\
+                     Available versions: 5.1.5, 5.3.6, 5.4.8\n\
+                     Build it from source with: depot lua install {} --from-source\n\
+                     Or set a version-specific source with: depot config set lua_binary_sources.{} <url>",
+                    version,
+                    self.get_source_url(version),
+                    version,
+                    version
+                ))
+            })
+    }
+
+    /// Download a Lua binary, or `None` if the source has none for this
+    /// version and platform, or none that can be verified
+    ///
+    /// Binaries are never installed without a configured, pinned or published
+    /// checksum; without one the caller builds from the pinned source release
+    /// instead. A checksum mismatch is still an error, so callers never fall
+    /// back to another source when a download has been tampered with.
+    pub async fn try_download_binary(
+        &self,
+        version: &str,
+        binary: &str,
+    ) -> DepotResult<Option<PathBuf>> {
+        let Ok(filename) = self.get_binary_name(version, binary) else {
+            return Ok(None);
+        };
         let source_url = self.get_source_url(version);
         let url = format!("{}/{}", source_url, filename);
 
-        let dest_path = self.cache_dir.join(&filename);
-
-        // Return cached if exists
-        if dest_path.exists() {
-            return Ok(dest_path);
+        // Return cached if it still matches its checksum
+        if let Some(cached) = self.cached(version, &filename)? {
+            return Ok(Some(cached));
         }
 
+        let Some(expected) = self.expected_checksum(version, &filename).await? else {
+            eprintln!(
+                "⚠ No checksum available for {} (Lua {}): add its sha256 under lua_binary_sources.{} \
+                 or publish {} at {}",
+                filename, version, version, CHECKSUM_FILE, source_url
+            );
+            return Ok(None);
+        };
+
         println!("Downloading {} for Lua {}...", binary, version);
 
         let response = self.client.get(&url).send().await?;
         if !response.status().is_success() {
-            return Err(DepotError::Package(format!(
-                "Lua {} is not available for this platform from source: {}\n\
-                 Available versions: 5.1.5, 5.3.6, 5.4.8\n\
-                 You can set a version-specific source with: depot config set lua_binary_sources.{} <url>",
-                version,
-                source_url,
-                version
-            )));
+            return Ok(None);
         }
 
         let bytes = response.bytes().await?;
-        std::fs::create_dir_all(&self.cache_dir)?;
-        std::fs::write(&dest_path, &bytes)?;
+        let dest_path = self.store_verified(version, &filename, &url, &bytes, Some(expected))?;
 
         // Make executable on Unix
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = std::fs::metadata(&dest_path)?.permissions();
+            let mut perms = fs::metadata(&dest_path)?.permissions();
             perms.set_mode(0o755);
-            std::fs::set_permissions(&dest_path, perms)?;
+            fs::set_permissions(&dest_path, perms)?;
         }
 
         println!("✓ Downloaded {}", binary);
+        Ok(Some(dest_path))
+    }
+
+    /// Download the official source release of a Lua version
+    ///
+    /// The tarball is verified like a binary, except that lua.org publishes
+    /// no `SHA256SUMS`: its hash comes from `lua_binary_sources` or the
+    /// pinned table, and is otherwise recorded on first download. GitHub
+    /// archives of LuaJIT are not published by a release process, so they
+    /// are refused without a configured or pinned hash.
+    pub async fn download_source(&self, version: &str) -> DepotResult<PathBuf> {
+        let filename = source_tarball_name(version);
+        if let Some(cached) = self.cached(version, &filename)? {
+            return Ok(cached);
+        }
+
+        let url = self.source_release_url(version)?;
+        let expected = self.known_checksum(version, &filename);
+        if expected.is_none() && (self.strict_checksums || is_luajit(version)) {
+            return Err(DepotError::Package(format!(
+                "No checksum available for {} ({}). Add its sha256 under lua_binary_sources.{}",
+                filename, url, version
+            )));
+        }
+
+        println!("Downloading source for Lua {}...", version);
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(DepotError::Package(format!(
+                "Failed to download Lua {} source from {}: HTTP {}",
+                version,
+                url,
+                response.status()
+            )));
+        }
+
+        let bytes = response.bytes().await?;
+        self.store_verified(version, &filename, &url, &bytes, expected)
+    }
+
+    /// Cached download of `filename`, if it still matches its checksum
+    ///
+    /// A cached file that fails verification is removed so it is downloaded again.
+    fn cached(&self, version: &str, filename: &str) -> DepotResult<Option<PathBuf>> {
+        let path = self.cache_dir.join(version).join(filename);
+        if !path.exists() {
+            return Ok(None);
+        }
+        match self.verify_cached(version, filename, &path) {
+            Ok(()) => Ok(Some(path)),
+            Err(e) => {
+                eprintln!("⚠ {}; downloading it again", e);
+                fs::remove_file(&path)?;
+                let _ = fs::remove_file(Self::hash_record(&path));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Check a download against its expected hash and write it to the cache
+    ///
+    /// Without an expected hash the download is refused, unless
+    /// `strict_lua_checksums` was turned off, in which case its hash is
+    /// recorded on first use.
+    fn store_verified(
+        &self,
+        version: &str,
+        filename: &str,
+        url: &str,
+        bytes: &[u8],
+        expected: Option<(String, &'static str)>,
+    ) -> DepotResult<PathBuf> {
+        let actual = checksums::sha256_hex(bytes);
+
+        match expected {
+            Some((expected, origin)) if expected != actual => {
+                return Err(DepotError::Package(format!(
+                    "Checksum mismatch for {} (Lua {}) from {}: expected {} ({}), got {}",
+                    filename, version, url, expected, origin, actual
+                )));
+            }
+            Some(_) => println!("  ✓ Checksum verified"),
+            None if self.strict_checksums => {
+                return Err(DepotError::Package(format!(
+                    "No checksum available for {} (Lua {}). Add its sha256 under lua_binary_sources.{}",
+                    filename, version, version
+                )));
+            }
+            None => eprintln!(
+                "⚠ No checksum available for {} (Lua {}); recording sha256 {} for later verification",
+                filename, version, actual
+            ),
+        }
+
+        // Write to a temporary name so a partial file is never picked up from the cache
+        let version_dir = self.cache_dir.join(version);
+        let dest_path = version_dir.join(filename);
+        fs::create_dir_all(&version_dir)?;
+        let partial = version_dir.join(format!("{}.part", filename));
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, &dest_path)?;
+        fs::write(Self::hash_record(&dest_path), format!("{}\n", actual))?;
         Ok(dest_path)
     }
 
@@ -139,18 +432,40 @@ impl LuaDownloader {
         ]
     }
 
+    /// Versions whose source releases have pinned checksums
+    ///
+    /// Any release published on lua.org can be built from source; these are
+    /// the ones verified without trusting the first download.
+    pub fn list_source_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = checksums::PINNED_CHECKSUMS
+            .iter()
+            .filter(|p| p.asset == source_tarball_name(p.version))
+            .map(|p| p.version.to_string())
+            .collect();
+        versions.sort();
+        versions.dedup();
+        versions
+    }
+
     /// Resolve version aliases to actual versions
     ///
     /// Handles:
     /// - "latest" -> most recent known version
-    /// - "5.1", "5.3", "5.4" -> latest patch for that minor version
+    /// - "5.1", "5.2", "5.3", "5.4" -> latest patch for that minor version
+    /// - "luajit" -> the LuaJIT 2.1 branch
+    /// - "openresty-luajit", "openresty-luajit-2.1" -> the pinned OpenResty release
     /// - Specific versions pass through unchanged
     pub fn resolve_version(&self, version: &str) -> String {
         match version {
             "latest" => "5.4.8".to_string(),
             "5.1" => "5.1.5".to_string(),
+            "5.2" => "5.2.4".to_string(),
             "5.3" => "5.3.6".to_string(),
             "5.4" => "5.4.8".to_string(),
+            "luajit" => "luajit-2.1".to_string(),
+            "openresty-luajit" | "openresty-luajit-2.1" => {
+                format!("openresty-luajit-{}", OPENRESTY_LUAJIT_RELEASE)
+            }
             _ => version.to_string(), // Pass through - might be a future version
         }
     }
@@ -187,11 +502,35 @@ mod tests {
 
         assert_eq!(downloader.resolve_version("latest"), "5.4.8");
         assert_eq!(downloader.resolve_version("5.1"), "5.1.5");
+        assert_eq!(downloader.resolve_version("5.2"), "5.2.4");
         assert_eq!(downloader.resolve_version("5.3"), "5.3.6");
         assert_eq!(downloader.resolve_version("5.4"), "5.4.8");
         assert_eq!(downloader.resolve_version("5.4.8"), "5.4.8");
     }
 
+    #[test]
+    fn test_offered_versions_have_pinned_sources() {
+        let temp = TempDir::new().unwrap();
+        let downloader = LuaDownloader::new(temp.path().to_path_buf()).unwrap();
+        let sources = downloader.list_source_versions();
+
+        for alias in ["latest", "5.1", "5.2", "5.3", "5.4"] {
+            let version = downloader.resolve_version(alias);
+            assert!(
+                sources.contains(&version),
+                "{} has no pinned source",
+                version
+            );
+        }
+        for version in downloader.list_available_versions() {
+            assert!(
+                sources.contains(&version),
+                "{} has no pinned source",
+                version
+            );
+        }
+    }
+
     #[test]
     fn test_lua_downloader_is_known_version() {
         let temp = TempDir::new().unwrap();
@@ -302,4 +641,295 @@ mod tests {
         let url = downloader.get_source_url("unknown");
         assert_eq!(url, &downloader.default_source_url);
     }
+
+    async fn serve_binary(
+        server: &wiremock::MockServer,
+        filename: &str,
+        body: &[u8],
+        sums: Option<String>,
+    ) {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", filename)))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body.to_vec()))
+            .mount(server)
+            .await;
+        let sums_response = match sums {
+            Some(content) => ResponseTemplate::new(200).set_body_string(content),
+            None => ResponseTemplate::new(404),
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/{}", CHECKSUM_FILE)))
+            .respond_with(sums_response)
+            .mount(server)
+            .await;
+    }
+
+    fn downloader_for(
+        cache: &Path,
+        server: &wiremock::MockServer,
+        sources: HashMap<String, LuaBinarySource>,
+        strict: bool,
+    ) -> LuaDownloader {
+        LuaDownloader::with_sources(cache.to_path_buf(), server.uri(), sources, strict)
+    }
+
+    #[tokio::test]
+    async fn test_download_binary_verified_by_checksum_file() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let downloader = downloader_for(temp.path(), &server, HashMap::new(), true);
+        let filename = downloader.get_binary_name("5.4.8", "lua").unwrap();
+
+        let sums = format!("{}  {}\n", checksums::sha256_hex(b"lua binary"), filename);
+        serve_binary(&server, &filename, b"lua binary", Some(sums)).await;
+
+        let path = downloader.download_binary("5.4.8", "lua").await.unwrap();
+        assert_eq!(path, temp.path().join("5.4.8").join(&filename));
+        assert_eq!(fs::read(&path).unwrap(), b"lua binary");
+        assert!(LuaDownloader::hash_record(&path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_binary_rejects_mismatch() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let downloader = downloader_for(temp.path(), &server, HashMap::new(), false);
+        let filename = downloader.get_binary_name("5.4.8", "lua").unwrap();
+
+        let sums = format!("{}  {}\n", checksums::sha256_hex(b"expected"), filename);
+        serve_binary(&server, &filename, b"tampered", Some(sums)).await;
+
+        let err = downloader
+            .download_binary("5.4.8", "lua")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!temp.path().join("5.4.8").join(&filename).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_binary_configured_hash() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let filename =
+            LuaDownloader::with_sources(PathBuf::new(), String::new(), HashMap::new(), false)
+                .get_binary_name("5.4.8", "lua")
+                .unwrap();
+
+        let mut sources = HashMap::new();
+        sources.insert(
+            "5.4.8".to_string(),
+            LuaBinarySource::Detailed {
+                url: None,
+                sha256: HashMap::from([(filename.clone(), checksums::sha256_hex(b"other"))]),
+            },
+        );
+        let downloader = downloader_for(temp.path(), &server, sources, true);
+        serve_binary(&server, &filename, b"lua binary", None).await;
+
+        let err = downloader
+            .download_binary("5.4.8", "lua")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("lua_binary_sources"));
+    }
+
+    #[tokio::test]
+    async fn test_download_binary_without_checksum() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let filename =
+            LuaDownloader::with_sources(PathBuf::new(), String::new(), HashMap::new(), false)
+                .get_binary_name("5.4.8", "lua")
+                .unwrap();
+        serve_binary(&server, &filename, b"lua binary", None).await;
+
+        // An unverifiable binary is skipped so the install builds from source,
+        // with or without strict mode
+        for strict in [true, false] {
+            let downloader = downloader_for(temp.path(), &server, HashMap::new(), strict);
+            assert!(downloader
+                .try_download_binary("5.4.8", "lua")
+                .await
+                .unwrap()
+                .is_none());
+            let err = downloader
+                .download_binary("5.4.8", "lua")
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("--from-source"));
+        }
+        assert!(!temp.path().join("5.4.8").join(&filename).exists());
+    }
+
+    #[tokio::test]
+    async fn test_try_download_binary_missing() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let downloader = downloader_for(temp.path(), &server, HashMap::new(), false);
+
+        // Nothing is served, so the caller can fall back to a source build
+        assert_eq!(
+            downloader
+                .try_download_binary("5.2.4", "lua")
+                .await
+                .unwrap(),
+            None
+        );
+        let err = downloader
+            .download_binary("5.2.4", "lua")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("--from-source"));
+    }
+
+    #[test]
+    fn test_list_source_versions() {
+        let downloader =
+            LuaDownloader::with_sources(PathBuf::new(), String::new(), HashMap::new(), false);
+        let versions = downloader.list_source_versions();
+        assert!(versions.contains(&"5.2.4".to_string()));
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_luajit_sources() {
+        let downloader =
+            LuaDownloader::with_sources(PathBuf::new(), String::new(), HashMap::new(), false);
+        assert_eq!(downloader.resolve_version("luajit"), "luajit-2.1");
+        assert_eq!(
+            downloader.resolve_version("openresty-luajit"),
+            "openresty-luajit-2.1-20231117"
+        );
+        assert!(is_luajit("luajit-2.1"));
+        assert!(is_luajit("openresty-luajit-2.1-20231117"));
+        assert!(!is_luajit("5.1.5"));
+
+        assert_eq!(source_tarball_name("luajit-2.1"), "luajit-2.1.tar.gz");
+        let commit = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(
+            downloader
+                .source_release_url(&format!("luajit-{}", commit))
+                .unwrap(),
+            format!("https://github.com/LuaJIT/LuaJIT/archive/{}.tar.gz", commit)
+        );
+        assert_eq!(
+            downloader
+                .source_release_url("openresty-luajit-2.1-20231117")
+                .unwrap(),
+            "https://github.com/openresty/luajit2/archive/refs/tags/v2.1-20231117.tar.gz"
+        );
+        assert_eq!(
+            downloader.source_release_url("5.4.8").unwrap(),
+            "https://www.lua.org/ftp/lua-5.4.8.tar.gz"
+        );
+
+        // The 2.1 branch moves, so it needs a configured commit tarball
+        let err = downloader.source_release_url("luajit-2.1").unwrap_err();
+        assert!(err.to_string().contains("moving branch"));
+        let sources = HashMap::from([(
+            "luajit-2.1".to_string(),
+            LuaBinarySource::Url(format!("{}/{}.tar.gz", LUAJIT_SOURCE_URL, commit)),
+        )]);
+        let downloader = LuaDownloader::with_sources(PathBuf::new(), String::new(), sources, false);
+        assert_eq!(
+            downloader.source_release_url("luajit-2.1").unwrap(),
+            format!("https://github.com/LuaJIT/LuaJIT/archive/{}.tar.gz", commit)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_luajit_source_requires_checksum() {
+        let temp = TempDir::new().unwrap();
+        let downloader = LuaDownloader::with_sources(
+            temp.path().to_path_buf(),
+            String::new(),
+            HashMap::new(),
+            false,
+        );
+
+        // Refused before anything is downloaded, even without strict checksums
+        let err = downloader
+            .download_source("openresty-luajit-2.1-20231117")
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Add its sha256 under lua_binary_sources.openresty-luajit-2.1-20231117"));
+        assert!(!temp.path().join("openresty-luajit-2.1-20231117").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_source() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let filename = source_tarball_name("5.4.8");
+        serve_binary(&server, &filename, b"lua source", None).await;
+
+        let mut sources = HashMap::new();
+        sources.insert(
+            "5.4.8".to_string(),
+            LuaBinarySource::Detailed {
+                url: None,
+                sha256: HashMap::from([(filename.clone(), checksums::sha256_hex(b"lua source"))]),
+            },
+        );
+        let downloader = downloader_for(temp.path(), &server, sources, true)
+            .with_source_tarball_url(server.uri());
+
+        let path = downloader.download_source("5.4.8").await.unwrap();
+        assert_eq!(path, temp.path().join("5.4.8").join("lua-5.4.8.tar.gz"));
+        assert_eq!(fs::read(&path).unwrap(), b"lua source");
+
+        // A release the mirror does not have is reported as such
+        let err = downloader.download_source("5.4.7").await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Failed to download Lua 5.4.7 source"));
+    }
+
+    #[tokio::test]
+    async fn test_unpinned_source_is_refused() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let filename = source_tarball_name("5.4.5");
+        serve_binary(&server, &filename, b"lua source", None).await;
+
+        // Refused before anything is downloaded
+        let downloader = downloader_for(temp.path(), &server, HashMap::new(), true)
+            .with_source_tarball_url(server.uri());
+        let err = downloader.download_source("5.4.5").await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Add its sha256 under lua_binary_sources.5.4.5"));
+        assert!(!temp.path().join("5.4.5").exists());
+
+        // Only trusted on first use when strict checksums are turned off
+        let lenient = downloader_for(temp.path(), &server, HashMap::new(), false)
+            .with_source_tarball_url(server.uri());
+        let path = lenient.download_source("5.4.5").await.unwrap();
+        assert!(lenient.verify_cached("5.4.5", &filename, &path).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cached_binary_is_reverified() {
+        let server = wiremock::MockServer::start().await;
+        let temp = TempDir::new().unwrap();
+        let downloader = downloader_for(temp.path(), &server, HashMap::new(), false);
+        let filename = downloader.get_binary_name("5.4.8", "lua").unwrap();
+        let sums = format!("{}  {}\n", checksums::sha256_hex(b"lua binary"), filename);
+        serve_binary(&server, &filename, b"lua binary", Some(sums)).await;
+
+        let path = downloader.download_binary("5.4.8", "lua").await.unwrap();
+        assert!(downloader.verify_cached("5.4.8", &filename, &path).is_ok());
+
+        // Tampering with the cache is detected and the binary is fetched again
+        fs::write(&path, b"tampered").unwrap();
+        assert!(downloader.verify_cached("5.4.8", &filename, &path).is_err());
+        let path = downloader.download_binary("5.4.8", "lua").await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"lua binary");
+    }
 }
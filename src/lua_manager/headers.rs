@@ -0,0 +1,181 @@
+//! C headers for managed Lua versions
+//!
+//! Native modules must be compiled against the headers of the Lua they are
+//! loaded into. Each managed version gets the headers from its official
+//! source release in `versions/<version>/include`, so modules for 5.1 and 5.4
+//! can be built on the same machine without system Lua packages.
+
+use crate::core::{DepotError, DepotResult};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tar::Archive;
+
+/// Headers every Lua source release provides
+pub const REQUIRED_HEADERS: &[&str] = &["lua.h", "luaconf.h", "lualib.h", "lauxlib.h"];
+
+/// C++ wrapper header, in `src/` since 5.2 and `etc/` before
+const CXX_HEADER: &str = "lua.hpp";
+
+/// LuaJIT's own API header; generated during the build on the 2.1 branch
+const LUAJIT_HEADER: &str = "luajit.h";
+
+/// Include directory of an installed version
+pub fn include_dir(version_dir: &Path) -> PathBuf {
+    version_dir.join("include")
+}
+
+/// Whether an installed version has its headers
+pub fn has_headers(version_dir: &Path) -> bool {
+    let include = include_dir(version_dir);
+    REQUIRED_HEADERS.iter().all(|h| include.join(h).is_file())
+}
+
+/// Extract the headers from a Lua source tarball into the version's include directory
+///
+/// The directory is replaced as a whole, so an interrupted extraction never
+/// leaves a mix of headers from two releases.
+pub fn extract_headers(tarball: &Path, version_dir: &Path) -> DepotResult<PathBuf> {
+    let include = include_dir(version_dir);
+    let staging = version_dir.join("include.part");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    let mut archive = Archive::new(GzDecoder::new(fs::File::open(tarball)?));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let parent = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|p| p.to_str());
+
+        let wanted = match parent {
+            Some("src") => REQUIRED_HEADERS.contains(&name) || name == CXX_HEADER,
+            // Only 5.1 keeps lua.hpp in etc/; never let it shadow the src/ copy
+            Some("etc") => name == CXX_HEADER && !staging.join(name).exists(),
+            _ => false,
+        };
+        if wanted {
+            entry.unpack(staging.join(name))?;
+        }
+    }
+
+    install_staged(&staging, &include, tarball)
+}
+
+/// Copy the headers from a built LuaJIT `src` directory into the version's include directory
+pub fn copy_headers(src_dir: &Path, version_dir: &Path) -> DepotResult<PathBuf> {
+    let include = include_dir(version_dir);
+    let staging = version_dir.join("include.part");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+
+    for name in REQUIRED_HEADERS
+        .iter()
+        .chain([CXX_HEADER, LUAJIT_HEADER].iter())
+    {
+        let header = src_dir.join(name);
+        if header.is_file() {
+            fs::copy(&header, staging.join(name))?;
+        }
+    }
+    install_staged(&staging, &include, src_dir)
+}
+
+/// Replace `include` with `staging` once it has every required header
+fn install_staged(staging: &Path, include: &Path, origin: &Path) -> DepotResult<PathBuf> {
+    let missing: Vec<&str> = REQUIRED_HEADERS
+        .iter()
+        .copied()
+        .filter(|h| !staging.join(h).exists())
+        .collect();
+    if !missing.is_empty() {
+        fs::remove_dir_all(staging)?;
+        return Err(DepotError::Package(format!(
+            "{} is not a Lua source release (missing {})",
+            origin.display(),
+            missing.join(", ")
+        )));
+    }
+
+    if include.exists() {
+        fs::remove_dir_all(include)?;
+    }
+    fs::rename(staging, include)?;
+    Ok(include.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+
+    fn write_tarball(path: &Path, files: &[(&str, &str)]) {
+        let encoder = GzEncoder::new(fs::File::create(path).unwrap(), Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_headers() {
+        let temp = TempDir::new().unwrap();
+        let tarball = temp.path().join("lua-5.1.5.tar.gz");
+        write_tarball(
+            &tarball,
+            &[
+                ("lua-5.1.5/src/lua.h", "/* lua.h */"),
+                ("lua-5.1.5/src/luaconf.h", "/* luaconf.h */"),
+                ("lua-5.1.5/src/lualib.h", "/* lualib.h */"),
+                ("lua-5.1.5/src/lauxlib.h", "/* lauxlib.h */"),
+                ("lua-5.1.5/src/lapi.h", "/* internal */"),
+                ("lua-5.1.5/etc/lua.hpp", "/* lua.hpp */"),
+                ("lua-5.1.5/Makefile", "all:"),
+            ],
+        );
+        let version_dir = temp.path().join("versions").join("5.1.5");
+        assert!(!has_headers(&version_dir));
+
+        let include = extract_headers(&tarball, &version_dir).unwrap();
+        assert_eq!(include, include_dir(&version_dir));
+        assert!(has_headers(&version_dir));
+        assert_eq!(
+            fs::read_to_string(include.join("lua.hpp")).unwrap(),
+            "/* lua.hpp */"
+        );
+        assert!(!include.join("lapi.h").exists());
+        assert!(!version_dir.join("include.part").exists());
+    }
+
+    #[test]
+    fn test_extract_headers_incomplete() {
+        let temp = TempDir::new().unwrap();
+        let tarball = temp.path().join("lua-5.4.8.tar.gz");
+        write_tarball(&tarball, &[("lua-5.4.8/src/lua.h", "/* lua.h */")]);
+        let version_dir = temp.path().join("5.4.8");
+
+        let err = extract_headers(&tarball, &version_dir).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("missing luaconf.h, lualib.h, lauxlib.h"));
+        assert!(!include_dir(&version_dir).exists());
+    }
+}
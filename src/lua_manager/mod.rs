@@ -1,4 +1,8 @@
+pub mod checksums;
 pub mod downloader;
+pub mod headers;
+pub mod shim;
+pub mod source;
 pub mod switcher;
 pub mod versions;
 pub mod wrappers;
@@ -0,0 +1,207 @@
+//! Multi-call shim for the managed interpreters
+//!
+//! `lua`, `luac` and `luajit` in the depot bin directory are links to the
+//! depot executable itself. Invoked under one of those names, depot selects
+//! the version for the working directory (`DEPOT_LUA_VERSION`,
+//! `.lua-version`, `.tool-versions`, then the global version) and runs that
+//! version's interpreter. Inside a project the interpreter first loads
+//! `depot.loader`, so `require` finds the packages in `lua_modules`.
+
+use crate::core::path::{find_project_root, lua_modules_dir};
+use crate::core::{DepotError, DepotResult};
+use crate::lua_manager::switcher::{project_version, VersionSwitcher};
+use crate::path_setup::PathSetup;
+use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Names the depot executable answers to as a shim
+pub const SHIMMED_BINARIES: &[&str] = &["lua", "luac", "luajit"];
+
+/// The shimmed binary depot was invoked as, from its `argv[0]`
+pub fn shim_name(argv0: &OsStr) -> Option<&'static str> {
+    let name = Path::new(argv0).file_name()?.to_str()?;
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    SHIMMED_BINARIES.iter().copied().find(|b| *b == name)
+}
+
+/// File name of a binary on this platform
+pub fn executable_name(binary: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", binary)
+    } else {
+        binary.to_string()
+    }
+}
+
+/// The interpreter a shim invocation runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShimTarget {
+    /// Installed version directory name (e.g. "5.4.8", "luajit-2.1")
+    pub version: String,
+    /// Where the version was selected
+    pub source: &'static str,
+    /// The version's `binary`
+    pub path: PathBuf,
+}
+
+/// Select the installed version and binary for a shim invoked in `cwd`
+pub fn resolve(depot_home: &Path, binary: &str, cwd: &Path) -> DepotResult<ShimTarget> {
+    let switcher = VersionSwitcher::new(depot_home);
+    let (version, source) = match project_version(cwd) {
+        Some((requested, source)) => {
+            let installed = switcher.find_installed(&requested)?.ok_or_else(|| {
+                DepotError::Package(format!(
+                    "Lua {} (from {}) is not installed. Run: depot lua install {}",
+                    requested, source, requested
+                ))
+            })?;
+            (installed, source)
+        }
+        None => (switcher.current()?, "global version"),
+    };
+
+    let path = switcher
+        .version_dir(&version)
+        .join("bin")
+        .join(executable_name(binary));
+    if !path.is_file() {
+        return Err(DepotError::Package(format!(
+            "Lua {} (from {}) has no {} binary",
+            version, source, binary
+        )));
+    }
+    Ok(ShimTarget {
+        version,
+        source,
+        path,
+    })
+}
+
+/// Lua code that loads the project's `depot.loader`, if `cwd` is in an installed project
+///
+/// The loader module is written on first use, as `depot run` does.
+pub fn loader_init(cwd: &Path) -> DepotResult<Option<String>> {
+    let Ok(project_root) = find_project_root(cwd) else {
+        return Ok(None);
+    };
+    if !lua_modules_dir(&project_root).is_dir() {
+        return Ok(None);
+    }
+    if !PathSetup::loader_path(&project_root).is_file() {
+        PathSetup::install_loader(&project_root)?;
+    }
+
+    let module_dir = PathSetup::depot_module_dir(&project_root)
+        .to_string_lossy()
+        .replace('\\', "/");
+    Ok(Some(format!(
+        "package.path = [==[{}]==] .. '/?.lua;' .. package.path; require('depot.loader')",
+        module_dir
+    )))
+}
+
+/// Whether the user set `LUA_INIT` or a versioned `LUA_INIT_5_x` themselves
+fn has_lua_init() -> bool {
+    env::vars_os().any(|(key, _)| key.to_str().is_some_and(|k| k.starts_with("LUA_INIT")))
+}
+
+/// Command running `target` with the project loader applied through `LUA_INIT`
+///
+/// `LUA_INIT` runs before the interpreter's arguments are handled, so the
+/// REPL, `-e`, `-` and scripts all see the loader. An existing `LUA_INIT`
+/// is left alone, and `luac` does not run code.
+pub fn command(target: &ShimTarget, binary: &str, cwd: &Path) -> DepotResult<Command> {
+    let mut command = Command::new(&target.path);
+    if binary != "luac" && !has_lua_init() {
+        if let Some(init) = loader_init(cwd)? {
+            command.env("LUA_INIT", init);
+        }
+    }
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn install(depot_home: &Path, version: &str, binaries: &[&str]) {
+        let bin_dir = depot_home.join("versions").join(version).join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        for binary in binaries {
+            fs::write(bin_dir.join(executable_name(binary)), "mock binary").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_shim_name() {
+        assert_eq!(shim_name(OsStr::new("lua")), Some("lua"));
+        assert_eq!(
+            shim_name(OsStr::new("/home/me/.depot/bin/luac")),
+            Some("luac")
+        );
+        assert_eq!(shim_name(OsStr::new("luajit.exe")), Some("luajit"));
+        assert_eq!(shim_name(OsStr::new("/usr/local/bin/depot")), None);
+        assert_eq!(shim_name(OsStr::new("lua5.1")), None);
+    }
+
+    #[test]
+    fn test_resolve_project_version() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        install(home.path(), "5.1.5", &["lua", "luac"]);
+        install(home.path(), "5.4.8", &["lua", "luac"]);
+        VersionSwitcher::new(home.path()).switch("5.1.5").unwrap();
+
+        let target = resolve(home.path(), "lua", project.path()).unwrap();
+        assert_eq!(target.version, "5.1.5");
+        assert_eq!(target.source, "global version");
+
+        fs::write(project.path().join(".lua-version"), "5.4\n").unwrap();
+        let target = resolve(home.path(), "luac", project.path()).unwrap();
+        assert_eq!(target.version, "5.4.8");
+        assert_eq!(target.source, ".lua-version");
+        assert_eq!(
+            target.path,
+            home.path()
+                .join("versions")
+                .join("5.4.8")
+                .join("bin")
+                .join(executable_name("luac"))
+        );
+
+        let err = resolve(home.path(), "luajit", project.path()).unwrap_err();
+        assert!(err.to_string().contains("has no luajit binary"));
+    }
+
+    #[test]
+    fn test_resolve_pinned_version_not_installed() {
+        let home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        fs::write(project.path().join(".tool-versions"), "lua 5.3.6\n").unwrap();
+
+        let err = resolve(home.path(), "lua", project.path()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Lua 5.3.6 (from .tool-versions) is not installed"));
+    }
+
+    #[test]
+    fn test_loader_init() {
+        let project = TempDir::new().unwrap();
+        fs::write(
+            project.path().join("package.yaml"),
+            "name: test\nversion: 1.0.0\n",
+        )
+        .unwrap();
+        assert_eq!(loader_init(project.path()).unwrap(), None);
+
+        fs::create_dir_all(project.path().join("lua_modules")).unwrap();
+        let init = loader_init(project.path()).unwrap().unwrap();
+        assert!(init.ends_with("require('depot.loader')"));
+        assert!(PathSetup::loader_path(project.path()).is_file());
+    }
+}
@@ -0,0 +1,459 @@
+//! Building Lua from official source releases
+//!
+//! Used for versions and platforms without pre-built binaries. The sources
+//! are compiled directly with the system C compiler rather than through the
+//! release's Makefile, whose Linux targets need readline in 5.1 to 5.3. The
+//! result is installed in the same layout as a binary install, plus
+//! `lib/liblua.a` for native builds that link against Lua.
+//!
+//! LuaJIT generates part of its sources and assembles its interpreter per
+//! architecture, so it is built with its own Makefile instead. It installs
+//! `luajit` (also as `lua`) and `lib/libluajit-5.1.a`.
+
+use crate::core::{DepotError, DepotResult};
+use crate::lua_manager::downloader::is_luajit;
+use crate::lua_manager::headers;
+use flate2::read::GzDecoder;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tar::Archive;
+
+/// Static library installed next to the headers
+pub const STATIC_LIB: &str = "liblua.a";
+
+/// Static library of a LuaJIT install, named as LuaJIT's own `make install` does
+pub const LUAJIT_STATIC_LIB: &str = "libluajit-5.1.a";
+
+/// Directory `liblua.a` is installed in
+pub fn lib_dir(version_dir: &Path) -> PathBuf {
+    version_dir.join("lib")
+}
+
+/// Link name of the static library installed for a version, if any
+pub fn static_library(version_dir: &Path) -> Option<&'static str> {
+    let lib_dir = lib_dir(version_dir);
+    if lib_dir.join(STATIC_LIB).is_file() {
+        Some("lua")
+    } else if lib_dir.join(LUAJIT_STATIC_LIB).is_file() {
+        Some("luajit-5.1")
+    } else {
+        None
+    }
+}
+
+/// Sources that are not part of the library: the two programs, and the
+/// single-file amalgamation some releases ship
+const NON_LIBRARY_SOURCES: &[&str] = &["lua.c", "luac.c", "onelua.c"];
+
+/// Compiles Lua with the system C compiler
+pub struct SourceBuilder {
+    cc: String,
+    ar: String,
+    make: String,
+}
+
+impl Default for SourceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SourceBuilder {
+    /// Use `$CC`, `$AR` and `$MAKE`, defaulting to `cc`, `ar` and `make`
+    pub fn new() -> Self {
+        Self {
+            cc: env::var("CC").unwrap_or_else(|_| "cc".to_string()),
+            ar: env::var("AR").unwrap_or_else(|_| "ar".to_string()),
+            make: env::var("MAKE").unwrap_or_else(|_| "make".to_string()),
+        }
+    }
+
+    /// Whether the C compiler can be run
+    pub fn compiler_available(&self) -> bool {
+        Command::new(&self.cc)
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success())
+    }
+
+    /// Build a source tarball and install it into `version_dir`
+    ///
+    /// `work_dir` is used for the unpacked sources and object files and is
+    /// removed afterwards.
+    pub fn build(
+        &self,
+        version: &str,
+        tarball: &Path,
+        version_dir: &Path,
+        work_dir: &Path,
+    ) -> DepotResult<()> {
+        if cfg!(windows) {
+            return Err(DepotError::NotImplemented(
+                "building Lua from source on Windows; configure lua_binary_sources instead"
+                    .to_string(),
+            ));
+        }
+        if !self.compiler_available() {
+            return Err(DepotError::Package(format!(
+                "Building Lua {} from source needs a C compiler, but '{}' could not be run. \
+                 Install one or set CC.",
+                version, self.cc
+            )));
+        }
+
+        if work_dir.exists() {
+            fs::remove_dir_all(work_dir)?;
+        }
+        fs::create_dir_all(work_dir)?;
+        let result = if is_luajit(version) {
+            self.build_luajit_in(version, tarball, version_dir, work_dir)
+        } else {
+            self.build_in(version, tarball, version_dir, work_dir)
+        };
+        let _ = fs::remove_dir_all(work_dir);
+        result
+    }
+
+    fn build_in(
+        &self,
+        version: &str,
+        tarball: &Path,
+        version_dir: &Path,
+        work_dir: &Path,
+    ) -> DepotResult<()> {
+        println!("Compiling Lua {} with {}...", version, self.cc);
+        Archive::new(GzDecoder::new(fs::File::open(tarball)?)).unpack(work_dir)?;
+        let src = find_source_dir(work_dir)?;
+
+        let mut library_sources = Vec::new();
+        for entry in fs::read_dir(&src)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            if name.ends_with(".c") && !NON_LIBRARY_SOURCES.contains(&name) {
+                library_sources.push(path);
+            }
+        }
+        library_sources.sort();
+
+        let cflags = compile_flags(version);
+        let obj_dir = work_dir.join("obj");
+        fs::create_dir_all(&obj_dir)?;
+        let mut objects = Vec::new();
+        for source in &library_sources {
+            let object = obj_dir.join(source.with_extension("o").file_name().unwrap_or_default());
+            self.run(
+                Command::new(&self.cc)
+                    .args(&cflags)
+                    .arg("-c")
+                    .arg(source)
+                    .arg("-o")
+                    .arg(&object),
+            )?;
+            objects.push(object);
+        }
+
+        let library = work_dir.join(STATIC_LIB);
+        self.run(
+            Command::new(&self.ar)
+                .arg("rcs")
+                .arg(&library)
+                .args(&objects),
+        )?;
+
+        let bin_dir = version_dir.join("bin");
+        fs::create_dir_all(&bin_dir)?;
+        for program in ["lua", "luac"] {
+            let output = work_dir.join(program);
+            self.run(
+                Command::new(&self.cc)
+                    .args(&cflags)
+                    .arg("-o")
+                    .arg(&output)
+                    .arg(src.join(format!("{}.c", program)))
+                    .arg(&library)
+                    .args(link_flags()),
+            )?;
+            fs::copy(&output, bin_dir.join(program))?;
+        }
+
+        fs::create_dir_all(lib_dir(version_dir))?;
+        fs::copy(&library, lib_dir(version_dir).join(STATIC_LIB))?;
+        headers::extract_headers(tarball, version_dir)?;
+
+        println!("✓ Compiled Lua {}", version);
+        Ok(())
+    }
+
+    fn build_luajit_in(
+        &self,
+        version: &str,
+        tarball: &Path,
+        version_dir: &Path,
+        work_dir: &Path,
+    ) -> DepotResult<()> {
+        println!("Compiling {} with {}...", version, self.make);
+        Archive::new(GzDecoder::new(fs::File::open(tarball)?)).unpack(work_dir)?;
+        let root = find_luajit_root(work_dir)?;
+
+        let jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let mut make = Command::new(&self.make);
+        make.current_dir(&root)
+            .arg(format!("-j{}", jobs))
+            .arg(format!("CC={}", self.cc));
+        // LuaJIT refuses to build on macOS without a deployment target
+        if cfg!(target_os = "macos") && env::var_os("MACOSX_DEPLOYMENT_TARGET").is_none() {
+            make.env("MACOSX_DEPLOYMENT_TARGET", "11.0");
+        }
+        self.run(&mut make)?;
+
+        // Installed as `lua` too, so the lua wrapper and `depot run` use it
+        let src = root.join("src");
+        let bin_dir = version_dir.join("bin");
+        fs::create_dir_all(&bin_dir)?;
+        for program in ["luajit", "lua"] {
+            fs::copy(src.join("luajit"), bin_dir.join(program))?;
+        }
+
+        fs::create_dir_all(lib_dir(version_dir))?;
+        fs::copy(
+            src.join("libluajit.a"),
+            lib_dir(version_dir).join(LUAJIT_STATIC_LIB),
+        )?;
+        headers::copy_headers(&src, version_dir)?;
+
+        println!("✓ Compiled {}", version);
+        Ok(())
+    }
+
+    fn run(&self, command: &mut Command) -> DepotResult<()> {
+        let output = command.output().map_err(|e| {
+            DepotError::Package(format!(
+                "Failed to run {}: {}",
+                command.get_program().to_string_lossy(),
+                e
+            ))
+        })?;
+        if !output.status.success() {
+            return Err(DepotError::Package(format!(
+                "{} failed while building Lua:\n{}",
+                command.get_program().to_string_lossy(),
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The `src` directory of an unpacked release (`lua-<version>/src`)
+fn find_source_dir(work_dir: &Path) -> DepotResult<PathBuf> {
+    for entry in fs::read_dir(work_dir)? {
+        let src = entry?.path().join("src");
+        if src.join("lua.h").is_file() && src.join("lua.c").is_file() {
+            return Ok(src);
+        }
+    }
+    Err(DepotError::Package(
+        "Source tarball does not contain a Lua release (no src/lua.c)".to_string(),
+    ))
+}
+
+/// The root of an unpacked LuaJIT release (`LuaJIT-<branch>` or `luajit2-<tag>`)
+fn find_luajit_root(work_dir: &Path) -> DepotResult<PathBuf> {
+    for entry in fs::read_dir(work_dir)? {
+        let root = entry?.path();
+        if root.join("Makefile").is_file() && root.join("src").join("lj_arch.h").is_file() {
+            return Ok(root);
+        }
+    }
+    Err(DepotError::Package(
+        "Source tarball does not contain a LuaJIT release (no src/lj_arch.h)".to_string(),
+    ))
+}
+
+/// C flags matching the official Makefiles, with dynamic loading enabled
+/// so native modules can be required
+fn compile_flags(version: &str) -> Vec<String> {
+    let mut flags: Vec<String> = ["-O2", "-Wall", "-std=gnu99", "-fPIC"]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+    flags.push("-DLUA_USE_POSIX".to_string());
+    flags.push("-DLUA_USE_DLOPEN".to_string());
+
+    let compat = match version.split('.').take(2).collect::<Vec<_>>().as_slice() {
+        ["5", "2"] => Some("-DLUA_COMPAT_ALL"),
+        ["5", "3"] => Some("-DLUA_COMPAT_5_2"),
+        ["5", "4"] => Some("-DLUA_COMPAT_5_3"),
+        _ => None,
+    };
+    flags.extend(compat.map(str::to_string));
+    flags
+}
+
+/// Linker flags; `-Wl,-E` exports the Lua API to modules loaded at runtime
+fn link_flags() -> Vec<&'static str> {
+    if cfg!(target_os = "macos") {
+        vec!["-lm"]
+    } else if cfg!(target_os = "linux") {
+        vec!["-Wl,-E", "-lm", "-ldl"]
+    } else {
+        vec!["-Wl,-E", "-lm"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+
+    fn write_tarball(path: &Path, files: &[(&str, &str)]) {
+        let encoder = GzEncoder::new(fs::File::create(path).unwrap(), Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_compile_flags() {
+        assert!(compile_flags("5.1.5")
+            .iter()
+            .all(|f| !f.starts_with("-DLUA_COMPAT")));
+        assert!(compile_flags("5.2.4").contains(&"-DLUA_COMPAT_ALL".to_string()));
+        assert!(compile_flags("5.4.8").contains(&"-DLUA_COMPAT_5_3".to_string()));
+        assert!(compile_flags("5.4.8").contains(&"-DLUA_USE_DLOPEN".to_string()));
+    }
+
+    #[test]
+    fn test_build_from_source() {
+        let builder = SourceBuilder::new();
+        if cfg!(windows) || !builder.compiler_available() {
+            return;
+        }
+
+        // A stand-in release: one library source and the two programs
+        let temp = TempDir::new().unwrap();
+        let tarball = temp.path().join("lua-5.4.8.tar.gz");
+        let main =
+            "int lapi_answer(void);\nint main(void) { return lapi_answer() == 42 ? 0 : 1; }\n";
+        write_tarball(
+            &tarball,
+            &[
+                ("lua-5.4.8/Makefile", "all:\n"),
+                ("lua-5.4.8/src/lua.h", "/* lua.h */\n"),
+                ("lua-5.4.8/src/luaconf.h", "/* luaconf.h */\n"),
+                ("lua-5.4.8/src/lualib.h", "/* lualib.h */\n"),
+                ("lua-5.4.8/src/lauxlib.h", "/* lauxlib.h */\n"),
+                (
+                    "lua-5.4.8/src/lapi.c",
+                    "int lapi_answer(void) { return 42; }\n",
+                ),
+                ("lua-5.4.8/src/lua.c", main),
+                ("lua-5.4.8/src/luac.c", main),
+            ],
+        );
+
+        let version_dir = temp.path().join("versions").join("5.4.8");
+        let work_dir = temp.path().join("build");
+        builder
+            .build("5.4.8", &tarball, &version_dir, &work_dir)
+            .unwrap();
+
+        let status = Command::new(version_dir.join("bin").join("lua"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(version_dir.join("bin").join("luac").is_file());
+        assert_eq!(static_library(&version_dir), Some("lua"));
+        assert!(headers::has_headers(&version_dir));
+        assert!(!work_dir.exists());
+    }
+
+    #[test]
+    fn test_build_luajit_with_make() {
+        let builder = SourceBuilder::new();
+        let make_available = Command::new(&builder.make)
+            .arg("--version")
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if cfg!(windows) || !builder.compiler_available() || !make_available {
+            return;
+        }
+
+        // A stand-in release whose Makefile produces what LuaJIT's does
+        let temp = TempDir::new().unwrap();
+        let tarball = temp.path().join("luajit-2.1.tar.gz");
+        let makefile = "all:\n\
+                        \tprintf '#!/bin/sh\\nexit 0\\n' > src/luajit\n\
+                        \tchmod +x src/luajit\n\
+                        \ttouch src/libluajit.a\n\
+                        \tcp src/luajit_rolling.h src/luajit.h\n";
+        write_tarball(
+            &tarball,
+            &[
+                ("LuaJIT-2.1/Makefile", makefile),
+                ("LuaJIT-2.1/src/lj_arch.h", "/* lj_arch.h */\n"),
+                ("LuaJIT-2.1/src/luajit_rolling.h", "/* luajit.h */\n"),
+                ("LuaJIT-2.1/src/lua.h", "/* lua.h */\n"),
+                ("LuaJIT-2.1/src/luaconf.h", "/* luaconf.h */\n"),
+                ("LuaJIT-2.1/src/lualib.h", "/* lualib.h */\n"),
+                ("LuaJIT-2.1/src/lauxlib.h", "/* lauxlib.h */\n"),
+            ],
+        );
+
+        let version_dir = temp.path().join("versions").join("luajit-2.1");
+        let work_dir = temp.path().join("build");
+        builder
+            .build("luajit-2.1", &tarball, &version_dir, &work_dir)
+            .unwrap();
+
+        for program in ["luajit", "lua"] {
+            let status = Command::new(version_dir.join("bin").join(program))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        assert_eq!(static_library(&version_dir), Some("luajit-5.1"));
+        assert!(headers::has_headers(&version_dir));
+        assert!(headers::include_dir(&version_dir)
+            .join("luajit.h")
+            .is_file());
+        assert!(!work_dir.exists());
+    }
+
+    #[test]
+    fn test_build_rejects_non_lua_tarball() {
+        let builder = SourceBuilder::new();
+        if cfg!(windows) || !builder.compiler_available() {
+            return;
+        }
+
+        let temp = TempDir::new().unwrap();
+        let tarball = temp.path().join("other.tar.gz");
+        write_tarball(&tarball, &[("other/README", "hello\n")]);
+
+        let err = builder
+            .build(
+                "5.4.8",
+                &tarball,
+                &temp.path().join("5.4.8"),
+                &temp.path().join("build"),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("no src/lua.c"));
+    }
+}
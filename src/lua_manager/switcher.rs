@@ -1,7 +1,62 @@
 use crate::core::{DepotError, DepotResult};
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Per-project version file, looked up in the working directory and its parents
+pub const LOCAL_VERSION_FILE: &str = ".lua-version";
+
+/// asdf/mise version file; its `lua` or `luajit` entry pins the version
+pub const TOOL_VERSIONS_FILE: &str = ".tool-versions";
+
+/// Environment variable that overrides the project and global version
+pub const VERSION_ENV: &str = "DEPOT_LUA_VERSION";
+
+/// Version selected for `start`, and where it was selected
+///
+/// `DEPOT_LUA_VERSION` wins, then the nearest `.lua-version` or
+/// `.tool-versions` with a Lua entry in `start` or its parents. In one
+/// directory `.lua-version` takes precedence.
+pub fn project_version(start: &Path) -> Option<(String, &'static str)> {
+    if let Some(version) = env::var(VERSION_ENV)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        return Some((version, VERSION_ENV));
+    }
+    pinned_version(start)
+}
+
+/// Version pinned by the nearest version file in `start` or its parents
+fn pinned_version(start: &Path) -> Option<(String, &'static str)> {
+    start.ancestors().find_map(|dir| {
+        if let Ok(content) = fs::read_to_string(dir.join(LOCAL_VERSION_FILE)) {
+            let version = content.trim();
+            if !version.is_empty() {
+                return Some((version.to_string(), LOCAL_VERSION_FILE));
+            }
+        }
+        let content = fs::read_to_string(dir.join(TOOL_VERSIONS_FILE)).ok()?;
+        tool_versions_entry(&content).map(|v| (v, TOOL_VERSIONS_FILE))
+    })
+}
+
+/// The Lua version in a `.tool-versions` file (`lua 5.4.6` or `luajit 2.1`)
+///
+/// Only the first version of an entry is used; asdf's fallbacks are ignored.
+fn tool_versions_entry(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        match (fields.next()?, fields.next()?) {
+            ("lua", version) => Some(version.to_string()),
+            ("luajit", version) => Some(format!("luajit-{}", version)),
+            _ => None,
+        }
+    })
+}
+
 pub struct VersionSwitcher {
     versions_dir: PathBuf,
     current_file: PathBuf,
@@ -63,7 +118,7 @@ impl VersionSwitcher {
         }
 
         // Write .lua-version file
-        let version_file = project_dir.join(".lua-version");
+        let version_file = project_dir.join(LOCAL_VERSION_FILE);
         fs::write(&version_file, format!("{}\n", version))?;
 
         println!("✓ Set project Lua version to {}", version);
@@ -132,6 +187,33 @@ impl VersionSwitcher {
         Ok(versions)
     }
 
+    /// Directory a version is installed in
+    pub fn version_dir(&self, version: &str) -> PathBuf {
+        self.versions_dir.join(version)
+    }
+
+    /// Find the installed version that satisfies `requested`
+    ///
+    /// An exact match wins; otherwise a prefix such as "5.4" selects the
+    /// newest installed 5.4.x, and "luajit" the newest luajit-x.
+    pub fn find_installed(&self, requested: &str) -> DepotResult<Option<String>> {
+        let installed = self.list_installed()?;
+        if installed.iter().any(|v| v == requested) {
+            return Ok(Some(requested.to_string()));
+        }
+
+        // "5.4" matches "5.4.8"; "luajit" matches "luajit-2.1"
+        let prefixes = [format!("{}.", requested), format!("{}-", requested)];
+        Ok(installed
+            .into_iter()
+            .filter(|v| prefixes.iter().any(|p| v.starts_with(p)))
+            .max_by_key(|v| {
+                v.split(['.', '-'])
+                    .map(|part| part.parse::<u64>().unwrap_or(0))
+                    .collect::<Vec<_>>()
+            }))
+    }
+
     /// Verify that the current version file points to the expected version
     fn verify_current_version(&self, expected: &str) -> DepotResult<()> {
         let actual = self.current()?;
@@ -280,6 +362,81 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_find_installed() {
+        let temp = TempDir::new().unwrap();
+        let switcher = VersionSwitcher::new(temp.path());
+        for version in &[
+            "5.1.5",
+            "5.4.8",
+            "5.4.10",
+            "luajit-2.1",
+            "openresty-luajit-2.1-20231117",
+            "openresty-luajit-2.1-20240314",
+        ] {
+            let version_dir = temp.path().join("versions").join(version);
+            fs::create_dir_all(version_dir.join("bin")).unwrap();
+            fs::write(version_dir.join("bin").join("lua"), "mock binary").unwrap();
+        }
+
+        assert_eq!(
+            switcher.find_installed("5.4.8").unwrap().as_deref(),
+            Some("5.4.8")
+        );
+        assert_eq!(
+            switcher.find_installed("5.4").unwrap().as_deref(),
+            Some("5.4.10")
+        );
+        assert_eq!(switcher.find_installed("5.3").unwrap(), None);
+        assert_eq!(switcher.find_installed(">=5.1").unwrap(), None);
+        assert_eq!(
+            switcher.find_installed("luajit").unwrap().as_deref(),
+            Some("luajit-2.1")
+        );
+        assert_eq!(
+            switcher
+                .find_installed("openresty-luajit")
+                .unwrap()
+                .as_deref(),
+            Some("openresty-luajit-2.1-20240314")
+        );
+    }
+
+    #[test]
+    fn test_project_version() {
+        let temp = TempDir::new().unwrap();
+        let nested = temp.path().join("project").join("src");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(pinned_version(&nested), None);
+
+        // A .tool-versions without Lua does not stop the search
+        fs::write(nested.join(".tool-versions"), "nodejs 20.11.0\n").unwrap();
+        fs::write(temp.path().join(".tool-versions"), "lua 5.3.6 5.1.5\n").unwrap();
+        assert_eq!(
+            pinned_version(&nested),
+            Some(("5.3.6".to_string(), TOOL_VERSIONS_FILE))
+        );
+
+        fs::write(temp.path().join("project").join(".lua-version"), "5.1.5\n").unwrap();
+        assert_eq!(
+            pinned_version(&nested),
+            Some(("5.1.5".to_string(), LOCAL_VERSION_FILE))
+        );
+    }
+
+    #[test]
+    fn test_tool_versions_entry() {
+        assert_eq!(
+            tool_versions_entry("# tools\nnodejs 20\nlua 5.4.6 # pinned\n").as_deref(),
+            Some("5.4.6")
+        );
+        assert_eq!(
+            tool_versions_entry("luajit 2.1\n").as_deref(),
+            Some("luajit-2.1")
+        );
+        assert_eq!(tool_versions_entry("lua\nruby 3.3.0\n"), None);
+    }
+
     #[test]
     fn test_version_switcher_check_installed_via_list() {
         let temp = TempDir::new().unwrap();
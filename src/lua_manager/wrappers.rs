@@ -1,180 +1,73 @@
-use crate::core::{DepotError, DepotResult};
+use crate::core::DepotResult;
+use crate::lua_manager::shim::{executable_name, SHIMMED_BINARIES};
+use std::env;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 
+/// Installs the `lua`, `luac` and `luajit` shims in the depot bin directory
+///
+/// Each shim is a link to the depot executable, which dispatches on the
+/// name it was invoked as (see [`crate::lua_manager::shim`]).
 pub struct WrapperGenerator {
-    bin_dir: std::path::PathBuf,
+    bin_dir: PathBuf,
+    executable: Option<PathBuf>,
 }
 
 impl WrapperGenerator {
     pub fn new(lpm_home: &Path) -> Self {
         Self {
             bin_dir: lpm_home.join("bin"),
+            executable: None,
         }
     }
 
-    /// Generate binary wrappers for lua and luac
+    /// Link the shims to `executable` instead of the running depot
+    pub fn with_executable(mut self, executable: PathBuf) -> Self {
+        self.executable = Some(executable);
+        self
+    }
+
+    /// Link the lua, luac and luajit shims to the depot executable
     pub fn generate(&self) -> DepotResult<()> {
         fs::create_dir_all(&self.bin_dir)?;
 
-        self.compile_wrapper("lua")?;
-        self.compile_wrapper("luac")?;
+        let executable = match &self.executable {
+            Some(executable) => executable.clone(),
+            None => env::current_exe()?,
+        };
+        for binary in SHIMMED_BINARIES {
+            self.link_shim(&executable, binary)?;
+        }
 
-        println!("✓ Generated wrappers in {}", self.bin_dir.display());
+        println!("✓ Linked Lua shims in {}", self.bin_dir.display());
         self.print_setup_instructions();
 
         Ok(())
     }
 
-    /// Compile a wrapper binary
+    /// Replace `bin/<binary>` with a link to the depot executable
     ///
-    /// The wrapper is a small Rust program that:
-    /// 1. Checks for .lua-version file in current/parent directories
-    /// 2. Falls back to current version
-    /// 3. Executes the correct binary
-    fn compile_wrapper(&self, binary: &str) -> DepotResult<()> {
-        let wrapper_source = self.wrapper_source_code(binary);
-        let source_path = self.bin_dir.join(format!("{}_wrapper.rs", binary));
-        fs::write(&source_path, wrapper_source)?;
-
-        let output_name = if cfg!(target_os = "windows") {
-            format!("{}.exe", binary)
-        } else {
-            binary.to_string()
-        };
+    /// Unix gets a symlink, so the shims follow depot when it is upgraded in
+    /// place. Windows gets a hardlink, which needs no privileges, or a copy
+    /// when the bin directory is on another volume.
+    fn link_shim(&self, executable: &Path, binary: &str) -> DepotResult<()> {
+        let shim = self.bin_dir.join(executable_name(binary));
+        // Also replaces wrappers compiled by earlier versions of depot
+        if shim.symlink_metadata().is_ok() {
+            fs::remove_file(&shim)?;
+        }
 
-        // Compile using rustc (or cargo if available)
-        let status = Command::new("rustc")
-            .arg(&source_path)
-            .arg("-o")
-            .arg(self.bin_dir.join(&output_name))
-            .status()?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(executable, &shim)?;
 
-        if !status.success() {
-            return Err(DepotError::Package(format!(
-                "Failed to compile {} wrapper. Make sure rustc is available.",
-                binary
-            )));
+        #[cfg(not(unix))]
+        if fs::hard_link(executable, &shim).is_err() {
+            fs::copy(executable, &shim)?;
         }
 
-        // Clean up source file
-        fs::remove_file(&source_path)?;
-
         Ok(())
     }
 
-    fn wrapper_source_code(&self, binary: &str) -> String {
-        format!(
-            r#"fn main() {{
-    use std::env;
-    use std::path::PathBuf;
-    use std::process::Command;
-    
-    let lpm_home = env::var("Depot_LUA_DIR")
-        .unwrap_or_else(|_| {{
-            #[cfg(unix)]
-            {{
-                let home = env::var("HOME")
-                    .or_else(|_| env::var("USERPROFILE"))
-                    .unwrap_or_else(|_| {{
-                        eprintln!("Error: Could not determine home directory");
-                        eprintln!("Neither HOME nor USERPROFILE environment variables are set");
-                        std::process::exit(1);
-                    }});
-                format!("{{}}/.depot", home)
-            }}
-            #[cfg(windows)]
-            {{
-                let home = env::var("APPDATA")
-                    .or_else(|_| env::var("USERPROFILE").map(|p| format!("{{}}\\\\AppData\\\\Roaming", p)))
-                    .unwrap_or_else(|_| {{
-                        eprintln!("Error: Could not determine application data directory");
-                        eprintln!("Neither APPDATA nor USERPROFILE environment variables are set");
-                        std::process::exit(1);
-                    }});
-                format!("{{}}\\\\depot", home)
-            }}
-        }});
-    
-    // Check for .lua-version file
-    let mut dir = match env::current_dir() {{
-        Ok(d) => d,
-        Err(e) => {{
-            eprintln!("Error: Failed to get current directory: {{}}", e);
-            std::process::exit(1);
-        }}
-    }};
-    let mut version = None;
-    
-    loop {{
-        let version_file = dir.join(".lua-version");
-        if version_file.exists() {{
-            if let Ok(content) = std::fs::read_to_string(&version_file) {{
-                version = Some(content.trim().to_string());
-                break;
-            }}
-        }}
-        
-        if let Some(parent) = dir.parent() {{
-            dir = parent.to_path_buf();
-        }} else {{
-            break;
-        }}
-    }}
-    
-    // Determine which binary to use
-    let bin_path = if let Some(ver) = version {{
-        let version_dir = PathBuf::from(&lpm_home).join("versions").join(&ver);
-        version_dir.join("bin").join("{}")
-    }} else {{
-        // Use current version (read from current file)
-        let current_file = PathBuf::from(&lpm_home).join("current");
-        if current_file.exists() {{
-            if let Ok(version) = std::fs::read_to_string(&current_file) {{
-                let version = version.trim();
-                if !version.is_empty() {{
-                    PathBuf::from(&lpm_home).join("versions").join(version).join("bin").join("{}")
-                }} else {{
-                    eprintln!("Error: No Lua version is currently selected");
-                    eprintln!("Run: depot lua use <version>");
-                    std::process::exit(1);
-                }}
-            }} else {{
-                eprintln!("Error: Failed to read current version file");
-                std::process::exit(1);
-            }}
-        }} else {{
-            eprintln!("Error: No Lua version is currently selected");
-            eprintln!("Run: depot lua use <version>");
-            std::process::exit(1);
-        }}
-    }};
-    
-    if !bin_path.exists() {{
-        eprintln!("Error: Lua binary not found at {{}}", bin_path.display());
-        std::process::exit(1);
-    }}
-    
-    // Execute the binary with all arguments
-    let args: Vec<String> = env::args().skip(1).collect();
-    let status = match Command::new(&bin_path)
-        .args(&args)
-        .status() {{
-        Ok(s) => s,
-        Err(e) => {{
-            eprintln!("Error: Failed to execute Lua binary: {{}}", e);
-            std::process::exit(1);
-        }}
-    }};
-
-    std::process::exit(status.code().unwrap_or(1));
-}}
-"#,
-            binary, binary
-        )
-    }
-
     fn print_setup_instructions(&self) {
         println!();
         println!("To use Depot-managed Lua versions, add this to your PATH:");
@@ -216,38 +109,28 @@ mod tests {
     }
 
     #[test]
-    fn test_wrapper_source_code_lua() {
+    fn test_generate_links_shims() {
         let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
+        let executable = temp.path().join("depot");
+        fs::write(&executable, "depot binary").unwrap();
 
-        // Verify the source code contains expected patterns
-        assert!(source.contains("fn main()"));
-        assert!(source.contains("Depot_LUA_DIR"));
-        assert!(source.contains(".lua-version"));
-        assert!(source.contains("lua"));
-    }
+        // A wrapper compiled by an earlier depot is replaced
+        let bin_dir = temp.path().join("home").join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        fs::write(bin_dir.join(executable_name("lua")), "old wrapper").unwrap();
 
-    #[test]
-    fn test_wrapper_source_code_luac() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("luac");
-
-        // Verify the source code contains expected patterns
-        assert!(source.contains("fn main()"));
-        assert!(source.contains("luac"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_contains_error_handling() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
+        let generator =
+            WrapperGenerator::new(&temp.path().join("home")).with_executable(executable.clone());
+        generator.generate().unwrap();
+        // Generating again replaces the existing links
+        generator.generate().unwrap();
 
-        // Verify error handling is present
-        assert!(source.contains("eprintln!"));
-        assert!(source.contains("std::process::exit"));
+        for binary in SHIMMED_BINARIES {
+            let shim = bin_dir.join(executable_name(binary));
+            assert_eq!(fs::read_to_string(&shim).unwrap(), "depot binary");
+            #[cfg(unix)]
+            assert_eq!(fs::read_link(&shim).unwrap(), executable);
+        }
     }
 
     #[test]
@@ -258,89 +141,6 @@ mod tests {
         generator.print_setup_instructions();
     }
 
-    #[test]
-    fn test_wrapper_source_code_contains_version_check() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should check for .lua-version file
-        assert!(source.contains(".lua-version"));
-        assert!(source.contains("version_file"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_handles_current_version() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should handle current version file
-        assert!(source.contains("current"));
-        assert!(source.contains("versions"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_has_proper_rust_syntax() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Verify basic Rust syntax elements
-        assert!(source.contains("fn main() {"));
-        assert!(source.contains("use std::"));
-        assert!(source.starts_with("fn main()"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_uses_environment_vars() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should use environment variables
-        assert!(source.contains("env::var"));
-        assert!(source.contains("HOME") || source.contains("APPDATA"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_has_path_logic() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should traverse parent directories
-        assert!(source.contains("parent()"));
-        assert!(source.contains("current_dir"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_executes_binary() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should execute the actual binary
-        assert!(source.contains("Command::new"));
-        assert!(source.contains(".args"));
-        assert!(source.contains(".status"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_different_for_lua_and_luac() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-
-        let lua_source = generator.wrapper_source_code("lua");
-        let luac_source = generator.wrapper_source_code("luac");
-
-        // Sources should be different (contain different binary names)
-        assert_ne!(lua_source, luac_source);
-        assert!(lua_source.contains("lua"));
-        assert!(!lua_source.contains("luac"));
-        assert!(luac_source.contains("luac"));
-    }
-
     #[test]
     fn test_generate_creates_bin_dir() {
         let temp = TempDir::new().unwrap();
@@ -349,31 +149,12 @@ mod tests {
         // Bin dir should not exist initially
         assert!(!generator.bin_dir.exists());
 
-        // Note: We can't test the actual generate() function without rustc
-        // but we can test that the bin_dir path is correct
-        assert!(generator.bin_dir.ends_with("bin"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_handles_windows_paths() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should have Windows-specific path handling
-        assert!(source.contains("APPDATA"));
-        assert!(source.contains("#[cfg(windows)]"));
-    }
-
-    #[test]
-    fn test_wrapper_source_code_handles_unix_paths() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should have Unix-specific path handling
-        assert!(source.contains("HOME"));
-        assert!(source.contains("#[cfg(unix)]"));
+        fs::write(temp.path().join("depot"), "depot binary").unwrap();
+        generator
+            .with_executable(temp.path().join("depot"))
+            .generate()
+            .unwrap();
+        assert!(temp.path().join("bin").is_dir());
     }
 
     #[test]
@@ -386,16 +167,4 @@ mod tests {
             assert!(generator.bin_dir.is_absolute());
         }
     }
-
-    #[test]
-    fn test_wrapper_source_code_checks_file_existence() {
-        let temp = TempDir::new().unwrap();
-        let generator = WrapperGenerator::new(temp.path());
-        let source = generator.wrapper_source_code("lua");
-
-        // Should check if files exist
-        assert!(source.contains(".exists()"));
-        assert!(source.contains("version_file.exists()"));
-        assert!(source.contains("bin_path.exists()"));
-    }
 }
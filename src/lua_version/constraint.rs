@@ -22,18 +22,24 @@ pub enum LuaVersionConstraint {
 
 impl LuaVersionConstraint {
     /// Check if a Lua version satisfies this constraint
+    ///
+    /// LuaJIT counts as Lua 5.1, but a constraint naming LuaJIT ("luajit")
+    /// is only met by LuaJIT.
     pub fn matches(&self, version: &LuaVersion) -> bool {
+        let same_minor = |v: &LuaVersion| {
+            version.major == v.major
+                && version.minor == v.minor
+                && (!v.is_luajit() || version.is_luajit())
+        };
         match self {
-            LuaVersionConstraint::Exact(v) => version.major == v.major && version.minor == v.minor,
+            LuaVersionConstraint::Exact(v) => same_minor(v),
             LuaVersionConstraint::GreaterOrEqual(v) => {
                 version.major > v.major || (version.major == v.major && version.minor >= v.minor)
             }
             LuaVersionConstraint::LessThan(v) => {
                 version.major < v.major || (version.major == v.major && version.minor < v.minor)
             }
-            LuaVersionConstraint::Multiple(versions) => versions
-                .iter()
-                .any(|v| version.major == v.major && version.minor == v.minor),
+            LuaVersionConstraint::Multiple(versions) => versions.iter().any(same_minor),
         }
     }
 }
@@ -218,4 +224,19 @@ mod tests {
         assert!(constraint.matches(&LuaVersion::new(5, 4, 0)));
         assert!(!constraint.matches(&LuaVersion::new(5, 2, 0)));
     }
+
+    #[test]
+    fn test_constraint_matches_luajit() {
+        let luajit = LuaVersion::luajit("2.1");
+        assert!(parse_lua_version_constraint("5.1")
+            .unwrap()
+            .matches(&luajit));
+        assert!(parse_lua_version_constraint("5.1 || 5.4")
+            .unwrap()
+            .matches(&luajit));
+
+        let constraint = parse_lua_version_constraint("luajit").unwrap();
+        assert!(constraint.matches(&luajit));
+        assert!(!constraint.matches(&LuaVersion::new(5, 1, 5)));
+    }
 }
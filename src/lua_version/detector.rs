@@ -9,11 +9,17 @@ pub struct LuaVersion {
     pub major: u64,
     pub minor: u64,
     pub patch: u64,
+    /// LuaJIT release (e.g. "2.1", "2.1-20231117"); `major.minor` is then
+    /// the language version LuaJIT implements, 5.1
+    pub luajit: Option<String>,
 }
 
 impl fmt::Display for LuaVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        match &self.luajit {
+            Some(release) => write!(f, "LuaJIT {}", release),
+            None => write!(f, "{}.{}.{}", self.major, self.minor, self.patch),
+        }
     }
 }
 
@@ -24,6 +30,15 @@ impl LuaVersion {
             major,
             minor,
             patch,
+            luajit: None,
+        }
+    }
+
+    /// Create a LuaJIT version, which implements the Lua 5.1 API
+    pub fn luajit(release: &str) -> Self {
+        Self {
+            luajit: Some(release.to_string()),
+            ..Self::new(5, 1, 0)
         }
     }
 
@@ -33,9 +48,27 @@ impl LuaVersion {
     /// - "Lua 5.4.6"
     /// - "Lua 5.3.6"
     /// - "Lua 5.1.5"
+    /// - "LuaJIT 2.1.1700008891 -- Copyright ..." and managed names such as
+    ///   "luajit-2.1" or "openresty-luajit-2.1-20231117"
     pub fn parse(version_str: &str) -> DepotResult<Self> {
         // Remove "Lua" prefix and whitespace
         let version_str = version_str.trim();
+
+        let lower = version_str.to_ascii_lowercase();
+        let jit = lower.strip_prefix("openresty-").unwrap_or(&lower);
+        if let Some(rest) = jit.strip_prefix("luajit") {
+            let release = rest
+                .trim_start_matches(['-', ' '])
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            return Ok(Self::luajit(if release.is_empty() {
+                "2.1"
+            } else {
+                release
+            }));
+        }
+
         let version_str = version_str
             .strip_prefix("Lua")
             .map(|s| s.trim())
@@ -61,21 +94,37 @@ impl LuaVersion {
 
         // Accept any Lua version (5.x and potentially future versions)
         // No hardcoded restriction - let the ecosystem evolve
-        Ok(Self {
-            major,
-            minor,
-            patch,
-        })
+        Ok(Self::new(major, minor, patch))
     }
 
-    /// Get version as string (e.g., "5.4.6")
+    /// Get version as string (e.g., "5.4.6" or "LuaJIT 2.1")
     pub fn version_string(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, self.patch)
+        self.to_string()
     }
 
     /// Get major.minor version (e.g., "5.4")
+    ///
+    /// LuaJIT is keyed by its own release line (e.g. "luajit-2.1"), since its
+    /// modules are not interchangeable with those built for Lua 5.1.
     pub fn major_minor(&self) -> String {
-        format!("{}.{}", self.major, self.minor)
+        match &self.luajit {
+            Some(release) => {
+                let line: Vec<&str> = release
+                    .split('-')
+                    .next()
+                    .unwrap_or_default()
+                    .split('.')
+                    .take(2)
+                    .collect();
+                format!("luajit-{}", line.join("."))
+            }
+            None => format!("{}.{}", self.major, self.minor),
+        }
+    }
+
+    /// Check if this is LuaJIT
+    pub fn is_luajit(&self) -> bool {
+        self.luajit.is_some()
     }
 
     /// Check if this is Lua 5.1
@@ -95,7 +144,9 @@ impl LuaVersion {
 
     /// Get mlua feature flag for this version
     pub fn mlua_feature(&self) -> &'static str {
-        if self.is_5_1() {
+        if self.is_luajit() {
+            "luajit"
+        } else if self.is_5_1() {
             "lua51"
         } else if self.is_5_3() {
             "lua53"
@@ -112,7 +163,7 @@ impl LuaVersion {
     /// This checks for:
     /// - lua (default Lua binary)
     /// - lua5.1, lua5.3, lua5.4 (versioned binaries)
-    /// - luajit (LuaJIT, reports as Lua 5.1)
+    /// - luajit (LuaJIT, which implements Lua 5.1)
     ///
     /// Returns a sorted list of unique versions
     pub fn discover_installed() -> Vec<LuaVersion> {
@@ -127,8 +178,7 @@ impl LuaVersion {
 
         for cmd in &commands {
             if let Ok(version) = LuaVersionDetector::detect_with_command(cmd) {
-                let key = (version.major, version.minor);
-                if seen.insert(key) {
+                if seen.insert(version.major_minor()) {
                     versions.push(version);
                 }
             }
@@ -234,6 +284,29 @@ mod tests {
         assert_eq!(LuaVersion::new(5, 1, 0).mlua_feature(), "lua51");
         assert_eq!(LuaVersion::new(5, 3, 0).mlua_feature(), "lua53");
         assert_eq!(LuaVersion::new(5, 4, 0).mlua_feature(), "lua54");
+        assert_eq!(LuaVersion::luajit("2.1").mlua_feature(), "luajit");
+    }
+
+    #[test]
+    fn test_parse_luajit() {
+        let v = LuaVersion::parse(
+            "LuaJIT 2.1.1700008891 -- Copyright (C) 2005-2023 Mike Pall. https://luajit.org/",
+        )
+        .unwrap();
+        assert_eq!(v, LuaVersion::luajit("2.1.1700008891"));
+        assert!(v.is_luajit());
+        assert!(v.is_5_1());
+        assert_eq!(v.major_minor(), "luajit-2.1");
+        assert_eq!(v.to_string(), "LuaJIT 2.1.1700008891");
+
+        let openresty = LuaVersion::parse("openresty-luajit-2.1-20231117").unwrap();
+        assert_eq!(openresty.luajit.as_deref(), Some("2.1-20231117"));
+        assert_eq!(openresty.major_minor(), "luajit-2.1");
+
+        assert_eq!(
+            LuaVersion::parse("luajit").unwrap(),
+            LuaVersion::luajit("2.1")
+        );
     }
 
     #[test]
@@ -319,8 +392,10 @@ mod tests {
         let versions = LuaVersion::discover_installed();
         let mut seen = std::collections::HashSet::new();
         for v in &versions {
-            let key = (v.major, v.minor);
-            assert!(seen.insert(key), "Should not have duplicate versions");
+            assert!(
+                seen.insert(v.major_minor()),
+                "Should not have duplicate versions"
+            );
         }
     }
 }
@@ -1,5 +1,6 @@
 use clap::{Parser, Subcommand};
-use depot::core::DepotError;
+use depot::core::{DepotError, DepotResult};
+use depot::lua_manager::shim::shim_name;
 use std::process::ExitCode;
 use tracing_subscriber::EnvFilter;
 
@@ -64,6 +65,9 @@ enum Commands {
         /// Install from a specific release tag
         #[arg(short = 'r', long, conflicts_with_all = &["branch", "commit"])]
         release: Option<String>,
+        /// Resolve releases younger than min_release_age
+        #[arg(long)]
+        ignore_cooldown: bool,
     },
     /// Remove a dependency
     Remove {
@@ -83,6 +87,18 @@ enum Commands {
         /// Filter workspace packages (e.g., "package-a", "packages/*", "...package-a", "package-a...")
         #[arg(short = 'f', long)]
         filter: Vec<String>,
+        /// Pick upgrade targets interactively (stay, patch, minor or latest)
+        #[arg(short = 'i', long, conflicts_with_all = &["package", "filter"])]
+        interactive: bool,
+        /// Skip fetching upstream release notes and commit summaries
+        #[arg(long)]
+        no_notes: bool,
+        /// Write the update summary (with release notes) to a JSON file
+        #[arg(long, value_name = "FILE")]
+        json: Option<String>,
+        /// Resolve releases younger than min_release_age
+        #[arg(long)]
+        ignore_cooldown: bool,
     },
     /// List installed packages
     List {
@@ -93,8 +109,68 @@ enum Commands {
         #[arg(short = 'g', long)]
         global: bool,
     },
+    /// Explain why a package is installed
+    Why {
+        /// Package name (owner/repo or repo)
+        package: String,
+    },
+    /// Export the dependency graph (DOT, Mermaid or JSON)
+    Graph {
+        /// Output format: dot, mermaid or json
+        #[arg(long, default_value = "dot")]
+        format: String,
+        /// Include dev dependencies
+        #[arg(long)]
+        dev: bool,
+        /// Maximum depth to include (1 = direct dependencies only)
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Highlight packages that contain native code
+        #[arg(long)]
+        highlight_native: bool,
+        /// Colour packages with known vulnerabilities (queries OSV)
+        #[arg(long)]
+        audit: bool,
+        /// Write the graph to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Generate a software bill of materials from depot.lock
+    Sbom {
+        /// Output format: cyclonedx-json or spdx-json
+        #[arg(long, default_value = "cyclonedx-json")]
+        format: String,
+        /// Write the SBOM to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Check dependency licenses and generate notices
+    #[command(subcommand)]
+    Licenses(cli::licenses::LicensesCommands),
+    /// Report dependencies that use sensitive Lua APIs
+    ScanCapabilities {
+        /// Only scan this package
+        #[arg(short, long)]
+        package: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check dependencies against the organisation dependency policy
+    #[command(subcommand)]
+    Policy(cli::policy::PolicyCommands),
+    /// Manage repository identities pinned in depot.lock
+    #[command(subcommand)]
+    Trust(cli::trust::TrustCommands),
     /// Verify package checksums
-    Verify,
+    Verify {
+        /// Check installed files in lua_modules/ against the hashes recorded at install
+        #[arg(long)]
+        installed: bool,
+        /// Reinstall packages whose installed files were modified or removed
+        #[arg(long, requires = "installed")]
+        repair: bool,
+    },
     /// Clean lua_modules directory
     Clean,
     /// Run a script
@@ -129,7 +205,25 @@ enum Commands {
         target: Option<String>,
     },
     /// Security audit
-    Audit,
+    Audit {
+        #[command(subcommand)]
+        command: Option<cli::audit::AuditCommands>,
+        /// Use only the local advisory database (no network access)
+        #[arg(long, global = true)]
+        offline: bool,
+        /// Advisory database directory or zip of OSV JSON files (overrides config advisory_db)
+        #[arg(long, value_name = "PATH", requires = "offline", global = true)]
+        db: Option<String>,
+        /// Output format: text, json or sarif
+        #[arg(long, default_value = "text")]
+        format: String,
+        /// Report archived, stale and branch-pinned dependencies instead of vulnerabilities
+        #[arg(long, conflicts_with = "offline")]
+        health: bool,
+        /// Months without commits or releases before --health reports a dependency
+        #[arg(long, value_name = "N", default_value_t = depot::security::health::DEFAULT_STALE_MONTHS, requires = "health")]
+        stale_months: u32,
+    },
     /// Check Lua version compatibility
     Compat {
         /// Only show incompatibilities
@@ -466,6 +560,11 @@ fn get_shell_profile(shell: &str) -> String {
 
 #[tokio::main]
 async fn main() -> ExitCode {
+    // Invoked through a lua, luac or luajit link: act as that interpreter
+    if let Some(binary) = std::env::args_os().next().as_deref().and_then(shim_name) {
+        return exit_code(cli::shim::run(binary));
+    }
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
@@ -494,6 +593,7 @@ async fn main() -> ExitCode {
             branch,
             commit,
             release,
+            ignore_cooldown,
         } => {
             cli::install::run(cli::install::InstallOptions {
                 package,
@@ -507,6 +607,7 @@ async fn main() -> ExitCode {
                 branch,
                 commit,
                 release,
+                ignore_cooldown,
             })
             .await
         }
@@ -515,9 +616,63 @@ async fn main() -> ExitCode {
             global,
             filter,
         } => cli::remove::run(package, global, filter),
-        Commands::Update { package, filter } => cli::update::run(package, filter).await,
+        Commands::Update {
+            package,
+            filter,
+            interactive,
+            no_notes,
+            json,
+            ignore_cooldown,
+        } => {
+            cli::update::run(cli::update::UpdateOptions {
+                package,
+                filter,
+                interactive,
+                no_notes,
+                json,
+                ignore_cooldown,
+            })
+            .await
+        }
         Commands::List { tree, global } => cli::list::run(tree, global),
-        Commands::Verify => cli::verify::run(),
+        Commands::Why { package } => cli::why::run(package),
+        Commands::Graph {
+            format,
+            dev,
+            depth,
+            highlight_native,
+            audit,
+            output,
+        } => {
+            cli::graph::run(cli::graph::GraphOptions {
+                format,
+                dev,
+                depth,
+                highlight_native,
+                audit,
+                output,
+            })
+            .await
+        }
+        Commands::Sbom { format, output } => {
+            cli::sbom::run(cli::sbom::SbomOptions { format, output })
+        }
+        Commands::Licenses(cmd) => cli::licenses::run(cmd),
+        Commands::ScanCapabilities { package, json } => {
+            cli::scan_capabilities::run(cli::scan_capabilities::ScanCapabilitiesOptions {
+                package,
+                json,
+            })
+        }
+        Commands::Policy(cmd) => cli::policy::run(cmd).await,
+        Commands::Trust(cmd) => cli::trust::run(cmd).await,
+        Commands::Verify { installed, repair } => {
+            if installed {
+                cli::verify::run_installed(repair).await
+            } else {
+                cli::verify::run()
+            }
+        }
         Commands::Clean => cli::clean::run(),
         Commands::Run { script, filter } => cli::run::run(script, filter),
         Commands::Exec { command } => cli::exec::run(command),
@@ -527,7 +682,28 @@ async fn main() -> ExitCode {
             filter,
         } => cli::build::run(target, all_targets, filter),
         Commands::Package { target } => cli::package::run(target),
-        Commands::Audit => cli::audit::run().await,
+        Commands::Audit {
+            command,
+            offline,
+            db,
+            format,
+            health,
+            stale_months,
+        } => {
+            let options = cli::audit::AuditOptions {
+                offline,
+                db,
+                format,
+                health,
+                stale_months,
+            };
+            match command {
+                Some(cli::audit::AuditCommands::Fix { allow_major }) => {
+                    cli::audit::fix(options, allow_major).await
+                }
+                None => cli::audit::run(options).await,
+            }
+        }
         Commands::Compat { quiet, json } => cli::compat::run(quiet, json),
         Commands::Setup => depot_setup(),
         Commands::Config(cmd) => match cmd {
@@ -550,7 +726,11 @@ async fn main() -> ExitCode {
         }
     };
 
-    // Handle result and exit codes
+    exit_code(result)
+}
+
+/// Report a command's result and turn it into the process exit code
+fn exit_code(result: DepotResult<()>) -> ExitCode {
     match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
@@ -1,18 +1,240 @@
 use crate::core::{DepotError, DepotResult};
 use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs::{self, File};
-use std::path::{Path, PathBuf};
-use tar::Archive;
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, EntryType};
+use walkdir::WalkDir;
+
+/// Unix file type bits, as stored in zip external attributes
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+
+/// Limits applied while extracting a package archive
+///
+/// They bound the extracted size, not the download, so a small compressed
+/// archive cannot expand into gigabytes on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveLimits {
+    /// Maximum total size of extracted files, in bytes
+    #[serde(default = "default_max_total_size")]
+    pub max_total_size: u64,
+    /// Maximum number of entries (files, directories and links)
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_max_total_size() -> u64 {
+    512 * 1024 * 1024
+}
+
+fn default_max_entries() -> usize {
+    20_000
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: default_max_total_size(),
+            max_entries: default_max_entries(),
+        }
+    }
+}
 
 /// Extracts package archives (tar.gz, zip) to temporary directories
+///
+/// Entries are checked one by one before anything is written: absolute
+/// paths, `..` components, links leaving the extraction directory, device
+/// files and duplicate entries are rejected, and the configured
+/// [`ArchiveLimits`] are enforced. Once extracted, every symlink must
+/// resolve to a path inside the extracted tree.
 pub struct PackageExtractor {
     dest_dir: PathBuf,
+    limits: ArchiveLimits,
+}
+
+/// Running totals checked against the limits while extracting
+struct ExtractionState {
+    limits: ArchiveLimits,
+    seen: HashSet<PathBuf>,
+    entries: usize,
+    total_size: u64,
+}
+
+impl ExtractionState {
+    fn new(limits: ArchiveLimits) -> Self {
+        Self {
+            limits,
+            seen: HashSet::new(),
+            entries: 0,
+            total_size: 0,
+        }
+    }
+
+    /// Record an entry, rejecting duplicates and enforcing the entry limit
+    fn add_entry(&mut self, path: &Path) -> DepotResult<()> {
+        if !self.seen.insert(path.to_path_buf()) {
+            return Err(DepotError::Package(format!(
+                "Archive contains duplicate entry '{}'",
+                path.display()
+            )));
+        }
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(DepotError::Package(format!(
+                "Archive has more than {} entries",
+                self.limits.max_entries
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reserve space for a file, enforcing the total size limit
+    fn add_size(&mut self, path: &Path, size: u64) -> DepotResult<()> {
+        self.total_size = self.total_size.saturating_add(size);
+        if self.total_size > self.limits.max_total_size {
+            return Err(DepotError::Package(format!(
+                "Archive expands to more than {} bytes (limit reached at '{}')",
+                self.limits.max_total_size,
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Normalize an entry path to a relative path inside the archive
+///
+/// Returns `None` for entries that name the archive root itself (e.g. `./`).
+fn sanitize_entry_path(path: &Path) -> DepotResult<Option<PathBuf>> {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(DepotError::Package(format!(
+                    "Archive entry '{}' uses '..' to escape the package directory",
+                    path.display()
+                )));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(DepotError::Package(format!(
+                    "Archive entry '{}' has an absolute path",
+                    path.display()
+                )));
+            }
+        }
+    }
+    Ok((!clean.as_os_str().is_empty()).then_some(clean))
+}
+
+/// Check that `path`, or its nearest existing ancestor, resolves inside `root`
+///
+/// `root` must be canonical. Called before anything is created at `path`,
+/// so no directory is ever created through a symlink that leaves the archive.
+fn check_inside(root: &Path, path: &Path, entry: &Path) -> DepotResult<()> {
+    let mut existing = path;
+    while fs::symlink_metadata(existing).is_err() {
+        existing = existing.parent().unwrap_or(root);
+    }
+    let inside = existing
+        .canonicalize()
+        .is_ok_and(|resolved| resolved.starts_with(root));
+    if !inside {
+        return Err(DepotError::Package(format!(
+            "Archive entry '{}' escapes the package directory",
+            entry.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Check that every symlink in an extracted tree resolves to a path inside it
+///
+/// Link targets are checked one at a time during extraction, which cannot
+/// see a target that climbs out through another link. Resolving each link on
+/// disk catches those chains; links that do not resolve are refused too.
+fn check_extracted_links(temp_dir: &Path) -> DepotResult<()> {
+    let root = temp_dir.canonicalize()?;
+    for entry in WalkDir::new(temp_dir).follow_links(false) {
+        let entry =
+            entry.map_err(|e| DepotError::Package(format!("Failed to read archive: {}", e)))?;
+        if !entry.path_is_symlink() {
+            continue;
+        }
+        let inside = entry
+            .path()
+            .canonicalize()
+            .is_ok_and(|resolved| resolved.starts_with(&root));
+        if !inside {
+            let relative = entry.path().strip_prefix(temp_dir).unwrap_or(entry.path());
+            let target = fs::read_link(entry.path()).unwrap_or_default();
+            return Err(DepotError::Package(format!(
+                "Archive symlink '{}' points outside the package directory ('{}')",
+                relative.display(),
+                target.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check that a symlink at `entry` pointing to `target` stays inside the archive
+fn check_symlink_target(entry: &Path, target: &Path) -> DepotResult<()> {
+    let escapes = || {
+        DepotError::Package(format!(
+            "Archive symlink '{}' points outside the package directory ('{}')",
+            entry.display(),
+            target.display()
+        ))
+    };
+
+    let mut depth: Vec<&std::ffi::OsStr> = entry
+        .parent()
+        .map(|p| {
+            p.components()
+                .filter_map(|c| match c {
+                    Component::Normal(part) => Some(part),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if target.as_os_str().is_empty() {
+        return Err(escapes());
+    }
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => depth.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                depth.pop().ok_or_else(escapes)?;
+            }
+            Component::RootDir | Component::Prefix(_) => return Err(escapes()),
+        }
+    }
+    Ok(())
 }
 
 impl PackageExtractor {
-    /// Create a new PackageExtractor
+    /// Create a new PackageExtractor with the default limits
     pub fn new(dest_dir: PathBuf) -> Self {
-        Self { dest_dir }
+        Self {
+            dest_dir,
+            limits: ArchiveLimits::default(),
+        }
+    }
+
+    /// Use custom extraction limits
+    pub fn with_limits(mut self, limits: ArchiveLimits) -> Self {
+        self.limits = limits;
+        self
     }
 
     /// Extract an archive file
@@ -35,59 +257,119 @@ impl PackageExtractor {
 
         // Cleanup temp directory on error
         if result.is_err() {
-            let temp_dir = self.dest_dir.join(format!(
-                ".tmp-{}",
-                archive_path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-            ));
-            let _ = fs::remove_dir_all(&temp_dir); // Ignore cleanup errors
+            let _ = fs::remove_dir_all(self.temp_dir(archive_path)); // Ignore cleanup errors
         }
 
         result
     }
 
-    fn extract_targz(&self, archive_path: &Path) -> DepotResult<PathBuf> {
-        let file = File::open(archive_path)?;
-        let decoder = GzDecoder::new(file);
-        let mut archive = Archive::new(decoder);
-
-        let temp_dir = self.dest_dir.join(format!(
+    fn temp_dir(&self, archive_path: &Path) -> PathBuf {
+        self.dest_dir.join(format!(
             ".tmp-{}",
             archive_path
                 .file_stem()
                 .and_then(|s| s.to_str())
                 .unwrap_or("unknown")
-        ));
+        ))
+    }
 
-        // Clean up any existing temp dir
+    /// Create an empty temp directory for an archive, removing any previous one
+    fn prepare_temp_dir(&self, archive_path: &Path) -> DepotResult<PathBuf> {
+        let temp_dir = self.temp_dir(archive_path);
         if temp_dir.exists() {
             fs::remove_dir_all(&temp_dir)?;
         }
         fs::create_dir_all(&temp_dir)?;
+        Ok(temp_dir)
+    }
+
+    fn extract_targz(&self, archive_path: &Path) -> DepotResult<PathBuf> {
+        let file = File::open(archive_path)?;
+        let decoder = GzDecoder::new(file);
+        let mut archive = Archive::new(decoder);
+        archive.set_preserve_permissions(false);
+        archive.set_unpack_xattrs(false);
+
+        let temp_dir = self.prepare_temp_dir(archive_path)?;
+        let mut state = ExtractionState::new(self.limits);
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let entry_type = entry.header().entry_type();
+
+            // pax/GNU metadata records (e.g. GitHub's pax_global_header) carry no file
+            if matches!(
+                entry_type,
+                EntryType::XGlobalHeader
+                    | EntryType::XHeader
+                    | EntryType::GNULongName
+                    | EntryType::GNULongLink
+            ) {
+                continue;
+            }
 
-        archive.unpack(&temp_dir)?;
-
-        // Find root directory (standardize: use first directory found)
-        let mut entries = fs::read_dir(&temp_dir)?;
-        let root = entries
-            .find_map(|e| {
-                let entry = e.ok()?;
-                if entry.file_type().ok()?.is_dir() {
-                    Some(entry.path())
-                } else {
-                    None
+            let raw_path = entry.path()?.into_owned();
+            let Some(path) = sanitize_entry_path(&raw_path)? else {
+                continue;
+            };
+            state.add_entry(&path)?;
+
+            match entry_type {
+                EntryType::Regular | EntryType::Continuous | EntryType::GNUSparse => {
+                    state.add_size(&path, entry.size())?;
+                }
+                EntryType::Directory => {}
+                EntryType::Symlink => {
+                    let target = entry.link_name()?.ok_or_else(|| {
+                        DepotError::Package(format!(
+                            "Archive symlink '{}' has no target",
+                            path.display()
+                        ))
+                    })?;
+                    check_symlink_target(&path, &target)?;
+                }
+                EntryType::Link => {
+                    let target = entry.link_name()?.ok_or_else(|| {
+                        DepotError::Package(format!(
+                            "Archive hardlink '{}' has no target",
+                            path.display()
+                        ))
+                    })?;
+                    let target = sanitize_entry_path(&target)?.unwrap_or_default();
+                    if !state.seen.contains(&target) || target == path {
+                        return Err(DepotError::Package(format!(
+                            "Archive hardlink '{}' must point to an earlier file in the archive ('{}')",
+                            path.display(),
+                            target.display()
+                        )));
+                    }
                 }
-            })
-            .ok_or_else(|| {
-                // If no root dir, archive might have files at root - use temp_dir itself
-                DepotError::Package(
-                    "Archive has no root directory. Files at root level not supported.".to_string(),
-                )
-            })?;
+                EntryType::Char | EntryType::Block | EntryType::Fifo => {
+                    return Err(DepotError::Package(format!(
+                        "Archive entry '{}' is a device or FIFO, which packages may not contain",
+                        path.display()
+                    )));
+                }
+                other => {
+                    return Err(DepotError::Package(format!(
+                        "Archive entry '{}' has unsupported type {:?}",
+                        path.display(),
+                        other
+                    )));
+                }
+            }
+
+            // unpack_in refuses to write through symlinks that leave temp_dir
+            if !entry.unpack_in(&temp_dir)? {
+                return Err(DepotError::Package(format!(
+                    "Archive entry '{}' escapes the package directory",
+                    path.display()
+                )));
+            }
+        }
 
-        Ok(root)
+        check_extracted_links(&temp_dir)?;
+        Self::find_root(&temp_dir)
     }
 
     fn extract_zip(&self, archive_path: &Path) -> DepotResult<PathBuf> {
@@ -97,42 +379,88 @@ impl PackageExtractor {
         let mut archive = ZipArchive::new(file)
             .map_err(|e| DepotError::Package(format!("Invalid zip: {}", e)))?;
 
-        let temp_dir = self.dest_dir.join(format!(
-            ".tmp-{}",
-            archive_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("unknown")
-        ));
+        let temp_dir = self.prepare_temp_dir(archive_path)?;
+        let root = temp_dir.canonicalize()?;
+        let mut state = ExtractionState::new(self.limits);
+
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|e| DepotError::Package(format!("Extract failed: {}", e)))?;
+
+            let raw_path = PathBuf::from(file.name().replace('\\', "/"));
+            let Some(path) = sanitize_entry_path(&raw_path)? else {
+                continue;
+            };
+            state.add_entry(&path)?;
+            let dest = temp_dir.join(&path);
+
+            // Refuse to create anything through a symlink created by an earlier entry
+            let file_type = file.unix_mode().map(|m| m & S_IFMT).unwrap_or(0);
+            if file.is_dir() || file_type == S_IFDIR {
+                check_inside(&root, &dest, &path)?;
+                fs::create_dir_all(&dest)?;
+                continue;
+            }
 
-        // Clean up any existing temp dir
-        if temp_dir.exists() {
-            fs::remove_dir_all(&temp_dir)?;
-        }
-        fs::create_dir_all(&temp_dir)?;
+            let parent = dest.parent().unwrap_or(&temp_dir);
+            check_inside(&root, parent, &path)?;
+            fs::create_dir_all(parent)?;
+            if fs::symlink_metadata(&dest).is_ok() {
+                return Err(DepotError::Package(format!(
+                    "Archive entry '{}' would overwrite an existing path",
+                    path.display()
+                )));
+            }
 
-        archive
-            .extract(&temp_dir)
-            .map_err(|e| DepotError::Package(format!("Extract failed: {}", e)))?;
-
-        // Find root directory (standardize: use first directory found)
-        let mut entries = fs::read_dir(&temp_dir)?;
-        let root = entries
-            .find_map(|e| {
-                let entry = e.ok()?;
-                if entry.file_type().ok()?.is_dir() {
-                    Some(entry.path())
-                } else {
-                    None
+            match file_type {
+                S_IFLNK => {
+                    let mut target = String::new();
+                    file.read_to_string(&mut target)?;
+                    let target = PathBuf::from(target);
+                    check_symlink_target(&path, &target)?;
+                    #[cfg(unix)]
+                    std::os::unix::fs::symlink(&target, &dest)?;
+                }
+                0 | S_IFREG => {
+                    // The declared size can lie, so count the bytes actually written
+                    let remaining = state.limits.max_total_size - state.total_size;
+                    let mut out = File::create(&dest)?;
+                    let written =
+                        io::copy(&mut (&mut file).take(remaining.saturating_add(1)), &mut out)?;
+                    state.add_size(&path, written)?;
+                }
+                _ => {
+                    return Err(DepotError::Package(format!(
+                        "Archive entry '{}' is a device or FIFO, which packages may not contain",
+                        path.display()
+                    )));
                 }
-            })
-            .ok_or_else(|| {
-                DepotError::Package(
-                    "Archive has no root directory. Files at root level not supported.".to_string(),
-                )
-            })?;
-
-        Ok(root)
+            }
+        }
+
+        check_extracted_links(&temp_dir)?;
+        Self::find_root(&temp_dir)
+    }
+
+    /// The single top-level directory of an extracted archive
+    fn find_root(temp_dir: &Path) -> DepotResult<PathBuf> {
+        let entries: Vec<fs::DirEntry> = fs::read_dir(temp_dir)?.filter_map(|e| e.ok()).collect();
+        let dirs: Vec<&fs::DirEntry> = entries
+            .iter()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .collect();
+
+        match dirs.as_slice() {
+            [] => Err(DepotError::Package(
+                "Archive has no root directory. Files at root level not supported.".to_string(),
+            )),
+            [root] if entries.len() == 1 => Ok(root.path()),
+            _ => Err(DepotError::Package(format!(
+                "Archive must contain a single root directory, found {} top-level entries",
+                entries.len()
+            ))),
+        }
     }
 }
 
@@ -3,10 +3,14 @@
 use crate::core::path::{depot_metadata_dir, ensure_dir, lua_modules_dir, packages_metadata_dir};
 use crate::core::{DepotError, DepotResult};
 use crate::di::traits::{CacheProvider, GitHubProvider};
-use crate::github::types::ResolvedVersion;
-use crate::package::extractor::PackageExtractor;
+use crate::github::types::{RefType, ResolvedVersion};
+use crate::package::extractor::{ArchiveLimits, PackageExtractor};
+use crate::package::license::{detect_license, is_license_file};
 use crate::package::lockfile::{LockedPackage, Lockfile};
 use crate::package::manifest::PackageManifest;
+use crate::package::metadata::PackageMetadata;
+use crate::security::signature::{SignaturePolicy, SignatureStatus, PACKAGE_SIGNATURE_ASSET};
+use crate::security::trust;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -22,6 +26,7 @@ pub struct PackageInstaller {
     github: Arc<dyn GitHubProvider>,
     extractor: PackageExtractor,
     fallback_chain: Vec<String>,
+    signatures: SignaturePolicy,
 }
 
 impl PackageInstaller {
@@ -46,9 +51,22 @@ impl PackageInstaller {
             github,
             extractor,
             fallback_chain,
+            signatures: SignaturePolicy::default(),
         })
     }
 
+    /// Apply limits to the size and entry count of extracted archives
+    pub fn with_archive_limits(mut self, limits: ArchiveLimits) -> Self {
+        self.extractor = PackageExtractor::new(self.lua_modules.clone()).with_limits(limits);
+        self
+    }
+
+    /// Verify release signatures against trusted publisher keys
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signatures = policy;
+        self
+    }
+
     /// Initialize the directory structure
     pub fn init(&self) -> DepotResult<()> {
         ensure_dir(&self.lua_modules)?;
@@ -77,8 +95,13 @@ impl PackageInstaller {
         }
         let owner = parts[0];
         let repo = parts[1];
+        let lockfile = Lockfile::load(&self.project_root)?;
+        let locked_pkg = lockfile.as_ref().and_then(|l| l.get_package(repository));
 
-        // Step 1: Resolve version using GitHub API
+        // Step 1: Check the pinned repository identity, then resolve the version
+        if let Some(pinned) = locked_pkg.and_then(|pkg| pkg.repository_id) {
+            trust::verify_repository(self.github.as_ref(), repository, Some(pinned)).await?;
+        }
         println!("  Resolving version...");
         let resolved = self
             .github
@@ -98,33 +121,51 @@ impl PackageInstaller {
             .await?;
 
         // Step 3: Verify checksum if lockfile exists
-        if let Some(lockfile) = Lockfile::load(&self.project_root)? {
-            if let Some(locked_pkg) = lockfile.get_package(repository) {
-                println!("  Verifying checksum...");
-                let actual = self.cache.checksum(&tarball_path)?;
-                if actual != locked_pkg.checksum {
-                    return Err(DepotError::Package(format!(
-                        "Checksum mismatch for {}. Expected {}, got {}",
-                        repository, locked_pkg.checksum, actual
-                    )));
-                }
-                println!("  ✓ Checksum verified");
+        if let Some(locked_pkg) = locked_pkg {
+            println!("  Verifying checksum...");
+            let actual = self.cache.checksum(&tarball_path)?;
+            if actual != locked_pkg.checksum {
+                return Err(DepotError::Package(format!(
+                    "Checksum mismatch for {}. Expected {}, got {}",
+                    repository, locked_pkg.checksum, actual
+                )));
             }
+            println!("  ✓ Checksum verified");
         }
 
-        // Step 4: Extract tarball
+        // Step 4: Verify the publisher signature
+        self.verify_signature(owner, repo, &resolved, &tarball_path)
+            .await?;
+
+        // Step 5: Extract tarball
         println!("  Extracting...");
         let extracted_path = self.extractor.extract(&tarball_path)?;
 
-        // Step 5: Try to read package.yaml for build instructions
+        // Step 6: Try to read package.yaml for build instructions
         let package_manifest = self.read_package_manifest(&extracted_path).ok();
 
-        // Step 6: Install files
+        // Step 7: Install files
         println!("  Installing...");
         let package_name = format!("{}/{}", owner, repo);
         self.install_from_extracted(&extracted_path, &package_name, package_manifest.as_ref())?;
 
-        // Step 7: Calculate checksum and size for lockfile
+        // Step 8: Record per-file hashes for `depot verify --installed`
+        let mut metadata = PackageMetadata::new(
+            package_name.clone(),
+            resolved.ref_value.clone(),
+            package_name.clone(),
+            format!("{:?}", resolved.ref_type),
+            resolved.ref_value.clone(),
+            resolved.commit_sha.clone(),
+        );
+        metadata.license = package_manifest
+            .as_ref()
+            .and_then(|m| m.license.clone())
+            .or_else(|| detect_license(&extracted_path));
+        metadata.record_files(&self.lua_modules.join(&package_name))?;
+        metadata.save(&PackageMetadata::path_in(&self.packages_dir, &package_name))?;
+
+        // Step 9: Calculate checksum and size for lockfile
         let checksum = self.cache.checksum(&tarball_path)?;
         let _size = fs::metadata(&tarball_path)?.len();
 
@@ -133,6 +174,60 @@ impl PackageInstaller {
         Ok(self.lua_modules.join(&package_name))
     }
 
+    /// Check the release's signature asset against the signature policy
+    ///
+    /// Only releases can carry a signature; tags, branches and commits are
+    /// treated as unsigned.
+    async fn verify_signature(
+        &self,
+        owner: &str,
+        repo: &str,
+        resolved: &ResolvedVersion,
+        tarball_path: &Path,
+    ) -> DepotResult<()> {
+        if !self.signatures.is_enabled() {
+            return Ok(());
+        }
+
+        let package = format!("{}/{}", owner, repo);
+        let mut signature = None;
+        if resolved.ref_type == RefType::Release {
+            let releases = self.github.get_releases(owner, repo).await?;
+            let asset = releases
+                .iter()
+                .find(|r| r.tag_name == resolved.ref_value)
+                .and_then(|r| r.assets.iter().find(|a| a.name == PACKAGE_SIGNATURE_ASSET));
+            if let Some(asset) = asset {
+                let bytes = self
+                    .github
+                    .download_asset(&asset.browser_download_url)
+                    .await?;
+                signature = Some(String::from_utf8(bytes).map_err(|_| {
+                    DepotError::Package(format!(
+                        "Invalid signature for {}: {} is not text",
+                        package, PACKAGE_SIGNATURE_ASSET
+                    ))
+                })?);
+            }
+        }
+
+        let tarball = fs::read(tarball_path)?;
+        match self
+            .signatures
+            .check(&package, "source tarball", &tarball, signature.as_deref())?
+        {
+            SignatureStatus::Verified(comment) => {
+                println!("  ✓ Signature verified ({})", comment)
+            }
+            SignatureStatus::Untrusted => println!(
+                "  ⚠️  {} is signed, but no trusted key is configured for it",
+                package
+            ),
+            SignatureStatus::Unsigned => {}
+        }
+        Ok(())
+    }
+
     /// Read package.yaml from extracted package
     fn read_package_manifest(&self, extracted_path: &Path) -> DepotResult<PackageManifest> {
         let filenames = vec!["package.yaml", "package.yml", ".depot", ".depot.yaml"];
@@ -178,15 +273,19 @@ impl PackageInstaller {
             }
         }
 
-        // Copy any .lua files in the root
+        // Copy any .lua files and license files in the root
         for entry in WalkDir::new(source_path)
             .max_depth(1)
             .into_iter()
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
-            if path.is_file() && path.extension().is_some_and(|ext| ext == "lua") {
-                let file_name = path.file_name().unwrap();
+            if !path.is_file() {
+                continue;
+            }
+            let file_name = path.file_name().unwrap();
+            let is_lua = path.extension().is_some_and(|ext| ext == "lua");
+            if is_lua || file_name.to_str().is_some_and(is_license_file) {
                 fs::copy(path, dest.join(file_name))?;
             }
         }
@@ -215,7 +314,7 @@ impl PackageInstaller {
         fs::remove_dir_all(&package_dir)?;
 
         // Remove metadata file if it exists
-        let metadata_file = self.packages_dir.join(format!("{}.yaml", package_name));
+        let metadata_file = PackageMetadata::path_in(&self.packages_dir, package_name);
         if metadata_file.exists() {
             fs::remove_file(&metadata_file)?;
         }
@@ -223,6 +322,48 @@ impl PackageInstaller {
         Ok(())
     }
 
+    /// Reinstall a package, keeping the current copy until the new one is in place
+    ///
+    /// The installed directory and its metadata are moved aside first and put
+    /// back if the install fails.
+    pub async fn reinstall_package(
+        &self,
+        repository: &str,
+        version: Option<&str>,
+    ) -> DepotResult<PathBuf> {
+        let package_dir = self.lua_modules.join(repository);
+        let metadata_file = PackageMetadata::path_in(&self.packages_dir, repository);
+        let staged = [
+            (package_dir.clone(), staged_path(&package_dir)),
+            (metadata_file.clone(), staged_path(&metadata_file)),
+        ];
+
+        for (path, backup) in &staged {
+            remove_path(backup)?;
+            if path.exists() {
+                fs::rename(path, backup)?;
+            }
+        }
+
+        match self.install_package(repository, version).await {
+            Ok(installed) => {
+                for (_, backup) in &staged {
+                    remove_path(backup)?;
+                }
+                Ok(installed)
+            }
+            Err(e) => {
+                for (path, backup) in &staged {
+                    if backup.exists() {
+                        remove_path(path)?;
+                        fs::rename(backup, path)?;
+                    }
+                }
+                Err(e)
+            }
+        }
+    }
+
     /// Create a lockfile entry for an installed package
     pub fn create_lockfile_entry(
         &self,
@@ -267,10 +408,32 @@ impl PackageInstaller {
             dependencies: dep_map,
             build: None,
             native_code: None,
+            license: PackageMetadata::recorded_license(&self.packages_dir, repository),
+            capabilities: Vec::new(),
+            repository_id: None,
         })
     }
 }
 
+/// Sibling path an installed file or directory is moved to during a reinstall
+fn staged_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.depot-old", name))
+}
+
+/// Remove a file or directory if it exists
+fn remove_path(path: &Path) -> DepotResult<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
 /// Copy a directory recursively
 fn copy_dir_recursive(src: &Path, dst: &Path) -> DepotResult<()> {
     fs::create_dir_all(dst)?;
@@ -300,6 +463,7 @@ mod tests {
     use super::*;
     use crate::di::mocks::{MockCacheProvider, MockGitHubProvider};
     use crate::github::types::{RefType, ResolvedVersion};
+    use std::collections::HashMap;
     use tempfile::TempDir;
 
     #[test]
@@ -313,6 +477,118 @@ mod tests {
         assert_eq!(installer.project_root, temp.path());
     }
 
+    fn signed_release_installer(
+        temp: &TempDir,
+        signature: Option<String>,
+        policy: SignaturePolicy,
+    ) -> (PackageInstaller, ResolvedVersion, PathBuf) {
+        use crate::github::types::{GitHubRelease, ReleaseAsset};
+
+        let tarball = temp.path().join("lib.tar.gz");
+        fs::write(&tarball, b"tarball bytes").unwrap();
+
+        let github = MockGitHubProvider::new();
+        let url = "https://github.com/owner/lib/releases/download/v1.0.0/source.tar.gz.minisig";
+        let mut assets = Vec::new();
+        if let Some(signature) = signature {
+            github.add_asset(url, signature.into_bytes());
+            assets.push(ReleaseAsset {
+                name: PACKAGE_SIGNATURE_ASSET.to_string(),
+                browser_download_url: url.to_string(),
+                size: 0,
+                content_type: "application/octet-stream".to_string(),
+            });
+        }
+        github.add_release(
+            "owner",
+            "lib",
+            GitHubRelease {
+                tag_name: "v1.0.0".to_string(),
+                name: None,
+                draft: false,
+                prerelease: false,
+                tarball_url: String::new(),
+                zipball_url: String::new(),
+                assets,
+                body: None,
+                published_at: None,
+            },
+        );
+
+        let installer = PackageInstaller::new(
+            temp.path(),
+            Arc::new(MockCacheProvider::new()),
+            Arc::new(github),
+            vec!["release".to_string()],
+        )
+        .unwrap()
+        .with_signature_policy(policy);
+        let resolved = ResolvedVersion {
+            ref_type: RefType::Release,
+            ref_value: "v1.0.0".to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+        };
+        (installer, resolved, tarball)
+    }
+
+    #[tokio::test]
+    async fn test_verify_signature_of_release() {
+        use crate::security::signature::sign_for_tests;
+
+        let temp = TempDir::new().unwrap();
+        let (signature, key) = sign_for_tests(3, b"tarball bytes");
+        let policy = SignaturePolicy {
+            trusted_keys: HashMap::from([("owner".to_string(), vec![key])]),
+            require: true,
+        };
+
+        let (installer, resolved, tarball) =
+            signed_release_installer(&temp, Some(signature), policy.clone());
+        installer
+            .verify_signature("owner", "lib", &resolved, &tarball)
+            .await
+            .unwrap();
+
+        // A tarball that differs from what was signed is refused
+        fs::write(&tarball, b"tampered bytes").unwrap();
+        assert!(installer
+            .verify_signature("owner", "lib", &resolved, &tarball)
+            .await
+            .is_err());
+
+        // Strict mode refuses a release without a signature asset
+        let (installer, resolved, tarball) = signed_release_installer(&temp, None, policy.clone());
+        let err = installer
+            .verify_signature("owner", "lib", &resolved, &tarball)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is not signed"));
+
+        // So does a trusted key for the owner, even outside strict mode
+        let lenient = SignaturePolicy {
+            require: false,
+            ..policy
+        };
+        let (installer, resolved, tarball) = signed_release_installer(&temp, None, lenient);
+        let err = installer
+            .verify_signature("owner", "lib", &resolved, &tarball)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("a trusted key is configured"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_signature_disabled_by_default() {
+        let temp = TempDir::new().unwrap();
+        let (installer, resolved, tarball) =
+            signed_release_installer(&temp, None, SignaturePolicy::default());
+        installer
+            .verify_signature("owner", "lib", &resolved, &tarball)
+            .await
+            .unwrap();
+    }
+
     #[test]
     fn test_installer_init() {
         let temp = TempDir::new().unwrap();
@@ -346,6 +622,50 @@ mod tests {
             .contains("Invalid repository format"));
     }
 
+    #[tokio::test]
+    async fn test_install_package_repository_id_changed() {
+        let temp = TempDir::new().unwrap();
+        let github = MockGitHubProvider::new();
+        github.set_repo_id("owner", "repo", 2);
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/repo".to_string(),
+            LockedPackage {
+                version: "1.0.0".to_string(),
+                repository: "owner/repo".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.0.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: String::new(),
+                checksum: "blake3:00".to_string(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: Some(1),
+            },
+        );
+        lockfile.save(temp.path()).unwrap();
+
+        let installer = PackageInstaller::new(
+            temp.path(),
+            Arc::new(MockCacheProvider::new()),
+            Arc::new(github),
+            vec!["release".to_string()],
+        )
+        .unwrap();
+        installer.init().unwrap();
+
+        let err = installer
+            .install_package("owner/repo", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("depot trust update owner/repo"));
+    }
+
     #[test]
     fn test_create_lockfile_entry() {
         let temp = TempDir::new().unwrap();
@@ -378,6 +698,28 @@ mod tests {
 
         assert_eq!(entry.repository, "owner/repo");
         assert_eq!(entry.version, "v1.0.0");
+        assert_eq!(entry.license, None);
+
+        // The license recorded at install time ends up in the lockfile
+        let mut metadata = PackageMetadata::new(
+            "owner/repo".to_string(),
+            "v1.0.0".to_string(),
+            "owner/repo".to_string(),
+            "Release".to_string(),
+            "v1.0.0".to_string(),
+            "abc123".to_string(),
+        );
+        metadata.license = Some("MIT".to_string());
+        metadata
+            .save(&PackageMetadata::path_in(
+                &packages_metadata_dir(temp.path()),
+                "owner/repo",
+            ))
+            .unwrap();
+        let entry = installer
+            .create_lockfile_entry("owner/repo", &resolved, &deps)
+            .unwrap();
+        assert_eq!(entry.license.as_deref(), Some("MIT"));
     }
 
     #[test]
@@ -435,4 +777,27 @@ mod tests {
         assert!(dst.join("lib").exists());
         assert!(dst.join("lib/module.lua").exists());
     }
+
+    #[test]
+    fn test_install_default_keeps_license_files() {
+        let temp = TempDir::new().unwrap();
+        let cache = Arc::new(MockCacheProvider::new());
+        let github = Arc::new(MockGitHubProvider::new());
+        let fallback = vec!["release".to_string()];
+
+        let installer = PackageInstaller::new(temp.path(), cache, github, fallback).unwrap();
+
+        let src = temp.path().join("src");
+        let dst = temp.path().join("dst");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(src.join("init.lua"), "-- init").unwrap();
+        fs::write(src.join("LICENSE.md"), "MIT License").unwrap();
+        fs::write(src.join("README.md"), "# readme").unwrap();
+
+        installer.install_default(&src, &dst).unwrap();
+
+        assert!(dst.join("LICENSE.md").exists());
+        assert!(!dst.join("README.md").exists());
+    }
 }
@@ -0,0 +1,669 @@
+//! Dependency license detection and policy checks
+//!
+//! Licenses come from the `license` field of each package's manifest. When a
+//! package does not declare one, the license files shipped in its tarball
+//! (LICENSE, COPYING, ...) are classified instead.
+
+use crate::core::path::lua_modules_dir;
+use crate::core::{DepotError, DepotResult};
+use crate::package::lockfile::Lockfile;
+use crate::package::manifest::{LicensePolicy, PackageManifest};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SPDX license identifiers recognised in `license` fields
+///
+/// The licenses Lua packages commonly use, not the full SPDX list; anything
+/// else is reported by name rather than as an SPDX id.
+const SPDX_LICENSE_IDS: &[&str] = &[
+    "0BSD",
+    "AFL-3.0",
+    "AGPL-3.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-1.1",
+    "Apache-2.0",
+    "Artistic-2.0",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSD-3-Clause-Clear",
+    "BSD-4-Clause",
+    "BSL-1.0",
+    "CC-BY-3.0",
+    "CC-BY-4.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "EPL-1.0",
+    "EPL-2.0",
+    "EUPL-1.2",
+    "GPL-2.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "ISC",
+    "LGPL-2.0-only",
+    "LGPL-2.0-or-later",
+    "LGPL-2.1",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "MIT",
+    "MIT-0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MS-PL",
+    "NCSA",
+    "OFL-1.1",
+    "PostgreSQL",
+    "Python-2.0",
+    "Unlicense",
+    "UPL-1.0",
+    "WTFPL",
+    "X11",
+    "Zlib",
+];
+
+/// File name prefixes (case-insensitive) of license and notice files
+const LICENSE_FILE_PREFIXES: &[&str] = &["license", "licence", "copying", "unlicense", "notice"];
+
+/// Whether a file name looks like a license or notice file
+pub fn is_license_file(file_name: &str) -> bool {
+    let lower = file_name.to_ascii_lowercase();
+    LICENSE_FILE_PREFIXES.iter().any(|p| lower.starts_with(p))
+}
+
+/// License and notice files at the top level of a directory, sorted by name
+pub fn find_license_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(is_license_file)
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// Classify license text as an SPDX identifier
+///
+/// Recognises the licenses commonly used by Lua packages; anything else
+/// returns `None`.
+pub fn identify_license(text: &str) -> Option<&'static str> {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let has = |needle: &str| text.contains(needle);
+
+    if has("gnu affero general public license") {
+        Some("AGPL-3.0-only")
+    } else if has("gnu lesser general public license") {
+        if has("version 3") {
+            Some("LGPL-3.0-only")
+        } else {
+            Some("LGPL-2.1-only")
+        }
+    } else if has("gnu general public license") {
+        if has("version 3") {
+            Some("GPL-3.0-only")
+        } else if has("version 2") {
+            Some("GPL-2.0-only")
+        } else {
+            None
+        }
+    } else if has("mozilla public license") && has("2.0") {
+        Some("MPL-2.0")
+    } else if has("apache license") && has("version 2.0") {
+        Some("Apache-2.0")
+    } else if has("boost software license") {
+        Some("BSL-1.0")
+    } else if has("free and unencumbered software released into the public domain") {
+        Some("Unlicense")
+    } else if has("provided 'as-is'")
+        && has("permission is granted to anyone to use this software for any purpose")
+    {
+        Some("Zlib")
+    } else if has(
+        "permission to use, copy, modify, and/or distribute this software for any purpose",
+    ) || has(
+        "permission to use, copy, modify, and distribute this software for any purpose",
+    ) {
+        Some("ISC")
+    } else if has("permission is hereby granted, free of charge") {
+        Some("MIT")
+    } else if has("redistribution and use in source and binary forms") {
+        if has("neither the name") || has("names of its contributors") {
+            Some("BSD-3-Clause")
+        } else {
+            Some("BSD-2-Clause")
+        }
+    } else {
+        None
+    }
+}
+
+/// Detect a package's license from the license files in a directory
+pub fn detect_license(dir: &Path) -> Option<String> {
+    find_license_files(dir).iter().find_map(|path| {
+        let text = fs::read_to_string(path).ok()?;
+        identify_license(&text).map(String::from)
+    })
+}
+
+/// The known SPDX identifier matching `id` case-insensitively, in its canonical case
+pub fn spdx_license_id(id: &str) -> Option<&'static str> {
+    SPDX_LICENSE_IDS
+        .iter()
+        .copied()
+        .find(|known| known.eq_ignore_ascii_case(id.trim()))
+}
+
+/// Whether `expr` is an SPDX expression made only of known identifiers and `LicenseRef-` references
+pub fn is_spdx_expression(expr: &str) -> bool {
+    LicenseExpr::parse(expr).is_some_and(|parsed| {
+        parsed
+            .ids()
+            .iter()
+            .all(|id| spdx_license_id(id).is_some() || id.starts_with("LicenseRef-"))
+    })
+}
+
+/// A parsed SPDX license expression
+#[derive(Debug, Clone, PartialEq)]
+enum LicenseExpr {
+    Id(String),
+    And(Vec<LicenseExpr>),
+    Or(Vec<LicenseExpr>),
+}
+
+impl LicenseExpr {
+    /// Parse `MIT`, `MIT OR Apache-2.0`, `(MIT AND BSD-3-Clause)`, `GPL-2.0 WITH ...`
+    fn parse(expr: &str) -> Option<Self> {
+        let spaced = expr.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut pos = 0;
+        let parsed = Self::parse_or(&tokens, &mut pos)?;
+        (pos == tokens.len()).then_some(parsed)
+    }
+
+    fn parse_or(tokens: &[&str], pos: &mut usize) -> Option<Self> {
+        let mut terms = vec![Self::parse_and(tokens, pos)?];
+        while tokens
+            .get(*pos)
+            .is_some_and(|t| t.eq_ignore_ascii_case("or"))
+        {
+            *pos += 1;
+            terms.push(Self::parse_and(tokens, pos)?);
+        }
+        Some(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Self::Or(terms)
+        })
+    }
+
+    fn parse_and(tokens: &[&str], pos: &mut usize) -> Option<Self> {
+        let mut terms = vec![Self::parse_atom(tokens, pos)?];
+        while tokens
+            .get(*pos)
+            .is_some_and(|t| t.eq_ignore_ascii_case("and"))
+        {
+            *pos += 1;
+            terms.push(Self::parse_atom(tokens, pos)?);
+        }
+        Some(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Self::And(terms)
+        })
+    }
+
+    fn parse_atom(tokens: &[&str], pos: &mut usize) -> Option<Self> {
+        let token = *tokens.get(*pos)?;
+        *pos += 1;
+        if token == "(" {
+            let inner = Self::parse_or(tokens, pos)?;
+            if tokens.get(*pos) != Some(&")") {
+                return None;
+            }
+            *pos += 1;
+            return Some(inner);
+        }
+        if token == ")" || ["and", "or", "with"].contains(&token.to_ascii_lowercase().as_str()) {
+            return None;
+        }
+        // Exceptions narrow a license but do not change which license applies
+        if tokens
+            .get(*pos)
+            .is_some_and(|t| t.eq_ignore_ascii_case("with"))
+        {
+            tokens.get(*pos + 1)?;
+            *pos += 2;
+        }
+        Some(Self::Id(token.to_string()))
+    }
+
+    /// Whether some choice of licenses satisfies `accept`
+    fn satisfied_by(&self, accept: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Self::Id(id) => accept(id),
+            Self::And(terms) => terms.iter().all(|t| t.satisfied_by(accept)),
+            Self::Or(terms) => terms.iter().any(|t| t.satisfied_by(accept)),
+        }
+    }
+
+    fn ids(&self) -> Vec<&str> {
+        match self {
+            Self::Id(id) => vec![id.as_str()],
+            Self::And(terms) | Self::Or(terms) => terms.iter().flat_map(|t| t.ids()).collect(),
+        }
+    }
+}
+
+fn contains_id(list: &[String], id: &str) -> bool {
+    list.iter().any(|l| l.eq_ignore_ascii_case(id))
+}
+
+/// Outcome of checking one package against a license policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum LicenseStatus {
+    /// The license satisfies the policy
+    Allowed,
+    /// A per-package exception accepts the package
+    Excepted,
+    /// Every way of satisfying the expression uses a denied license
+    Denied,
+    /// The license is not on the allowlist
+    NotAllowed,
+    /// No license was declared or detected
+    Unknown,
+}
+
+/// License check result for one package
+#[derive(Debug, Clone)]
+pub struct LicenseCheck {
+    pub package: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub status: LicenseStatus,
+}
+
+impl LicenseCheck {
+    /// Whether this result should fail `depot install` and `depot licenses check`
+    ///
+    /// An unknown license only fails a policy with an allowlist.
+    pub fn is_violation(&self, policy: &LicensePolicy) -> bool {
+        match self.status {
+            LicenseStatus::Allowed | LicenseStatus::Excepted => false,
+            LicenseStatus::Denied | LicenseStatus::NotAllowed => true,
+            LicenseStatus::Unknown => !policy.allow.is_empty(),
+        }
+    }
+}
+
+/// Evaluate one package's license against a policy
+pub fn evaluate(policy: &LicensePolicy, package: &str, license: Option<&str>) -> LicenseStatus {
+    let expr = license
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|l| LicenseExpr::parse(l).unwrap_or_else(|| LicenseExpr::Id(l.to_string())));
+
+    if let Some(exception) = policy.exceptions.iter().find(|e| e.package == package) {
+        let accepted = exception.licenses.is_empty()
+            || expr
+                .as_ref()
+                .is_some_and(|e| e.satisfied_by(&|id| contains_id(&exception.licenses, id)));
+        if accepted {
+            return LicenseStatus::Excepted;
+        }
+    }
+
+    let Some(expr) = expr else {
+        return LicenseStatus::Unknown;
+    };
+    if expr
+        .ids()
+        .iter()
+        .all(|id| ["NOASSERTION", "NONE", "UNKNOWN"].contains(&id.to_ascii_uppercase().as_str()))
+    {
+        return LicenseStatus::Unknown;
+    }
+
+    let acceptable = |id: &str| {
+        !contains_id(&policy.deny, id)
+            && (policy.allow.is_empty() || contains_id(&policy.allow, id))
+    };
+    if expr.satisfied_by(&acceptable) {
+        LicenseStatus::Allowed
+    } else if expr.ids().iter().any(|id| contains_id(&policy.deny, id)) {
+        LicenseStatus::Denied
+    } else {
+        LicenseStatus::NotAllowed
+    }
+}
+
+/// The policy in effect for a project: package.yaml replaces the global config
+pub fn effective_policy<'a>(
+    manifest: Option<&'a PackageManifest>,
+    config_policy: Option<&'a LicensePolicy>,
+) -> Option<&'a LicensePolicy> {
+    manifest
+        .and_then(|m| m.license_policy.as_ref())
+        .or(config_policy)
+}
+
+/// License of a locked package, detecting it from the installed files when
+/// the lockfile has none
+pub fn package_license(project_root: &Path, lockfile: &Lockfile, package: &str) -> Option<String> {
+    lockfile
+        .get_package(package)
+        .and_then(|p| p.license.clone())
+        .or_else(|| detect_license(&lua_modules_dir(project_root).join(package)))
+}
+
+/// Check every package in the lockfile, sorted by package name
+pub fn check_lockfile(
+    policy: &LicensePolicy,
+    project_root: &Path,
+    lockfile: &Lockfile,
+) -> Vec<LicenseCheck> {
+    let mut names: Vec<&String> = lockfile.packages.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let license = package_license(project_root, lockfile, name);
+            LicenseCheck {
+                package: name.clone(),
+                version: lockfile.packages[name].version.clone(),
+                status: evaluate(policy, name, license.as_deref()),
+                license,
+            }
+        })
+        .collect()
+}
+
+/// Fail with a summary of the violations, if there are any
+pub fn enforce(policy: &LicensePolicy, checks: &[LicenseCheck]) -> DepotResult<()> {
+    let violations: Vec<String> = checks
+        .iter()
+        .filter(|c| c.is_violation(policy))
+        .map(|c| match (&c.status, &c.license) {
+            (LicenseStatus::Denied, Some(l)) => format!("{} ({}) is denied", c.package, l),
+            (LicenseStatus::NotAllowed, Some(l)) => {
+                format!("{} ({}) is not on the allowlist", c.package, l)
+            }
+            _ => format!("{} has no declared or detectable license", c.package),
+        })
+        .collect();
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(DepotError::Package(format!(
+        "License policy violation:\n  {}\nAdd an exception under license_policy.exceptions to accept a package",
+        violations.join("\n  ")
+    )))
+}
+
+/// Render a third-party notices file with the license texts of every locked package
+pub fn render_notices(project_root: &Path, lockfile: &Lockfile) -> DepotResult<String> {
+    let lua_modules = lua_modules_dir(project_root);
+    let mut names: Vec<&String> = lockfile.packages.keys().collect();
+    names.sort();
+
+    let mut out = String::from("THIRD-PARTY SOFTWARE NOTICES\n\n");
+    out.push_str("This project includes the following third-party packages.\n");
+
+    for name in names {
+        let pkg = &lockfile.packages[name];
+        let license = package_license(project_root, lockfile, name);
+
+        out.push_str(&format!("\n{}\n", "=".repeat(72)));
+        out.push_str(&format!("{} {}\n", name, pkg.version));
+        out.push_str(&format!("https://github.com/{}\n", pkg.repository));
+        out.push_str(&format!(
+            "License: {}\n",
+            license.as_deref().unwrap_or("UNKNOWN")
+        ));
+        out.push_str(&format!("{}\n", "=".repeat(72)));
+
+        let files = find_license_files(&lua_modules.join(name));
+        if files.is_empty() {
+            out.push_str("\nNo license file was found in this package.\n");
+        }
+        for file in files {
+            let text = fs::read_to_string(&file).map_err(|e| {
+                DepotError::Package(format!("Failed to read {}: {}", file.display(), e))
+            })?;
+            out.push('\n');
+            out.push_str(text.trim_end());
+            out.push('\n');
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::LockedPackage;
+    use crate::package::manifest::LicenseException;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    const MIT_TEXT: &str = "MIT License\n\nCopyright (c) 2024 Someone\n\nPermission is hereby granted, free of charge, to any person obtaining a copy";
+
+    fn policy(allow: &[&str], deny: &[&str]) -> LicensePolicy {
+        LicensePolicy {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+            exceptions: Vec::new(),
+        }
+    }
+
+    fn locked(repo: &str, license: Option<&str>) -> LockedPackage {
+        LockedPackage {
+            version: "1.0.0".to_string(),
+            repository: repo.to_string(),
+            ref_type: "Release".to_string(),
+            ref_value: "v1.0.0".to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: format!("https://github.com/{}/archive/v1.0.0.tar.gz", repo),
+            checksum: "blake3:00".to_string(),
+            size: 1,
+            dependencies: HashMap::new(),
+            build: None,
+            native_code: None,
+            license: license.map(String::from),
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn lockfile(packages: Vec<LockedPackage>) -> Lockfile {
+        let mut lockfile = Lockfile::new();
+        for pkg in packages {
+            lockfile.add_package(pkg.repository.clone(), pkg);
+        }
+        lockfile
+    }
+
+    #[test]
+    fn test_identify_license_texts() {
+        assert_eq!(identify_license(MIT_TEXT), Some("MIT"));
+        assert_eq!(
+            identify_license("Apache License\n  Version 2.0, January 2004"),
+            Some("Apache-2.0")
+        );
+        assert_eq!(
+            identify_license("GNU LESSER GENERAL PUBLIC LICENSE\nVersion 3, 29 June 2007"),
+            Some("LGPL-3.0-only")
+        );
+        assert_eq!(
+            identify_license("GNU GENERAL PUBLIC LICENSE\nVersion 2, June 1991"),
+            Some("GPL-2.0-only")
+        );
+        assert_eq!(
+            identify_license(
+                "Redistribution and use in source and binary forms, with or without modification... Neither the name of the copyright holder"
+            ),
+            Some("BSD-3-Clause")
+        );
+        assert_eq!(identify_license("All rights reserved."), None);
+    }
+
+    #[test]
+    fn test_detect_license_from_files() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("init.lua"), "return {}").unwrap();
+        assert_eq!(detect_license(temp.path()), None);
+
+        fs::write(temp.path().join("LICENSE.txt"), MIT_TEXT).unwrap();
+        assert_eq!(detect_license(temp.path()), Some("MIT".to_string()));
+        assert_eq!(find_license_files(temp.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_evaluate_allow_and_deny() {
+        let p = policy(&["MIT", "Apache-2.0"], &["GPL-3.0-only"]);
+        assert_eq!(evaluate(&p, "a/b", Some("mit")), LicenseStatus::Allowed);
+        assert_eq!(
+            evaluate(&p, "a/b", Some("BSD-2-Clause")),
+            LicenseStatus::NotAllowed
+        );
+        assert_eq!(
+            evaluate(&p, "a/b", Some("GPL-3.0-only")),
+            LicenseStatus::Denied
+        );
+        assert_eq!(evaluate(&p, "a/b", None), LicenseStatus::Unknown);
+
+        let deny_only = policy(&[], &["AGPL-3.0-only"]);
+        assert_eq!(
+            evaluate(&deny_only, "a/b", Some("Zlib")),
+            LicenseStatus::Allowed
+        );
+    }
+
+    #[test]
+    fn test_evaluate_expressions() {
+        let p = policy(&["MIT"], &["GPL-3.0-only"]);
+        assert_eq!(
+            evaluate(&p, "a/b", Some("GPL-3.0-only OR MIT")),
+            LicenseStatus::Allowed
+        );
+        assert_eq!(
+            evaluate(&p, "a/b", Some("(MIT AND GPL-3.0-only)")),
+            LicenseStatus::Denied
+        );
+        assert_eq!(
+            evaluate(&p, "a/b", Some("MIT WITH Some-exception")),
+            LicenseStatus::Allowed
+        );
+    }
+
+    #[test]
+    fn test_evaluate_exceptions() {
+        let mut p = policy(&["MIT"], &["GPL-3.0-only"]);
+        p.exceptions = vec![
+            LicenseException {
+                package: "a/gpl".to_string(),
+                licenses: vec!["GPL-3.0-only".to_string()],
+                reason: Some("Build tool only".to_string()),
+            },
+            LicenseException {
+                package: "a/any".to_string(),
+                licenses: Vec::new(),
+                reason: None,
+            },
+        ];
+        assert_eq!(
+            evaluate(&p, "a/gpl", Some("GPL-3.0-only")),
+            LicenseStatus::Excepted
+        );
+        assert_eq!(
+            evaluate(&p, "a/gpl", Some("AGPL-3.0-only")),
+            LicenseStatus::NotAllowed
+        );
+        assert_eq!(evaluate(&p, "a/any", None), LicenseStatus::Excepted);
+    }
+
+    #[test]
+    fn test_check_lockfile_detects_and_enforces() {
+        let temp = TempDir::new().unwrap();
+        let installed = lua_modules_dir(temp.path()).join("owner/detected");
+        fs::create_dir_all(&installed).unwrap();
+        fs::write(installed.join("LICENSE"), MIT_TEXT).unwrap();
+
+        let lockfile = lockfile(vec![
+            locked("owner/declared", Some("Apache-2.0")),
+            locked("owner/detected", None),
+            locked("owner/missing", None),
+        ]);
+
+        let p = policy(&["MIT", "Apache-2.0"], &[]);
+        let checks = check_lockfile(&p, temp.path(), &lockfile);
+        assert_eq!(checks[1].license.as_deref(), Some("MIT"));
+        assert_eq!(checks[1].status, LicenseStatus::Allowed);
+        assert_eq!(checks[2].status, LicenseStatus::Unknown);
+
+        let err = enforce(&p, &checks).unwrap_err().to_string();
+        assert!(err.contains("owner/missing has no declared or detectable license"));
+        assert!(!err.contains("owner/detected"));
+
+        assert!(enforce(&policy(&[], &["GPL-3.0-only"]), &checks).is_ok());
+    }
+
+    #[test]
+    fn test_render_notices() {
+        let temp = TempDir::new().unwrap();
+        let installed = lua_modules_dir(temp.path()).join("owner/json");
+        fs::create_dir_all(&installed).unwrap();
+        fs::write(installed.join("LICENSE"), MIT_TEXT).unwrap();
+
+        let lockfile = lockfile(vec![
+            locked("owner/json", None),
+            locked("owner/bare", Some("ISC")),
+        ]);
+        let notices = render_notices(temp.path(), &lockfile).unwrap();
+
+        let bare = notices.find("owner/bare 1.0.0").unwrap();
+        let json = notices.find("owner/json 1.0.0").unwrap();
+        assert!(bare < json);
+        assert!(notices.contains("License: ISC"));
+        assert!(notices.contains("No license file was found"));
+        assert!(notices.contains("License: MIT"));
+        assert!(notices.contains("Permission is hereby granted"));
+    }
+
+    #[test]
+    fn test_spdx_ids() {
+        assert_eq!(spdx_license_id("mit"), Some("MIT"));
+        assert_eq!(spdx_license_id("Apache-2.0"), Some("Apache-2.0"));
+        assert_eq!(spdx_license_id("Proprietary"), None);
+
+        assert!(is_spdx_expression("MIT OR Apache-2.0"));
+        assert!(is_spdx_expression("(MIT AND LicenseRef-Custom)"));
+        assert!(is_spdx_expression(
+            "GPL-2.0-only WITH Classpath-exception-2.0"
+        ));
+        assert!(!is_spdx_expression("Public Domain"));
+        assert!(!is_spdx_expression("MIT OR"));
+    }
+}
@@ -50,6 +50,18 @@ pub struct LockedPackage {
     // Native code tracking
     #[serde(default)]
     pub native_code: Option<NativeCodeInfo>,
+
+    // License declared in the package's package.yaml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    // Sensitive APIs the package's Lua code uses (see `depot scan-capabilities`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+
+    // GitHub repository ID pinned on first install (see `depot trust update`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,6 +190,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         lockfile.add_package("test-package".to_string(), package);
@@ -199,6 +214,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         lockfile.add_package("test-package".to_string(), package.clone());
@@ -222,6 +240,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         assert!(!lockfile.has_package("test-package"));
@@ -245,6 +266,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         lockfile.add_package("test-package".to_string(), package);
@@ -284,6 +308,9 @@ mod tests {
             dependencies,
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         assert_eq!(package.dependencies.len(), 2);
@@ -320,6 +347,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: Some(build),
             native_code: Some(native_code),
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         assert!(package.build.is_some());
@@ -388,6 +418,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-package".to_string(), package);
 
@@ -432,6 +465,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         old_lockfile.add_package("old-package".to_string(), old_package);
 
@@ -448,6 +484,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         new_lockfile.add_package("new-package".to_string(), new_package);
 
@@ -1,13 +1,15 @@
 //! Lockfile builder - Takes a manifest, resolves dependencies, downloads tarballs, and builds a lockfile
 
-use crate::core::path::packages_metadata_dir;
+use crate::core::path::{lua_modules_dir, packages_metadata_dir};
 use crate::core::{DepotError, DepotResult};
 use crate::di::traits::{CacheProvider, GitHubProvider};
+use crate::lua_analysis::capabilities;
 use crate::package::downloader::{DownloadTask, ParallelDownloader};
 use crate::package::lockfile::{LockedPackage, Lockfile};
 use crate::package::manifest::PackageManifest;
 use crate::package::metadata::PackageMetadata;
 use crate::resolver::{DependencyResolver, ResolvedPackage};
+use crate::security::trust;
 use chrono::Utc;
 use depot_core::package::manifest::DependencySpec;
 use std::collections::HashMap;
@@ -21,6 +23,7 @@ pub struct LockfileBuilder {
     cache: Arc<dyn CacheProvider>,
     github: Arc<dyn GitHubProvider>,
     fallback_chain: Vec<String>,
+    pins: HashMap<String, String>,
 }
 
 impl LockfileBuilder {
@@ -36,15 +39,22 @@ impl LockfileBuilder {
             cache,
             github,
             fallback_chain,
+            pins: HashMap::new(),
         }
     }
 
+    /// Lock these packages to the given versions, including transitive ones
+    pub fn with_pins(mut self, pins: HashMap<String, String>) -> Self {
+        self.pins = pins;
+        self
+    }
+
     /// Build a complete lockfile from a manifest
     ///
     /// Steps:
     /// 1. Validate that each package has a valid metadata file
     /// 2. Take the dependencies from .depot (if it exists)
-    /// 3. Resolve dependencies if they exist
+    /// 3. Resolve dependencies if they exist, checking repository IDs pinned in the previous lockfile
     /// 4. Download the tarballs
     /// 5. Calculate checksums
     /// 6. Build a Lockfile
@@ -90,6 +100,7 @@ impl LockfileBuilder {
             "  Resolved {} packages (including transitive)",
             resolved.len()
         );
+        let repository_ids = self.verify_repository_ids(&resolved).await?;
 
         // Step 4: Download the tarballs
         println!("  Downloading tarballs...");
@@ -129,6 +140,11 @@ impl LockfileBuilder {
                 })
                 .collect();
 
+            // Record the sensitive APIs the installed code uses
+            let installed_dir = lua_modules_dir(&self.project_root).join(repo);
+            let capabilities =
+                capabilities::capability_names(&capabilities::scan_dir(&installed_dir));
+
             // Create locked package
             let locked_pkg = LockedPackage {
                 version: resolved_pkg.version.clone(),
@@ -142,6 +158,14 @@ impl LockfileBuilder {
                 dependencies: dep_map,
                 build: None,
                 native_code: None,
+                license: resolved_pkg.license.clone().or_else(|| {
+                    PackageMetadata::recorded_license(
+                        &packages_metadata_dir(&self.project_root),
+                        repo,
+                    )
+                }),
+                capabilities,
+                repository_id: repository_ids.get(repo).copied(),
             };
 
             locked_packages.insert(repo.clone(), locked_pkg);
@@ -166,7 +190,7 @@ impl LockfileBuilder {
         let packages_dir = packages_metadata_dir(&self.project_root);
 
         for name in manifest.dependencies.keys() {
-            let metadata_path = packages_dir.join(name).join(".metadata");
+            let metadata_path = PackageMetadata::path_in(&packages_dir, name);
 
             // If metadata file exists, validate it
             if metadata_path.exists() {
@@ -184,11 +208,33 @@ impl LockfileBuilder {
         dependencies: &HashMap<String, DependencySpec>,
     ) -> DepotResult<HashMap<String, ResolvedPackage>> {
         let resolver =
-            DependencyResolver::new(Arc::clone(&self.github), self.fallback_chain.clone());
+            DependencyResolver::new(Arc::clone(&self.github), self.fallback_chain.clone())
+                .with_pins(self.pins.clone());
 
         resolver.resolve(dependencies).await
     }
 
+    /// Step 3: Check each resolved repository against the ID pinned in the
+    /// previous lockfile, returning the IDs to pin in the new one
+    async fn verify_repository_ids(
+        &self,
+        resolved: &HashMap<String, ResolvedPackage>,
+    ) -> DepotResult<HashMap<String, u64>> {
+        let previous = Lockfile::load(&self.project_root)?;
+        let mut ids = HashMap::new();
+
+        for repo in resolved.keys() {
+            let pinned = previous
+                .as_ref()
+                .and_then(|lockfile| lockfile.get_package(repo))
+                .and_then(|pkg| pkg.repository_id);
+            let id = trust::verify_repository(self.github.as_ref(), repo, pinned).await?;
+            ids.insert(repo.clone(), id);
+        }
+
+        Ok(ids)
+    }
+
     /// Step 4: Download the tarballs
     async fn download_tarballs(
         &self,
@@ -220,7 +266,7 @@ impl LockfileBuilder {
         let packages_dir = packages_metadata_dir(&self.project_root);
 
         for (repo, pkg) in resolved {
-            let metadata_path = packages_dir.join(repo).join(".metadata");
+            let metadata_path = PackageMetadata::path_in(&packages_dir, repo);
 
             if metadata_path.exists() {
                 // Update existing metadata
@@ -355,17 +401,47 @@ mod tests {
         github.add_tarball("owner", "repo", "v1.0.0", tarball_path);
 
         let fallback = vec!["release".to_string()];
-        let builder = LockfileBuilder::new(temp.path(), cache, github, fallback);
+        let builder = LockfileBuilder::new(temp.path(), cache, github.clone(), fallback);
 
         let mut manifest = PackageManifest::default("test".to_string());
         manifest
             .dependencies
             .insert("owner/repo".to_string(), "v1.0.0".to_string());
 
+        // License detected from the extracted tarball at install time
+        let mut metadata = PackageMetadata::new(
+            "owner/repo".to_string(),
+            "v1.0.0".to_string(),
+            "owner/repo".to_string(),
+            "Release".to_string(),
+            "v1.0.0".to_string(),
+            String::new(),
+        );
+        metadata.license = Some("ISC".to_string());
+        metadata
+            .save(&PackageMetadata::path_in(
+                &packages_metadata_dir(temp.path()),
+                "owner/repo",
+            ))
+            .unwrap();
+
         let lockfile = builder.build(&manifest).await.unwrap();
         assert_eq!(lockfile.version, 2);
         assert_eq!(lockfile.packages.len(), 1);
         assert!(lockfile.packages.contains_key("owner/repo"));
+        assert_eq!(
+            lockfile.packages["owner/repo"].license.as_deref(),
+            Some("ISC")
+        );
+
+        // The repository ID is pinned on first use and checked on the next build
+        assert_eq!(lockfile.packages["owner/repo"].repository_id, Some(1));
+        lockfile.save(temp.path()).unwrap();
+        github.set_repo_id("owner", "repo", 2);
+        let err = builder.build(&manifest).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Repository identity of owner/repo changed"));
     }
 
     #[test]
@@ -391,6 +467,7 @@ mod tests {
                         .to_string(),
                 },
                 dependencies: HashMap::new(),
+                license: None,
             },
         );
 
@@ -1,10 +1,13 @@
 //! Package installation metadata
 
+use crate::cache::Cache;
 use crate::core::{DepotError, DepotResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 /// Metadata for an installed package
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +30,32 @@ pub struct PackageMetadata {
     pub native_code_types: Vec<String>,
     #[serde(default)]
     pub prebuilt_binary: bool,
+
+    // License declared in the package manifest or detected from its license files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    // Hash of every installed file, keyed by path relative to the package directory
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, String>,
+}
+
+/// Differences between an installed package and its recorded file hashes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCheck {
+    /// Files whose contents changed
+    pub modified: Vec<String>,
+    /// Recorded files that no longer exist
+    pub missing: Vec<String>,
+    /// Files that were not installed by depot
+    pub extra: Vec<String>,
+}
+
+impl FileCheck {
+    /// Whether the package directory matches the recorded hashes
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty() && self.extra.is_empty()
+    }
 }
 
 impl PackageMetadata {
@@ -51,9 +80,23 @@ impl PackageMetadata {
             updated_at: now,
             native_code_types: Vec::new(),
             prebuilt_binary: false,
+            license: None,
+            files: BTreeMap::new(),
         }
     }
 
+    /// Path of a package's `.metadata` file within the packages metadata directory
+    pub fn path_in(packages_dir: &Path, package_name: &str) -> PathBuf {
+        packages_dir.join(package_name).join(".metadata")
+    }
+
+    /// License recorded when an installed package was extracted
+    pub fn recorded_license(packages_dir: &Path, package_name: &str) -> Option<String> {
+        Self::load(&Self::path_in(packages_dir, package_name))
+            .ok()
+            .and_then(|metadata| metadata.license)
+    }
+
     /// Load metadata from a file
     pub fn load(path: &Path) -> DepotResult<Self> {
         if !path.exists() {
@@ -92,6 +135,64 @@ impl PackageMetadata {
     pub fn is_fresh_install(&self) -> bool {
         (self.updated_at.timestamp() - self.installed_at.timestamp()).abs() <= 1
     }
+
+    /// Record the hash of every file in the installed package directory
+    pub fn record_files(&mut self, package_dir: &Path) -> DepotResult<()> {
+        self.files = hash_files(package_dir)?;
+        Ok(())
+    }
+
+    /// Compare the installed package directory with the recorded hashes
+    pub fn check_files(&self, package_dir: &Path) -> DepotResult<FileCheck> {
+        let current = if package_dir.is_dir() {
+            hash_files(package_dir)?
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut check = FileCheck::default();
+        for (path, hash) in &self.files {
+            match current.get(path) {
+                Some(actual) if actual == hash => {}
+                Some(_) => check.modified.push(path.clone()),
+                None => check.missing.push(path.clone()),
+            }
+        }
+        check.extra = current
+            .into_keys()
+            .filter(|path| !self.files.contains_key(path))
+            .collect();
+        Ok(check)
+    }
+}
+
+/// Hash every file under `dir`, keyed by relative path with `/` separators
+///
+/// Symlinks are recorded by their target rather than followed.
+fn hash_files(dir: &Path) -> DepotResult<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(dir) {
+        let entry = entry.map_err(|e| DepotError::Path(e.to_string()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(dir)
+            .map_err(|e| DepotError::Path(e.to_string()))?;
+        let key = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let hash = if entry.path_is_symlink() {
+            format!("symlink:{}", fs::read_link(entry.path())?.display())
+        } else {
+            Cache::checksum(entry.path())?
+        };
+        files.insert(key, hash);
+    }
+    Ok(files)
 }
 
 #[cfg(test)]
@@ -167,4 +268,47 @@ mod tests {
         assert!(metadata.updated_at > original_updated);
         assert!(!metadata.is_fresh_install());
     }
+
+    #[test]
+    fn test_check_files() {
+        let temp = TempDir::new().unwrap();
+        let package_dir = temp.path().join("owner/repo");
+        fs::create_dir_all(package_dir.join("lib")).unwrap();
+        fs::write(package_dir.join("init.lua"), "return {}").unwrap();
+        fs::write(package_dir.join("lib/util.lua"), "return 1").unwrap();
+        fs::write(package_dir.join("LICENSE"), "MIT").unwrap();
+
+        let mut metadata = PackageMetadata::new(
+            "owner/repo".to_string(),
+            "v1.0.0".to_string(),
+            "owner/repo".to_string(),
+            "Release".to_string(),
+            "v1.0.0".to_string(),
+            "abc123".to_string(),
+        );
+        metadata.record_files(&package_dir).unwrap();
+        assert_eq!(
+            metadata.files.keys().collect::<Vec<_>>(),
+            vec!["LICENSE", "init.lua", "lib/util.lua"]
+        );
+        assert!(metadata.check_files(&package_dir).unwrap().is_clean());
+
+        // Recorded hashes survive a save and load
+        let path = PackageMetadata::path_in(&temp.path().join("packages"), "owner/repo");
+        metadata.save(&path).unwrap();
+        let metadata = PackageMetadata::load(&path).unwrap();
+
+        fs::write(package_dir.join("lib/util.lua"), "os.execute('x')").unwrap();
+        fs::remove_file(package_dir.join("LICENSE")).unwrap();
+        fs::write(package_dir.join("backdoor.lua"), "").unwrap();
+
+        let check = metadata.check_files(&package_dir).unwrap();
+        assert_eq!(check.modified, vec!["lib/util.lua"]);
+        assert_eq!(check.missing, vec!["LICENSE"]);
+        assert_eq!(check.extra, vec!["backdoor.lua"]);
+
+        // A removed package directory reports every file missing
+        fs::remove_dir_all(&package_dir).unwrap();
+        assert_eq!(metadata.check_files(&package_dir).unwrap().missing.len(), 3);
+    }
 }
@@ -4,6 +4,7 @@ pub mod downloader;
 pub mod extractor;
 pub mod installer;
 pub mod interactive;
+pub mod license;
 pub mod lockfile;
 pub mod lockfile_builder;
 // manifest moved to depot-core, re-export for backward compatibility
@@ -12,8 +13,11 @@ pub mod manifest {
 }
 pub mod metadata;
 pub mod packager;
+pub mod release_notes;
 pub mod rollback;
+pub mod sbom;
 pub mod update_diff;
+pub mod upgrade;
 pub mod validator;
 pub mod verifier;
 
@@ -0,0 +1,199 @@
+//! Upstream release notes for package updates
+//!
+//! Collects the GitHub release bodies published between two versions of a
+//! package, plus the compare-commits summary, so update diffs can show what
+//! changed upstream.
+
+use crate::core::version::Version;
+use crate::core::DepotResult;
+use crate::di::GitHubProvider;
+use crate::package::upgrade::parse_tag;
+use crate::resolver::dependency_resolver::parse_repository;
+use serde::Serialize;
+
+/// A release published between the current and new version
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseNote {
+    pub tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// Commits between the two tags
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareSummary {
+    pub url: String,
+    pub total_commits: u64,
+    /// Short SHA and first line of each commit message
+    pub commits: Vec<String>,
+}
+
+/// Everything that changed upstream for one package update
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageReleaseNotes {
+    pub package: String,
+    pub from: String,
+    pub to: String,
+    /// Releases in `(from, to]`, oldest first
+    pub releases: Vec<ReleaseNote>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare: Option<CompareSummary>,
+}
+
+/// Fetch release notes and the compare summary for `repository` between two versions
+///
+/// A failing compare request is not fatal: the notes are returned without it.
+pub async fn fetch_release_notes(
+    github: &dyn GitHubProvider,
+    repository: &str,
+    from: &Version,
+    to: &Version,
+) -> DepotResult<PackageReleaseNotes> {
+    let (owner, repo) = parse_repository(repository)?;
+    let releases = github.get_releases(&owner, &repo).await?;
+
+    let mut from_tag = None;
+    let mut to_tag = None;
+    let mut in_range: Vec<(Version, ReleaseNote)> = Vec::new();
+
+    for release in releases.into_iter().filter(|r| !r.draft) {
+        let Some(version) = parse_tag(&release.tag_name) else {
+            continue;
+        };
+
+        if version == *from {
+            from_tag = Some(release.tag_name.clone());
+        }
+        if version == *to {
+            to_tag = Some(release.tag_name.clone());
+        }
+        if version > *from && version <= *to {
+            in_range.push((
+                version,
+                ReleaseNote {
+                    tag: release.tag_name,
+                    name: release.name.filter(|n| !n.trim().is_empty()),
+                    published_at: release.published_at,
+                    body: release.body.filter(|b| !b.trim().is_empty()),
+                },
+            ));
+        }
+    }
+
+    in_range.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let base = from_tag.unwrap_or_else(|| format!("v{}", from));
+    let head = to_tag.unwrap_or_else(|| format!("v{}", to));
+    let compare = github
+        .compare_commits(&owner, &repo, &base, &head)
+        .await
+        .ok()
+        .map(|comparison| CompareSummary {
+            url: comparison.html_url,
+            total_commits: comparison.total_commits,
+            commits: comparison
+                .commits
+                .iter()
+                .map(|c| {
+                    let short_sha: String = c.sha.chars().take(7).collect();
+                    let subject = c.commit.message.lines().next().unwrap_or_default();
+                    format!("{} {}", short_sha, subject)
+                })
+                .collect(),
+        });
+
+    Ok(PackageReleaseNotes {
+        package: repository.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+        releases: in_range.into_iter().map(|(_, note)| note).collect(),
+        compare,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::types::{CommitDetail, GitHubCommit, GitHubComparison};
+    use crate::github::GitHubRelease;
+
+    fn release(tag: &str, body: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: Some(tag.to_string()),
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: Some(body.to_string()),
+            published_at: Some("2024-01-01T00:00:00Z".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_release_notes_in_range() {
+        let github = MockGitHubProvider::new();
+        for tag in ["v1.3.0", "v1.0.0", "v1.1.0", "v1.2.0"] {
+            github.add_release("owner", "json", release(tag, &format!("notes for {}", tag)));
+        }
+        github.add_comparison(
+            "owner",
+            "json",
+            "v1.0.0",
+            "v1.2.0",
+            GitHubComparison {
+                html_url: "https://github.com/owner/json/compare/v1.0.0...v1.2.0".to_string(),
+                status: "ahead".to_string(),
+                ahead_by: 1,
+                total_commits: 1,
+                commits: vec![GitHubCommit {
+                    sha: "0123456789abcdef".to_string(),
+                    commit: CommitDetail {
+                        message: "Fix decoder\n\nLonger description".to_string(),
+                        committer: None,
+                    },
+                }],
+            },
+        );
+
+        let notes = fetch_release_notes(
+            &github,
+            "owner/json",
+            &Version::new(1, 0, 0),
+            &Version::new(1, 2, 0),
+        )
+        .await
+        .unwrap();
+
+        let tags: Vec<&str> = notes.releases.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, vec!["v1.1.0", "v1.2.0"]);
+
+        let compare = notes.compare.unwrap();
+        assert_eq!(compare.total_commits, 1);
+        assert_eq!(compare.commits, vec!["0123456 Fix decoder"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_release_notes_without_compare() {
+        let github = MockGitHubProvider::new();
+        github.add_release("owner", "json", release("2.0.0", "breaking"));
+
+        let notes = fetch_release_notes(
+            &github,
+            "owner/json",
+            &Version::new(1, 0, 0),
+            &Version::new(2, 0, 0),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(notes.releases.len(), 1);
+        assert!(notes.compare.is_none());
+    }
+}
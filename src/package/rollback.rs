@@ -217,6 +217,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-package".to_string(), package);
         lockfile.save(temp.path()).unwrap();
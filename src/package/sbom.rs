@@ -0,0 +1,615 @@
+//! Software bill of materials (`depot sbom`)
+//!
+//! Renders the packages locked in depot.lock as a CycloneDX 1.5 or SPDX 2.3
+//! JSON document, including source repository, commit, checksum, declared
+//! license, dependency relationships and native build details.
+
+use crate::core::{DepotError, DepotResult};
+use crate::package::license::{is_spdx_expression, spdx_license_id};
+use crate::package::lockfile::{LockedPackage, Lockfile};
+use crate::package::manifest::PackageManifest;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+const NOASSERTION: &str = "NOASSERTION";
+
+/// SBOM document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    CycloneDxJson,
+    SpdxJson,
+}
+
+impl SbomFormat {
+    /// Parse an SBOM format from a string
+    pub fn parse(s: &str) -> DepotResult<Self> {
+        match s.to_lowercase().as_str() {
+            "cyclonedx-json" | "cyclonedx" => Ok(SbomFormat::CycloneDxJson),
+            "spdx-json" | "spdx" => Ok(SbomFormat::SpdxJson),
+            _ => Err(DepotError::Config(format!(
+                "Invalid SBOM format '{}'. Must be 'cyclonedx-json' or 'spdx-json'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Locked packages and the project they belong to
+pub struct Sbom<'a> {
+    manifest: &'a PackageManifest,
+    packages: BTreeMap<&'a str, &'a LockedPackage>,
+    created: DateTime<Utc>,
+}
+
+impl<'a> Sbom<'a> {
+    pub fn new(manifest: &'a PackageManifest, lockfile: &'a Lockfile) -> Self {
+        Self {
+            manifest,
+            packages: lockfile
+                .packages
+                .iter()
+                .map(|(name, pkg)| (name.as_str(), pkg))
+                .collect(),
+            created: Utc::now(),
+        }
+    }
+
+    /// Use a fixed creation time (for reproducible output)
+    pub fn with_created(mut self, created: DateTime<Utc>) -> Self {
+        self.created = created;
+        self
+    }
+
+    /// Render the SBOM as a JSON document
+    pub fn render(&self, format: SbomFormat) -> DepotResult<String> {
+        let document = match format {
+            SbomFormat::CycloneDxJson => self.cyclonedx(),
+            SbomFormat::SpdxJson => self.spdx(),
+        };
+
+        serde_json::to_string_pretty(&document)
+            .map(|s| s + "\n")
+            .map_err(|e| DepotError::Package(format!("Failed to serialize SBOM: {}", e)))
+    }
+
+    fn cyclonedx(&self) -> Value {
+        let root_ref = format!("{}@{}", self.manifest.name, self.manifest.version);
+
+        let mut root = json!({
+            "type": "application",
+            "bom-ref": root_ref,
+            "name": self.manifest.name,
+            "version": self.manifest.version,
+        });
+        if let Some(ref license) = self.manifest.license {
+            root["licenses"] = cyclonedx_license(license);
+        }
+
+        let components: Vec<Value> = self
+            .packages
+            .iter()
+            .map(|(name, pkg)| cyclonedx_component(name, pkg))
+            .collect();
+
+        let mut dependencies = vec![json!({
+            "ref": root_ref,
+            "dependsOn": self.locked_refs(self.manifest.dependencies.keys()),
+        })];
+        dependencies.extend(self.packages.iter().map(|(name, pkg)| {
+            json!({
+                "ref": purl(name, pkg),
+                "dependsOn": self.locked_refs(pkg.dependencies.keys()),
+            })
+        }));
+
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "serialNumber": format!("urn:uuid:{}", self.document_id()),
+            "version": 1,
+            "metadata": {
+                "timestamp": self.timestamp(),
+                "tools": {
+                    "components": [{
+                        "type": "application",
+                        "name": "depot",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }],
+                },
+                "component": root,
+            },
+            "components": components,
+            "dependencies": dependencies,
+        })
+    }
+
+    fn spdx(&self) -> Value {
+        let root_id = spdx_id(&self.manifest.name);
+
+        let mut packages = vec![json!({
+            "name": self.manifest.name,
+            "SPDXID": root_id,
+            "versionInfo": self.manifest.version,
+            "downloadLocation": NOASSERTION,
+            "filesAnalyzed": false,
+            "licenseConcluded": NOASSERTION,
+            "licenseDeclared": spdx_license(self.manifest.license.as_deref()),
+            "copyrightText": NOASSERTION,
+            "primaryPackagePurpose": "APPLICATION",
+        })];
+        packages.extend(
+            self.packages
+                .iter()
+                .map(|(name, pkg)| spdx_package(name, pkg)),
+        );
+
+        let mut relationships = vec![json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": root_id,
+        })];
+        let edges = std::iter::once((self.manifest.name.as_str(), &self.manifest.dependencies))
+            .chain(
+                self.packages
+                    .iter()
+                    .map(|(name, pkg)| (*name, &pkg.dependencies)),
+            );
+        for (parent, deps) in edges {
+            let mut children: Vec<&String> = deps
+                .keys()
+                .filter(|d| self.packages.contains_key(d.as_str()))
+                .collect();
+            children.sort();
+            for child in children {
+                relationships.push(json!({
+                    "spdxElementId": spdx_id(parent),
+                    "relationshipType": "DEPENDS_ON",
+                    "relatedSpdxElement": spdx_id(child),
+                }));
+            }
+        }
+
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": format!("{}-{}", self.manifest.name, self.manifest.version),
+            "documentNamespace": format!(
+                "https://spdx.org/spdxdocs/{}-{}-{}",
+                self.manifest.name.replace('/', "-"),
+                self.manifest.version,
+                self.document_id()
+            ),
+            "creationInfo": {
+                "created": self.timestamp(),
+                "creators": [format!("Tool: depot-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+
+    /// CycloneDX references for the locked packages among `names`
+    fn locked_refs<'b>(&self, names: impl Iterator<Item = &'b String>) -> Vec<String> {
+        let mut refs: Vec<String> = names
+            .filter_map(|name| self.packages.get(name.as_str()).map(|pkg| purl(name, pkg)))
+            .collect();
+        refs.sort();
+        refs
+    }
+
+    fn timestamp(&self) -> String {
+        self.created.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Stable UUID-formatted identifier for this project, lock state and time
+    fn document_id(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.manifest.name.as_bytes());
+        hasher.update(self.manifest.version.as_bytes());
+        hasher.update(self.timestamp().as_bytes());
+        for (name, pkg) in &self.packages {
+            hasher.update(name.as_bytes());
+            hasher.update(pkg.checksum.as_bytes());
+        }
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+        // Mark as a version 4 (random), RFC 4122 variant UUID
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex = hex::encode(bytes);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+}
+
+fn cyclonedx_component(name: &str, pkg: &LockedPackage) -> Value {
+    let (group, short_name) = name.split_once('/').unwrap_or(("", name));
+    let repo_url = repository_url(pkg);
+
+    let mut component = json!({
+        "type": "library",
+        "bom-ref": purl(name, pkg),
+        "group": group,
+        "name": short_name,
+        "version": pkg.version,
+        "purl": purl(name, pkg),
+        "externalReferences": [
+            { "type": "vcs", "url": repo_url },
+            { "type": "distribution", "url": pkg.tarball_url },
+        ],
+        "pedigree": {
+            "commits": [{
+                "uid": pkg.commit_sha,
+                "url": format!("{}/commit/{}", repo_url, pkg.commit_sha),
+            }],
+        },
+        "properties": properties(pkg)
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect::<Vec<_>>(),
+    });
+
+    if let Some((alg, content)) = split_checksum(&pkg.checksum) {
+        component["hashes"] = json!([{ "alg": alg, "content": content }]);
+    }
+    if let Some(ref license) = pkg.license {
+        component["licenses"] = cyclonedx_license(license);
+    }
+
+    component
+}
+
+fn spdx_package(name: &str, pkg: &LockedPackage) -> Value {
+    let repo_url = repository_url(pkg);
+
+    let mut package = json!({
+        "name": name,
+        "SPDXID": spdx_id(name),
+        "versionInfo": pkg.version,
+        "downloadLocation": format!("git+{}.git@{}", repo_url, pkg.commit_sha),
+        "homepage": repo_url,
+        "sourceInfo": format!(
+            "{} {} at commit {}, from {}",
+            pkg.ref_type.to_lowercase(),
+            pkg.ref_value,
+            pkg.commit_sha,
+            pkg.tarball_url
+        ),
+        "filesAnalyzed": false,
+        "licenseConcluded": NOASSERTION,
+        "licenseDeclared": spdx_license(pkg.license.as_deref()),
+        "copyrightText": NOASSERTION,
+        "primaryPackagePurpose": "LIBRARY",
+        "externalRefs": [{
+            "referenceCategory": "PACKAGE-MANAGER",
+            "referenceType": "purl",
+            "referenceLocator": purl(name, pkg),
+        }],
+    });
+
+    if let Some((alg, content)) = split_checksum(&pkg.checksum) {
+        package["checksums"] = json!([{ "algorithm": alg, "checksumValue": content }]);
+    }
+
+    let native: Vec<String> = properties(pkg)
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("depot:native") || name.starts_with("depot:build"))
+        .map(|(name, value)| format!("{}={}", name.trim_start_matches("depot:"), value))
+        .collect();
+    if !native.is_empty() {
+        package["comment"] = json!(native.join("; "));
+    }
+
+    package
+}
+
+/// Source and native build details recorded in the lockfile
+fn properties(pkg: &LockedPackage) -> Vec<(&'static str, String)> {
+    let mut props = vec![
+        ("depot:ref_type", pkg.ref_type.to_lowercase()),
+        ("depot:ref", pkg.ref_value.clone()),
+        ("depot:commit", pkg.commit_sha.clone()),
+        ("depot:size", pkg.size.to_string()),
+    ];
+
+    if let Some(ref native) = pkg.native_code {
+        props.push(("depot:native_code.types", native.types.join(",")));
+        props.push((
+            "depot:native_code.build_required",
+            native.build_required.to_string(),
+        ));
+        props.push((
+            "depot:native_code.prebuilt_used",
+            native.prebuilt_used.to_string(),
+        ));
+    }
+
+    if let Some(ref build) = pkg.build {
+        props.push(("depot:build.type", build.build_type.clone()));
+        props.push(("depot:build.target", build.target.clone()));
+        props.push((
+            "depot:build.built_at",
+            build.built_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+        ));
+        if !build.installed_files.is_empty() {
+            props.push((
+                "depot:build.installed_files",
+                build.installed_files.join(","),
+            ));
+        }
+    }
+
+    props
+}
+
+/// Package URL for a GitHub-hosted package
+fn purl(name: &str, pkg: &LockedPackage) -> String {
+    format!("pkg:github/{}@{}", name.to_lowercase(), pkg.version)
+}
+
+fn repository_url(pkg: &LockedPackage) -> String {
+    format!("https://github.com/{}", pkg.repository)
+}
+
+/// Split a `blake3:<hex>` style checksum into an algorithm name and digest
+fn split_checksum(checksum: &str) -> Option<(String, &str)> {
+    let (alg, content) = checksum.split_once(':')?;
+    let alg = match alg.to_lowercase().as_str() {
+        "blake3" => "BLAKE3".to_string(),
+        "sha256" => "SHA-256".to_string(),
+        "sha512" => "SHA-512".to_string(),
+        other => other.to_uppercase(),
+    };
+    Some((alg, content))
+}
+
+/// CycloneDX license choice: a known SPDX id, an SPDX expression, or a license name
+fn cyclonedx_license(license: &str) -> Value {
+    let license = license.trim();
+    if let Some(id) = spdx_license_id(license) {
+        json!([{ "license": { "id": id } }])
+    } else if license.contains(' ') && is_spdx_expression(license) {
+        json!([{ "expression": license }])
+    } else {
+        json!([{ "license": { "name": license } }])
+    }
+}
+
+/// SPDX `licenseDeclared` value: a known SPDX id or expression, otherwise `NOASSERTION`
+///
+/// License names such as "MIT License" and `LicenseRef-` references, which
+/// the document does not define, would make it invalid.
+fn spdx_license(license: Option<&str>) -> String {
+    let Some(license) = license.map(str::trim) else {
+        return NOASSERTION.to_string();
+    };
+    if let Some(id) = spdx_license_id(license) {
+        id.to_string()
+    } else if is_spdx_expression(license) && !license.contains("LicenseRef-") {
+        license.to_string()
+    } else {
+        NOASSERTION.to_string()
+    }
+}
+
+/// SPDX element ID for a package name (letters, digits, '.' and '-' only)
+fn spdx_id(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("SPDXRef-Package-{}", sanitized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::{LockedBuild, NativeCodeInfo};
+    use chrono::TimeZone;
+
+    fn locked(repository: &str, deps: &[(&str, &str)], license: Option<&str>) -> LockedPackage {
+        LockedPackage {
+            version: "v1.2.0".to_string(),
+            repository: repository.to_string(),
+            ref_type: "Release".to_string(),
+            ref_value: "v1.2.0".to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: format!("https://api.github.com/repos/{}/tarball/v1.2.0", repository),
+            checksum: "blake3:deadbeef".to_string(),
+            size: 2048,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: license.map(String::from),
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn fixture() -> (PackageManifest, Lockfile) {
+        let mut manifest = PackageManifest::default("my-app".to_string());
+        manifest.license = Some("MIT".to_string());
+        manifest
+            .dependencies
+            .insert("owner/web".to_string(), "^1.0.0".to_string());
+
+        let mut web = locked("owner/web", &[("owner/json", "^1.0.0")], Some("Apache-2.0"));
+        web.native_code = Some(NativeCodeInfo {
+            types: vec!["c".to_string()],
+            build_required: true,
+            prebuilt_used: false,
+        });
+        web.build = Some(LockedBuild {
+            build_type: "cmake".to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            built_at: Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+            installed_files: vec!["web.so".to_string()],
+        });
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("owner/web".to_string(), web);
+        lockfile.add_package(
+            "owner/json".to_string(),
+            locked("owner/json", &[], Some("MIT OR Apache-2.0")),
+        );
+        (manifest, lockfile)
+    }
+
+    fn render(format: SbomFormat) -> Value {
+        let (manifest, lockfile) = fixture();
+        let created = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let output = Sbom::new(&manifest, &lockfile)
+            .with_created(created)
+            .render(format)
+            .unwrap();
+        serde_json::from_str(&output).unwrap()
+    }
+
+    #[test]
+    fn test_sbom_format_parse() {
+        assert_eq!(
+            SbomFormat::parse("cyclonedx-json").unwrap(),
+            SbomFormat::CycloneDxJson
+        );
+        assert_eq!(
+            SbomFormat::parse("SPDX-JSON").unwrap(),
+            SbomFormat::SpdxJson
+        );
+        assert!(SbomFormat::parse("xml").is_err());
+    }
+
+    #[test]
+    fn test_cyclonedx_components() {
+        let bom = render(SbomFormat::CycloneDxJson);
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["metadata"]["timestamp"], "2024-06-01T00:00:00Z");
+        assert_eq!(
+            bom["metadata"]["component"]["licenses"][0]["license"]["id"],
+            "MIT"
+        );
+
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        let json = &components[0];
+        assert_eq!(json["purl"], "pkg:github/owner/json@v1.2.0");
+        assert_eq!(json["hashes"][0]["alg"], "BLAKE3");
+        assert_eq!(json["hashes"][0]["content"], "deadbeef");
+        assert_eq!(json["licenses"][0]["expression"], "MIT OR Apache-2.0");
+        assert_eq!(json["pedigree"]["commits"][0]["uid"], "abc123");
+        assert_eq!(
+            json["externalReferences"][0]["url"],
+            "https://github.com/owner/json"
+        );
+
+        let web = &components[1];
+        let props = web["properties"].as_array().unwrap();
+        assert!(props
+            .iter()
+            .any(|p| p["name"] == "depot:native_code.types" && p["value"] == "c"));
+        assert!(
+            props
+                .iter()
+                .any(|p| p["name"] == "depot:build.target"
+                    && p["value"] == "x86_64-unknown-linux-gnu")
+        );
+    }
+
+    #[test]
+    fn test_cyclonedx_dependencies() {
+        let bom = render(SbomFormat::CycloneDxJson);
+        let deps = bom["dependencies"].as_array().unwrap();
+        assert_eq!(deps[0]["ref"], "my-app@1.0.0");
+        assert_eq!(deps[0]["dependsOn"][0], "pkg:github/owner/web@v1.2.0");
+        let web = deps
+            .iter()
+            .find(|d| d["ref"] == "pkg:github/owner/web@v1.2.0")
+            .unwrap();
+        assert_eq!(web["dependsOn"][0], "pkg:github/owner/json@v1.2.0");
+    }
+
+    #[test]
+    fn test_spdx_document() {
+        let doc = render(SbomFormat::SpdxJson);
+        assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+        assert_eq!(doc["creationInfo"]["created"], "2024-06-01T00:00:00Z");
+
+        let packages = doc["packages"].as_array().unwrap();
+        assert_eq!(packages.len(), 3);
+        let web = &packages[2];
+        assert_eq!(web["SPDXID"], "SPDXRef-Package-owner-web");
+        assert_eq!(web["licenseDeclared"], "Apache-2.0");
+        assert_eq!(
+            web["downloadLocation"],
+            "git+https://github.com/owner/web.git@abc123"
+        );
+        assert_eq!(web["checksums"][0]["algorithm"], "BLAKE3");
+        assert!(web["comment"]
+            .as_str()
+            .unwrap()
+            .contains("native_code.build_required=true"));
+
+        let relationships = doc["relationships"].as_array().unwrap();
+        assert!(relationships
+            .iter()
+            .any(|r| r["spdxElementId"] == "SPDXRef-Package-owner-web"
+                && r["relationshipType"] == "DEPENDS_ON"
+                && r["relatedSpdxElement"] == "SPDXRef-Package-owner-json"));
+    }
+
+    #[test]
+    fn test_document_id_is_uuid_shaped() {
+        let (manifest, lockfile) = fixture();
+        let id = Sbom::new(&manifest, &lockfile).document_id();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+    }
+
+    #[test]
+    fn test_spdx_license() {
+        assert_eq!(spdx_license(Some("mit")), "MIT");
+        assert_eq!(spdx_license(Some("MIT OR Apache-2.0")), "MIT OR Apache-2.0");
+        assert_eq!(spdx_license(Some("MIT License")), NOASSERTION);
+        assert_eq!(spdx_license(Some("BSD")), NOASSERTION);
+        assert_eq!(spdx_license(Some("LicenseRef-Custom")), NOASSERTION);
+        assert_eq!(spdx_license(None), NOASSERTION);
+    }
+
+    #[test]
+    fn test_cyclonedx_license() {
+        assert_eq!(
+            cyclonedx_license("mit"),
+            json!([{ "license": { "id": "MIT" } }])
+        );
+        assert_eq!(
+            cyclonedx_license("MIT OR Apache-2.0"),
+            json!([{ "expression": "MIT OR Apache-2.0" }])
+        );
+        assert_eq!(
+            cyclonedx_license("Proprietary"),
+            json!([{ "license": { "name": "Proprietary" } }])
+        );
+        assert_eq!(
+            cyclonedx_license("Public Domain"),
+            json!([{ "license": { "name": "Public Domain" } }])
+        );
+    }
+}
@@ -1,8 +1,16 @@
 use crate::core::version::Version;
+use crate::core::{DepotError, DepotResult};
+use crate::di::GitHubProvider;
+use crate::lua_analysis::capabilities::{self, CapabilityChange};
 use crate::package::lockfile::Lockfile;
+use crate::package::release_notes::{fetch_release_notes, PackageReleaseNotes};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Commits listed per package before the rest are summarised
+const MAX_DISPLAYED_COMMITS: usize = 10;
+
 /// Represents a change to a package during update
 #[derive(Debug, Clone)]
 pub enum PackageChange {
@@ -34,6 +42,10 @@ pub struct PackageFileChanges {
 pub struct UpdateDiff {
     pub package_changes: Vec<PackageChange>,
     pub file_changes: Vec<PackageFileChanges>,
+    /// Upstream release notes keyed by package name (as shown in `package_changes`)
+    pub release_notes: HashMap<String, PackageReleaseNotes>,
+    /// Sensitive APIs packages started using since the previous lockfile
+    pub capability_changes: Vec<CapabilityChange>,
 }
 
 impl UpdateDiff {
@@ -41,7 +53,29 @@ impl UpdateDiff {
         Self {
             package_changes: Vec::new(),
             file_changes: Vec::new(),
+            release_notes: HashMap::new(),
+            capability_changes: Vec::new(),
+        }
+    }
+
+    /// Build a diff from the old lockfile and a freshly built one
+    pub fn from_lockfiles(current_lockfile: &Option<Lockfile>, new_lockfile: &Lockfile) -> Self {
+        let resolved: HashMap<String, Version> = new_lockfile
+            .packages
+            .iter()
+            .filter_map(|(name, pkg)| {
+                Version::parse(pkg.version.trim_start_matches('v'))
+                    .ok()
+                    .map(|v| (name.clone(), v))
+            })
+            .collect();
+
+        let mut diff = Self::calculate(current_lockfile, &resolved, &HashMap::new());
+        diff.package_changes.sort_by(|a, b| a.name().cmp(b.name()));
+        if let Some(current) = current_lockfile {
+            diff.capability_changes = capabilities::diff_lockfiles(current, new_lockfile);
         }
+        diff
     }
 
     /// Calculate the diff between current lockfile and resolved versions
@@ -56,7 +90,9 @@ impl UpdateDiff {
         for (name, new_version) in resolved_versions {
             if let Some(lockfile) = current_lockfile {
                 if let Some(locked_pkg) = lockfile.get_package(name) {
-                    if let Ok(current_version) = Version::parse(&locked_pkg.version) {
+                    if let Ok(current_version) =
+                        Version::parse(locked_pkg.version.trim_start_matches('v'))
+                    {
                         if current_version == *new_version {
                             diff.package_changes.push(PackageChange::UpToDate {
                                 name: name.clone(),
@@ -97,7 +133,9 @@ impl UpdateDiff {
         for (name, new_version) in resolved_dev_versions {
             if let Some(lockfile) = current_lockfile {
                 if let Some(locked_pkg) = lockfile.get_package(name) {
-                    if let Ok(current_version) = Version::parse(&locked_pkg.version) {
+                    if let Ok(current_version) =
+                        Version::parse(locked_pkg.version.trim_start_matches('v'))
+                    {
                         if current_version != *new_version {
                             diff.package_changes.push(PackageChange::Updated {
                                 name: format!("{} (dev)", name),
@@ -127,7 +165,8 @@ impl UpdateDiff {
                 if !resolved_versions.contains_key(name)
                     && !resolved_dev_versions.contains_key(name)
                 {
-                    if let Ok(version) = Version::parse(&locked_pkg.version) {
+                    if let Ok(version) = Version::parse(locked_pkg.version.trim_start_matches('v'))
+                    {
                         diff.package_changes.push(PackageChange::Removed {
                             name: name.clone(),
                             version,
@@ -140,6 +179,36 @@ impl UpdateDiff {
         diff
     }
 
+    /// Fetch upstream release notes for every updated package
+    ///
+    /// Failures are reported as warnings; the diff is still usable without notes.
+    pub async fn fetch_release_notes(&mut self, github: &dyn GitHubProvider) {
+        for change in &self.package_changes {
+            if let PackageChange::Updated {
+                name,
+                current_version,
+                new_version,
+            } = change
+            {
+                if new_version <= current_version {
+                    continue;
+                }
+                let repository = name.trim_end_matches(" (dev)");
+                match fetch_release_notes(github, repository, current_version, new_version).await {
+                    Ok(notes) => {
+                        self.release_notes.insert(name.clone(), notes);
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Warning: Failed to fetch release notes for {}: {}",
+                            repository, e
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     /// Calculate file changes for packages that will be updated
     pub fn calculate_file_changes(&mut self, project_root: &Path) {
         use crate::core::path::lua_modules_dir;
@@ -245,6 +314,9 @@ impl UpdateDiff {
                     new_version,
                 } => {
                     println!("  ⬆️  {}: {} → {}", name, current_version, new_version);
+                    if let Some(notes) = self.release_notes.get(name) {
+                        print!("{}", format_release_notes(notes));
+                    }
                     updated_count += 1;
                 }
                 PackageChange::Added { name, version } => {
@@ -268,6 +340,12 @@ impl UpdateDiff {
             updated_count, added_count, removed_count, up_to_date_count
         );
 
+        if !self.capability_changes.is_empty() {
+            println!("\n🔐 New Capabilities:");
+            print!("{}", capabilities::format_changes(&self.capability_changes));
+            println!("  Run 'depot scan-capabilities' to see where they are used");
+        }
+
         // Display file changes if any
         if !self.file_changes.is_empty() {
             println!("\n📁 File Changes:");
@@ -295,6 +373,51 @@ impl UpdateDiff {
         }
     }
 
+    /// Serialize the diff, including release notes, as pretty-printed JSON
+    pub fn to_json(&self) -> DepotResult<String> {
+        let report = JsonUpdateDiff {
+            packages: self
+                .package_changes
+                .iter()
+                .map(|change| {
+                    let (change_type, from, to) = match change {
+                        PackageChange::Updated {
+                            current_version,
+                            new_version,
+                            ..
+                        } => (
+                            "updated",
+                            Some(current_version.to_string()),
+                            Some(new_version.to_string()),
+                        ),
+                        PackageChange::Added { version, .. } => {
+                            ("added", None, Some(version.to_string()))
+                        }
+                        PackageChange::Removed { version, .. } => {
+                            ("removed", Some(version.to_string()), None)
+                        }
+                        PackageChange::UpToDate { version, .. } => (
+                            "up_to_date",
+                            Some(version.to_string()),
+                            Some(version.to_string()),
+                        ),
+                    };
+                    JsonPackageChange {
+                        name: change.name(),
+                        change: change_type,
+                        from,
+                        to,
+                        release_notes: self.release_notes.get(change.name()),
+                    }
+                })
+                .collect(),
+            capability_changes: &self.capability_changes,
+        };
+
+        serde_json::to_string_pretty(&report)
+            .map_err(|e| DepotError::Package(format!("Failed to serialize update diff: {}", e)))
+    }
+
     /// Check if there are any changes
     pub fn has_changes(&self) -> bool {
         self.package_changes.iter().any(|c| {
@@ -308,6 +431,80 @@ impl UpdateDiff {
     }
 }
 
+impl PackageChange {
+    /// Package name as shown in the summary
+    pub fn name(&self) -> &str {
+        match self {
+            PackageChange::Updated { name, .. }
+            | PackageChange::Added { name, .. }
+            | PackageChange::Removed { name, .. }
+            | PackageChange::UpToDate { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonUpdateDiff<'a> {
+    packages: Vec<JsonPackageChange<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    capability_changes: &'a [CapabilityChange],
+}
+
+#[derive(Serialize)]
+struct JsonPackageChange<'a> {
+    name: &'a str,
+    change: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    release_notes: Option<&'a PackageReleaseNotes>,
+}
+
+/// Render release notes and the commit summary indented under a package line
+fn format_release_notes(notes: &PackageReleaseNotes) -> String {
+    let mut output = String::new();
+
+    for release in &notes.releases {
+        let title = match &release.name {
+            Some(name) if name != &release.tag => format!("{} — {}", release.tag, name),
+            _ => release.tag.clone(),
+        };
+        match &release.published_at {
+            Some(date) => output.push_str(&format!(
+                "      📝 {} ({})\n",
+                title,
+                date.split('T').next().unwrap_or(date)
+            )),
+            None => output.push_str(&format!("      📝 {}\n", title)),
+        }
+        if let Some(body) = &release.body {
+            for line in body.lines().filter(|l| !l.trim().is_empty()) {
+                output.push_str(&format!("         │ {}\n", line.trim_end()));
+            }
+        }
+    }
+
+    if let Some(compare) = &notes.compare {
+        output.push_str(&format!(
+            "      🔀 {} commit(s): {}\n",
+            compare.total_commits, compare.url
+        ));
+        for commit in compare.commits.iter().take(MAX_DISPLAYED_COMMITS) {
+            output.push_str(&format!("         {}\n", commit));
+        }
+        if compare.commits.len() > MAX_DISPLAYED_COMMITS {
+            output.push_str(&format!(
+                "         … and {} more\n",
+                compare.commits.len() - MAX_DISPLAYED_COMMITS
+            ));
+        }
+    }
+
+    output
+}
+
 impl Default for UpdateDiff {
     fn default() -> Self {
         Self::new()
@@ -335,6 +532,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-package".to_string(), package);
         lockfile
@@ -473,6 +673,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("dev-package".to_string(), dev_package);
 
@@ -641,6 +844,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("test-package".to_string(), package);
         let resolved = HashMap::from([("test-package".to_string(), Version::new(2, 0, 0))]);
@@ -723,6 +929,9 @@ mod tests {
             dependencies: HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         lockfile.add_package("dev-pkg".to_string(), package);
         let resolved = HashMap::new();
@@ -731,4 +940,111 @@ mod tests {
         // Should not have changes for up-to-date dev dependency
         assert!(!diff.has_changes());
     }
+
+    #[test]
+    fn test_update_diff_from_lockfiles_strips_tag_prefix() {
+        let old = create_test_lockfile();
+        let mut new = Lockfile::new();
+        let mut package = old.get_package("test-package").unwrap().clone();
+        package.version = "v1.2.0".to_string();
+        new.add_package("test-package".to_string(), package);
+
+        let mut old = old;
+        old.packages.get_mut("test-package").unwrap().version = "v1.0.0".to_string();
+
+        let diff = UpdateDiff::from_lockfiles(&Some(old), &new);
+        match &diff.package_changes[0] {
+            PackageChange::Updated {
+                current_version,
+                new_version,
+                ..
+            } => {
+                assert_eq!(current_version, &Version::new(1, 0, 0));
+                assert_eq!(new_version, &Version::new(1, 2, 0));
+            }
+            other => panic!("Expected Updated change, but got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_update_diff_to_json_with_release_notes() {
+        use crate::package::release_notes::{CompareSummary, ReleaseNote};
+
+        let mut diff = UpdateDiff::new();
+        diff.package_changes.push(PackageChange::Updated {
+            name: "owner/json".to_string(),
+            current_version: Version::new(1, 0, 0),
+            new_version: Version::new(1, 1, 0),
+        });
+        diff.package_changes.push(PackageChange::Added {
+            name: "owner/log".to_string(),
+            version: Version::new(0, 3, 0),
+        });
+        diff.release_notes.insert(
+            "owner/json".to_string(),
+            PackageReleaseNotes {
+                package: "owner/json".to_string(),
+                from: "1.0.0".to_string(),
+                to: "1.1.0".to_string(),
+                releases: vec![ReleaseNote {
+                    tag: "v1.1.0".to_string(),
+                    name: None,
+                    published_at: Some("2024-02-01T00:00:00Z".to_string()),
+                    body: Some("- faster decoding".to_string()),
+                }],
+                compare: Some(CompareSummary {
+                    url: "https://github.com/owner/json/compare/v1.0.0...v1.1.0".to_string(),
+                    total_commits: 1,
+                    commits: vec!["0123456 Speed up decoder".to_string()],
+                }),
+            },
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        let packages = json["packages"].as_array().unwrap();
+        assert_eq!(packages[0]["change"], "updated");
+        assert_eq!(packages[0]["from"], "1.0.0");
+        assert_eq!(packages[0]["release_notes"]["releases"][0]["tag"], "v1.1.0");
+        assert_eq!(packages[1]["change"], "added");
+        assert!(packages[1].get("release_notes").is_none());
+
+        let text = format_release_notes(&diff.release_notes["owner/json"]);
+        assert!(text.contains("v1.1.0 (2024-02-01)"));
+        assert!(text.contains("│ - faster decoding"));
+        assert!(text.contains("1 commit(s)"));
+
+        diff.display(); // Should not panic
+    }
+
+    #[test]
+    fn test_update_diff_from_lockfiles_capability_changes() {
+        let mut current = create_test_lockfile();
+        let mut updated = current.clone();
+        updated.packages.get_mut("test-package").unwrap().version = "1.1.0".to_string();
+        updated
+            .packages
+            .get_mut("test-package")
+            .unwrap()
+            .capabilities = vec!["os.execute".to_string()];
+
+        let diff = UpdateDiff::from_lockfiles(&Some(current.clone()), &updated);
+        assert_eq!(diff.capability_changes.len(), 1);
+        assert_eq!(diff.capability_changes[0].added, vec!["os.execute"]);
+
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["capability_changes"][0]["package"], "test-package");
+        assert_eq!(json["capability_changes"][0]["from_version"], "1.0.0");
+
+        // Already-recorded capabilities are not reported again
+        current
+            .packages
+            .get_mut("test-package")
+            .unwrap()
+            .capabilities = vec!["os.execute".to_string()];
+        let diff = UpdateDiff::from_lockfiles(&Some(current), &updated);
+        assert!(diff.capability_changes.is_empty());
+        assert!(UpdateDiff::from_lockfiles(&None, &updated)
+            .capability_changes
+            .is_empty());
+    }
 }
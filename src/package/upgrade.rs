@@ -0,0 +1,361 @@
+//! Upgrade candidates for `depot update --interactive`
+//!
+//! Compares the versions locked in depot.lock against the releases published
+//! on GitHub and groups the newer ones into patch, minor and latest targets.
+
+use crate::core::version::Version;
+use crate::di::GitHubProvider;
+use crate::package::lockfile::Lockfile;
+use crate::package::manifest::PackageManifest;
+use crate::resolver::dependency_resolver::parse_repository;
+
+/// Maximum number of lines kept from a release body
+const NOTES_EXCERPT_LINES: usize = 6;
+
+/// What to do with an outdated package
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeChoice {
+    Stay,
+    Patch,
+    Minor,
+    Latest,
+}
+
+impl UpgradeChoice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpgradeChoice::Stay => "stay",
+            UpgradeChoice::Patch => "patch",
+            UpgradeChoice::Minor => "minor",
+            UpgradeChoice::Latest => "latest",
+        }
+    }
+}
+
+/// A published release newer than the locked version
+#[derive(Debug, Clone)]
+pub struct AvailableRelease {
+    pub version: Version,
+    pub tag: String,
+    pub body: Option<String>,
+}
+
+/// A direct dependency with newer releases available
+#[derive(Debug, Clone)]
+pub struct UpgradeCandidate {
+    pub name: String,
+    pub dev: bool,
+    pub constraint: String,
+    pub current: Version,
+    /// Newer releases, highest version first
+    pub releases: Vec<AvailableRelease>,
+}
+
+impl UpgradeCandidate {
+    /// Highest release with the same major and minor version
+    pub fn patch(&self) -> Option<&AvailableRelease> {
+        self.releases.iter().find(|r| {
+            r.version.major == self.current.major && r.version.minor == self.current.minor
+        })
+    }
+
+    /// Highest release with the same major version
+    pub fn minor(&self) -> Option<&AvailableRelease> {
+        self.releases
+            .iter()
+            .find(|r| r.version.major == self.current.major)
+    }
+
+    /// Highest release overall
+    pub fn latest(&self) -> Option<&AvailableRelease> {
+        self.releases.first()
+    }
+
+    /// Release targeted by a choice (`None` for `Stay`)
+    pub fn target(&self, choice: UpgradeChoice) -> Option<&AvailableRelease> {
+        match choice {
+            UpgradeChoice::Stay => None,
+            UpgradeChoice::Patch => self.patch(),
+            UpgradeChoice::Minor => self.minor(),
+            UpgradeChoice::Latest => self.latest(),
+        }
+    }
+
+    /// Choices that lead somewhere distinct, always starting with `Stay`
+    pub fn choices(&self) -> Vec<UpgradeChoice> {
+        let mut choices = vec![UpgradeChoice::Stay];
+        let mut seen: Vec<&Version> = Vec::new();
+
+        for choice in [
+            UpgradeChoice::Patch,
+            UpgradeChoice::Minor,
+            UpgradeChoice::Latest,
+        ] {
+            if let Some(release) = self.target(choice) {
+                if !seen.contains(&&release.version) {
+                    seen.push(&release.version);
+                    choices.push(choice);
+                }
+            }
+        }
+
+        choices
+    }
+}
+
+/// Find direct dependencies that have newer releases than the locked version
+///
+/// Packages that are not locked, are locked to a non-semver ref (branch or
+/// commit), or whose releases cannot be fetched are skipped.
+pub async fn find_upgrades(
+    github: &dyn GitHubProvider,
+    manifest: &PackageManifest,
+    lockfile: Option<&Lockfile>,
+) -> Vec<UpgradeCandidate> {
+    let mut deps: Vec<(&String, &String, bool)> = manifest
+        .dependencies
+        .iter()
+        .map(|(n, c)| (n, c, false))
+        .chain(manifest.dev_dependencies.iter().map(|(n, c)| (n, c, true)))
+        .collect();
+    deps.sort();
+
+    let mut candidates = Vec::new();
+
+    for (name, constraint, dev) in deps {
+        let Some(current) = lockfile
+            .and_then(|lf| lf.get_package(name))
+            .and_then(|pkg| parse_tag(&pkg.version))
+        else {
+            continue;
+        };
+
+        let Ok((owner, repo)) = parse_repository(name) else {
+            continue;
+        };
+
+        let releases = match github.get_releases(&owner, &repo).await {
+            Ok(releases) => releases,
+            Err(e) => {
+                eprintln!("Warning: Failed to fetch releases for {}: {}", name, e);
+                continue;
+            }
+        };
+
+        let mut newer: Vec<AvailableRelease> = releases
+            .into_iter()
+            .filter(|r| !r.draft && !r.prerelease)
+            .filter_map(|r| {
+                let version = parse_tag(&r.tag_name)?;
+                (version > current).then_some(AvailableRelease {
+                    version,
+                    tag: r.tag_name,
+                    body: r.body,
+                })
+            })
+            .collect();
+
+        if newer.is_empty() {
+            continue;
+        }
+        newer.sort_by(|a, b| b.version.cmp(&a.version));
+
+        candidates.push(UpgradeCandidate {
+            name: name.clone(),
+            dev,
+            constraint: constraint.clone(),
+            current,
+            releases: newer,
+        });
+    }
+
+    candidates
+}
+
+/// Rewrite a manifest constraint to point at a newer release
+///
+/// The resolver looks a spec up as a literal release, tag or branch name, so
+/// the constraint becomes the release's tag. Returns `None` when that would
+/// drop bounds the user set: `new` is outside a `>=X, <Y` range, or the
+/// constraint is an upper bound alone, has `||` alternatives or is otherwise
+/// not understood.
+pub fn rewrite_constraint(old: &str, new: &AvailableRelease) -> Option<String> {
+    let old = old.trim();
+    if old.contains("||") || old.starts_with('<') {
+        return None;
+    }
+    if let Some((lower, upper)) = old.split_once(',') {
+        Version::parse(lower.trim().strip_prefix(">=")?.trim()).ok()?;
+        let upper_version = Version::parse(upper.trim().strip_prefix('<')?.trim()).ok()?;
+        return (new.version < upper_version).then(|| new.tag.clone());
+    }
+    if old.split_whitespace().count() > 1 {
+        return None;
+    }
+
+    Some(new.tag.clone())
+}
+
+/// First few non-empty lines of a release body
+pub fn notes_excerpt(body: &str) -> String {
+    let lines: Vec<&str> = body
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+
+    let mut excerpt = lines
+        .iter()
+        .take(NOTES_EXCERPT_LINES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if lines.len() > NOTES_EXCERPT_LINES {
+        excerpt.push_str("\n…");
+    }
+    excerpt
+}
+
+/// Parse a release tag such as `v1.2.3` into a version
+pub(crate) fn parse_tag(tag: &str) -> Option<Version> {
+    Version::parse(tag.trim_start_matches('v')).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::types::GitHubRelease;
+    use crate::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+
+    fn release(tag: &str, body: Option<&str>) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: body.map(str::to_string),
+            published_at: None,
+        }
+    }
+
+    fn locked(version: &str) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: version.to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: HashMap::new(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_upgrades_groups_versions() {
+        let github = MockGitHubProvider::new();
+        for tag in ["v1.0.0", "v1.0.3", "v1.2.0", "v2.1.0"] {
+            github.add_release("owner", "json", release(tag, Some("notes")));
+        }
+        let mut beta = release("v3.0.0-beta.1", None);
+        beta.prerelease = true;
+        github.add_release("owner", "json", beta);
+        github.add_release("owner", "log", release("v0.3.0", None));
+
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/json".to_string(), "^1.0.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("owner/log".to_string(), "^0.3.0".to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("owner/json".to_string(), locked("v1.0.0"));
+        lockfile.add_package("owner/log".to_string(), locked("v0.3.0"));
+
+        let candidates = find_upgrades(&github, &manifest, Some(&lockfile)).await;
+        assert_eq!(candidates.len(), 1);
+
+        let json = &candidates[0];
+        assert_eq!(json.name, "owner/json");
+        assert_eq!(json.patch().unwrap().version, Version::new(1, 0, 3));
+        assert_eq!(json.minor().unwrap().version, Version::new(1, 2, 0));
+        assert_eq!(json.latest().unwrap().version, Version::new(2, 1, 0));
+        assert_eq!(json.choices().len(), 4);
+    }
+
+    #[test]
+    fn test_choices_skip_duplicate_targets() {
+        let candidate = UpgradeCandidate {
+            name: "owner/json".to_string(),
+            dev: false,
+            constraint: "^1.0.0".to_string(),
+            current: Version::new(1, 0, 0),
+            releases: vec![AvailableRelease {
+                version: Version::new(1, 0, 1),
+                tag: "v1.0.1".to_string(),
+                body: None,
+            }],
+        };
+
+        assert_eq!(
+            candidate.choices(),
+            vec![UpgradeChoice::Stay, UpgradeChoice::Patch]
+        );
+    }
+
+    fn v2_1_0() -> AvailableRelease {
+        AvailableRelease {
+            version: Version::new(2, 1, 0),
+            tag: "v2.1.0".to_string(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_rewrite_constraint() {
+        let v = v2_1_0();
+        let rewrite = |old: &str| rewrite_constraint(old, &v);
+        // Every rewrite names the release tag, which the resolver can find
+        assert_eq!(rewrite("v1.0.0").as_deref(), Some("v2.1.0"));
+        assert_eq!(rewrite("1.0.0").as_deref(), Some("v2.1.0"));
+        assert_eq!(rewrite("^1.0.0").as_deref(), Some("v2.1.0"));
+        assert_eq!(rewrite("~1.0.0").as_deref(), Some("v2.1.0"));
+        assert_eq!(rewrite(">=1.0.0").as_deref(), Some("v2.1.0"));
+        assert_eq!(rewrite("*").as_deref(), Some("v2.1.0"));
+    }
+
+    #[test]
+    fn test_rewrite_constraint_compound() {
+        let v = v2_1_0();
+        let rewrite = |old: &str| rewrite_constraint(old, &v);
+        // A range is replaced while the new version is inside it
+        assert_eq!(rewrite(">=1.0.0, <3.0.0").as_deref(), Some("v2.1.0"));
+        // Otherwise the user's bounds would be lost, so nothing is rewritten
+        assert_eq!(rewrite(">=1.0.0, <2.0.0"), None);
+        assert_eq!(rewrite(">=1.0.0 <2.0.0"), None);
+        assert_eq!(rewrite("<2.0.0"), None);
+        assert_eq!(rewrite("^1.0.0 || ^2.0.0"), None);
+    }
+
+    #[test]
+    fn test_notes_excerpt() {
+        let body = "## Changes\n\n- one\n- two\n- three\n- four\n- five\n- six\n";
+        let excerpt = notes_excerpt(body);
+        assert!(excerpt.starts_with("## Changes\n- one"));
+        assert!(excerpt.ends_with("…"));
+        assert!(!excerpt.contains("six"));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::cache::Cache;
+use crate::core::path::{lua_modules_dir, packages_metadata_dir};
 use crate::core::{DepotError, DepotResult};
 use crate::package::lockfile::{LockedPackage, Lockfile};
+use crate::package::metadata::{FileCheck, PackageMetadata};
 use std::path::Path;
 
 /// Verifies package checksums against the lockfile
@@ -100,6 +102,67 @@ impl PackageVerifier {
     }
 }
 
+/// State of an installed package compared with the file hashes recorded at install
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstalledState {
+    /// Every file matches its recorded hash
+    Intact,
+    /// Files were modified, removed or added
+    Damaged(FileCheck),
+    /// The package directory does not exist
+    NotInstalled,
+    /// No file hashes were recorded (installed by an older depot)
+    Unrecorded,
+}
+
+impl InstalledState {
+    /// Whether the package fails verification and must be reinstalled,
+    /// either to restore its files or to record their hashes
+    pub fn needs_repair(&self) -> bool {
+        !matches!(self, Self::Intact)
+    }
+}
+
+/// Compare the files of each locked package in `lua_modules/` with the hashes
+/// recorded when it was installed
+///
+/// Results are sorted by package name.
+pub fn verify_installed(
+    lockfile: &Lockfile,
+    project_root: &Path,
+) -> DepotResult<Vec<(String, InstalledState)>> {
+    let lua_modules = lua_modules_dir(project_root);
+    let packages_dir = packages_metadata_dir(project_root);
+
+    let mut names: Vec<&String> = lockfile.packages.keys().collect();
+    names.sort();
+
+    let mut states = Vec::new();
+    for name in names {
+        let package_dir = lua_modules.join(name);
+        let metadata_path = PackageMetadata::path_in(&packages_dir, name);
+        let state = if !package_dir.is_dir() {
+            InstalledState::NotInstalled
+        } else if !metadata_path.exists() {
+            InstalledState::Unrecorded
+        } else {
+            let metadata = PackageMetadata::load(&metadata_path)?;
+            if metadata.files.is_empty() {
+                InstalledState::Unrecorded
+            } else {
+                let check = metadata.check_files(&package_dir)?;
+                if check.is_clean() {
+                    InstalledState::Intact
+                } else {
+                    InstalledState::Damaged(check)
+                }
+            }
+        };
+        states.push((name.clone(), state));
+    }
+    Ok(states)
+}
+
 /// Result of verification operation
 #[derive(Debug, Clone)]
 pub struct VerificationResult {
@@ -303,6 +366,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         let result = verifier.verify_package("test-package", &package, temp.path());
@@ -328,6 +394,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         let result = verifier.verify_package("test-package", &package, temp.path());
@@ -364,6 +433,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         // For GitHub packages, the error is "Source file not found" (tarball not downloaded)
@@ -414,6 +486,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         let result = verifier.verify_package("test-package", &package, temp.path());
@@ -440,6 +515,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
         let package2 = LockedPackage {
             version: "2.0.0".to_string(),
@@ -453,6 +531,9 @@ mod tests {
             dependencies: std::collections::HashMap::new(),
             build: None,
             native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
         };
 
         lockfile.add_package("pkg1".to_string(), package1);
@@ -476,4 +557,80 @@ mod tests {
         result.add_failure("pkg2".to_string(), "error".to_string());
         assert!(!result.is_success());
     }
+
+    #[test]
+    fn test_verify_installed() {
+        let temp = TempDir::new().unwrap();
+        let mut lockfile = Lockfile::new();
+        for name in [
+            "owner/damaged",
+            "owner/intact",
+            "owner/missing",
+            "owner/old",
+        ] {
+            lockfile.add_package(
+                name.to_string(),
+                LockedPackage {
+                    version: "1.0.0".to_string(),
+                    repository: name.to_string(),
+                    ref_type: "Release".to_string(),
+                    ref_value: "v1.0.0".to_string(),
+                    commit_sha: "abc123".to_string(),
+                    tarball_url: String::new(),
+                    checksum: "blake3:00".to_string(),
+                    size: 1,
+                    dependencies: std::collections::HashMap::new(),
+                    build: None,
+                    native_code: None,
+                    license: None,
+                    capabilities: Vec::new(),
+                    repository_id: None,
+                },
+            );
+        }
+
+        let lua_modules = lua_modules_dir(temp.path());
+        let packages_dir = packages_metadata_dir(temp.path());
+        for name in ["owner/damaged", "owner/intact", "owner/old"] {
+            let package_dir = lua_modules.join(name);
+            fs::create_dir_all(&package_dir).unwrap();
+            fs::write(package_dir.join("init.lua"), "return {}").unwrap();
+        }
+        for name in ["owner/damaged", "owner/intact"] {
+            let mut metadata = PackageMetadata::new(
+                name.to_string(),
+                "v1.0.0".to_string(),
+                name.to_string(),
+                "Release".to_string(),
+                "v1.0.0".to_string(),
+                "abc123".to_string(),
+            );
+            metadata.record_files(&lua_modules.join(name)).unwrap();
+            metadata
+                .save(&PackageMetadata::path_in(&packages_dir, name))
+                .unwrap();
+        }
+        fs::write(lua_modules.join("owner/damaged/init.lua"), "return nil").unwrap();
+
+        let states = verify_installed(&lockfile, temp.path()).unwrap();
+        assert_eq!(
+            states,
+            vec![
+                (
+                    "owner/damaged".to_string(),
+                    InstalledState::Damaged(FileCheck {
+                        modified: vec!["init.lua".to_string()],
+                        ..Default::default()
+                    })
+                ),
+                ("owner/intact".to_string(), InstalledState::Intact),
+                ("owner/missing".to_string(), InstalledState::NotInstalled),
+                ("owner/old".to_string(), InstalledState::Unrecorded),
+            ]
+        );
+        assert!(states[0].1.needs_repair());
+        assert!(!states[1].1.needs_repair());
+        assert!(states[2].1.needs_repair());
+        assert!(states[3].1.needs_repair());
+    }
 }
@@ -39,6 +39,8 @@ pub struct ResolvedPackage {
     pub version: String,    // Resolved version string
     pub resolved: ResolvedVersion,
     pub dependencies: HashMap<String, DependencySpec>,
+    /// License declared in the package's package.yaml
+    pub license: Option<String>,
 }
 
 /// Resolves dependencies from GitHub repositories
@@ -46,6 +48,8 @@ pub struct DependencyResolver {
     github: Arc<dyn GitHubProvider>,
     _strategy: ResolutionStrategy,
     fallback_chain: Vec<String>,
+    /// Versions that replace the requested spec for these packages
+    pins: HashMap<String, String>,
 }
 
 impl DependencyResolver {
@@ -55,6 +59,7 @@ impl DependencyResolver {
             github,
             _strategy: ResolutionStrategy::default(),
             fallback_chain,
+            pins: HashMap::new(),
         }
     }
 
@@ -68,9 +73,16 @@ impl DependencyResolver {
             github,
             _strategy,
             fallback_chain,
+            pins: HashMap::new(),
         }
     }
 
+    /// Resolve these packages to the given versions wherever they appear in the graph
+    pub fn with_pins(mut self, pins: HashMap<String, String>) -> Self {
+        self.pins = pins;
+        self
+    }
+
     /// Resolve all dependencies from a package manifest
     ///
     /// This implements a breadth-first dependency resolution:
@@ -124,7 +136,11 @@ impl DependencyResolver {
             let (owner, repo) = parse_repository(repository)?;
 
             // Resolve version using GitHub API
-            let version_spec = dep_spec.version.as_deref();
+            let version_spec = self
+                .pins
+                .get(&dep_key)
+                .map(String::as_str)
+                .or(dep_spec.version.as_deref());
             let resolved_version = self
                 .github
                 .resolve_version(&owner, &repo, version_spec, &self.fallback_chain)
@@ -138,11 +154,11 @@ impl DependencyResolver {
                 .fetch_package_manifest(&owner, &repo, &resolved_version.ref_value)
                 .await;
 
-            let transitive_deps = match package_manifest {
-                Ok(manifest) => manifest.dependencies.clone(),
+            let (transitive_deps, license) = match package_manifest {
+                Ok(manifest) => (manifest.dependencies, manifest.license),
                 Err(_) => {
                     // No package.yaml found - assume no dependencies
-                    HashMap::new()
+                    (HashMap::new(), None)
                 }
             };
 
@@ -168,6 +184,7 @@ impl DependencyResolver {
                     version: resolved_version.ref_value.clone(),
                     resolved: resolved_version,
                     dependencies: transitive_dep_specs.clone(),
+                    license,
                 },
             );
 
@@ -261,7 +278,7 @@ impl DependencyResolver {
 
 /// Parse owner/repo from repository string
 /// Accepts formats: "owner/repo", "github.com/owner/repo", "https://github.com/owner/repo"
-fn parse_repository(repository: &str) -> DepotResult<(String, String)> {
+pub(crate) fn parse_repository(repository: &str) -> DepotResult<(String, String)> {
     let repo = repository.trim();
 
     // Strip protocol if present
@@ -375,4 +392,95 @@ mod tests {
         let result = resolver.resolve(&deps).await.unwrap();
         assert!(result.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_resolve_records_license() {
+        use crate::di::mocks::MockGitHubProvider;
+        use crate::github::GitHubRelease;
+        use std::sync::Arc;
+
+        let github = Arc::new(MockGitHubProvider::new());
+        github.add_release(
+            "owner",
+            "json",
+            GitHubRelease {
+                tag_name: "v1.0.0".to_string(),
+                name: None,
+                draft: false,
+                prerelease: false,
+                tarball_url: String::new(),
+                zipball_url: String::new(),
+                assets: Vec::new(),
+                body: None,
+                published_at: None,
+            },
+        );
+        github.add_file_content(
+            "owner",
+            "json",
+            "package.yaml",
+            "v1.0.0",
+            "name: json\nversion: 1.0.0\nlicense: MIT\n".to_string(),
+        );
+        let resolver = DependencyResolver::new(github, vec!["release".to_string()]);
+
+        let mut deps = HashMap::new();
+        deps.insert(
+            "owner/json".to_string(),
+            DependencySpec {
+                version: Some("v1.0.0".to_string()),
+                repository: None,
+            },
+        );
+        let result = resolver.resolve(&deps).await.unwrap();
+        assert_eq!(result["owner/json"].license.as_deref(), Some("MIT"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_pins_transitive_dependency() {
+        use crate::di::mocks::MockGitHubProvider;
+        use crate::github::GitHubRelease;
+        use std::sync::Arc;
+
+        let release = |tag: &str| GitHubRelease {
+            tag_name: tag.to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: None,
+            published_at: None,
+        };
+        let github = Arc::new(MockGitHubProvider::new());
+        github.add_release("owner", "web", release("v2.0.0"));
+        github.add_release("owner", "json", release("v1.0.0"));
+        github.add_release("owner", "json", release("v1.3.0"));
+        github.add_file_content(
+            "owner",
+            "web",
+            "package.yaml",
+            "v2.0.0",
+            "name: web\nversion: 2.0.0\ndependencies:\n  owner/json: v1.0.0\n".to_string(),
+        );
+
+        let mut deps = HashMap::new();
+        deps.insert(
+            "owner/web".to_string(),
+            DependencySpec {
+                version: Some("v2.0.0".to_string()),
+                repository: None,
+            },
+        );
+        let resolver = DependencyResolver::new(github.clone(), vec!["release".to_string()]);
+        let result = resolver.resolve(&deps).await.unwrap();
+        assert_eq!(result["owner/json"].version, "v1.0.0");
+
+        let pins = HashMap::from([("owner/json".to_string(), "v1.3.0".to_string())]);
+        let resolver = DependencyResolver::new(github, vec!["release".to_string()]).with_pins(pins);
+        let result = resolver.resolve(&deps).await.unwrap();
+        assert_eq!(result["owner/web"].version, "v2.0.0");
+        assert_eq!(result["owner/json"].version, "v1.3.0");
+    }
 }
@@ -0,0 +1,561 @@
+//! Dependency graph export (`depot graph`)
+//!
+//! Builds a renderable graph from the project manifest and lockfile and
+//! emits it as Graphviz DOT, Mermaid or JSON.
+
+use crate::core::{DepotError, DepotResult};
+use crate::package::lockfile::Lockfile;
+use crate::package::manifest::PackageManifest;
+use crate::security::vulnerability::Severity;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+
+/// Output format for graph export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl GraphFormat {
+    /// Parse a graph format from a string
+    pub fn parse(s: &str) -> DepotResult<Self> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "mermaid" | "mmd" => Ok(GraphFormat::Mermaid),
+            "json" => Ok(GraphFormat::Json),
+            _ => Err(DepotError::Config(format!(
+                "Invalid graph format '{}'. Must be 'dot', 'mermaid' or 'json'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Options controlling which nodes are exported and how they are decorated
+#[derive(Debug, Clone, Default)]
+pub struct GraphExportOptions {
+    /// Include dev_dependencies of the root manifest
+    pub include_dev: bool,
+    /// Maximum depth from the root (direct dependencies are depth 1)
+    pub max_depth: Option<usize>,
+    /// Mark packages that contain native code
+    pub highlight_native: bool,
+    /// Highest known vulnerability severity per package
+    pub vulnerabilities: HashMap<String, Severity>,
+}
+
+/// A package in the exported graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub depth: usize,
+    pub dev: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub native_code: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vulnerability: Option<Severity>,
+}
+
+/// A dependency edge with the constraint declared by the parent
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub constraint: String,
+    pub dev: bool,
+}
+
+/// Dependency graph ready to be rendered
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportGraph {
+    pub root: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ExportGraph {
+    /// Build the graph from a manifest and its lockfile
+    ///
+    /// Walks breadth-first from the root so each package is recorded at its
+    /// shallowest depth. A package is dev-only when no production dependency
+    /// reaches it, whatever the path it was first found on. Nodes and edges
+    /// are sorted for stable output.
+    pub fn build(
+        manifest: &PackageManifest,
+        lockfile: Option<&Lockfile>,
+        options: &GraphExportOptions,
+    ) -> Self {
+        let root = manifest.name.clone();
+        let mut nodes: HashMap<String, GraphNode> = HashMap::new();
+        let mut edges: Vec<GraphEdge> = Vec::new();
+        let mut seen_edges: HashSet<(String, String)> = HashSet::new();
+        let mut queue: VecDeque<(String, usize, bool)> = VecDeque::new();
+
+        nodes.insert(
+            root.clone(),
+            GraphNode {
+                id: root.clone(),
+                version: Some(manifest.version.clone()),
+                depth: 0,
+                dev: false,
+                native_code: Vec::new(),
+                vulnerability: None,
+            },
+        );
+
+        let mut direct: Vec<(&String, &String, bool)> = manifest
+            .dependencies
+            .iter()
+            .map(|(n, c)| (n, c, false))
+            .collect();
+        if options.include_dev {
+            direct.extend(manifest.dev_dependencies.iter().map(|(n, c)| (n, c, true)));
+        }
+        direct.sort();
+
+        let within_depth = |depth: usize| options.max_depth.is_none_or(|max| depth <= max);
+        let production = production_packages(manifest, lockfile);
+
+        if within_depth(1) {
+            for (name, constraint, dev) in direct {
+                edges.push(GraphEdge {
+                    from: root.clone(),
+                    to: name.clone(),
+                    constraint: constraint.clone(),
+                    dev,
+                });
+                seen_edges.insert((root.clone(), name.clone()));
+                queue.push_back((name.clone(), 1, dev));
+            }
+        }
+
+        while let Some((name, depth, _)) = queue.pop_front() {
+            if nodes.contains_key(&name) {
+                continue;
+            }
+            let dev = !production.contains(&name);
+
+            let locked = lockfile.and_then(|lf| lf.get_package(&name));
+            let native_code = if options.highlight_native {
+                locked
+                    .and_then(|pkg| pkg.native_code.as_ref())
+                    .map(|info| info.types.clone())
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+
+            nodes.insert(
+                name.clone(),
+                GraphNode {
+                    id: name.clone(),
+                    version: locked.map(|pkg| pkg.version.clone()),
+                    depth,
+                    dev,
+                    native_code,
+                    vulnerability: options.vulnerabilities.get(&name).copied(),
+                },
+            );
+
+            let Some(pkg) = locked else {
+                continue;
+            };
+            if !within_depth(depth + 1) {
+                continue;
+            }
+
+            let mut deps: Vec<(&String, &String)> = pkg.dependencies.iter().collect();
+            deps.sort();
+            for (dep_name, dep_constraint) in deps {
+                if seen_edges.insert((name.clone(), dep_name.clone())) {
+                    edges.push(GraphEdge {
+                        from: name.clone(),
+                        to: dep_name.clone(),
+                        constraint: dep_constraint.clone(),
+                        dev,
+                    });
+                }
+                queue.push_back((dep_name.clone(), depth + 1, dev));
+            }
+        }
+
+        let mut nodes: Vec<GraphNode> = nodes.into_values().collect();
+        nodes.sort_by(|a, b| a.depth.cmp(&b.depth).then_with(|| a.id.cmp(&b.id)));
+        edges.sort_by(|a, b| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to)));
+
+        Self { root, nodes, edges }
+    }
+
+    /// Render the graph in the requested format
+    pub fn render(&self, format: GraphFormat) -> DepotResult<String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Mermaid => Ok(self.to_mermaid()),
+            GraphFormat::Json => serde_json::to_string_pretty(self)
+                .map(|s| s + "\n")
+                .map_err(|e| DepotError::Package(format!("Failed to serialize graph: {}", e))),
+        }
+    }
+
+    /// Render as Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "digraph dependencies {{");
+        let _ = writeln!(output, "  rankdir=LR;");
+        let _ = writeln!(output, "  node [shape=box, style=rounded];");
+
+        for node in &self.nodes {
+            let mut attrs = vec![format!(
+                "label=\"{}\"",
+                dot_escape(&node_label(node, "\\n"))
+            )];
+            let mut styles = vec!["rounded"];
+
+            if node.id == self.root {
+                attrs.push("shape=doubleoctagon".to_string());
+            }
+            if node.dev {
+                styles.push("dashed");
+            }
+            if !node.native_code.is_empty() {
+                styles.push("bold");
+                attrs.push("color=\"#1f6feb\"".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            if let Some(severity) = node.vulnerability {
+                styles.push("filled");
+                attrs.push(format!("fillcolor=\"{}\"", severity_color(severity)));
+            }
+            attrs.push(format!("style=\"{}\"", styles.join(",")));
+
+            let _ = writeln!(
+                output,
+                "  \"{}\" [{}];",
+                dot_escape(&node.id),
+                attrs.join(", ")
+            );
+        }
+
+        for edge in &self.edges {
+            let mut attrs = vec![format!("label=\"{}\"", dot_escape(&edge.constraint))];
+            if edge.dev {
+                attrs.push("style=dashed".to_string());
+            }
+            let _ = writeln!(
+                output,
+                "  \"{}\" -> \"{}\" [{}];",
+                dot_escape(&edge.from),
+                dot_escape(&edge.to),
+                attrs.join(", ")
+            );
+        }
+
+        let _ = writeln!(output, "}}");
+        output
+    }
+
+    /// Render as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), format!("n{}", i)))
+            .collect();
+
+        let mut output = String::new();
+        let _ = writeln!(output, "graph LR");
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                output,
+                "  {}[\"{}\"]",
+                ids[node.id.as_str()],
+                mermaid_escape(&node_label(node, "<br/>"))
+            );
+        }
+
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str()))
+            else {
+                continue;
+            };
+            let arrow = if edge.dev { "-.->" } else { "-->" };
+            let _ = writeln!(
+                output,
+                "  {} {}|\"{}\"| {}",
+                from,
+                arrow,
+                mermaid_escape(&edge.constraint),
+                to
+            );
+        }
+
+        let class_members = |filter: &dyn Fn(&GraphNode) -> bool| -> Vec<&str> {
+            self.nodes
+                .iter()
+                .filter(|n| filter(n))
+                .map(|n| ids[n.id.as_str()].as_str())
+                .collect()
+        };
+
+        let native = class_members(&|n| !n.native_code.is_empty());
+        if !native.is_empty() {
+            let _ = writeln!(output, "  classDef native stroke:#1f6feb,stroke-width:3px;");
+            let _ = writeln!(output, "  class {} native;", native.join(","));
+        }
+
+        for severity in [
+            Severity::Critical,
+            Severity::High,
+            Severity::Medium,
+            Severity::Low,
+        ] {
+            let members = class_members(&|n| n.vulnerability == Some(severity));
+            if members.is_empty() {
+                continue;
+            }
+            let class = format!("vuln_{}", severity.as_str().to_lowercase());
+            let _ = writeln!(
+                output,
+                "  classDef {} fill:{};",
+                class,
+                severity_color(severity)
+            );
+            let _ = writeln!(output, "  class {} {};", members.join(","), class);
+        }
+
+        output
+    }
+}
+
+/// Packages reachable from the manifest's production dependencies
+///
+/// Computed over the whole lockfile, ignoring `max_depth`, so a package is
+/// only marked dev when production code can never load it.
+fn production_packages(manifest: &PackageManifest, lockfile: Option<&Lockfile>) -> HashSet<String> {
+    let mut production = HashSet::new();
+    let mut pending: Vec<&String> = manifest.dependencies.keys().collect();
+    while let Some(name) = pending.pop() {
+        if !production.insert(name.clone()) {
+            continue;
+        }
+        if let Some(pkg) = lockfile.and_then(|lf| lf.get_package(name)) {
+            pending.extend(pkg.dependencies.keys());
+        }
+    }
+    production
+}
+
+/// Label shown for a node: name, version and decorations
+fn node_label(node: &GraphNode, separator: &str) -> String {
+    let mut label = node.id.clone();
+    if let Some(ref version) = node.version {
+        label.push_str(separator);
+        label.push_str(version);
+    }
+    if !node.native_code.is_empty() {
+        label.push_str(separator);
+        label.push_str(&format!("native: {}", node.native_code.join(", ")));
+    }
+    if let Some(severity) = node.vulnerability {
+        label.push_str(separator);
+        label.push_str(&format!("{} vulnerability", severity.as_str()));
+    }
+    label
+}
+
+fn severity_color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "#d73a49",
+        Severity::High => "#f66a0a",
+        Severity::Medium => "#ffd33d",
+        Severity::Low => "#c8e1ff",
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    // Keep `\n` line breaks produced by node_label intact
+    s.replace('"', "\\\"")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::{LockedPackage, NativeCodeInfo};
+
+    fn locked(version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: format!("v{}", version),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn sample() -> (PackageManifest, Lockfile) {
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/web".to_string(), "^2.0.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("owner/test".to_string(), "^1.0.0".to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked("2.0.0", &[("owner/json", "^1.0.0")]),
+        );
+        let mut json = locked("1.2.0", &[("owner/cjson", "*")]);
+        json.native_code = Some(NativeCodeInfo {
+            types: vec!["c".to_string()],
+            build_required: true,
+            prebuilt_used: false,
+        });
+        lockfile.add_package("owner/json".to_string(), json);
+        lockfile.add_package("owner/cjson".to_string(), locked("0.1.0", &[]));
+        (manifest, lockfile)
+    }
+
+    #[test]
+    fn test_graph_format_parse() {
+        assert_eq!(GraphFormat::parse("dot").unwrap(), GraphFormat::Dot);
+        assert_eq!(GraphFormat::parse("Mermaid").unwrap(), GraphFormat::Mermaid);
+        assert_eq!(GraphFormat::parse("json").unwrap(), GraphFormat::Json);
+        assert!(GraphFormat::parse("svg").is_err());
+    }
+
+    #[test]
+    fn test_build_excludes_dev_by_default() {
+        let (manifest, lockfile) = sample();
+        let graph = ExportGraph::build(&manifest, Some(&lockfile), &Default::default());
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["app", "owner/web", "owner/json", "owner/cjson"]);
+        assert_eq!(graph.edges.len(), 3);
+    }
+
+    #[test]
+    fn test_build_with_dev_and_depth() {
+        let (manifest, lockfile) = sample();
+        let options = GraphExportOptions {
+            include_dev: true,
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let graph = ExportGraph::build(&manifest, Some(&lockfile), &options);
+
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["app", "owner/test", "owner/web"]);
+        assert!(graph.nodes.iter().any(|n| n.id == "owner/test" && n.dev));
+    }
+
+    #[test]
+    fn test_build_dev_diamond() {
+        // The dev path reaches owner/shared first; production reaches it later
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("zzz/prod".to_string(), "^1.0.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("aaa/dev".to_string(), "^1.0.0".to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "aaa/dev".to_string(),
+            locked("1.0.0", &[("owner/shared", "^1.0.0")]),
+        );
+        lockfile.add_package(
+            "zzz/prod".to_string(),
+            locked("1.0.0", &[("owner/mid", "^1.0.0")]),
+        );
+        lockfile.add_package(
+            "owner/mid".to_string(),
+            locked("1.0.0", &[("owner/shared", "^1.0.0")]),
+        );
+        lockfile.add_package(
+            "owner/shared".to_string(),
+            locked("1.0.0", &[("owner/leaf", "^1.0.0")]),
+        );
+        lockfile.add_package("owner/leaf".to_string(), locked("1.0.0", &[]));
+
+        let options = GraphExportOptions {
+            include_dev: true,
+            ..Default::default()
+        };
+        let graph = ExportGraph::build(&manifest, Some(&lockfile), &options);
+
+        let dev_nodes: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.dev)
+            .map(|n| n.id.as_str())
+            .collect();
+        assert_eq!(dev_nodes, vec!["aaa/dev"]);
+
+        let edge = |from: &str, to: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|e| e.from == from && e.to == to)
+                .unwrap()
+                .dev
+        };
+        assert!(edge("app", "aaa/dev"));
+        assert!(edge("aaa/dev", "owner/shared"));
+        assert!(!edge("owner/shared", "owner/leaf"));
+    }
+
+    #[test]
+    fn test_render_highlights() {
+        let (manifest, lockfile) = sample();
+        let mut vulnerabilities = HashMap::new();
+        vulnerabilities.insert("owner/web".to_string(), Severity::High);
+        let options = GraphExportOptions {
+            highlight_native: true,
+            vulnerabilities,
+            ..Default::default()
+        };
+        let graph = ExportGraph::build(&manifest, Some(&lockfile), &options);
+
+        let dot = graph.render(GraphFormat::Dot).unwrap();
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"owner/web\" -> \"owner/json\" [label=\"^1.0.0\"]"));
+        assert!(dot.contains("native: c"));
+        assert!(dot.contains("fillcolor=\"#f66a0a\""));
+
+        let mermaid = graph.render(GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.starts_with("graph LR"));
+        assert!(mermaid.contains("classDef native"));
+        assert!(mermaid.contains("classDef vuln_high"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&graph.render(GraphFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["root"], "app");
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 4);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod dependency_graph;
 pub mod dependency_resolver;
+pub mod graph_export;
+pub mod why;
 
 pub use dependency_graph::DependencyGraph;
 pub use dependency_resolver::{DependencyResolver, ResolutionStrategy, ResolvedPackage};
@@ -0,0 +1,291 @@
+//! Reverse dependency explanation (`depot why`)
+//!
+//! Walks the locked dependency graph from a root manifest down to a target
+//! package and reports every path that leads to it, together with the
+//! constraint declared at each edge.
+
+use crate::package::lockfile::Lockfile;
+use std::collections::HashMap;
+
+/// A single edge in a dependency path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathEdge {
+    /// Package the edge points to
+    pub name: String,
+    /// Constraint declared by the parent for this package
+    pub constraint: String,
+    /// Version recorded in the lockfile, if the package is locked
+    pub locked_version: Option<String>,
+}
+
+/// A path from a root manifest to the target package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyPath {
+    /// Name of the root (project or workspace member)
+    pub root: String,
+    /// Whether the first edge comes from dev_dependencies
+    pub dev: bool,
+    /// Edges from the root down to (and including) the target
+    pub edges: Vec<PathEdge>,
+}
+
+/// A root to start the search from: a project or workspace member manifest
+#[derive(Debug, Clone)]
+pub struct WhyRoot<'a> {
+    pub name: String,
+    pub dependencies: &'a HashMap<String, String>,
+    pub dev_dependencies: &'a HashMap<String, String>,
+    pub lockfile: Option<&'a Lockfile>,
+}
+
+/// Resolve a user-supplied package name against the packages known to the roots
+///
+/// Accepts either the full key (`owner/repo`) or the bare repository name
+/// (`repo`) when it is unambiguous.
+pub fn resolve_target(roots: &[WhyRoot<'_>], query: &str) -> Result<String, Vec<String>> {
+    let mut known: Vec<String> = Vec::new();
+    for root in roots {
+        known.extend(root.dependencies.keys().cloned());
+        known.extend(root.dev_dependencies.keys().cloned());
+        if let Some(lockfile) = root.lockfile {
+            for (name, pkg) in &lockfile.packages {
+                known.push(name.clone());
+                known.extend(pkg.dependencies.keys().cloned());
+            }
+        }
+    }
+    known.sort();
+    known.dedup();
+
+    if known.iter().any(|k| k == query) {
+        return Ok(query.to_string());
+    }
+
+    let matches: Vec<String> = known
+        .into_iter()
+        .filter(|k| k.rsplit('/').next() == Some(query))
+        .collect();
+
+    if matches.len() == 1 {
+        Ok(matches[0].clone())
+    } else {
+        Err(matches)
+    }
+}
+
+/// Find every path from the given roots to `target`
+///
+/// Paths are returned sorted by root and then by the chain of package names,
+/// so the output is stable across runs. Cycles in the lockfile are skipped.
+pub fn find_paths(roots: &[WhyRoot<'_>], target: &str) -> Vec<DependencyPath> {
+    let mut paths = Vec::new();
+
+    for root in roots {
+        let direct = root
+            .dependencies
+            .iter()
+            .map(|(n, c)| (n, c, false))
+            .chain(root.dev_dependencies.iter().map(|(n, c)| (n, c, true)));
+
+        for (name, constraint, dev) in direct {
+            let mut chain = vec![make_edge(root.lockfile, name, constraint)];
+            let mut found = Vec::new();
+            walk(root.lockfile, target, &mut chain, &mut found);
+
+            for edges in found {
+                paths.push(DependencyPath {
+                    root: root.name.clone(),
+                    dev,
+                    edges,
+                });
+            }
+        }
+    }
+
+    paths.sort_by(|a, b| {
+        a.root.cmp(&b.root).then_with(|| {
+            let a_names = a.edges.iter().map(|e| e.name.as_str());
+            let b_names = b.edges.iter().map(|e| e.name.as_str());
+            a_names.cmp(b_names)
+        })
+    });
+    paths
+}
+
+fn make_edge(lockfile: Option<&Lockfile>, name: &str, constraint: &str) -> PathEdge {
+    PathEdge {
+        name: name.to_string(),
+        constraint: constraint.to_string(),
+        locked_version: lockfile
+            .and_then(|lf| lf.get_package(name))
+            .map(|pkg| pkg.version.clone()),
+    }
+}
+
+/// Depth-first walk collecting every chain that ends at `target`
+fn walk(
+    lockfile: Option<&Lockfile>,
+    target: &str,
+    chain: &mut Vec<PathEdge>,
+    found: &mut Vec<Vec<PathEdge>>,
+) {
+    let current = match chain.last() {
+        Some(edge) => edge.name.clone(),
+        None => return,
+    };
+
+    if current == target {
+        found.push(chain.clone());
+        return;
+    }
+
+    let Some(pkg) = lockfile.and_then(|lf| lf.get_package(&current)) else {
+        return;
+    };
+
+    let mut deps: Vec<(&String, &String)> = pkg.dependencies.iter().collect();
+    deps.sort();
+
+    for (dep_name, dep_constraint) in deps {
+        if chain.iter().any(|e| &e.name == dep_name) {
+            continue;
+        }
+        chain.push(make_edge(lockfile, dep_name, dep_constraint));
+        walk(lockfile, target, chain, found);
+        chain.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::LockedPackage;
+
+    fn locked(version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: format!("v{}", version),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn sample_lockfile() -> Lockfile {
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked(
+                "2.0.0",
+                &[("owner/json", "^1.0.0"), ("owner/log", "^0.3.0")],
+            ),
+        );
+        lockfile.add_package(
+            "owner/log".to_string(),
+            locked("0.3.1", &[("owner/json", "~1.2.0")]),
+        );
+        lockfile.add_package("owner/json".to_string(), locked("1.2.4", &[]));
+        lockfile
+    }
+
+    #[test]
+    fn test_find_paths_direct_and_transitive() {
+        let lockfile = sample_lockfile();
+        let mut deps = HashMap::new();
+        deps.insert("owner/web".to_string(), "^2.0.0".to_string());
+        let dev_deps = HashMap::new();
+
+        let roots = vec![WhyRoot {
+            name: "app".to_string(),
+            dependencies: &deps,
+            dev_dependencies: &dev_deps,
+            lockfile: Some(&lockfile),
+        }];
+
+        let paths = find_paths(&roots, "owner/json");
+        assert_eq!(paths.len(), 2);
+
+        let names: Vec<Vec<&str>> = paths
+            .iter()
+            .map(|p| p.edges.iter().map(|e| e.name.as_str()).collect())
+            .collect();
+        assert_eq!(names[0], vec!["owner/web", "owner/json"]);
+        assert_eq!(names[1], vec!["owner/web", "owner/log", "owner/json"]);
+
+        let last = paths[1].edges.last().unwrap();
+        assert_eq!(last.constraint, "~1.2.0");
+        assert_eq!(last.locked_version.as_deref(), Some("1.2.4"));
+    }
+
+    #[test]
+    fn test_find_paths_dev_dependency() {
+        let lockfile = sample_lockfile();
+        let deps = HashMap::new();
+        let mut dev_deps = HashMap::new();
+        dev_deps.insert("owner/log".to_string(), "^0.3.0".to_string());
+
+        let roots = vec![WhyRoot {
+            name: "app".to_string(),
+            dependencies: &deps,
+            dev_dependencies: &dev_deps,
+            lockfile: Some(&lockfile),
+        }];
+
+        let paths = find_paths(&roots, "owner/json");
+        assert_eq!(paths.len(), 1);
+        assert!(paths[0].dev);
+    }
+
+    #[test]
+    fn test_find_paths_handles_cycles() {
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("a/a".to_string(), locked("1.0.0", &[("b/b", "*")]));
+        lockfile.add_package("b/b".to_string(), locked("1.0.0", &[("a/a", "*")]));
+
+        let mut deps = HashMap::new();
+        deps.insert("a/a".to_string(), "*".to_string());
+        let dev_deps = HashMap::new();
+
+        let roots = vec![WhyRoot {
+            name: "app".to_string(),
+            dependencies: &deps,
+            dev_dependencies: &dev_deps,
+            lockfile: Some(&lockfile),
+        }];
+
+        let paths = find_paths(&roots, "b/b");
+        assert_eq!(paths.len(), 1);
+        assert!(find_paths(&roots, "c/c").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_target_short_name() {
+        let lockfile = sample_lockfile();
+        let mut deps = HashMap::new();
+        deps.insert("owner/web".to_string(), "^2.0.0".to_string());
+        let dev_deps = HashMap::new();
+
+        let roots = vec![WhyRoot {
+            name: "app".to_string(),
+            dependencies: &deps,
+            dev_dependencies: &dev_deps,
+            lockfile: Some(&lockfile),
+        }];
+
+        assert_eq!(resolve_target(&roots, "json").unwrap(), "owner/json");
+        assert_eq!(resolve_target(&roots, "owner/log").unwrap(), "owner/log");
+        assert!(resolve_target(&roots, "missing").unwrap_err().is_empty());
+    }
+}
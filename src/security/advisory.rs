@@ -1,6 +1,12 @@
-use crate::core::DepotResult;
+use crate::core::{DepotError, DepotResult};
+use crate::di::ConfigProvider;
+use crate::security::osv::parse_osv_record;
 use crate::security::vulnerability::Vulnerability;
 use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+use walkdir::WalkDir;
 
 /// Database of security advisories
 pub struct AdvisoryDatabase {
@@ -15,9 +21,72 @@ impl AdvisoryDatabase {
         }
     }
 
-    /// Load advisories from a source
-    pub fn load() -> DepotResult<Self> {
-        Ok(Self::new())
+    /// Load advisories from the database configured as `advisory_db`
+    ///
+    /// Returns an empty database when none is configured.
+    pub fn load(config: &dyn ConfigProvider) -> DepotResult<Self> {
+        match config.advisory_db() {
+            Some(path) => Self::load_from(path),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Load OSV-format JSON advisories from a directory or a zip file
+    ///
+    /// Directories are searched recursively for `*.json` files. Files that
+    /// are not valid OSV records are skipped with a warning.
+    pub fn load_from(path: &Path) -> DepotResult<Self> {
+        let mut db = Self::new();
+
+        if path.is_dir() {
+            for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+                let file = entry.path();
+                if entry.file_type().is_file() && is_json(file) {
+                    let content = fs::read_to_string(file)?;
+                    db.add_record(&file.display().to_string(), &content);
+                }
+            }
+        } else if path.is_file() {
+            let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(|e| {
+                DepotError::Package(format!(
+                    "Failed to open advisory archive {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i).map_err(|e| {
+                    DepotError::Package(format!("Failed to read advisory archive: {}", e))
+                })?;
+                if !entry.is_file() || !is_json(Path::new(entry.name())) {
+                    continue;
+                }
+
+                let name = entry.name().to_string();
+                let mut content = String::new();
+                entry.read_to_string(&mut content)?;
+                db.add_record(&name, &content);
+            }
+        } else {
+            return Err(DepotError::Path(format!(
+                "Advisory database not found: {}",
+                path.display()
+            )));
+        }
+
+        Ok(db)
+    }
+
+    fn add_record(&mut self, source: &str, content: &str) {
+        match parse_osv_record(content) {
+            Ok(vulns) => {
+                for vuln in vulns {
+                    self.add_advisory(vuln);
+                }
+            }
+            Err(e) => eprintln!("Warning: Skipping {}: {}", source, e),
+        }
     }
 
     /// Add an advisory to the database
@@ -55,6 +124,20 @@ impl AdvisoryDatabase {
     pub fn has_vulnerabilities(&self, package: &str) -> bool {
         self.advisories.contains_key(package)
     }
+
+    /// Total number of advisories across all packages
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(Vec::len).sum()
+    }
+
+    /// Check if the database has no advisories
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("json")
 }
 
 impl Default for AdvisoryDatabase {
@@ -66,6 +149,7 @@ impl Default for AdvisoryDatabase {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::di::mocks::MockConfigProvider;
     use crate::security::vulnerability::Severity;
 
     #[test]
@@ -80,6 +164,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -110,6 +196,8 @@ mod tests {
             title: "Test 1".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -121,6 +209,8 @@ mod tests {
             title: "Test 2".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("1.5.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -143,6 +233,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -169,6 +261,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -192,7 +286,86 @@ mod tests {
 
     #[test]
     fn test_advisory_database_load() {
-        let db = AdvisoryDatabase::load().unwrap();
+        let config = MockConfigProvider::default();
+        let db = AdvisoryDatabase::load(&config).unwrap();
         assert!(db.advisories.is_empty());
+
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("OSV-1.json"),
+            osv_record("OSV-1", "owner/json", "1.2.0"),
+        )
+        .unwrap();
+        let config = MockConfigProvider {
+            advisory_db: Some(temp.path().to_path_buf()),
+            ..Default::default()
+        };
+        let db = AdvisoryDatabase::load(&config).unwrap();
+        assert_eq!(db.len(), 1);
+    }
+
+    fn osv_record(id: &str, package: &str, fixed: &str) -> String {
+        serde_json::json!({
+            "id": id,
+            "summary": format!("{} summary", id),
+            "affected": [{
+                "package": {"ecosystem": "Lua", "name": package},
+                "ranges": [{
+                    "type": "SEMVER",
+                    "events": [{"introduced": "0"}, {"fixed": fixed}]
+                }]
+            }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_advisory_database_load_from_dir() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let nested = temp.path().join("internal");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            temp.path().join("OSV-1.json"),
+            osv_record("OSV-1", "owner/json", "1.2.0"),
+        )
+        .unwrap();
+        fs::write(
+            nested.join("INT-1.json"),
+            osv_record("INT-1", "owner/json", "2.0.0"),
+        )
+        .unwrap();
+        fs::write(temp.path().join("broken.json"), "{").unwrap();
+        fs::write(temp.path().join("README.md"), "not an advisory").unwrap();
+
+        let db = AdvisoryDatabase::load_from(temp.path()).unwrap();
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.check_package("owner/json", "v1.1.0").len(), 2);
+        assert_eq!(db.check_package("owner/json", "v1.5.0").len(), 1);
+        assert!(db.check_package("owner/json", "v2.0.0").is_empty());
+    }
+
+    #[test]
+    fn test_advisory_database_load_from_zip() {
+        use std::io::Write;
+        use zip::write::{FileOptions, ZipWriter};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let zip_path = temp.path().join("all.zip");
+        let mut zip = ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("OSV-1.json", FileOptions::default())
+            .unwrap();
+        zip.write_all(osv_record("OSV-1", "owner/log", "0.3.0").as_bytes())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let db = AdvisoryDatabase::load_from(&zip_path).unwrap();
+        assert_eq!(db.len(), 1);
+        assert!(db.has_vulnerabilities("owner/log"));
+    }
+
+    #[test]
+    fn test_advisory_database_load_from_missing_path() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert!(AdvisoryDatabase::load_from(&temp.path().join("missing")).is_err());
     }
 }
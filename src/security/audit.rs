@@ -1,7 +1,9 @@
 use crate::core::{DepotError, DepotResult};
+use crate::di::ConfigProvider;
 use crate::package::lockfile::Lockfile;
 use crate::security::advisory::AdvisoryDatabase;
 use crate::security::osv::OsvApi;
+use crate::security::osv_cache::OsvCache;
 use crate::security::vulnerability::{Severity, Vulnerability, VulnerabilityReport};
 use std::path::Path;
 
@@ -11,70 +13,129 @@ pub struct SecurityAuditor {
 }
 
 impl SecurityAuditor {
-    /// Create a new security auditor
-    pub fn new() -> DepotResult<Self> {
-        let advisory_db = AdvisoryDatabase::load()?;
+    /// Create a new security auditor backed by the configured advisory database
+    pub fn new(config: &dyn ConfigProvider) -> DepotResult<Self> {
+        let advisory_db = AdvisoryDatabase::load(config)?;
         Ok(Self { advisory_db })
     }
 
+    /// Create a security auditor backed by an existing advisory database
+    pub fn with_database(advisory_db: AdvisoryDatabase) -> Self {
+        Self { advisory_db }
+    }
+
     /// Create a new security auditor with OSV integration.
     ///
-    /// Queries OSV for vulnerabilities in the provided packages (name + version pairs).
-    pub async fn new_with_osv(osv: &OsvApi, packages: &[(String, String)]) -> DepotResult<Self> {
-        let mut advisory_db = AdvisoryDatabase::load()?;
-
-        for (name, version) in packages {
-            match osv.query_package(name, version).await {
-                Ok(vulns) => {
-                    for vuln in vulns {
-                        advisory_db.add_advisory(vuln);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to query OSV for {}: {}", name, e);
+    /// Queries OSV for vulnerabilities in the provided packages (name + version pairs)
+    /// with a single batch request.
+    pub async fn new_with_osv(
+        config: &dyn ConfigProvider,
+        osv: &OsvApi,
+        packages: &[(String, String)],
+    ) -> DepotResult<Self> {
+        let mut advisory_db = AdvisoryDatabase::load(config)?;
+
+        if packages.is_empty() {
+            return Ok(Self { advisory_db });
+        }
+
+        match osv.query_batch(packages).await {
+            Ok(results) => {
+                for vuln in results.into_iter().flatten() {
+                    advisory_db.add_advisory(vuln);
                 }
             }
+            Err(e) => {
+                eprintln!("Warning: Failed to query OSV: {}", e);
+            }
         }
 
         Ok(Self { advisory_db })
     }
 
     /// Run a security audit on the current project
-    pub fn audit_project(project_root: &Path) -> DepotResult<VulnerabilityReport> {
-        let auditor = Self::new()?;
-        auditor.audit(project_root)
+    pub fn audit_project(
+        project_root: &Path,
+        config: &dyn ConfigProvider,
+    ) -> DepotResult<VulnerabilityReport> {
+        let auditor = Self::new(config)?;
+        Ok(auditor.audit(&Self::load_lockfile(project_root)?))
+    }
+
+    /// The project's lockfile, which every audit reads
+    fn load_lockfile(project_root: &Path) -> DepotResult<Lockfile> {
+        Lockfile::load(project_root)?.ok_or_else(|| {
+            DepotError::Package(format!(
+                "No {} found. Run 'depot install' first.",
+                crate::package::lockfile::LOCKFILE_NAME
+            ))
+        })
+    }
+
+    /// Run a security audit against a local advisory database only
+    ///
+    /// Uses `db_path` when given, otherwise the configured `advisory_db`.
+    /// No network requests are made.
+    pub fn audit_project_offline(
+        project_root: &Path,
+        db_path: Option<&Path>,
+        config: &dyn ConfigProvider,
+    ) -> DepotResult<VulnerabilityReport> {
+        Self::audit_lockfile_offline(&Self::load_lockfile(project_root)?, db_path, config)
+    }
+
+    /// Audit a lockfile that may not be saved yet against a local advisory database
+    pub fn audit_lockfile_offline(
+        lockfile: &Lockfile,
+        db_path: Option<&Path>,
+        config: &dyn ConfigProvider,
+    ) -> DepotResult<VulnerabilityReport> {
+        let advisory_db = match db_path {
+            Some(path) => AdvisoryDatabase::load_from(path)?,
+            None => {
+                let configured = config.advisory_db().ok_or_else(|| {
+                    DepotError::Config(
+                        "No advisory database configured. Set advisory_db in the config or pass --db <PATH>"
+                            .to_string(),
+                    )
+                })?;
+                AdvisoryDatabase::load_from(configured)?
+            }
+        };
+
+        Ok(Self::with_database(advisory_db).audit(lockfile))
     }
 
     /// Run a security audit with OSV integration
-    pub async fn audit_project_with_osv(project_root: &Path) -> DepotResult<VulnerabilityReport> {
-        let lockfile =
-            crate::package::lockfile::Lockfile::load(project_root)?.ok_or_else(|| {
-                DepotError::Package(format!(
-                    "No {} found. Run 'depot install' first.",
-                    crate::package::lockfile::LOCKFILE_NAME
-                ))
-            })?;
+    pub async fn audit_project_with_osv(
+        project_root: &Path,
+        config: &dyn ConfigProvider,
+    ) -> DepotResult<VulnerabilityReport> {
+        Self::audit_lockfile_with_osv(&Self::load_lockfile(project_root)?, config).await
+    }
 
+    /// Audit a lockfile that may not be saved yet, with OSV integration
+    pub async fn audit_lockfile_with_osv(
+        lockfile: &Lockfile,
+        config: &dyn ConfigProvider,
+    ) -> DepotResult<VulnerabilityReport> {
         let packages: Vec<(String, String)> = lockfile
             .packages
             .iter()
             .map(|(name, info)| (name.clone(), info.version.clone()))
             .collect();
 
-        let osv = OsvApi::new();
-        let auditor = Self::new_with_osv(&osv, &packages).await?;
-        auditor.audit(project_root)
+        let cache_dir = config.cache_dir()?.join("osv");
+        let osv = OsvApi::new().with_cache(OsvCache::new(
+            cache_dir,
+            chrono::Duration::hours(config.osv_cache_ttl_hours()),
+        ));
+        let auditor = Self::new_with_osv(config, &osv, &packages).await?;
+        Ok(auditor.audit(lockfile))
     }
 
     /// Perform security audit
-    fn audit(&self, project_root: &Path) -> DepotResult<VulnerabilityReport> {
-        let lockfile = Lockfile::load(project_root)?.ok_or_else(|| {
-            DepotError::Package(format!(
-                "No {} found. Run 'depot install' first.",
-                crate::package::lockfile::LOCKFILE_NAME
-            ))
-        })?;
-
+    fn audit(&self, lockfile: &Lockfile) -> VulnerabilityReport {
         let mut report = VulnerabilityReport::new();
         report.package_count = lockfile.packages.len();
 
@@ -90,7 +151,7 @@ impl SecurityAuditor {
             }
         }
 
-        Ok(report)
+        report
     }
 
     /// Check a specific package for vulnerabilities
@@ -113,6 +174,7 @@ pub fn format_report(report: &VulnerabilityReport) -> String {
     if report.is_empty() {
         let _ = writeln!(output, "✓ No known vulnerabilities found");
         let _ = writeln!(output, "  Checked {} package(s)", report.checked_packages);
+        write_ignored(&mut output, report);
         return output;
     }
 
@@ -193,6 +255,11 @@ pub fn format_report(report: &VulnerabilityReport) -> String {
         let _ = writeln!(output);
     }
 
+    if !report.ignored.is_empty() {
+        write_ignored(&mut output, report);
+        let _ = writeln!(output);
+    }
+
     // Recommendations
     let _ = writeln!(output, "Recommendations:");
     if report.has_critical() || report.has_high() {
@@ -210,9 +277,39 @@ pub fn format_report(report: &VulnerabilityReport) -> String {
     output
 }
 
+/// List findings suppressed by the audit ignore list
+fn write_ignored(output: &mut String, report: &VulnerabilityReport) {
+    use std::fmt::Write;
+
+    if report.ignored.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(output);
+    let _ = writeln!(output, "Ignored: {} finding(s)", report.ignored.len());
+    for ignored in &report.ignored {
+        let vuln = &ignored.vulnerability;
+        let id = vuln
+            .id
+            .as_deref()
+            .or(vuln.cve.as_deref())
+            .unwrap_or(&vuln.title);
+        let _ = writeln!(
+            output,
+            "  - {} {} ({}, until {}): {}",
+            vuln.package,
+            id,
+            vuln.severity.as_str(),
+            ignored.expires,
+            ignored.reason
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::di::mocks::MockConfigProvider;
     use crate::security::advisory::AdvisoryDatabase;
     use crate::security::osv::OsvApi;
     use crate::security::vulnerability::{Severity, Vulnerability};
@@ -239,6 +336,8 @@ mod tests {
             title: "Test Vulnerability".to_string(),
             description: "A test vulnerability".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: vec!["https://example.com/advisory".to_string()],
         };
         report.add(vuln);
@@ -262,6 +361,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         db.add_advisory(vuln);
@@ -286,6 +387,8 @@ mod tests {
             title: "Test 1".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         let vuln2 = Vulnerability {
@@ -296,6 +399,8 @@ mod tests {
             title: "Test 2".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("1.5.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         db.add_advisory(vuln1);
@@ -328,6 +433,8 @@ mod tests {
             title: "Critical".to_string(),
             description: "Critical".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         let high = Vulnerability {
@@ -338,6 +445,8 @@ mod tests {
             title: "High".to_string(),
             description: "High".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         let medium = Vulnerability {
@@ -348,6 +457,8 @@ mod tests {
             title: "Medium".to_string(),
             description: "Medium".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -375,6 +486,8 @@ mod tests {
             title: "Test Vulnerability".to_string(),
             description: "A test vulnerability".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: vec![
                 "https://example.com/advisory".to_string(),
                 "https://example.com/cve".to_string(),
@@ -402,6 +515,8 @@ mod tests {
             title: "Test Vulnerability".to_string(),
             description: "A test vulnerability".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         report.add(vuln);
@@ -424,6 +539,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         report.add(vuln);
@@ -447,6 +564,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
         report.add(vuln);
@@ -460,7 +579,9 @@ mod tests {
     async fn test_audit_project_with_osv_no_lockfile() {
         let temp = tempfile::TempDir::new().unwrap();
         // No lockfile
-        let result = SecurityAuditor::audit_project_with_osv(temp.path()).await;
+        let result =
+            SecurityAuditor::audit_project_with_osv(temp.path(), &MockConfigProvider::default())
+                .await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No depot.lock"));
     }
@@ -468,7 +589,7 @@ mod tests {
     #[tokio::test]
     async fn test_new_with_osv_empty_packages() {
         let osv = OsvApi::new();
-        let result = SecurityAuditor::new_with_osv(&osv, &[]).await;
+        let result = SecurityAuditor::new_with_osv(&MockConfigProvider::default(), &osv, &[]).await;
         assert!(result.is_ok());
         let auditor = result.unwrap();
         let advisories = auditor.get_advisories("nonexistent");
@@ -479,7 +600,8 @@ mod tests {
     async fn test_new_with_osv_with_packages() {
         let osv = OsvApi::new();
         let packages = vec![("test-package".to_string(), "1.0.0".to_string())];
-        let result = SecurityAuditor::new_with_osv(&osv, &packages).await;
+        let result =
+            SecurityAuditor::new_with_osv(&MockConfigProvider::default(), &osv, &packages).await;
         // May succeed or fail depending on network, but tests the path
         let _ = result;
     }
@@ -487,7 +609,7 @@ mod tests {
     #[test]
     fn test_audit_project_no_lockfile() {
         let temp = tempfile::TempDir::new().unwrap();
-        let result = SecurityAuditor::audit_project(temp.path());
+        let result = SecurityAuditor::audit_project(temp.path(), &MockConfigProvider::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No depot.lock"));
     }
@@ -499,11 +621,66 @@ mod tests {
         let lockfile = Lockfile::new();
         lockfile.save(temp.path()).unwrap();
 
-        let auditor = SecurityAuditor::new().unwrap();
-        let result = auditor.audit(temp.path());
-        assert!(result.is_ok());
-        let report = result.unwrap();
+        let auditor = SecurityAuditor::new(&MockConfigProvider::default()).unwrap();
+        let report = auditor.audit(&Lockfile::load(temp.path()).unwrap().unwrap());
         assert!(report.is_empty());
         assert_eq!(report.package_count, 0);
     }
+
+    #[test]
+    fn test_audit_project_offline_with_db() {
+        use crate::package::lockfile::LockedPackage;
+        use std::collections::HashMap;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/json".to_string(),
+            LockedPackage {
+                version: "v1.0.0".to_string(),
+                repository: "owner/json".to_string(),
+                ref_type: "release".to_string(),
+                ref_value: "v1.0.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: String::new(),
+                checksum: "blake3:abc".to_string(),
+                size: 0,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id: None,
+            },
+        );
+        lockfile.save(temp.path()).unwrap();
+
+        let db_dir = temp.path().join("advisories");
+        std::fs::create_dir_all(&db_dir).unwrap();
+        std::fs::write(
+            db_dir.join("INT-1.json"),
+            serde_json::json!({
+                "id": "INT-1",
+                "summary": "Decoder overflow",
+                "affected": [{
+                    "package": {"ecosystem": "Lua", "name": "owner/json"},
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{"introduced": "0"}, {"fixed": "1.1.0"}]
+                    }]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let report = SecurityAuditor::audit_project_offline(
+            temp.path(),
+            Some(&db_dir),
+            &MockConfigProvider::default(),
+        )
+        .unwrap();
+        assert_eq!(report.vulnerabilities.len(), 1);
+        assert_eq!(report.vulnerabilities[0].title, "Decoder overflow");
+    }
 }
@@ -0,0 +1,421 @@
+//! Fix planning for `depot audit fix`
+//!
+//! For every vulnerable locked package, finds the smallest published release
+//! that none of its reported advisories affect, then decides whether the
+//! manifest constraint can be set to its tag or the package cannot be fixed
+//! from this project.
+
+use crate::core::version::{parse_compound_constraint, Version};
+use crate::di::GitHubProvider;
+use crate::package::lockfile::Lockfile;
+use crate::package::manifest::PackageManifest;
+use crate::package::upgrade::{parse_tag, rewrite_constraint, AvailableRelease};
+use crate::resolver::dependency_resolver::parse_repository;
+use crate::security::vulnerability::{Vulnerability, VulnerabilityReport};
+use std::collections::BTreeMap;
+
+/// What `depot audit fix` will do for one vulnerable package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixAction {
+    /// Upgrade a direct dependency, setting its manifest constraint to `constraint`
+    /// (the release tag, which the resolver can look up)
+    Upgrade {
+        package: String,
+        dev: bool,
+        from: String,
+        to: Version,
+        /// Release tag of `to`
+        tag: String,
+        constraint: String,
+        /// Whether the fix crosses a major version
+        major: bool,
+        advisories: Vec<String>,
+    },
+    /// Transitive dependency whose parents already allow the fixed version
+    Reresolve {
+        package: String,
+        from: String,
+        to: Version,
+        /// Release tag of `to`, pinned when the lockfile is rebuilt
+        tag: String,
+        advisories: Vec<String>,
+    },
+    /// No upgrade can be applied
+    Unfixable {
+        package: String,
+        from: String,
+        reason: String,
+        advisories: Vec<String>,
+    },
+}
+
+impl FixAction {
+    pub fn package(&self) -> &str {
+        match self {
+            FixAction::Upgrade { package, .. }
+            | FixAction::Reresolve { package, .. }
+            | FixAction::Unfixable { package, .. } => package,
+        }
+    }
+
+    pub fn is_fixable(&self) -> bool {
+        !matches!(self, FixAction::Unfixable { .. })
+    }
+}
+
+/// Decide how to fix each vulnerable package in `report`
+///
+/// Only releases newer than the locked version are considered; drafts and
+/// prereleases are skipped. Fixes within the same major version may rewrite
+/// a constraint that excludes them, major upgrades need `allow_major`.
+pub async fn plan_fixes(
+    github: &dyn GitHubProvider,
+    report: &VulnerabilityReport,
+    manifest: &PackageManifest,
+    lockfile: &Lockfile,
+    allow_major: bool,
+) -> Vec<FixAction> {
+    let mut by_package: BTreeMap<&str, Vec<&Vulnerability>> = BTreeMap::new();
+    for vuln in &report.vulnerabilities {
+        by_package.entry(&vuln.package).or_default().push(vuln);
+    }
+
+    let mut actions = Vec::new();
+    for (package, vulns) in by_package {
+        actions.push(plan_package(github, package, &vulns, manifest, lockfile, allow_major).await);
+    }
+    actions
+}
+
+async fn plan_package(
+    github: &dyn GitHubProvider,
+    package: &str,
+    vulns: &[&Vulnerability],
+    manifest: &PackageManifest,
+    lockfile: &Lockfile,
+    allow_major: bool,
+) -> FixAction {
+    let advisories: Vec<String> = vulns.iter().map(|v| advisory_id(v)).collect();
+    let from = lockfile
+        .get_package(package)
+        .map(|pkg| pkg.version.clone())
+        .unwrap_or_else(|| "unknown".to_string());
+    let unfixable = |reason: String| FixAction::Unfixable {
+        package: package.to_string(),
+        from: from.clone(),
+        reason,
+        advisories: advisories.clone(),
+    };
+
+    let Some(current) = parse_tag(&from) else {
+        return unfixable(format!(
+            "locked to '{}', which is not a release version",
+            from
+        ));
+    };
+
+    let (target, tag) = match smallest_fix(github, package, &current, vulns).await {
+        Ok(Some(fix)) => fix,
+        Ok(None) => return unfixable("no published release clears the advisory".to_string()),
+        Err(e) => return unfixable(format!("failed to fetch releases: {}", e)),
+    };
+    let major = target.major > current.major;
+    let release = AvailableRelease {
+        version: target.clone(),
+        tag: tag.clone(),
+        body: None,
+    };
+
+    let direct = manifest
+        .dependencies
+        .get(package)
+        .map(|c| (c, false))
+        .or_else(|| manifest.dev_dependencies.get(package).map(|c| (c, true)));
+
+    if let Some((constraint, dev)) = direct {
+        let allowed = parse_compound_constraint(constraint).is_ok_and(|c| target.satisfies(&c));
+        let new_constraint = if allowed {
+            tag.clone()
+        } else if major && !allow_major {
+            return unfixable(format!(
+                "fixed in {}, outside '{}' (use --allow-major to rewrite the constraint)",
+                target, constraint
+            ));
+        } else {
+            match rewrite_constraint(constraint, &release) {
+                Some(rewritten) => rewritten,
+                None => {
+                    return unfixable(format!(
+                        "fixed in {}, but '{}' cannot be rewritten without losing its bounds",
+                        target, constraint
+                    ))
+                }
+            }
+        };
+
+        return FixAction::Upgrade {
+            package: package.to_string(),
+            dev,
+            from,
+            to: target,
+            tag,
+            constraint: new_constraint,
+            major,
+            advisories,
+        };
+    }
+
+    // Transitive: every parent must already allow the fixed version
+    let mut parents: Vec<(&String, &String)> = lockfile
+        .packages
+        .iter()
+        .filter_map(|(name, pkg)| pkg.dependencies.get(package).map(|c| (name, c)))
+        .collect();
+    parents.sort();
+
+    if parents.is_empty() {
+        return unfixable("not a dependency of this project or of any locked package".to_string());
+    }
+    if let Some((parent, constraint)) = parents.iter().find(|(_, constraint)| {
+        !parse_compound_constraint(constraint).is_ok_and(|c| target.satisfies(&c))
+    }) {
+        return unfixable(format!(
+            "fixed in {}, but {} requires '{}'",
+            target, parent, constraint
+        ));
+    }
+
+    FixAction::Reresolve {
+        package: package.to_string(),
+        from,
+        to: target,
+        tag,
+        advisories,
+    }
+}
+
+/// Smallest release newer than `current` that none of `vulns` affect, with its tag
+async fn smallest_fix(
+    github: &dyn GitHubProvider,
+    package: &str,
+    current: &Version,
+    vulns: &[&Vulnerability],
+) -> crate::core::DepotResult<Option<(Version, String)>> {
+    let (owner, repo) = parse_repository(package)?;
+    let releases = github.get_releases(&owner, &repo).await?;
+
+    let mut versions: Vec<(Version, String)> = releases
+        .into_iter()
+        .filter(|r| !r.draft && !r.prerelease)
+        .filter_map(|r| parse_tag(&r.tag_name).map(|v| (v, r.tag_name)))
+        .filter(|(v, _)| v > current)
+        .collect();
+    versions.sort();
+
+    Ok(versions.into_iter().find(|(v, _)| {
+        vulns
+            .iter()
+            .all(|vuln| !vuln.affects_version(&v.to_string()))
+    }))
+}
+
+fn advisory_id(vuln: &Vulnerability) -> String {
+    vuln.id
+        .clone()
+        .or_else(|| vuln.cve.clone())
+        .unwrap_or_else(|| vuln.title.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::types::GitHubRelease;
+    use crate::package::lockfile::LockedPackage;
+    use crate::security::vulnerability::Severity;
+
+    fn release(tag: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: None,
+            published_at: None,
+        }
+    }
+
+    fn locked(version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: version.to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn vuln(package: &str, affected: &str, id: &str) -> Vulnerability {
+        Vulnerability {
+            package: package.to_string(),
+            affected_versions: affected.to_string(),
+            severity: Severity::High,
+            cve: None,
+            id: Some(id.to_string()),
+            aliases: Vec::new(),
+            title: "Test".to_string(),
+            description: "Test".to_string(),
+            fixed_in: None,
+            references: Vec::new(),
+        }
+    }
+
+    fn setup(constraint: &str, tags: &[&str]) -> (MockGitHubProvider, PackageManifest, Lockfile) {
+        let github = MockGitHubProvider::new();
+        for tag in tags {
+            github.add_release("owner", "json", release(tag));
+        }
+
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/json".to_string(), constraint.to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("owner/json".to_string(), locked("v1.0.0", &[]));
+
+        (github, manifest, lockfile)
+    }
+
+    fn report(vulns: Vec<Vulnerability>) -> VulnerabilityReport {
+        let mut report = VulnerabilityReport::new();
+        for v in vulns {
+            report.add(v);
+        }
+        report
+    }
+
+    #[tokio::test]
+    async fn test_plan_picks_smallest_fixing_release() {
+        let (github, manifest, lockfile) = setup(
+            "^1.0.0",
+            &["v1.0.0", "v1.0.1", "v1.1.0", "v1.2.0", "v2.0.0"],
+        );
+        let report = report(vec![
+            vuln("owner/json", "<1.0.2", "GHSA-1"),
+            vuln("owner/json", ">=1.0.0, <1.1.0", "GHSA-2"),
+        ]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, false).await;
+        assert_eq!(
+            actions,
+            vec![FixAction::Upgrade {
+                package: "owner/json".to_string(),
+                dev: false,
+                from: "v1.0.0".to_string(),
+                to: Version::new(1, 1, 0),
+                tag: "v1.1.0".to_string(),
+                constraint: "v1.1.0".to_string(),
+                major: false,
+                advisories: vec!["GHSA-1".to_string(), "GHSA-2".to_string()],
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_major_upgrade_requires_flag() {
+        let (github, manifest, lockfile) = setup("^1.0.0", &["v1.0.0", "v1.0.1", "v2.0.0"]);
+        let report = report(vec![vuln("owner/json", "<2.0.0", "GHSA-1")]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, false).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Unfixable { reason, .. } if reason.contains("--allow-major")
+        ));
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, true).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Upgrade { constraint, major: true, .. } if constraint == "v2.0.0"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_allowed_fix_writes_tag() {
+        let (github, manifest, lockfile) = setup(">=1.0.0, <2.0.0", &["v1.0.0", "v1.0.1"]);
+        let report = report(vec![vuln("owner/json", "<1.0.1", "GHSA-1")]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, false).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Upgrade { constraint, .. } if constraint == "v1.0.1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_rewrites_pin_within_major() {
+        let (github, manifest, lockfile) = setup("1.0.0", &["v1.0.0", "v1.0.1"]);
+        let report = report(vec![vuln("owner/json", "<1.0.1", "GHSA-1")]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, false).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Upgrade { constraint, major: false, .. } if constraint == "v1.0.1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_no_fixed_release() {
+        let (github, manifest, lockfile) = setup("^1.0.0", &["v1.0.0", "v1.0.1"]);
+        let report = report(vec![vuln("owner/json", ">=0.0.0", "GHSA-1")]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, true).await;
+        assert!(!actions[0].is_fixable());
+    }
+
+    #[tokio::test]
+    async fn test_plan_transitive_dependency() {
+        let (github, _, mut lockfile) = setup("^1.0.0", &["v1.0.0", "v1.3.0"]);
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/web".to_string(), "^2.0.0".to_string());
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked("v2.0.0", &[("owner/json", "^1.0.0")]),
+        );
+        let report = report(vec![vuln("owner/json", "<1.3.0", "GHSA-1")]);
+
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, false).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Reresolve { to, tag, .. }
+                if *to == Version::new(1, 3, 0) && tag == "v1.3.0"
+        ));
+
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked("v2.0.0", &[("owner/json", "~1.0.0")]),
+        );
+        let actions = plan_fixes(&github, &report, &manifest, &lockfile, true).await;
+        assert!(matches!(
+            &actions[0],
+            FixAction::Unfixable { reason, .. } if reason.contains("owner/web requires '~1.0.0'")
+        ));
+    }
+}
@@ -0,0 +1,369 @@
+//! Repository health checks for locked dependencies
+//!
+//! Vulnerability advisories only cover known flaws. These checks look for
+//! dependencies that are likely abandoned: deleted, archived or disabled
+//! repositories, no commits or releases for a long time, packages pinned to a branch, and
+//! locked commits the default branch has long since moved past.
+
+use crate::core::{DepotError, DepotResult};
+use crate::di::traits::GitHubProvider;
+use crate::package::lockfile::{LockedPackage, Lockfile};
+use crate::resolver::dependency_resolver::parse_repository;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+
+/// Months without commits or releases before a repository is reported as stale
+pub const DEFAULT_STALE_MONTHS: u32 = 12;
+
+/// Commits the default branch may be ahead of the locked commit
+pub const MAX_COMMITS_BEHIND: u64 = 100;
+
+/// A sign that a dependency may be unmaintained
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HealthIssue {
+    /// Repository was deleted, or made private, since the package was locked
+    Missing,
+    /// Repository is archived and read-only
+    Archived,
+    /// Repository was disabled by GitHub
+    Disabled,
+    /// Nothing was pushed since `last_push`
+    NoRecentCommits { last_push: String },
+    /// Nothing was released since `last_release`
+    NoRecentReleases { last_release: String },
+    /// Locked to a branch instead of a release, tag or commit
+    BranchPinned { branch: String },
+    /// Default branch is this many commits ahead of the locked commit
+    FarBehind { branch: String, commits: u64 },
+}
+
+impl HealthIssue {
+    /// Whether the repository can no longer receive fixes at all
+    pub fn is_abandoned(&self) -> bool {
+        matches!(
+            self,
+            HealthIssue::Missing | HealthIssue::Archived | HealthIssue::Disabled
+        )
+    }
+}
+
+impl fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthIssue::Missing => write!(f, "repository no longer exists"),
+            HealthIssue::Archived => write!(f, "repository is archived"),
+            HealthIssue::Disabled => write!(f, "repository is disabled"),
+            HealthIssue::NoRecentCommits { last_push } => {
+                write!(f, "no commits since {}", date_only(last_push))
+            }
+            HealthIssue::NoRecentReleases { last_release } => {
+                write!(f, "no releases since {}", date_only(last_release))
+            }
+            HealthIssue::BranchPinned { branch } => {
+                write!(f, "pinned to branch '{}' instead of a tag", branch)
+            }
+            HealthIssue::FarBehind { branch, commits } => write!(
+                f,
+                "{} is {} commits ahead of the locked commit",
+                branch, commits
+            ),
+        }
+    }
+}
+
+/// Whether GitHub answered 404 Not Found or 410 Gone for the repository
+fn is_missing(error: &DepotError) -> bool {
+    let message = error.to_string();
+    message.contains("HTTP 404") || message.contains("HTTP 410")
+}
+
+fn date_only(timestamp: &str) -> &str {
+    timestamp.split('T').next().unwrap_or(timestamp)
+}
+
+/// Health of one locked package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageHealth {
+    pub package: String,
+    pub issues: Vec<HealthIssue>,
+}
+
+/// Check one locked package's repository
+///
+/// The last push and the last release are each reported when they are more
+/// than `stale_months` before `now`.
+pub async fn check_package(
+    github: &dyn GitHubProvider,
+    locked: &LockedPackage,
+    stale_months: u32,
+    now: DateTime<Utc>,
+) -> DepotResult<Vec<HealthIssue>> {
+    let (owner, repo) = parse_repository(&locked.repository)?;
+    let info = match github.get_repo(&owner, &repo).await {
+        Ok(info) => info,
+        Err(e) if is_missing(&e) => return Ok(vec![HealthIssue::Missing]),
+        Err(e) => return Err(e),
+    };
+    let cutoff = now - chrono::Duration::days(30 * i64::from(stale_months));
+    let is_stale = |timestamp: &str| {
+        DateTime::parse_from_rfc3339(timestamp).is_ok_and(|t| t.with_timezone(&Utc) < cutoff)
+    };
+
+    let mut issues = Vec::new();
+    if info.archived {
+        issues.push(HealthIssue::Archived);
+    }
+    if info.disabled {
+        issues.push(HealthIssue::Disabled);
+    }
+    if let Some(pushed_at) = info.pushed_at.as_deref().filter(|p| is_stale(p)) {
+        issues.push(HealthIssue::NoRecentCommits {
+            last_push: pushed_at.to_string(),
+        });
+    }
+
+    // Repositories that never made a release are judged by commits alone
+    let latest_release = github
+        .get_releases(&owner, &repo)
+        .await?
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| r.published_at)
+        .max();
+    if let Some(published_at) = latest_release.filter(|p| is_stale(p)) {
+        issues.push(HealthIssue::NoRecentReleases {
+            last_release: published_at,
+        });
+    }
+
+    if locked.ref_type.eq_ignore_ascii_case("branch") {
+        issues.push(HealthIssue::BranchPinned {
+            branch: locked.ref_value.clone(),
+        });
+    }
+
+    // A locked commit that is not on the default branch cannot be compared
+    if !locked.commit_sha.is_empty() {
+        if let Ok(comparison) = github
+            .compare_commits(&owner, &repo, &locked.commit_sha, &info.default_branch)
+            .await
+        {
+            if comparison.ahead_by > MAX_COMMITS_BEHIND {
+                issues.push(HealthIssue::FarBehind {
+                    branch: info.default_branch.clone(),
+                    commits: comparison.ahead_by,
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Check every package in the lockfile, sorted by name
+pub async fn check_lockfile(
+    github: &dyn GitHubProvider,
+    lockfile: &Lockfile,
+    stale_months: u32,
+) -> DepotResult<Vec<PackageHealth>> {
+    let mut names: Vec<&String> = lockfile.packages.keys().collect();
+    names.sort();
+
+    let now = Utc::now();
+    let mut results = Vec::new();
+    for name in names {
+        let issues = check_package(github, &lockfile.packages[name], stale_months, now).await?;
+        results.push(PackageHealth {
+            package: name.clone(),
+            issues,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::{GitHubComparison, GitHubRelease, GitHubRepo};
+    use std::collections::HashMap;
+
+    fn locked(ref_type: &str, ref_value: &str) -> LockedPackage {
+        LockedPackage {
+            version: "1.0.0".to_string(),
+            repository: "owner/lib".to_string(),
+            ref_type: ref_type.to_string(),
+            ref_value: ref_value.to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:00".to_string(),
+            size: 1,
+            dependencies: HashMap::new(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn release(tag: &str, published_at: &str) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: None,
+            published_at: Some(published_at.to_string()),
+        }
+    }
+
+    fn comparison(ahead_by: u64) -> GitHubComparison {
+        GitHubComparison {
+            html_url: String::new(),
+            status: "ahead".to_string(),
+            ahead_by,
+            total_commits: ahead_by,
+            commits: Vec::new(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-06-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[tokio::test]
+    async fn test_healthy_package() {
+        let github = MockGitHubProvider::new();
+        github.add_repo(
+            "owner",
+            "lib",
+            GitHubRepo {
+                id: 1,
+                name: "lib".to_string(),
+                full_name: "owner/lib".to_string(),
+                default_branch: "main".to_string(),
+                description: None,
+                created_at: None,
+                pushed_at: Some("2026-05-20T10:00:00Z".to_string()),
+                archived: false,
+                disabled: false,
+                fork: false,
+                stargazers_count: 0,
+                parent: None,
+            },
+        );
+        github.add_release("owner", "lib", release("v1.0.0", "2026-01-10T00:00:00Z"));
+        github.add_comparison("owner", "lib", "abc123", "main", comparison(12));
+
+        let issues = check_package(&github, &locked("Release", "v1.0.0"), 12, now())
+            .await
+            .unwrap();
+        assert!(issues.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_package() {
+        let github = MockGitHubProvider::new();
+        github.add_repo(
+            "owner",
+            "lib",
+            GitHubRepo {
+                id: 1,
+                name: "lib".to_string(),
+                full_name: "owner/lib".to_string(),
+                default_branch: "master".to_string(),
+                description: None,
+                created_at: None,
+                pushed_at: Some("2023-02-01T10:00:00Z".to_string()),
+                archived: true,
+                disabled: false,
+                fork: false,
+                stargazers_count: 0,
+                parent: None,
+            },
+        );
+        github.add_release("owner", "lib", release("v0.9.0", "2022-03-01T00:00:00Z"));
+        github.add_release("owner", "lib", release("v1.0.0", "2022-11-05T00:00:00Z"));
+        github.add_comparison("owner", "lib", "abc123", "master", comparison(250));
+
+        let issues = check_package(&github, &locked("Branch", "master"), 12, now())
+            .await
+            .unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                HealthIssue::Archived,
+                HealthIssue::NoRecentCommits {
+                    last_push: "2023-02-01T10:00:00Z".to_string()
+                },
+                HealthIssue::NoRecentReleases {
+                    last_release: "2022-11-05T00:00:00Z".to_string()
+                },
+                HealthIssue::BranchPinned {
+                    branch: "master".to_string()
+                },
+                HealthIssue::FarBehind {
+                    branch: "master".to_string(),
+                    commits: 250
+                },
+            ]
+        );
+        assert!(issues[0].is_abandoned());
+        assert_eq!(issues[1].to_string(), "no commits since 2023-02-01");
+        assert_eq!(
+            issues[4].to_string(),
+            "master is 250 commits ahead of the locked commit"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_lockfile_sorted() {
+        let github = MockGitHubProvider::new();
+        let mut lockfile = Lockfile::new();
+        let mut other = locked("Tag", "v2.0.0");
+        other.repository = "owner/other".to_string();
+        lockfile.add_package("owner/other".to_string(), other);
+        lockfile.add_package("owner/lib".to_string(), locked("Release", "v1.0.0"));
+
+        let results = check_lockfile(&github, &lockfile, DEFAULT_STALE_MONTHS)
+            .await
+            .unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.package.as_str()).collect();
+        assert_eq!(names, vec!["owner/lib", "owner/other"]);
+        assert!(results.iter().all(|r| r.issues.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_repository_is_reported() {
+        let github = MockGitHubProvider::new();
+        github.add_missing_repo("owner", "gone");
+        let mut lockfile = Lockfile::new();
+        let mut gone = locked("Release", "v1.0.0");
+        gone.repository = "owner/gone".to_string();
+        lockfile.add_package("owner/gone".to_string(), gone);
+        lockfile.add_package("owner/lib".to_string(), locked("Release", "v1.0.0"));
+
+        // The remaining packages are still checked
+        let results = check_lockfile(&github, &lockfile, DEFAULT_STALE_MONTHS)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].issues, vec![HealthIssue::Missing]);
+        assert!(results[0].issues[0].is_abandoned());
+        assert!(results[1].issues.is_empty());
+
+        // Other errors, such as rate limits, still fail the check
+        let limited = MockGitHubProvider::new().with_rate_limit();
+        assert!(check_lockfile(&limited, &lockfile, DEFAULT_STALE_MONTHS)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,294 @@
+//! Audit ignore list
+//!
+//! Entries come from `audit.ignore` in package.yaml and from
+//! `.depot/audit.yaml`. Every entry needs a reason and an expiry date;
+//! matching findings are reported as ignored until the entry expires, after
+//! which they fail the audit again.
+
+use crate::core::{DepotError, DepotResult};
+use crate::package::manifest::{AuditConfig, AuditIgnoreEntry, PackageManifest};
+use crate::security::vulnerability::{IgnoredVulnerability, Vulnerability, VulnerabilityReport};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::Path;
+
+/// Project-local audit settings file, relative to the project root
+pub const AUDIT_CONFIG_FILE: &str = ".depot/audit.yaml";
+
+/// A validated ignore entry
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    pub id: String,
+    pub package: Option<String>,
+    pub reason: String,
+    pub expires: NaiveDate,
+}
+
+impl IgnoreRule {
+    fn from_entry(entry: &AuditIgnoreEntry, source: &str) -> DepotResult<Self> {
+        if entry.id.trim().is_empty() {
+            return Err(DepotError::Config(format!(
+                "Audit ignore entry in {} is missing an advisory id",
+                source
+            )));
+        }
+        if entry.reason.trim().is_empty() {
+            return Err(DepotError::Config(format!(
+                "Audit ignore entry '{}' in {} must give a reason",
+                entry.id, source
+            )));
+        }
+        let expires =
+            NaiveDate::parse_from_str(entry.expires.trim(), "%Y-%m-%d").map_err(|_| {
+                DepotError::Config(format!(
+                    "Audit ignore entry '{}' in {} has invalid expires '{}' (expected YYYY-MM-DD)",
+                    entry.id, source, entry.expires
+                ))
+            })?;
+
+        Ok(Self {
+            id: entry.id.trim().to_string(),
+            package: entry.package.clone(),
+            reason: entry.reason.trim().to_string(),
+            expires,
+        })
+    }
+
+    /// Whether this rule names the given advisory (by ID, CVE or alias) and package
+    pub fn matches(&self, vuln: &Vulnerability) -> bool {
+        if self.package.as_ref().is_some_and(|p| *p != vuln.package) {
+            return false;
+        }
+        vuln.id
+            .iter()
+            .chain(vuln.cve.iter())
+            .chain(vuln.aliases.iter())
+            .any(|id| id.eq_ignore_ascii_case(&self.id))
+    }
+
+    /// Whether the rule has expired (it still applies on its expiry date)
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        today > self.expires
+    }
+}
+
+/// Ignore rules for a project
+#[derive(Debug, Clone, Default)]
+pub struct AuditIgnoreList {
+    rules: Vec<IgnoreRule>,
+}
+
+impl AuditIgnoreList {
+    /// Load rules from the manifest's `audit.ignore` and `.depot/audit.yaml`
+    pub fn load(project_root: &Path, manifest: Option<&PackageManifest>) -> DepotResult<Self> {
+        let mut list = Self::default();
+
+        if let Some(audit) = manifest.and_then(|m| m.audit.as_ref()) {
+            list.extend(&audit.ignore, "package.yaml")?;
+        }
+
+        let path = project_root.join(AUDIT_CONFIG_FILE);
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let config: AuditConfig = serde_yaml::from_str(&content).map_err(|e| {
+                DepotError::Config(format!("Failed to parse {}: {}", AUDIT_CONFIG_FILE, e))
+            })?;
+            list.extend(&config.ignore, AUDIT_CONFIG_FILE)?;
+        }
+
+        Ok(list)
+    }
+
+    /// Build a list from ignore entries, validating each one
+    pub fn from_entries(entries: &[AuditIgnoreEntry], source: &str) -> DepotResult<Self> {
+        let mut list = Self::default();
+        list.extend(entries, source)?;
+        Ok(list)
+    }
+
+    fn extend(&mut self, entries: &[AuditIgnoreEntry], source: &str) -> DepotResult<()> {
+        for entry in entries {
+            self.rules.push(IgnoreRule::from_entry(entry, source)?);
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rules(&self) -> &[IgnoreRule] {
+        &self.rules
+    }
+
+    /// Move findings covered by an active rule into `report.ignored`
+    ///
+    /// Returns the expired rules that matched a finding; those findings stay
+    /// in the report.
+    pub fn apply(&self, report: &mut VulnerabilityReport, today: NaiveDate) -> Vec<IgnoreRule> {
+        let mut expired: Vec<IgnoreRule> = Vec::new();
+        let mut remaining = Vec::new();
+
+        for vuln in std::mem::take(&mut report.vulnerabilities) {
+            let matching: Vec<&IgnoreRule> =
+                self.rules.iter().filter(|r| r.matches(&vuln)).collect();
+
+            match matching.iter().find(|r| !r.is_expired(today)) {
+                Some(rule) => report.ignored.push(IgnoredVulnerability {
+                    vulnerability: vuln,
+                    reason: rule.reason.clone(),
+                    expires: rule.expires.to_string(),
+                }),
+                None => {
+                    for rule in matching {
+                        if !expired
+                            .iter()
+                            .any(|e| e.id == rule.id && e.package == rule.package)
+                        {
+                            expired.push(rule.clone());
+                        }
+                    }
+                    remaining.push(vuln);
+                }
+            }
+        }
+
+        report.vulnerabilities = remaining;
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::vulnerability::Severity;
+    use tempfile::TempDir;
+
+    fn entry(id: &str, package: Option<&str>, reason: &str, expires: &str) -> AuditIgnoreEntry {
+        AuditIgnoreEntry {
+            id: id.to_string(),
+            package: package.map(String::from),
+            reason: reason.to_string(),
+            expires: expires.to_string(),
+        }
+    }
+
+    fn vuln(package: &str, id: &str) -> Vulnerability {
+        Vulnerability {
+            package: package.to_string(),
+            affected_versions: "<2.0.0".to_string(),
+            severity: Severity::High,
+            cve: Some("CVE-2024-0001".to_string()),
+            id: Some(id.to_string()),
+            aliases: vec!["CVE-2024-0001".to_string()],
+            title: "Test".to_string(),
+            description: "Test".to_string(),
+            fixed_in: Some("2.0.0".to_string()),
+            references: Vec::new(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_entry_requires_reason() {
+        let result = AuditIgnoreList::from_entries(
+            &[entry("GHSA-1", None, "  ", "2030-01-01")],
+            "package.yaml",
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("must give a reason"));
+    }
+
+    #[test]
+    fn test_entry_requires_valid_expiry() {
+        let result = AuditIgnoreList::from_entries(
+            &[entry("GHSA-1", None, "Not reachable", "next year")],
+            "package.yaml",
+        );
+        assert!(result.unwrap_err().to_string().contains("invalid expires"));
+    }
+
+    #[test]
+    fn test_apply_suppresses_by_id_and_alias() {
+        let list = AuditIgnoreList::from_entries(
+            &[
+                entry("GHSA-1", None, "Not reachable", "2030-01-01"),
+                entry(
+                    "cve-2024-0001",
+                    Some("owner/xml"),
+                    "Input is trusted",
+                    "2030-01-01",
+                ),
+            ],
+            "package.yaml",
+        )
+        .unwrap();
+
+        let mut report = VulnerabilityReport::new();
+        report.add(vuln("owner/json", "GHSA-1"));
+        report.add(vuln("owner/xml", "GHSA-2"));
+        report.add(vuln("owner/yaml", "GHSA-3"));
+
+        let expired = list.apply(&mut report, date("2025-01-01"));
+
+        assert!(expired.is_empty());
+        assert_eq!(report.vulnerabilities.len(), 1);
+        assert_eq!(report.vulnerabilities[0].package, "owner/yaml");
+        assert_eq!(report.ignored.len(), 2);
+        assert_eq!(report.ignored[1].reason, "Input is trusted");
+        assert_eq!(report.ignored[1].expires, "2030-01-01");
+    }
+
+    #[test]
+    fn test_apply_expired_entry_reports_again() {
+        let list = AuditIgnoreList::from_entries(
+            &[entry("GHSA-1", None, "Not reachable", "2025-01-01")],
+            "package.yaml",
+        )
+        .unwrap();
+
+        let mut report = VulnerabilityReport::new();
+        report.add(vuln("owner/json", "GHSA-1"));
+        list.apply(&mut report, date("2025-01-01"));
+        assert_eq!(report.ignored.len(), 1);
+
+        let mut report = VulnerabilityReport::new();
+        report.add(vuln("owner/json", "GHSA-1"));
+        let expired = list.apply(&mut report, date("2025-01-02"));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(report.vulnerabilities.len(), 1);
+        assert!(report.ignored.is_empty());
+        assert!(report.has_high());
+    }
+
+    #[test]
+    fn test_load_merges_manifest_and_audit_file() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".depot")).unwrap();
+        fs::write(
+            temp.path().join(AUDIT_CONFIG_FILE),
+            "ignore:\n  - id: GHSA-2\n    reason: Dev only\n    expires: 2030-06-30\n",
+        )
+        .unwrap();
+
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest.audit = Some(AuditConfig {
+            ignore: vec![entry("GHSA-1", None, "Not reachable", "2030-01-01")],
+        });
+
+        let list = AuditIgnoreList::load(temp.path(), Some(&manifest)).unwrap();
+        let ids: Vec<&str> = list.rules().iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["GHSA-1", "GHSA-2"]);
+    }
+
+    #[test]
+    fn test_load_without_entries() {
+        let temp = TempDir::new().unwrap();
+        assert!(AuditIgnoreList::load(temp.path(), None).unwrap().is_empty());
+    }
+}
@@ -1,9 +1,20 @@
 pub mod advisory;
 pub mod audit;
+pub mod fix;
+pub mod health;
+pub mod ignore;
 pub mod osv;
+pub mod osv_cache;
+pub mod policy;
+pub mod report;
+pub mod signature;
+pub mod suspicious;
+pub mod trust;
 pub mod vulnerability;
 
 pub use advisory::AdvisoryDatabase;
 pub use audit::SecurityAuditor;
 pub use osv::OsvApi;
+pub use osv_cache::OsvCache;
+pub use signature::SignaturePolicy;
 pub use vulnerability::{Severity, Vulnerability};
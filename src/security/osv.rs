@@ -1,12 +1,21 @@
 use crate::core::version::{Version, VersionConstraint};
 use crate::core::{DepotError, DepotResult};
+use crate::security::osv_cache::OsvCache;
 use crate::security::vulnerability::{Severity, Vulnerability};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// OSV ecosystem that depot packages are published under
+const OSV_ECOSYSTEM: &str = "Lua";
+
+/// Maximum number of queries OSV accepts in one `/v1/querybatch` request
+const QUERYBATCH_LIMIT: usize = 1000;
 
 /// Client for querying OSV (Open Source Vulnerabilities) API
 pub struct OsvApi {
     client: reqwest::Client,
     base_url: String,
+    cache: Option<OsvCache>,
 }
 
 #[derive(Serialize)]
@@ -27,14 +36,44 @@ struct OsvResponse {
     vulns: Vec<OsvVulnerability>,
 }
 
+#[derive(Serialize)]
+struct OsvBatchQuery<'a> {
+    queries: &'a [OsvQuery],
+}
+
+#[derive(Deserialize)]
+struct OsvBatchResponse {
+    #[serde(default)]
+    results: Vec<OsvBatchResult>,
+}
+
+#[derive(Deserialize)]
+struct OsvBatchResult {
+    #[serde(default)]
+    vulns: Vec<OsvVulnId>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvVulnId {
+    id: String,
+}
+
 #[derive(Deserialize)]
 struct OsvVulnerability {
     id: String,
+    #[serde(default)]
     summary: String,
+    #[serde(default)]
     details: String,
     #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
     severity: Vec<OsvSeverity>,
     #[serde(default)]
+    database_specific: Option<OsvDatabaseSpecific>,
+    #[serde(default)]
     affected: Vec<OsvAffected>,
     #[serde(default)]
     references: Vec<OsvReference>,
@@ -49,10 +88,26 @@ struct OsvSeverity {
     score: String,
 }
 
+#[derive(Deserialize)]
+struct OsvDatabaseSpecific {
+    #[serde(default)]
+    severity: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct OsvAffected {
+    #[serde(default)]
+    package: Option<OsvAffectedPackage>,
     #[serde(default)]
     ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvAffectedPackage {
+    ecosystem: String,
+    name: String,
 }
 
 #[derive(Deserialize)]
@@ -81,6 +136,7 @@ impl Default for OsvApi {
         Self {
             client: reqwest::Client::new(),
             base_url: "https://api.osv.dev".to_string(),
+            cache: None,
         }
     }
 }
@@ -91,6 +147,133 @@ impl OsvApi {
         Self::default()
     }
 
+    /// Cache results on disk so repeated audits skip unchanged package versions
+    pub fn with_cache(mut self, cache: OsvCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Query OSV for many package versions at once.
+    ///
+    /// Cached results are used where fresh. The remaining packages are sent
+    /// to `/v1/querybatch`, and full advisory details are fetched only for
+    /// the IDs it reports. Results are returned in the order of `packages`.
+    pub async fn query_batch(
+        &self,
+        packages: &[(String, String)],
+    ) -> DepotResult<Vec<Vec<Vulnerability>>> {
+        let mut results: Vec<Option<Vec<Vulnerability>>> = packages
+            .iter()
+            .map(|(name, version)| {
+                self.cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(name, version))
+            })
+            .collect();
+
+        let misses: Vec<usize> = (0..packages.len())
+            .filter(|&i| results[i].is_none())
+            .collect();
+
+        let mut details: HashMap<String, OsvVulnerability> = HashMap::new();
+
+        for chunk in misses.chunks(QUERYBATCH_LIMIT) {
+            let queries: Vec<OsvQuery> = chunk
+                .iter()
+                .map(|&i| OsvQuery {
+                    package: OsvPackage {
+                        ecosystem: OSV_ECOSYSTEM.to_string(),
+                        name: packages[i].0.clone(),
+                    },
+                    version: packages[i].1.clone(),
+                })
+                .collect();
+
+            let url = format!("{}/v1/querybatch", self.base_url);
+            let response = self
+                .client
+                .post(url)
+                .json(&OsvBatchQuery { queries: &queries })
+                .send()
+                .await
+                .map_err(DepotError::Http)?;
+
+            if !response.status().is_success() {
+                return Err(DepotError::Package(format!(
+                    "OSV batch query failed with status {}",
+                    response.status()
+                )));
+            }
+
+            let batch: OsvBatchResponse = response
+                .json()
+                .await
+                .map_err(|e| DepotError::Package(format!("OSV parse error: {}", e)))?;
+
+            // Results are matched to queries by position
+            if batch.results.len() != chunk.len() {
+                return Err(DepotError::Package(format!(
+                    "OSV batch query returned {} result(s) for {} package(s)",
+                    batch.results.len(),
+                    chunk.len()
+                )));
+            }
+
+            for (&i, result) in chunk.iter().zip(batch.results) {
+                let (name, version) = &packages[i];
+
+                // Paginated results are rare; fall back to a full single query
+                let vulns = if result.next_page_token.is_some() {
+                    self.query_package(name, version).await?
+                } else {
+                    let mut vulns = Vec::new();
+                    for hit in result.vulns {
+                        if !details.contains_key(&hit.id) {
+                            let record = self.get_vulnerability(&hit.id).await?;
+                            details.insert(hit.id.clone(), record);
+                        }
+                        let record = &details[&hit.id];
+                        vulns.push(to_vulnerability(record, name, &affected_for(record, name)));
+                    }
+                    vulns
+                };
+
+                if let Some(ref cache) = self.cache {
+                    if let Err(e) = cache.put(name, version, &vulns) {
+                        eprintln!("Warning: Failed to cache OSV results for {}: {}", name, e);
+                    }
+                }
+                results[i] = Some(vulns);
+            }
+        }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// Fetch the full record of a single advisory
+    async fn get_vulnerability(&self, id: &str) -> DepotResult<OsvVulnerability> {
+        let url = format!("{}/v1/vulns/{}", self.base_url, id);
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(DepotError::Http)?;
+
+        if !response.status().is_success() {
+            return Err(DepotError::Package(format!(
+                "Failed to fetch OSV advisory {}: status {}",
+                id,
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| DepotError::Package(format!("OSV parse error: {}", e)))
+    }
+
     /// Query OSV API for vulnerabilities in a package version.
     /// Returns empty vector if no vulnerabilities found or on API errors (non-fatal).
     pub async fn query_package(
@@ -100,7 +283,7 @@ impl OsvApi {
     ) -> DepotResult<Vec<Vulnerability>> {
         let query = OsvQuery {
             package: OsvPackage {
-                ecosystem: "Lua".to_string(),
+                ecosystem: OSV_ECOSYSTEM.to_string(),
                 name: name.to_string(),
             },
             version: version.to_string(),
@@ -126,41 +309,92 @@ impl OsvApi {
 
         Ok(osv_response
             .vulns
-            .into_iter()
-            .map(|v| {
-                let severity = parse_severity(&v.severity);
-                let affected_versions = parse_affected_versions(&v.affected);
-                let fixed_in = extract_fixed_version(&v.affected);
-                let references: Vec<String> = v.references.iter().map(|r| r.url.clone()).collect();
-
-                // Extract CVE: use id if it's a CVE, otherwise check aliases
-                let cve = if v.id.starts_with("CVE-") {
-                    Some(v.id.clone())
-                } else {
-                    v.aliases
-                        .iter()
-                        .find(|a| a.starts_with("CVE-"))
-                        .cloned()
-                        .or_else(|| Some(v.id.clone()))
-                };
-
-                Vulnerability {
-                    package: name.to_string(),
-                    affected_versions,
-                    severity,
-                    title: v.summary,
-                    description: v.details,
-                    cve,
-                    fixed_in,
-                    references,
-                }
-            })
+            .iter()
+            .map(|v| to_vulnerability(v, name, &v.affected.iter().collect::<Vec<_>>()))
             .collect())
     }
 }
 
+/// Parse a single OSV advisory record (as found in OSV exports) into vulnerabilities
+///
+/// One vulnerability is returned per affected package in the Lua ecosystem.
+/// Withdrawn advisories yield nothing.
+pub fn parse_osv_record(json: &str) -> DepotResult<Vec<Vulnerability>> {
+    let record: OsvVulnerability = serde_json::from_str(json)
+        .map_err(|e| DepotError::Package(format!("Invalid OSV advisory: {}", e)))?;
+
+    if record.withdrawn.is_some() {
+        return Ok(Vec::new());
+    }
+
+    Ok(record
+        .affected
+        .iter()
+        .filter_map(|a| {
+            let package = a.package.as_ref()?;
+            package
+                .ecosystem
+                .eq_ignore_ascii_case(OSV_ECOSYSTEM)
+                .then(|| to_vulnerability(&record, &package.name, &[a]))
+        })
+        .collect())
+}
+
+/// Affected entries of a record that apply to `package`
+///
+/// Entries without package information are assumed to apply.
+fn affected_for<'a>(record: &'a OsvVulnerability, package: &str) -> Vec<&'a OsvAffected> {
+    record
+        .affected
+        .iter()
+        .filter(|a| a.package.as_ref().is_none_or(|p| p.name == package))
+        .collect()
+}
+
+/// Convert an OSV record into a vulnerability for one package
+fn to_vulnerability(
+    v: &OsvVulnerability,
+    package: &str,
+    affected: &[&OsvAffected],
+) -> Vulnerability {
+    let severity = parse_severity(&v.severity, v.database_specific.as_ref());
+    let affected_versions = parse_affected_versions(affected);
+    let fixed_in = extract_fixed_version(affected);
+    let references: Vec<String> = v.references.iter().map(|r| r.url.clone()).collect();
+
+    // Extract CVE: use id if it's a CVE, otherwise check aliases
+    let cve = if v.id.starts_with("CVE-") {
+        Some(v.id.clone())
+    } else {
+        v.aliases
+            .iter()
+            .find(|a| a.starts_with("CVE-"))
+            .cloned()
+            .or_else(|| Some(v.id.clone()))
+    };
+
+    Vulnerability {
+        package: package.to_string(),
+        affected_versions,
+        severity,
+        title: v.summary.clone(),
+        description: v.details.clone(),
+        cve,
+        id: Some(v.id.clone()),
+        aliases: v.aliases.clone(),
+        fixed_in,
+        references,
+    }
+}
+
 /// Parse CVSS severity from OSV severity entries
-fn parse_severity(severity_entries: &[OsvSeverity]) -> Severity {
+///
+/// Falls back to the `database_specific.severity` label (e.g. "HIGH") used by
+/// GitHub advisories when no numeric score is available.
+fn parse_severity(
+    severity_entries: &[OsvSeverity],
+    database_specific: Option<&OsvDatabaseSpecific>,
+) -> Severity {
     severity_entries
         .iter()
         .find(|s| s.severity_type == "CVSS_V3")
@@ -178,6 +412,20 @@ fn parse_severity(severity_entries: &[OsvSeverity]) -> Severity {
                 }
             })
         })
+        .or_else(|| {
+            match database_specific?
+                .severity
+                .as_deref()?
+                .to_ascii_uppercase()
+                .as_str()
+            {
+                "CRITICAL" => Some(Severity::Critical),
+                "HIGH" => Some(Severity::High),
+                "MODERATE" | "MEDIUM" => Some(Severity::Medium),
+                "LOW" => Some(Severity::Low),
+                _ => None,
+            }
+        })
         .unwrap_or(Severity::Medium)
 }
 
@@ -186,14 +434,25 @@ fn parse_severity(severity_entries: &[OsvSeverity]) -> Severity {
 /// OSV events come in pairs: `{introduced: X}, {fixed: Y}` meaning
 /// the vulnerability affects versions in `[X, Y)`. Multiple pairs
 /// are combined with `||` (OR semantics).
-fn parse_affected_versions(affected: &[OsvAffected]) -> String {
-    let ranges: Vec<VersionConstraint> = affected
+fn parse_affected_versions(affected: &[&OsvAffected]) -> String {
+    let mut ranges: Vec<VersionConstraint> = affected
         .iter()
         .flat_map(|a| &a.ranges)
         .filter(|r| r.range_type == "SEMVER" || r.range_type == "ECOSYSTEM")
         .filter_map(|r| parse_osv_events(&r.events))
         .collect();
 
+    // Explicitly enumerated versions, used when no range is given
+    if ranges.is_empty() {
+        ranges.extend(
+            affected
+                .iter()
+                .flat_map(|a| &a.versions)
+                .filter_map(|v| Version::parse(v.trim_start_matches('v')).ok())
+                .map(VersionConstraint::Exact),
+        );
+    }
+
     let constraint = match ranges.len() {
         0 => return "<999.0.0".to_string(),
         1 => match ranges.into_iter().next() {
@@ -248,7 +507,7 @@ fn parse_osv_events(events: &[OsvEvent]) -> Option<VersionConstraint> {
 }
 
 /// Extract the first fixed version from OSV affected entries
-fn extract_fixed_version(affected: &[OsvAffected]) -> Option<String> {
+fn extract_fixed_version(affected: &[&OsvAffected]) -> Option<String> {
     affected
         .iter()
         .flat_map(|a| &a.ranges)
@@ -641,4 +900,194 @@ mod tests {
         ]);
         assert_eq!(format_constraint(&c), ">=0.0.0, <2.0.0 || >=3.0.0");
     }
+
+    #[tokio::test]
+    async fn test_query_batch_fetches_details_for_hits() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {"vulns": [{"id": "GHSA-1111", "modified": "2024-01-01T00:00:00Z"}]},
+                    {}
+                ]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/v1/vulns/GHSA-1111"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "GHSA-1111",
+                "summary": "Decoder overflow",
+                "details": "Details",
+                "aliases": ["CVE-2024-1111"],
+                "affected": [{
+                    "package": {"ecosystem": "Lua", "name": "owner/json"},
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{"introduced": "0"}, {"fixed": "1.2.0"}]
+                    }]
+                }]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut api = OsvApi::new();
+        api.base_url = mock_server.uri();
+
+        let packages = vec![
+            ("owner/json".to_string(), "1.0.0".to_string()),
+            ("owner/log".to_string(), "0.3.0".to_string()),
+        ];
+        let results = api.query_batch(&packages).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].len(), 1);
+        assert_eq!(results[0][0].package, "owner/json");
+        assert_eq!(results[0][0].cve, Some("CVE-2024-1111".to_string()));
+        assert_eq!(results[0][0].fixed_in, Some("1.2.0".to_string()));
+        assert!(results[1].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_uses_cache() {
+        use crate::security::osv_cache::OsvCache;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        // Only the first audit reaches OSV
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut api = OsvApi::new().with_cache(OsvCache::new(
+            temp.path().to_path_buf(),
+            chrono::Duration::hours(1),
+        ));
+        api.base_url = mock_server.uri();
+
+        let packages = vec![("owner/json".to_string(), "1.0.0".to_string())];
+        api.query_batch(&packages).await.unwrap();
+        let results = api.query_batch(&packages).await.unwrap();
+        assert!(results[0].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_error_status() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let mut api = OsvApi::new();
+        api.base_url = mock_server.uri();
+
+        let packages = vec![("owner/json".to_string(), "1.0.0".to_string())];
+        assert!(api.query_batch(&packages).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_batch_result_count_mismatch() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/v1/querybatch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [{}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let mut api = OsvApi::new();
+        api.base_url = mock_server.uri();
+
+        let packages = vec![
+            ("owner/json".to_string(), "1.0.0".to_string()),
+            ("owner/log".to_string(), "0.3.0".to_string()),
+        ];
+        let err = api.query_batch(&packages).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("returned 1 result(s) for 2 package(s)"));
+    }
+
+    #[test]
+    fn test_parse_osv_record() {
+        let json = serde_json::json!({
+            "id": "GHSA-aaaa-bbbb-cccc",
+            "summary": "Decoder overflow",
+            "aliases": ["CVE-2024-11111"],
+            "database_specific": {"severity": "HIGH"},
+            "affected": [
+                {
+                    "package": {"ecosystem": "Lua", "name": "owner/json"},
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{"introduced": "0"}, {"fixed": "1.2.0"}]
+                    }]
+                },
+                {
+                    "package": {"ecosystem": "npm", "name": "json"},
+                    "versions": ["1.0.0"]
+                }
+            ]
+        })
+        .to_string();
+
+        let vulns = parse_osv_record(&json).unwrap();
+        assert_eq!(vulns.len(), 1);
+        assert_eq!(vulns[0].package, "owner/json");
+        assert_eq!(vulns[0].severity, Severity::High);
+        assert_eq!(vulns[0].cve, Some("CVE-2024-11111".to_string()));
+        assert!(vulns[0].affects_version("1.1.0"));
+        assert!(!vulns[0].affects_version("1.2.0"));
+    }
+
+    #[test]
+    fn test_parse_osv_record_versions_and_withdrawn() {
+        let json = serde_json::json!({
+            "id": "INTERNAL-0001",
+            "affected": [{
+                "package": {"ecosystem": "Lua", "name": "owner/log"},
+                "versions": ["1.0.0", "v1.0.1"]
+            }]
+        })
+        .to_string();
+        let vulns = parse_osv_record(&json).unwrap();
+        assert!(vulns[0].affects_version("1.0.1"));
+        assert!(!vulns[0].affects_version("1.0.2"));
+
+        let withdrawn = serde_json::json!({
+            "id": "INTERNAL-0002",
+            "withdrawn": "2024-01-01T00:00:00Z",
+            "affected": [{"package": {"ecosystem": "Lua", "name": "owner/log"}}]
+        })
+        .to_string();
+        assert!(parse_osv_record(&withdrawn).unwrap().is_empty());
+
+        assert!(parse_osv_record("not json").is_err());
+    }
 }
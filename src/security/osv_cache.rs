@@ -0,0 +1,105 @@
+//! On-disk cache of OSV query results
+//!
+//! Stores the vulnerabilities found for each package version so repeated
+//! audits only query OSV for versions that are new or whose entry expired.
+
+use crate::core::{DepotError, DepotResult};
+use crate::security::vulnerability::Vulnerability;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// How long cached OSV results are considered fresh
+pub const DEFAULT_OSV_CACHE_TTL_HOURS: i64 = 24;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: DateTime<Utc>,
+    vulns: Vec<Vulnerability>,
+}
+
+/// Cache of OSV results keyed by package and version
+#[derive(Debug, Clone)]
+pub struct OsvCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl OsvCache {
+    /// Create a cache in `dir` with the given time-to-live
+    pub fn new(dir: PathBuf, ttl: Duration) -> Self {
+        Self { dir, ttl }
+    }
+
+    /// Cached vulnerabilities for a package version, if present and fresh
+    pub fn get(&self, package: &str, version: &str) -> Option<Vec<Vulnerability>> {
+        let content = fs::read_to_string(self.entry_path(package, version)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        (Utc::now().signed_duration_since(entry.fetched_at) < self.ttl).then_some(entry.vulns)
+    }
+
+    /// Store the vulnerabilities found for a package version
+    pub fn put(&self, package: &str, version: &str, vulns: &[Vulnerability]) -> DepotResult<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            fetched_at: Utc::now(),
+            vulns: vulns.to_vec(),
+        };
+        let content = serde_json::to_string(&entry).map_err(|e| {
+            DepotError::Package(format!("Failed to serialize OSV cache entry: {}", e))
+        })?;
+        fs::write(self.entry_path(package, version), content)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, package: &str, version: &str) -> PathBuf {
+        let key = blake3::hash(format!("{}@{}", package, version).as_bytes());
+        self.dir.join(format!("{}.json", key.to_hex()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::vulnerability::Severity;
+    use tempfile::TempDir;
+
+    fn vuln() -> Vulnerability {
+        Vulnerability {
+            package: "owner/json".to_string(),
+            affected_versions: "<2.0.0".to_string(),
+            severity: Severity::High,
+            cve: Some("CVE-2024-0001".to_string()),
+            title: "Test".to_string(),
+            description: "Test".to_string(),
+            fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
+            references: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let cache = OsvCache::new(temp.path().to_path_buf(), Duration::hours(1));
+
+        assert!(cache.get("owner/json", "1.0.0").is_none());
+        cache.put("owner/json", "1.0.0", &[vuln()]).unwrap();
+        cache.put("owner/log", "1.0.0", &[]).unwrap();
+
+        assert_eq!(cache.get("owner/json", "1.0.0").unwrap().len(), 1);
+        assert!(cache.get("owner/log", "1.0.0").unwrap().is_empty());
+        assert!(cache.get("owner/json", "1.0.1").is_none());
+    }
+
+    #[test]
+    fn test_cache_expired_entry() {
+        let temp = TempDir::new().unwrap();
+        let cache = OsvCache::new(temp.path().to_path_buf(), Duration::zero());
+
+        cache.put("owner/json", "1.0.0", &[vuln()]).unwrap();
+        assert!(cache.get("owner/json", "1.0.0").is_none());
+    }
+}
@@ -0,0 +1,548 @@
+//! Organisation dependency policy
+//!
+//! Rules live in `.depot/policy.yaml`, found by walking up from the project
+//! root, and in the file named by `policy_file` in the global config. Both
+//! apply, so a project policy cannot loosen the organisation's. They are
+//! enforced by `depot install`, `depot update` and `depot policy check`.
+
+use crate::core::path::lua_modules_dir;
+use crate::core::{DepotError, DepotResult};
+use crate::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use crate::package::manifest::PackageManifest;
+use crate::security::vulnerability::{Severity, VulnerabilityReport};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Policy file location, relative to the directory it applies to
+pub const POLICY_FILE: &str = ".depot/policy.yaml";
+
+/// File extensions that indicate native code in an installed package
+const NATIVE_EXTENSIONS: &[&str] = &["c", "h", "cc", "cpp", "rs", "so", "dylib", "dll"];
+
+/// Rules for which dependencies a project may use
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DependencyPolicy {
+    /// GitHub owners dependencies may come from (empty allows any)
+    #[serde(default)]
+    pub allowed_owners: Vec<String>,
+    /// Hosts package tarballs may be downloaded from; subdomains match (empty allows any)
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Refuse branch refs for dependencies that are not dev dependencies
+    #[serde(default)]
+    pub forbid_branch_refs: bool,
+    /// Require a depot.lock to be present
+    #[serde(default)]
+    pub require_lockfile: bool,
+    /// Refuse packages with native code unless they are in `native_code_allowlist`
+    #[serde(default)]
+    pub deny_native_code: bool,
+    /// Packages allowed to contain native code when `deny_native_code` is set
+    #[serde(default)]
+    pub native_code_allowlist: Vec<String>,
+    /// Highest advisory severity tolerated in the locked dependencies
+    #[serde(default)]
+    pub max_advisory_severity: Option<Severity>,
+}
+
+/// A dependency that breaks a policy rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    /// Offending package, or `None` for project-wide rules
+    pub package: Option<String>,
+    /// Rule name as written in policy.yaml
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl PolicyViolation {
+    fn new(package: Option<&str>, rule: &'static str, message: String) -> Self {
+        Self {
+            package: package.map(str::to_string),
+            rule,
+            message,
+        }
+    }
+}
+
+/// Find the nearest `.depot/policy.yaml` in `start` or one of its ancestors
+pub fn find_policy_file(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(POLICY_FILE))
+        .find(|path| path.is_file())
+}
+
+impl DependencyPolicy {
+    /// Load a policy file
+    pub fn load_from(path: &Path) -> DepotResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            DepotError::Config(format!(
+                "Failed to read policy file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            DepotError::Config(format!("Invalid policy file {}: {}", path.display(), e))
+        })
+    }
+
+    /// Load the policies that apply to a project
+    ///
+    /// `configured` (the global config's `policy_file`) comes first, then the
+    /// `.depot/policy.yaml` found upward from `project_root`. Every returned
+    /// policy is enforced; a file named by both is only loaded once.
+    pub fn discover(
+        project_root: &Path,
+        configured: Option<&Path>,
+    ) -> DepotResult<Vec<(PathBuf, Self)>> {
+        let mut paths: Vec<PathBuf> = configured.map(Path::to_path_buf).into_iter().collect();
+        if let Some(found) = find_policy_file(project_root) {
+            let same_file = |path: &PathBuf| {
+                path == &found
+                    || matches!(
+                        (fs::canonicalize(path), fs::canonicalize(&found)),
+                        (Ok(a), Ok(b)) if a == b
+                    )
+            };
+            if !paths.iter().any(same_file) {
+                paths.push(found);
+            }
+        }
+
+        paths
+            .into_iter()
+            .map(|path| Self::load_from(&path).map(|policy| (path, policy)))
+            .collect()
+    }
+
+    fn owner_allowed(&self, repository: &str) -> bool {
+        let owner = repository.split('/').next().unwrap_or(repository);
+        self.allowed_owners.is_empty()
+            || self
+                .allowed_owners
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(owner))
+    }
+
+    fn host_allowed(&self, url: &str) -> bool {
+        if self.allowed_hosts.is_empty() || url.is_empty() {
+            return true;
+        }
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+        else {
+            return false;
+        };
+        self.allowed_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_lowercase();
+            host == allowed || host.ends_with(&format!(".{}", allowed))
+        })
+    }
+
+    /// Check a dependency that is about to be added, before anything is fetched
+    pub fn check_dependency(&self, repository: &str) -> Vec<PolicyViolation> {
+        if self.owner_allowed(repository) {
+            return Vec::new();
+        }
+        vec![PolicyViolation::new(
+            Some(repository),
+            "allowed_owners",
+            format!(
+                "owner is not in allowed_owners ({})",
+                self.allowed_owners.join(", ")
+            ),
+        )]
+    }
+
+    /// Check the direct dependencies declared in package.yaml
+    pub fn check_manifest(&self, manifest: &PackageManifest) -> Vec<PolicyViolation> {
+        let mut names: Vec<&String> = manifest
+            .dependencies
+            .keys()
+            .chain(manifest.dev_dependencies.keys())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .flat_map(|name| self.check_dependency(name))
+            .collect()
+    }
+
+    /// Check every locked package
+    ///
+    /// `project_root` is used to look for native code in `lua_modules/`.
+    pub fn check_lockfile(
+        &self,
+        project_root: &Path,
+        manifest: &PackageManifest,
+        lockfile: &Lockfile,
+    ) -> Vec<PolicyViolation> {
+        let mut names: Vec<&String> = lockfile.packages.keys().collect();
+        names.sort();
+
+        let mut violations = Vec::new();
+        for name in names {
+            let pkg = &lockfile.packages[name];
+            if !self.owner_allowed(&pkg.repository) {
+                violations.push(PolicyViolation::new(
+                    Some(name),
+                    "allowed_owners",
+                    format!(
+                        "owner is not in allowed_owners ({})",
+                        self.allowed_owners.join(", ")
+                    ),
+                ));
+            }
+            if !self.host_allowed(&pkg.tarball_url) {
+                violations.push(PolicyViolation::new(
+                    Some(name),
+                    "allowed_hosts",
+                    format!(
+                        "{} is not on an allowed host ({})",
+                        pkg.tarball_url,
+                        self.allowed_hosts.join(", ")
+                    ),
+                ));
+            }
+            if self.forbid_branch_refs
+                && pkg.ref_type.eq_ignore_ascii_case("branch")
+                && !manifest.dev_dependencies.contains_key(name)
+            {
+                violations.push(PolicyViolation::new(
+                    Some(name),
+                    "forbid_branch_refs",
+                    format!(
+                        "locked to branch '{}'; pin a release, tag or commit",
+                        pkg.ref_value
+                    ),
+                ));
+            }
+            if self.deny_native_code
+                && !self.native_code_allowlist.iter().any(|p| p == name)
+                && (pkg.native_code.is_some()
+                    || pkg.build.is_some()
+                    || has_native_files(&lua_modules_dir(project_root).join(name)))
+            {
+                violations.push(PolicyViolation::new(
+                    Some(name),
+                    "deny_native_code",
+                    "contains native code and is not in native_code_allowlist".to_string(),
+                ));
+            }
+        }
+        violations
+    }
+
+    /// Check the project as a whole: package.yaml, then depot.lock if present
+    pub fn check_project(
+        &self,
+        project_root: &Path,
+        manifest: &PackageManifest,
+        lockfile: Option<&Lockfile>,
+    ) -> Vec<PolicyViolation> {
+        let mut violations = self.check_manifest(manifest);
+        match lockfile {
+            Some(lockfile) => {
+                // Direct dependencies are checked again as locked packages
+                violations.retain(|v| {
+                    v.package
+                        .as_ref()
+                        .is_none_or(|p| !lockfile.packages.contains_key(p))
+                });
+                violations.extend(self.check_lockfile(project_root, manifest, lockfile));
+            }
+            None if self.require_lockfile => violations.push(PolicyViolation::new(
+                None,
+                "require_lockfile",
+                format!("{} is missing", LOCKFILE_NAME),
+            )),
+            None => {}
+        }
+        violations
+    }
+
+    /// Check audit findings against `max_advisory_severity`
+    pub fn check_advisories(&self, report: &VulnerabilityReport) -> Vec<PolicyViolation> {
+        let Some(max) = self.max_advisory_severity else {
+            return Vec::new();
+        };
+        report
+            .vulnerabilities
+            .iter()
+            .filter(|v| v.severity > max)
+            .map(|v| {
+                let id = v.id.clone().or_else(|| v.cve.clone()).unwrap_or_default();
+                PolicyViolation::new(
+                    Some(&v.package),
+                    "max_advisory_severity",
+                    format!(
+                        "{} advisory {} ({}) exceeds {}",
+                        v.severity.as_str(),
+                        id,
+                        v.title,
+                        max.as_str()
+                    ),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Whether an installed package directory contains native sources or libraries
+fn has_native_files(dir: &Path) -> bool {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .any(|e| {
+            let path = e.path();
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| NATIVE_EXTENSIONS.contains(&ext))
+                || path
+                    .file_name()
+                    .is_some_and(|n| n == "Cargo.toml" || n == "CMakeLists.txt")
+        })
+}
+
+/// Render violations, one per line
+pub fn format_violations(violations: &[PolicyViolation]) -> String {
+    let mut output = String::new();
+    for violation in violations {
+        match &violation.package {
+            Some(package) => output.push_str(&format!(
+                "  ✗ {} [{}]: {}\n",
+                package, violation.rule, violation.message
+            )),
+            None => output.push_str(&format!(
+                "  ✗ [{}]: {}\n",
+                violation.rule, violation.message
+            )),
+        }
+    }
+    output
+}
+
+/// Fail with every violation listed, naming the policy file
+pub fn enforce(policy_path: &Path, violations: &[PolicyViolation]) -> DepotResult<()> {
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(DepotError::Package(format!(
+        "{} dependency policy violation(s) ({}):\n{}",
+        violations.len(),
+        policy_path.display(),
+        format_violations(violations).trim_end()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::LockedPackage;
+    use crate::security::vulnerability::Vulnerability;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn locked(repository: &str, ref_type: &str) -> LockedPackage {
+        LockedPackage {
+            version: "1.0.0".to_string(),
+            repository: repository.to_string(),
+            ref_type: ref_type.to_string(),
+            ref_value: "main".to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: format!("https://api.github.com/repos/{}/tarball/main", repository),
+            checksum: "blake3:00".to_string(),
+            size: 1,
+            dependencies: HashMap::new(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn project() -> (PackageManifest, Lockfile) {
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("myorg/core".to_string(), "^1.0.0".to_string());
+        manifest
+            .dev_dependencies
+            .insert("other/testlib".to_string(), "main".to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package("myorg/core".to_string(), locked("myorg/core", "Release"));
+        lockfile.add_package(
+            "other/testlib".to_string(),
+            locked("other/testlib", "Branch"),
+        );
+        lockfile.add_package("other/util".to_string(), locked("other/util", "Branch"));
+        (manifest, lockfile)
+    }
+
+    #[test]
+    fn test_policy_from_yaml() {
+        let policy: DependencyPolicy = serde_yaml::from_str(
+            "allowed_owners: [myorg]\nforbid_branch_refs: true\nrequire_lockfile: true\nmax_advisory_severity: medium\n",
+        )
+        .unwrap();
+        assert_eq!(policy.allowed_owners, vec!["myorg"]);
+        assert!(policy.forbid_branch_refs && policy.require_lockfile);
+        assert_eq!(policy.max_advisory_severity, Some(Severity::Medium));
+    }
+
+    #[test]
+    fn test_check_owners_and_branch_refs() {
+        let temp = TempDir::new().unwrap();
+        let (manifest, lockfile) = project();
+        let policy = DependencyPolicy {
+            allowed_owners: vec!["MyOrg".to_string(), "other".to_string()],
+            forbid_branch_refs: true,
+            ..Default::default()
+        };
+
+        // Branch refs are fine for the dev dependency, not for the transitive one
+        let violations = policy.check_lockfile(temp.path(), &manifest, &lockfile);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].package.as_deref(), Some("other/util"));
+        assert_eq!(violations[0].rule, "forbid_branch_refs");
+
+        let policy = DependencyPolicy {
+            allowed_owners: vec!["myorg".to_string()],
+            ..Default::default()
+        };
+        let violations = policy.check_project(temp.path(), &manifest, Some(&lockfile));
+        let packages: Vec<_> = violations
+            .iter()
+            .filter_map(|v| v.package.as_deref())
+            .collect();
+        assert_eq!(packages, vec!["other/testlib", "other/util"]);
+    }
+
+    #[test]
+    fn test_check_hosts() {
+        let policy = DependencyPolicy {
+            allowed_hosts: vec!["github.com".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.host_allowed("https://api.github.com/repos/o/r/tarball/v1"));
+        assert!(policy.host_allowed("https://github.com/o/r/archive/v1.tar.gz"));
+        assert!(!policy.host_allowed("https://evilgithub.com/o/r.tar.gz"));
+        assert!(!policy.host_allowed("not a url"));
+    }
+
+    #[test]
+    fn test_check_native_code() {
+        let temp = TempDir::new().unwrap();
+        let (manifest, lockfile) = project();
+        let native_dir = lua_modules_dir(temp.path()).join("other/util/src");
+        fs::create_dir_all(&native_dir).unwrap();
+        fs::write(native_dir.join("util.c"), "int x;").unwrap();
+
+        let mut policy = DependencyPolicy {
+            deny_native_code: true,
+            ..Default::default()
+        };
+        let violations = policy.check_lockfile(temp.path(), &manifest, &lockfile);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, "deny_native_code");
+
+        policy.native_code_allowlist = vec!["other/util".to_string()];
+        assert!(policy
+            .check_lockfile(temp.path(), &manifest, &lockfile)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_require_lockfile() {
+        let temp = TempDir::new().unwrap();
+        let (manifest, _) = project();
+        let policy = DependencyPolicy {
+            require_lockfile: true,
+            ..Default::default()
+        };
+        let violations = policy.check_project(temp.path(), &manifest, None);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].package, None);
+        assert_eq!(
+            format_violations(&violations),
+            "  ✗ [require_lockfile]: depot.lock is missing\n"
+        );
+
+        let err = enforce(Path::new(".depot/policy.yaml"), &violations).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1 dependency policy violation(s) (.depot/policy.yaml)"));
+    }
+
+    #[test]
+    fn test_check_advisories() {
+        let mut report = VulnerabilityReport::new();
+        for (package, severity) in [("a/low", Severity::Low), ("b/high", Severity::High)] {
+            report.add(Vulnerability {
+                package: package.to_string(),
+                affected_versions: "<2.0.0".to_string(),
+                severity,
+                cve: None,
+                id: Some("GHSA-1".to_string()),
+                aliases: Vec::new(),
+                title: "bad".to_string(),
+                description: String::new(),
+                fixed_in: None,
+                references: Vec::new(),
+            });
+        }
+
+        let policy = DependencyPolicy {
+            max_advisory_severity: Some(Severity::Medium),
+            ..Default::default()
+        };
+        let violations = policy.check_advisories(&report);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].package.as_deref(), Some("b/high"));
+        assert!(violations[0].message.contains("High advisory GHSA-1"));
+        assert!(DependencyPolicy::default()
+            .check_advisories(&report)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_discover_walks_up() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("apps/web");
+        fs::create_dir_all(&project).unwrap();
+        assert!(DependencyPolicy::discover(&project, None)
+            .unwrap()
+            .is_empty());
+
+        let configured = temp.path().join("org-policy.yaml");
+        fs::write(&configured, "require_lockfile: true\n").unwrap();
+        let policies = DependencyPolicy::discover(&project, Some(&configured)).unwrap();
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].0, configured);
+        assert!(policies[0].1.require_lockfile);
+
+        // A project policy is enforced alongside the configured one, not instead of it
+        fs::create_dir_all(temp.path().join(".depot")).unwrap();
+        fs::write(temp.path().join(POLICY_FILE), "forbid_branch_refs: true\n").unwrap();
+        let policies = DependencyPolicy::discover(&project, Some(&configured)).unwrap();
+        assert_eq!(policies.len(), 2);
+        assert!(policies[0].1.require_lockfile);
+        assert_eq!(policies[1].0, temp.path().join(POLICY_FILE));
+        assert!(policies[1].1.forbid_branch_refs);
+
+        // The same file named by both is loaded once
+        let policies =
+            DependencyPolicy::discover(&project, Some(&temp.path().join(POLICY_FILE))).unwrap();
+        assert_eq!(policies.len(), 1);
+    }
+}
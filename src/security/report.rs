@@ -0,0 +1,491 @@
+//! Machine-readable audit output (`depot audit --format json|sarif`)
+//!
+//! Enriches each finding with the installed version and the dependency
+//! paths that pull the vulnerable package in, then renders the result as
+//! JSON or as a SARIF 2.1.0 log for code-scanning dashboards.
+
+use crate::core::{DepotError, DepotResult};
+use crate::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use crate::package::manifest::PackageManifest;
+use crate::resolver::why::{find_paths, WhyRoot};
+use crate::security::audit::format_report;
+use crate::security::vulnerability::{Severity, Vulnerability, VulnerabilityReport};
+use serde::Serialize;
+use serde_json::json;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Output format for `depot audit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+impl AuditFormat {
+    /// Parse an audit format from a string
+    pub fn parse(s: &str) -> DepotResult<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(AuditFormat::Text),
+            "json" => Ok(AuditFormat::Json),
+            "sarif" => Ok(AuditFormat::Sarif),
+            _ => Err(DepotError::Config(format!(
+                "Invalid audit format '{}'. Must be 'text', 'json' or 'sarif'",
+                s
+            ))),
+        }
+    }
+}
+
+/// A vulnerability found in a locked package
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditFinding {
+    /// Advisory identifier, falling back to the CVE
+    pub id: Option<String>,
+    pub aliases: Vec<String>,
+    pub package: String,
+    pub installed_version: Option<String>,
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub affected_versions: String,
+    pub fixed_in: Option<String>,
+    pub references: Vec<String>,
+    /// Package chains from the project down to the vulnerable package
+    pub paths: Vec<Vec<String>>,
+    /// Set when the finding is suppressed by the audit ignore list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignored: Option<AuditSuppression>,
+}
+
+/// Justification for an ignored finding
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditSuppression {
+    pub reason: String,
+    pub expires: String,
+}
+
+impl AuditFinding {
+    /// Stable identifier used as the SARIF rule ID
+    fn rule_id(&self) -> String {
+        self.id
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}", self.package, self.title))
+    }
+}
+
+#[derive(Serialize)]
+struct JsonAuditReport<'a> {
+    checked_packages: usize,
+    summary: JsonSeveritySummary,
+    vulnerabilities: Vec<&'a AuditFinding>,
+    ignored: Vec<&'a AuditFinding>,
+}
+
+#[derive(Serialize)]
+struct JsonSeveritySummary {
+    critical: usize,
+    high: usize,
+    medium: usize,
+    low: usize,
+    ignored: usize,
+}
+
+/// Attach installed versions and dependency paths to every finding
+///
+/// Ignored findings are included with their suppression details.
+pub fn collect_findings(
+    report: &VulnerabilityReport,
+    manifest: Option<&PackageManifest>,
+    lockfile: Option<&Lockfile>,
+) -> Vec<AuditFinding> {
+    let root = manifest.map(|m| WhyRoot {
+        name: m.name.clone(),
+        dependencies: &m.dependencies,
+        dev_dependencies: &m.dev_dependencies,
+        lockfile,
+    });
+
+    let mut findings: Vec<AuditFinding> = report
+        .vulnerabilities
+        .iter()
+        .map(|vuln| to_finding(vuln, root.as_ref(), lockfile))
+        .chain(report.ignored.iter().map(|ignored| AuditFinding {
+            ignored: Some(AuditSuppression {
+                reason: ignored.reason.clone(),
+                expires: ignored.expires.clone(),
+            }),
+            ..to_finding(&ignored.vulnerability, root.as_ref(), lockfile)
+        }))
+        .collect();
+
+    findings.sort_by(|a, b| {
+        a.ignored
+            .is_some()
+            .cmp(&b.ignored.is_some())
+            .then_with(|| b.severity.cmp(&a.severity))
+            .then_with(|| a.package.cmp(&b.package))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    findings
+}
+
+fn to_finding(
+    vuln: &Vulnerability,
+    root: Option<&WhyRoot<'_>>,
+    lockfile: Option<&Lockfile>,
+) -> AuditFinding {
+    let mut paths: Vec<Vec<String>> = root
+        .map(|root| {
+            find_paths(std::slice::from_ref(root), &vuln.package)
+                .into_iter()
+                .map(|path| {
+                    std::iter::once(path.root)
+                        .chain(path.edges.into_iter().map(|e| e.name))
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort_by_key(Vec::len);
+
+    AuditFinding {
+        id: vuln.id.clone().or_else(|| vuln.cve.clone()),
+        aliases: vuln.aliases.clone(),
+        package: vuln.package.clone(),
+        installed_version: lockfile
+            .and_then(|lf| lf.get_package(&vuln.package))
+            .map(|pkg| pkg.version.clone()),
+        severity: vuln.severity,
+        title: vuln.title.clone(),
+        description: vuln.description.clone(),
+        affected_versions: vuln.affected_versions.clone(),
+        fixed_in: vuln.fixed_in.clone(),
+        references: vuln.references.clone(),
+        paths,
+        ignored: None,
+    }
+}
+
+/// Render a report in the requested format
+pub fn render_report(
+    format: AuditFormat,
+    report: &VulnerabilityReport,
+    findings: &[AuditFinding],
+) -> DepotResult<String> {
+    match format {
+        AuditFormat::Text => Ok(format_report(report)),
+        AuditFormat::Json => to_json(report, findings),
+        AuditFormat::Sarif => to_sarif(findings),
+    }
+}
+
+/// Render findings as JSON
+pub fn to_json(report: &VulnerabilityReport, findings: &[AuditFinding]) -> DepotResult<String> {
+    let (ignored, active): (Vec<&AuditFinding>, Vec<&AuditFinding>) =
+        findings.iter().partition(|f| f.ignored.is_some());
+    let count = |severity: Severity| active.iter().filter(|f| f.severity == severity).count();
+    let output = JsonAuditReport {
+        checked_packages: report.checked_packages,
+        summary: JsonSeveritySummary {
+            critical: count(Severity::Critical),
+            high: count(Severity::High),
+            medium: count(Severity::Medium),
+            low: count(Severity::Low),
+            ignored: ignored.len(),
+        },
+        vulnerabilities: active,
+        ignored,
+    };
+
+    serde_json::to_string_pretty(&output)
+        .map(|s| s + "\n")
+        .map_err(|e| DepotError::Package(format!("Failed to serialize audit report: {}", e)))
+}
+
+/// Render findings as a SARIF 2.1.0 log
+pub fn to_sarif(findings: &[AuditFinding]) -> DepotResult<String> {
+    let mut rules: Vec<serde_json::Value> = Vec::new();
+    let mut rule_ids: Vec<String> = Vec::new();
+
+    for finding in findings {
+        let rule_id = finding.rule_id();
+        if rule_ids.contains(&rule_id) {
+            continue;
+        }
+        rule_ids.push(rule_id.clone());
+
+        let mut rule = json!({
+            "id": rule_id,
+            "shortDescription": { "text": finding.title },
+            "fullDescription": { "text": finding.description },
+            "properties": {
+                "security-severity": security_severity(finding.severity),
+                "tags": ["security", "vulnerability"],
+            },
+        });
+        if let Some(url) = finding.references.first() {
+            rule["helpUri"] = json!(url);
+        }
+        rules.push(rule);
+    }
+
+    let results: Vec<serde_json::Value> = findings
+        .iter()
+        .map(|finding| {
+            let installed = finding.installed_version.as_deref().unwrap_or("unknown");
+            let mut message = format!(
+                "{}@{} is affected by {}: {}",
+                finding.package,
+                installed,
+                finding.rule_id(),
+                finding.title
+            );
+            if let Some(ref fixed) = finding.fixed_in {
+                message.push_str(&format!(" (fixed in {})", fixed));
+            }
+            if let Some(path) = finding.paths.first() {
+                message.push_str(&format!(". Path: {}", path.join(" -> ")));
+            }
+
+            let mut result = json!({
+                "ruleId": finding.rule_id(),
+                "ruleIndex": rule_ids.iter().position(|id| *id == finding.rule_id()),
+                "level": sarif_level(finding.severity),
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": LOCKFILE_NAME },
+                    },
+                    "logicalLocations": [{
+                        "name": finding.package,
+                        "kind": "package",
+                    }],
+                }],
+                "partialFingerprints": {
+                    "packageAdvisory": format!("{}/{}", finding.package, finding.rule_id()),
+                },
+                "properties": {
+                    "package": finding.package,
+                    "installedVersion": finding.installed_version,
+                    "affectedVersions": finding.affected_versions,
+                    "fixedIn": finding.fixed_in,
+                    "aliases": finding.aliases,
+                    "paths": finding.paths,
+                },
+            });
+            if let Some(ref suppression) = finding.ignored {
+                result["suppressions"] = json!([{
+                    "kind": "external",
+                    "status": "accepted",
+                    "justification": format!(
+                        "{} (expires {})",
+                        suppression.reason, suppression.expires
+                    ),
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "depot",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/forge18/depot",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&log)
+        .map(|s| s + "\n")
+        .map_err(|e| DepotError::Package(format!("Failed to serialize SARIF log: {}", e)))
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+/// CVSS-style score understood by GitHub code scanning
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.5",
+        Severity::High => "8.0",
+        Severity::Medium => "5.5",
+        Severity::Low => "2.0",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::package::lockfile::LockedPackage;
+    use crate::security::vulnerability::IgnoredVulnerability;
+
+    fn locked(version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            version: version.to_string(),
+            repository: "owner/repo".to_string(),
+            ref_type: "release".to_string(),
+            ref_value: version.to_string(),
+            commit_sha: "abc123".to_string(),
+            tarball_url: String::new(),
+            checksum: "blake3:abc".to_string(),
+            size: 0,
+            dependencies: deps
+                .iter()
+                .map(|(n, c)| (n.to_string(), c.to_string()))
+                .collect(),
+            build: None,
+            native_code: None,
+            license: None,
+            capabilities: Vec::new(),
+            repository_id: None,
+        }
+    }
+
+    fn fixture() -> (VulnerabilityReport, PackageManifest, Lockfile) {
+        let mut manifest = PackageManifest::default("app".to_string());
+        manifest
+            .dependencies
+            .insert("owner/web".to_string(), "^2.0.0".to_string());
+
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/web".to_string(),
+            locked("v2.0.0", &[("owner/json", "^1.0.0")]),
+        );
+        lockfile.add_package("owner/json".to_string(), locked("v1.0.0", &[]));
+
+        let mut report = VulnerabilityReport::new();
+        report.checked_packages = 2;
+        report.add(Vulnerability {
+            package: "owner/json".to_string(),
+            affected_versions: ">=0.0.0, <1.2.0".to_string(),
+            severity: Severity::High,
+            cve: Some("CVE-2024-1111".to_string()),
+            id: Some("GHSA-1111".to_string()),
+            aliases: vec!["CVE-2024-1111".to_string()],
+            title: "Decoder overflow".to_string(),
+            description: "Details".to_string(),
+            fixed_in: Some("1.2.0".to_string()),
+            references: vec!["https://example.com/GHSA-1111".to_string()],
+        });
+
+        (report, manifest, lockfile)
+    }
+
+    #[test]
+    fn test_audit_format_parse() {
+        assert_eq!(AuditFormat::parse("JSON").unwrap(), AuditFormat::Json);
+        assert_eq!(AuditFormat::parse("sarif").unwrap(), AuditFormat::Sarif);
+        assert!(AuditFormat::parse("xml").is_err());
+    }
+
+    #[test]
+    fn test_collect_findings_with_paths() {
+        let (report, manifest, lockfile) = fixture();
+        let findings = collect_findings(&report, Some(&manifest), Some(&lockfile));
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].id.as_deref(), Some("GHSA-1111"));
+        assert_eq!(findings[0].installed_version.as_deref(), Some("v1.0.0"));
+        assert_eq!(
+            findings[0].paths,
+            vec![vec!["app", "owner/web", "owner/json"]]
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let (report, manifest, lockfile) = fixture();
+        let findings = collect_findings(&report, Some(&manifest), Some(&lockfile));
+        let value: serde_json::Value =
+            serde_json::from_str(&to_json(&report, &findings).unwrap()).unwrap();
+
+        assert_eq!(value["checked_packages"], 2);
+        assert_eq!(value["summary"]["high"], 1);
+        assert_eq!(value["vulnerabilities"][0]["severity"], "High");
+        assert_eq!(value["vulnerabilities"][0]["fixed_in"], "1.2.0");
+        assert_eq!(value["vulnerabilities"][0]["aliases"][0], "CVE-2024-1111");
+    }
+
+    #[test]
+    fn test_to_sarif() {
+        let (report, manifest, lockfile) = fixture();
+        let findings = collect_findings(&report, Some(&manifest), Some(&lockfile));
+        let value: serde_json::Value = serde_json::from_str(&to_sarif(&findings).unwrap()).unwrap();
+
+        assert_eq!(value["version"], "2.1.0");
+        let run = &value["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "GHSA-1111");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["properties"]["security-severity"],
+            "8.0"
+        );
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "GHSA-1111");
+        assert_eq!(result["level"], "error");
+        assert!(result["message"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("app -> owner/web -> owner/json"));
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "depot.lock"
+        );
+    }
+
+    #[test]
+    fn test_to_sarif_empty() {
+        let value: serde_json::Value = serde_json::from_str(&to_sarif(&[]).unwrap()).unwrap();
+        assert!(value["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ignored_findings() {
+        let (mut report, manifest, lockfile) = fixture();
+        let vulnerability = report.vulnerabilities.pop().unwrap();
+        report.ignored.push(IgnoredVulnerability {
+            vulnerability,
+            reason: "Decoder is never fed untrusted input".to_string(),
+            expires: "2030-01-01".to_string(),
+        });
+        let findings = collect_findings(&report, Some(&manifest), Some(&lockfile));
+
+        let value: serde_json::Value =
+            serde_json::from_str(&to_json(&report, &findings).unwrap()).unwrap();
+        assert_eq!(value["summary"]["high"], 0);
+        assert_eq!(value["summary"]["ignored"], 1);
+        assert!(value["vulnerabilities"].as_array().unwrap().is_empty());
+        assert_eq!(value["ignored"][0]["ignored"]["expires"], "2030-01-01");
+
+        let value: serde_json::Value = serde_json::from_str(&to_sarif(&findings).unwrap()).unwrap();
+        let suppression = &value["runs"][0]["results"][0]["suppressions"][0];
+        assert_eq!(suppression["kind"], "external");
+        assert!(suppression["justification"]
+            .as_str()
+            .unwrap()
+            .contains("never fed untrusted input"));
+    }
+
+    #[test]
+    fn test_finding_without_manifest_has_no_paths() {
+        let (report, _, lockfile) = fixture();
+        let findings = collect_findings(&report, None, Some(&lockfile));
+        assert!(findings[0].paths.is_empty());
+    }
+}
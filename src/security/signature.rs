@@ -0,0 +1,305 @@
+//! Publisher signature verification for package tarballs and prebuilt binaries
+//!
+//! Signatures use the minisign format (Ed25519, pre-hashed or legacy). A
+//! package release is signed by attaching [`PACKAGE_SIGNATURE_ASSET`] to the
+//! GitHub release; a `binary_urls` artifact by publishing a detached
+//! signature at the artifact URL plus [`SIGNATURE_EXTENSION`].
+
+use crate::core::{DepotError, DepotResult};
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Release asset holding the signature of the release's source tarball
+pub const PACKAGE_SIGNATURE_ASSET: &str = "source.tar.gz.minisig";
+
+/// Suffix appended to a prebuilt binary URL to find its detached signature
+pub const SIGNATURE_EXTENSION: &str = ".minisig";
+
+/// Trusted publisher keys and whether unsigned artifacts are refused
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignaturePolicy {
+    /// Minisign public keys (the base64 line of `minisign.pub`) trusted for
+    /// every repository of an owner (`owner`) or for one repository (`owner/repo`)
+    #[serde(default)]
+    pub trusted_keys: HashMap<String, Vec<String>>,
+    /// Refuse packages and binaries without a valid signature from a trusted key
+    #[serde(default)]
+    pub require: bool,
+}
+
+/// Outcome of checking an artifact against the policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed by a trusted key; holds the signature's trusted comment
+    Verified(String),
+    /// Signed, but no key is trusted for the package
+    Untrusted,
+    /// No signature was published
+    Unsigned,
+}
+
+impl SignaturePolicy {
+    /// Whether signatures need to be fetched at all
+    pub fn is_enabled(&self) -> bool {
+        self.require || !self.trusted_keys.is_empty()
+    }
+
+    /// Keys trusted for a package: those listed for `owner/repo` and for `owner`
+    pub fn keys_for(&self, package: &str) -> Vec<&str> {
+        let owner = package.split('/').next().unwrap_or(package);
+        let mut keys: Vec<&str> = Vec::new();
+        for scope in [package, owner] {
+            if let Some(list) = self.trusted_keys.get(scope) {
+                keys.extend(list.iter().map(String::as_str));
+            }
+            if scope == owner {
+                break;
+            }
+        }
+        keys
+    }
+
+    /// Check `data` for the `owner/repo` package against an optional minisign signature
+    ///
+    /// A signature that does not verify is always an error, and so is a
+    /// missing signature when a key is trusted for the package. Other unsigned
+    /// artifacts and signatures without a trusted key are only errors in
+    /// strict mode.
+    pub fn check(
+        &self,
+        package: &str,
+        artifact: &str,
+        data: &[u8],
+        signature: Option<&str>,
+    ) -> DepotResult<SignatureStatus> {
+        self.check_with_keys(package, &self.keys_for(package), artifact, data, signature)
+    }
+
+    /// Check `data` against an optional signature using an explicit set of trusted keys
+    ///
+    /// For artifacts whose repository is unknown, so no key applies; `package`
+    /// is only used in messages.
+    pub fn check_with_keys(
+        &self,
+        package: &str,
+        keys: &[&str],
+        artifact: &str,
+        data: &[u8],
+        signature: Option<&str>,
+    ) -> DepotResult<SignatureStatus> {
+        let Some(signature) = signature else {
+            if self.require {
+                return Err(DepotError::Package(format!(
+                    "{} for {} is not signed and signatures are required",
+                    artifact, package
+                )));
+            }
+            if !keys.is_empty() {
+                return Err(DepotError::Package(format!(
+                    "{} for {} is not signed, but a trusted key is configured for it",
+                    artifact, package
+                )));
+            }
+            return Ok(SignatureStatus::Unsigned);
+        };
+
+        let signature = Signature::decode(signature).map_err(|e| {
+            DepotError::Package(format!(
+                "Invalid signature for {} of {}: {}",
+                artifact, package, e
+            ))
+        })?;
+
+        if keys.is_empty() {
+            if self.require {
+                return Err(DepotError::Package(format!(
+                    "{} for {} is signed, but no trusted key is configured for it",
+                    artifact, package
+                )));
+            }
+            return Ok(SignatureStatus::Untrusted);
+        }
+
+        let mut last_error = None;
+        for key in keys.iter().copied() {
+            let public_key = parse_public_key(key)?;
+            match public_key.verify(data, &signature, true) {
+                Ok(()) => {
+                    return Ok(SignatureStatus::Verified(
+                        signature.trusted_comment().to_string(),
+                    ))
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(DepotError::Package(format!(
+            "Signature verification failed for {} of {}: {}",
+            artifact,
+            package,
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        )))
+    }
+}
+
+/// Parse a trusted key given as the base64 line or the full `minisign.pub` content
+fn parse_public_key(key: &str) -> DepotResult<PublicKey> {
+    let key = key.trim();
+    let parsed = if key.contains('\n') {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+    parsed.map_err(|e| DepotError::Config(format!("Invalid trusted key '{}': {}", key, e)))
+}
+
+/// Sign `data` in minisign's legacy (plain Ed25519) format, returning the
+/// signature file and the matching public key
+#[cfg(test)]
+pub(crate) fn sign_for_tests(seed: u8, data: &[u8]) -> (String, String) {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let key = SigningKey::from_bytes(&[seed; 32]);
+    let key_id = [seed; 8];
+
+    let mut public_key = b"Ed".to_vec();
+    public_key.extend_from_slice(&key_id);
+    public_key.extend_from_slice(key.verifying_key().as_bytes());
+
+    let signature = key.sign(data).to_bytes();
+    let mut signature_line = b"Ed".to_vec();
+    signature_line.extend_from_slice(&key_id);
+    signature_line.extend_from_slice(&signature);
+
+    let trusted_comment = "timestamp:0\tfile:test";
+    let mut global = signature.to_vec();
+    global.extend_from_slice(trusted_comment.as_bytes());
+    let global_signature = key.sign(&global).to_bytes();
+
+    (
+        format!(
+            "untrusted comment: signature from test key\n{}\ntrusted comment: {}\n{}\n",
+            STANDARD.encode(signature_line),
+            trusted_comment,
+            STANDARD.encode(global_signature)
+        ),
+        STANDARD.encode(public_key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(scope: &str, key: &str, require: bool) -> SignaturePolicy {
+        SignaturePolicy {
+            trusted_keys: HashMap::from([(scope.to_string(), vec![key.to_string()])]),
+            require,
+        }
+    }
+
+    #[test]
+    fn test_verified_signature() {
+        let (signature, key) = sign_for_tests(1, b"tarball");
+        let status = policy("owner", &key, true)
+            .check("owner/lib", "source tarball", b"tarball", Some(&signature))
+            .unwrap();
+        assert_eq!(
+            status,
+            SignatureStatus::Verified("timestamp:0\tfile:test".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tampered_data_fails() {
+        let (signature, key) = sign_for_tests(1, b"tarball");
+        let err = policy("owner", &key, false)
+            .check("owner/lib", "source tarball", b"tampered", Some(&signature))
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Signature verification failed for source tarball of owner/lib"));
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let (signature, _) = sign_for_tests(1, b"tarball");
+        let (_, other_key) = sign_for_tests(2, b"tarball");
+        let err = policy("owner/lib", &other_key, false)
+            .check("owner/lib", "source tarball", b"tarball", Some(&signature))
+            .unwrap_err();
+        assert!(err.to_string().contains("different key"));
+    }
+
+    #[test]
+    fn test_unsigned_fails_in_strict_mode_or_with_trusted_key() {
+        let (_, key) = sign_for_tests(1, b"");
+        assert_eq!(
+            policy("someone-else", &key, false)
+                .check("owner/lib", "source tarball", b"tarball", None)
+                .unwrap(),
+            SignatureStatus::Unsigned
+        );
+        let err = policy("owner", &key, false)
+            .check("owner/lib", "source tarball", b"tarball", None)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("is not signed, but a trusted key is configured for it"));
+        let err = policy("someone-else", &key, true)
+            .check("owner/lib", "source tarball", b"tarball", None)
+            .unwrap_err();
+        assert!(err.to_string().contains("signatures are required"));
+    }
+
+    #[test]
+    fn test_signature_without_trusted_key() {
+        let (signature, key) = sign_for_tests(1, b"tarball");
+        assert_eq!(
+            policy("someone-else", &key, false)
+                .check("owner/lib", "source tarball", b"tarball", Some(&signature))
+                .unwrap(),
+            SignatureStatus::Untrusted
+        );
+        assert!(policy("someone-else", &key, true)
+            .check("owner/lib", "source tarball", b"tarball", Some(&signature))
+            .is_err());
+    }
+
+    #[test]
+    fn test_keys_for_owner_and_repository() {
+        let mut policy = policy("owner", "owner-key", false);
+        policy
+            .trusted_keys
+            .insert("owner/lib".to_string(), vec!["repo-key".to_string()]);
+        assert_eq!(policy.keys_for("owner/lib"), vec!["repo-key", "owner-key"]);
+        assert_eq!(policy.keys_for("owner/other"), vec!["owner-key"]);
+        assert!(policy.keys_for("other/lib").is_empty());
+        assert_eq!(policy.keys_for("owner"), vec!["owner-key"]);
+        assert!(policy.is_enabled());
+        assert!(!SignaturePolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn test_invalid_trusted_key() {
+        let (signature, _) = sign_for_tests(1, b"tarball");
+        let err = policy("owner", "not-a-key", false)
+            .check("owner/lib", "source tarball", b"tarball", Some(&signature))
+            .unwrap_err();
+        assert!(matches!(err, DepotError::Config(_)));
+    }
+
+    #[test]
+    fn test_policy_from_yaml() {
+        let policy: SignaturePolicy = serde_yaml::from_str(
+            "require: true\ntrusted_keys:\n  owner: [RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3]\n",
+        )
+        .unwrap();
+        assert!(policy.require);
+        assert_eq!(policy.keys_for("owner/lib").len(), 1);
+        assert!(parse_public_key(policy.keys_for("owner/lib")[0]).is_ok());
+    }
+}
@@ -0,0 +1,350 @@
+//! Heuristic checks for suspicious new dependencies
+//!
+//! Before a new dependency is added, its name is compared with popular and
+//! already-used packages to catch typosquats, and its GitHub metadata is
+//! checked for signs that it is not the package the user meant: a very young
+//! repository, an archived one, one without releases, or a fork of a more
+//! popular repository.
+
+use crate::core::{DepotError, DepotResult};
+use crate::di::traits::GitHubProvider;
+use crate::resolver::dependency_resolver::parse_repository;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Widely used Lua packages that typosquats are most likely to imitate
+pub const POPULAR_PACKAGES: &[&str] = &[
+    "brunoos/luasec",
+    "daurnimator/lua-http",
+    "kikito/inspect.lua",
+    "kikito/middleclass",
+    "kikito/semver.lua",
+    "leafo/moonscript",
+    "luafun/luafun",
+    "lunarmodules/busted",
+    "lunarmodules/ldoc",
+    "lunarmodules/lua-compat-5.3",
+    "lunarmodules/luacheck",
+    "lunarmodules/luafilesystem",
+    "lunarmodules/luassert",
+    "lunarmodules/luasocket",
+    "lunarmodules/penlight",
+    "luvit/luv",
+    "mpeterv/argparse",
+    "openresty/lua-cjson",
+    "pkulchenko/serpent",
+    "rxi/classic",
+    "rxi/json.lua",
+    "rxi/lume",
+    "tieske/date",
+];
+
+/// Repositories younger than this many days are flagged
+pub const MIN_REPOSITORY_AGE_DAYS: i64 = 30;
+
+/// What to do with a suspicious new dependency when depot is not interactive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuspiciousPackagePolicy {
+    /// Print the warnings and install anyway
+    #[default]
+    Warn,
+    /// Refuse to add the dependency
+    Deny,
+    /// Skip the checks entirely
+    Allow,
+}
+
+/// A reason a new dependency looks suspicious
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suspicion {
+    /// Name is an edit-distance neighbour of a popular or already-used package
+    SimilarName(String),
+    /// Repository was created this many days ago
+    NewRepository(i64),
+    /// Repository is archived
+    Archived,
+    /// Repository has no releases
+    NoReleases,
+    /// Repository is a fork of a more popular one
+    ForkOf { parent: String, parent_stars: u64 },
+}
+
+impl fmt::Display for Suspicion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Suspicion::SimilarName(other) => write!(f, "name is similar to {}", other),
+            Suspicion::NewRepository(days) => {
+                write!(f, "repository was created {} day(s) ago", days)
+            }
+            Suspicion::Archived => write!(f, "repository is archived"),
+            Suspicion::NoReleases => write!(f, "repository has no releases"),
+            Suspicion::ForkOf {
+                parent,
+                parent_stars,
+            } => write!(
+                f,
+                "repository is a fork of {} ({} stars)",
+                parent, parent_stars
+            ),
+        }
+    }
+}
+
+/// Known packages whose names are close to, but not the same as, `repository`
+///
+/// Both the full `owner/repo` names and the repository names alone are
+/// compared case-insensitively. Short names allow one edit, longer ones two.
+pub fn similar_names<'a>(repository: &str, known: &[&'a str]) -> Vec<&'a str> {
+    let candidate = repository.to_lowercase();
+    let candidate_repo = repo_name(&candidate);
+
+    let mut similar: Vec<&'a str> = known
+        .iter()
+        .copied()
+        .filter(|other| {
+            let other_lower = other.to_lowercase();
+            if other_lower == candidate {
+                return false;
+            }
+            let other_repo = repo_name(&other_lower);
+            is_neighbour(&candidate, &other_lower)
+                || (candidate_repo.len() >= 5 && is_neighbour(candidate_repo, other_repo))
+        })
+        .collect();
+    similar.sort_unstable();
+    similar.dedup();
+    similar
+}
+
+fn repo_name(repository: &str) -> &str {
+    repository.rsplit('/').next().unwrap_or(repository)
+}
+
+/// Whether two names differ, but by no more than the allowed number of edits
+fn is_neighbour(a: &str, b: &str) -> bool {
+    let max = if a.len().min(b.len()) < 12 { 1 } else { 2 };
+    let distance = edit_distance(a, b);
+    distance > 0 && distance <= max
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Check a prospective dependency against the name and repository heuristics
+///
+/// `known` lists the packages the project already uses; they are checked
+/// alongside [`POPULAR_PACKAGES`].
+pub async fn inspect(
+    github: &dyn GitHubProvider,
+    repository: &str,
+    known: &[String],
+) -> DepotResult<Vec<Suspicion>> {
+    let mut names: Vec<&str> = POPULAR_PACKAGES.to_vec();
+    names.extend(known.iter().map(String::as_str));
+    let mut suspicions: Vec<Suspicion> = similar_names(repository, &names)
+        .into_iter()
+        .map(|name| Suspicion::SimilarName(name.to_string()))
+        .collect();
+
+    let (owner, repo) = parse_repository(repository)?;
+    let info = github.get_repo(&owner, &repo).await?;
+
+    if let Some(created) = info
+        .created_at
+        .as_deref()
+        .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+    {
+        let days = (Utc::now() - created.with_timezone(&Utc)).num_days();
+        if days < MIN_REPOSITORY_AGE_DAYS {
+            suspicions.push(Suspicion::NewRepository(days));
+        }
+    }
+    if info.archived {
+        suspicions.push(Suspicion::Archived);
+    }
+    if github.get_releases(&owner, &repo).await?.is_empty() {
+        suspicions.push(Suspicion::NoReleases);
+    }
+    if let Some(parent) = info.parent.as_deref() {
+        if info.fork && parent.stargazers_count > info.stargazers_count {
+            suspicions.push(Suspicion::ForkOf {
+                parent: parent.full_name.clone(),
+                parent_stars: parent.stargazers_count,
+            });
+        }
+    }
+
+    Ok(suspicions)
+}
+
+/// Render the warnings printed for a suspicious dependency
+pub fn format_suspicions(repository: &str, suspicions: &[Suspicion]) -> String {
+    let mut output = format!("⚠️  {} looks suspicious:\n", repository);
+    for suspicion in suspicions {
+        output.push_str(&format!("    - {}\n", suspicion));
+    }
+    output
+}
+
+/// Apply the non-interactive policy to a suspicious dependency
+pub fn enforce(
+    policy: SuspiciousPackagePolicy,
+    repository: &str,
+    suspicions: &[Suspicion],
+) -> DepotResult<()> {
+    if policy == SuspiciousPackagePolicy::Deny && !suspicions.is_empty() {
+        return Err(DepotError::Package(format!(
+            "Refusing to add suspicious package {} (suspicious_packages: deny): {}",
+            repository,
+            suspicions
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join("; ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::github::{GitHubRelease, GitHubRepo};
+
+    fn repo(full_name: &str) -> GitHubRepo {
+        GitHubRepo {
+            id: 1,
+            name: full_name.rsplit('/').next().unwrap().to_string(),
+            full_name: full_name.to_string(),
+            default_branch: "main".to_string(),
+            description: None,
+            created_at: Some("2015-01-01T00:00:00Z".to_string()),
+            pushed_at: None,
+            archived: false,
+            disabled: false,
+            fork: false,
+            stargazers_count: 10,
+            parent: None,
+        }
+    }
+
+    fn release() -> GitHubRelease {
+        GitHubRelease {
+            tag_name: "v1.0.0".to_string(),
+            name: None,
+            draft: false,
+            prerelease: false,
+            tarball_url: String::new(),
+            zipball_url: String::new(),
+            assets: Vec::new(),
+            body: None,
+            published_at: None,
+        }
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("luasocket", "luasocket"), 0);
+        assert_eq!(edit_distance("luasocket", "luasockt"), 1);
+        assert_eq!(edit_distance("luasocket", "luasokcet"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_similar_names() {
+        let known = ["lunarmodules/luasocket", "rxi/json.lua", "rxi/lume"];
+
+        assert_eq!(
+            similar_names("lunarmodule/luasocket", &known),
+            vec!["lunarmodules/luasocket"]
+        );
+        assert_eq!(
+            similar_names("someone/luasockets", &known),
+            vec!["lunarmodules/luasocket"]
+        );
+        assert_eq!(similar_names("rxi/json.lau", &known), vec!["rxi/json.lua"]);
+
+        // The package itself, different casing, and unrelated names are fine
+        assert!(similar_names("LunarModules/LuaSocket", &known).is_empty());
+        assert!(similar_names("someone/penlight", &known).is_empty());
+        // Short repository names are only compared in full
+        assert!(similar_names("other/lumo", &known).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_inspect_established_package() {
+        let github = MockGitHubProvider::new();
+        github.add_repo("owner", "lib", repo("owner/lib"));
+        github.add_release("owner", "lib", release());
+
+        let suspicions = inspect(&github, "owner/lib", &[]).await.unwrap();
+        assert!(suspicions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_inspect_suspicious_fork() {
+        let github = MockGitHubProvider::new();
+        let mut info = repo("someone/penlight");
+        info.created_at = Some(Utc::now().to_rfc3339());
+        info.archived = true;
+        info.fork = true;
+        info.stargazers_count = 0;
+        let mut parent = repo("lunarmodules/penlight");
+        parent.stargazers_count = 1800;
+        info.parent = Some(Box::new(parent));
+        github.add_repo("someone", "penlight", info);
+
+        let known = vec!["someone/penlihgt".to_string()];
+        let suspicions = inspect(&github, "someone/penlight", &known).await.unwrap();
+        assert_eq!(
+            suspicions,
+            vec![
+                Suspicion::SimilarName("someone/penlihgt".to_string()),
+                Suspicion::NewRepository(0),
+                Suspicion::Archived,
+                Suspicion::NoReleases,
+                Suspicion::ForkOf {
+                    parent: "lunarmodules/penlight".to_string(),
+                    parent_stars: 1800,
+                },
+            ]
+        );
+
+        let output = format_suspicions("someone/penlight", &suspicions);
+        assert!(
+            output.contains("    - repository is a fork of lunarmodules/penlight (1800 stars)\n")
+        );
+    }
+
+    #[test]
+    fn test_enforce_policy() {
+        let suspicions = vec![Suspicion::Archived];
+        assert!(enforce(SuspiciousPackagePolicy::Warn, "owner/lib", &suspicions).is_ok());
+        assert!(enforce(SuspiciousPackagePolicy::Deny, "owner/lib", &[]).is_ok());
+
+        let err = enforce(SuspiciousPackagePolicy::Deny, "owner/lib", &suspicions).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Refusing to add suspicious package owner/lib"));
+
+        let policy: SuspiciousPackagePolicy = serde_yaml::from_str("deny").unwrap();
+        assert_eq!(policy, SuspiciousPackagePolicy::Deny);
+    }
+}
@@ -0,0 +1,144 @@
+//! Trust-on-first-use pinning of GitHub repository identity
+//!
+//! `owner/repo` is only a name: after a rename or account deletion someone
+//! else can register it. The numeric GitHub repository ID is recorded in
+//! `depot.lock` the first time a package is locked and every later resolve is
+//! checked against it. `depot trust update <pkg>` accepts a changed ID.
+
+use crate::core::{DepotError, DepotResult};
+use crate::di::traits::GitHubProvider;
+use crate::package::lockfile::{Lockfile, LOCKFILE_NAME};
+use crate::resolver::dependency_resolver::parse_repository;
+
+/// Fetch the current GitHub ID of `owner/repo`
+pub async fn repository_id(github: &dyn GitHubProvider, repository: &str) -> DepotResult<u64> {
+    let (owner, repo) = parse_repository(repository)?;
+    Ok(github.get_repo(&owner, &repo).await?.id)
+}
+
+/// Compare a repository's current ID with the one pinned in `depot.lock`
+pub fn check_pin(repository: &str, pinned: Option<u64>, current: u64) -> DepotResult<()> {
+    match pinned {
+        Some(pinned) if pinned != current => Err(DepotError::Package(format!(
+            "Repository identity of {} changed: {} pins GitHub repository ID {}, but it now resolves to ID {}. \
+             The repository may have been deleted and re-created by someone else. \
+             If the change is expected, run 'depot trust update {}'",
+            repository, LOCKFILE_NAME, pinned, current, repository
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Fetch a repository's ID and check it against the pinned one, returning the ID to record
+pub async fn verify_repository(
+    github: &dyn GitHubProvider,
+    repository: &str,
+    pinned: Option<u64>,
+) -> DepotResult<u64> {
+    let current = repository_id(github, repository).await?;
+    check_pin(repository, pinned, current)?;
+    Ok(current)
+}
+
+/// Re-pin a locked package to its repository's current ID
+///
+/// Returns the previously pinned ID (if any) and the new one.
+pub async fn update_pin(
+    github: &dyn GitHubProvider,
+    lockfile: &mut Lockfile,
+    package: &str,
+) -> DepotResult<(Option<u64>, u64)> {
+    let locked = lockfile.packages.get_mut(package).ok_or_else(|| {
+        DepotError::Package(format!(
+            "Package '{}' not found in {}",
+            package, LOCKFILE_NAME
+        ))
+    })?;
+
+    let current = repository_id(github, &locked.repository).await?;
+    let previous = locked.repository_id.replace(current);
+    Ok((previous, current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::mocks::MockGitHubProvider;
+    use crate::package::lockfile::LockedPackage;
+    use std::collections::HashMap;
+
+    fn lockfile_with(repository_id: Option<u64>) -> Lockfile {
+        let mut lockfile = Lockfile::new();
+        lockfile.add_package(
+            "owner/lib".to_string(),
+            LockedPackage {
+                version: "1.0.0".to_string(),
+                repository: "owner/lib".to_string(),
+                ref_type: "Release".to_string(),
+                ref_value: "v1.0.0".to_string(),
+                commit_sha: "abc123".to_string(),
+                tarball_url: String::new(),
+                checksum: "blake3:00".to_string(),
+                size: 1,
+                dependencies: HashMap::new(),
+                build: None,
+                native_code: None,
+                license: None,
+                capabilities: Vec::new(),
+                repository_id,
+            },
+        );
+        lockfile
+    }
+
+    #[test]
+    fn test_check_pin() {
+        assert!(check_pin("owner/lib", None, 7).is_ok());
+        assert!(check_pin("owner/lib", Some(7), 7).is_ok());
+
+        let err = check_pin("owner/lib", Some(7), 8).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("pins GitHub repository ID 7, but it now resolves to ID 8"));
+        assert!(message.contains("depot trust update owner/lib"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_repository() {
+        let github = MockGitHubProvider::new();
+        github.set_repo_id("owner", "lib", 42);
+
+        assert_eq!(
+            verify_repository(&github, "owner/lib", None).await.unwrap(),
+            42
+        );
+        assert_eq!(
+            verify_repository(&github, "owner/lib", Some(42))
+                .await
+                .unwrap(),
+            42
+        );
+        assert!(verify_repository(&github, "owner/lib", Some(41))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_pin() {
+        let github = MockGitHubProvider::new();
+        github.set_repo_id("owner", "lib", 99);
+
+        let mut lockfile = lockfile_with(Some(42));
+        let (previous, current) = update_pin(&github, &mut lockfile, "owner/lib")
+            .await
+            .unwrap();
+        assert_eq!((previous, current), (Some(42), 99));
+        assert_eq!(
+            lockfile.get_package("owner/lib").unwrap().repository_id,
+            Some(99)
+        );
+
+        assert!(update_pin(&github, &mut lockfile, "owner/other")
+            .await
+            .is_err());
+    }
+}
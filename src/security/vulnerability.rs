@@ -5,12 +5,16 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Hash)]
 pub enum Severity {
     /// Low severity - minor issue, unlikely to be exploited
+    #[serde(alias = "low")]
     Low,
     /// Medium severity - moderate risk, should be addressed
+    #[serde(alias = "medium")]
     Medium,
     /// High severity - significant risk, should be fixed soon
+    #[serde(alias = "high")]
     High,
     /// Critical severity - immediate action required
+    #[serde(alias = "critical")]
     Critical,
 }
 
@@ -45,6 +49,12 @@ pub struct Vulnerability {
     pub severity: Severity,
     /// CVE identifier (if available)
     pub cve: Option<String>,
+    /// Advisory identifier (e.g. a GHSA or OSV ID)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Other identifiers for the same advisory
+    #[serde(default)]
+    pub aliases: Vec<String>,
     /// Advisory title
     pub title: String,
     /// Detailed description
@@ -57,11 +67,13 @@ pub struct Vulnerability {
 
 impl Vulnerability {
     /// Check if a version is affected by this vulnerability
+    ///
+    /// Accepts release tags with a leading `v` (e.g. `v1.2.0`).
     pub fn affects_version(&self, version: &str) -> bool {
         use crate::core::version::{parse_compound_constraint, Version};
 
         if let Ok(constraint) = parse_compound_constraint(&self.affected_versions) {
-            if let Ok(v) = Version::parse(version) {
+            if let Ok(v) = Version::parse(version.trim_start_matches('v')) {
                 return v.satisfies(&constraint);
             }
         }
@@ -70,10 +82,22 @@ impl Vulnerability {
     }
 }
 
+/// A vulnerability suppressed by an audit ignore entry
+#[derive(Debug, Clone)]
+pub struct IgnoredVulnerability {
+    pub vulnerability: Vulnerability,
+    /// Justification given in the ignore entry
+    pub reason: String,
+    /// Date the ignore entry expires (YYYY-MM-DD)
+    pub expires: String,
+}
+
 /// Collection of vulnerabilities for reporting
 #[derive(Debug, Clone, Default)]
 pub struct VulnerabilityReport {
     pub vulnerabilities: Vec<Vulnerability>,
+    /// Findings suppressed by the audit ignore list; these do not fail the audit
+    pub ignored: Vec<IgnoredVulnerability>,
     pub package_count: usize,
     pub checked_packages: usize,
 }
@@ -82,6 +106,7 @@ impl VulnerabilityReport {
     pub fn new() -> Self {
         Self {
             vulnerabilities: Vec::new(),
+            ignored: Vec::new(),
             package_count: 0,
             checked_packages: 0,
         }
@@ -153,6 +178,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("3.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -170,6 +197,8 @@ mod tests {
             title: "Test Vulnerability".to_string(),
             description: "Test description".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: vec!["https://example.com/advisory".to_string()],
         };
 
@@ -188,6 +217,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -215,6 +246,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
@@ -235,6 +268,8 @@ mod tests {
             title: "Test1".to_string(),
             description: "Test1".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         });
 
@@ -246,6 +281,8 @@ mod tests {
             title: "Test2".to_string(),
             description: "Test2".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         });
 
@@ -257,6 +294,8 @@ mod tests {
             title: "Test3".to_string(),
             description: "Test3".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         });
 
@@ -280,6 +319,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         });
 
@@ -299,6 +340,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: None,
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         });
 
@@ -316,6 +359,8 @@ mod tests {
             title: "Test".to_string(),
             description: "Test".to_string(),
             fixed_in: Some("2.0.0".to_string()),
+            id: None,
+            aliases: Vec::new(),
             references: Vec::new(),
         };
 
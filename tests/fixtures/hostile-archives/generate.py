@@ -0,0 +1,139 @@
+#!/usr/bin/env python3
+"""Regenerate the hostile archive corpus used by tests/integration/hostile_archives.rs.
+
+Run from this directory: python3 generate.py
+Archives are written deterministically (fixed mtimes) so regenerating them
+does not produce spurious diffs.
+"""
+
+import gzip
+import io
+import tarfile
+import zipfile
+
+MTIME = 1700000000
+
+
+def tar_gz(name, entries, pax_header=False):
+    raw = io.BytesIO()
+    fmt = tarfile.PAX_FORMAT if pax_header else tarfile.GNU_FORMAT
+    pax = {"comment": "0123456789abcdef"} if pax_header else None
+    with tarfile.open(fileobj=raw, mode="w", format=fmt, pax_headers=pax) as tar:
+        for entry in entries:
+            info = tarfile.TarInfo(entry["name"])
+            info.mtime = MTIME
+            info.type = entry.get("type", tarfile.REGTYPE)
+            info.mode = entry.get("mode", 0o755 if info.type == tarfile.DIRTYPE else 0o644)
+            info.linkname = entry.get("link", "")
+            data = entry.get("data", b"")
+            if info.type == tarfile.REGTYPE:
+                info.size = len(data)
+                tar.addfile(info, io.BytesIO(data))
+            else:
+                tar.addfile(info)
+    with open(name, "wb") as out:
+        with gzip.GzipFile(fileobj=out, mode="wb", mtime=MTIME, filename="") as gz:
+            gz.write(raw.getvalue())
+
+
+def zip_file(name, entries):
+    with zipfile.ZipFile(name, "w", compression=zipfile.ZIP_DEFLATED) as zf:
+        for entry in entries:
+            info = zipfile.ZipInfo(entry["name"], date_time=(2023, 11, 14, 0, 0, 0))
+            info.compress_type = zipfile.ZIP_DEFLATED
+            info.create_system = 3  # Unix, so external_attr carries the file mode
+            info.external_attr = entry.get("mode", 0o100644) << 16
+            zf.writestr(info, entry.get("data", b""))
+
+
+def d(name):
+    return {"name": name, "type": tarfile.DIRTYPE}
+
+
+def f(name, data=b"return {}\n"):
+    return {"name": name, "data": data}
+
+
+# Well-formed archive in the shape GitHub serves, with an internal symlink and hardlink
+tar_gz(
+    "benign.tar.gz",
+    [
+        d("owner-pkg-abc123/"),
+        f("owner-pkg-abc123/init.lua"),
+        d("owner-pkg-abc123/lib/"),
+        {"name": "owner-pkg-abc123/lib/alias.lua", "type": tarfile.SYMTYPE, "link": "../init.lua"},
+        {"name": "owner-pkg-abc123/copy.lua", "type": tarfile.LNKTYPE, "link": "owner-pkg-abc123/init.lua"},
+    ],
+    pax_header=True,
+)
+
+tar_gz("absolute-path.tar.gz", [d("pkg/"), f("/tmp/depot-hostile-absolute.lua")])
+tar_gz("path-traversal.tar.gz", [d("pkg/"), f("pkg/../../depot-hostile-traversal.lua")])
+tar_gz(
+    "symlink-escape.tar.gz",
+    [d("pkg/"), {"name": "pkg/escape", "type": tarfile.SYMTYPE, "link": "../../.."}],
+)
+tar_gz(
+    "symlink-absolute.tar.gz",
+    [d("pkg/"), {"name": "pkg/passwd", "type": tarfile.SYMTYPE, "link": "/etc/passwd"}],
+)
+tar_gz(
+    "symlink-write-through.tar.gz",
+    [
+        d("pkg/"),
+        {"name": "pkg/out", "type": tarfile.SYMTYPE, "link": "../.."},
+        f("pkg/out/depot-hostile-through.lua"),
+    ],
+)
+# Each link stays inside on its own; resolved together, pkg/lib leaves the destination
+tar_gz(
+    "symlink-chain.tar.gz",
+    [
+        d("pkg/"),
+        d("pkg/x/"),
+        d("pkg/x/y/"),
+        d("pkg/x/y/z/"),
+        {"name": "pkg/x/y/z/s", "type": tarfile.SYMTYPE, "link": "../../../.."},
+        {"name": "pkg/lib", "type": tarfile.SYMTYPE, "link": "x/y/z/s/../../../../../etc"},
+    ],
+)
+tar_gz(
+    "hardlink-escape.tar.gz",
+    [d("pkg/"), {"name": "pkg/shadow", "type": tarfile.LNKTYPE, "link": "../../etc/passwd"}],
+)
+tar_gz("device-char.tar.gz", [d("pkg/"), {"name": "pkg/null", "type": tarfile.CHRTYPE}])
+tar_gz("device-fifo.tar.gz", [d("pkg/"), {"name": "pkg/pipe", "type": tarfile.FIFOTYPE}])
+tar_gz("duplicate-entry.tar.gz", [d("pkg/"), f("pkg/init.lua"), f("pkg/init.lua", b"os.exit()\n")])
+tar_gz("multiple-roots.tar.gz", [d("pkg/"), f("pkg/init.lua"), d("other/"), f("other/init.lua")])
+# 8 MiB of zeros; the tests extract it with a 1 MiB limit
+tar_gz("size-bomb.tar.gz", [d("pkg/"), f("pkg/zeros.bin", bytes(8 * 1024 * 1024))])
+# 300 entries; the tests extract it with a 100 entry limit
+tar_gz("count-bomb.tar.gz", [d("pkg/")] + [f("pkg/f%03d.lua" % i, b"") for i in range(299)])
+
+zip_file("zip-traversal.zip", [{"name": "pkg/", "mode": 0o040755}, {"name": "pkg/../../depot-hostile-zip.lua"}])
+zip_file("zip-absolute.zip", [{"name": "pkg/", "mode": 0o040755}, {"name": "/tmp/depot-hostile-zip.lua"}])
+zip_file(
+    "zip-symlink-escape.zip",
+    [{"name": "pkg/", "mode": 0o040755}, {"name": "pkg/escape", "mode": 0o120777, "data": b"../../.."}],
+)
+zip_file(
+    "zip-symlink-chain.zip",
+    [
+        {"name": "pkg/", "mode": 0o040755},
+        {"name": "pkg/x/y/s", "mode": 0o120777, "data": b"../../.."},
+        {"name": "pkg/out", "mode": 0o120777, "data": b"x/y/s/.."},
+    ],
+)
+# Directory created through a chained link that resolves outside the destination
+zip_file(
+    "zip-dir-through-symlink.zip",
+    [
+        {"name": "pkg/", "mode": 0o040755},
+        {"name": "pkg/x/y/s", "mode": 0o120777, "data": b"../../.."},
+        {"name": "pkg/out", "mode": 0o120777, "data": b"x/y/s/.."},
+        {"name": "pkg/out/depot-hostile-dir/init.lua"},
+    ],
+)
+zip_file("zip-device.zip", [{"name": "pkg/", "mode": 0o040755}, {"name": "pkg/null", "mode": 0o020644}])
+zip_file("zip-duplicate.zip", [{"name": "pkg/", "mode": 0o040755}, {"name": "pkg/init.lua"}, {"name": "pkg/init.lua"}])
+zip_file("zip-bomb.zip", [{"name": "pkg/", "mode": 0o040755}, {"name": "pkg/zeros.bin", "data": bytes(8 * 1024 * 1024)}])
@@ -0,0 +1,283 @@
+//! Tests for `depot audit` command
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path) {
+    fs::write(
+        project_root.join("package.yaml"),
+        r#"
+name: test-project
+version: 1.0.0
+dependencies:
+  owner/json: ^1.0.0
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: abc123
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:abc
+    size: 512
+"#,
+    )
+    .unwrap();
+}
+
+fn write_advisory(project_root: &std::path::Path) -> std::path::PathBuf {
+    let db = project_root.join("advisories");
+    fs::create_dir_all(&db).unwrap();
+    fs::write(
+        db.join("INT-2024-001.json"),
+        r#"{
+  "id": "INT-2024-001",
+  "aliases": ["CVE-2024-0001"],
+  "summary": "Unbounded recursion in decoder",
+  "details": "Deeply nested input exhausts the C stack.",
+  "database_specific": {"severity": "CRITICAL"},
+  "affected": [{
+    "package": {"ecosystem": "Lua", "name": "owner/json"},
+    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "1.1.0"}]}]
+  }]
+}"#,
+    )
+    .unwrap();
+    db
+}
+
+#[test]
+fn test_audit_offline_reports_local_advisory() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+
+    let db = write_advisory(project_root);
+
+    let output = depot_command()
+        .arg("audit")
+        .arg("--offline")
+        .arg("--db")
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Unbounded recursion in decoder"));
+    assert!(stdout.contains("Fixed in: 1.1.0"));
+}
+
+#[test]
+fn test_audit_offline_clean() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+
+    let db = project_root.join("advisories");
+    fs::create_dir_all(&db).unwrap();
+
+    let output = depot_command()
+        .arg("audit")
+        .arg("--offline")
+        .arg("--db")
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No known vulnerabilities found"));
+}
+
+#[test]
+fn test_audit_format_json() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+    let db = write_advisory(project_root);
+
+    let output = depot_command()
+        .args(["audit", "--offline", "--format", "json", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let vuln = &report["vulnerabilities"][0];
+    assert_eq!(report["summary"]["critical"], 1);
+    assert_eq!(vuln["id"], "INT-2024-001");
+    assert_eq!(vuln["aliases"][0], "CVE-2024-0001");
+    assert_eq!(vuln["installed_version"], "v1.0.0");
+    assert_eq!(vuln["fixed_in"], "1.1.0");
+    assert_eq!(vuln["paths"][0][0], "test-project");
+    assert_eq!(vuln["paths"][0][1], "owner/json");
+}
+
+#[test]
+fn test_audit_format_sarif() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+    let db = write_advisory(project_root);
+
+    let output = depot_command()
+        .args(["audit", "--offline", "--format", "sarif", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "INT-2024-001");
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["properties"]["fixedIn"], "1.1.0");
+}
+
+#[test]
+fn test_audit_ignore_entry_suppresses_finding() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+    let db = write_advisory(project_root);
+
+    let manifest = fs::read_to_string(project_root.join("package.yaml")).unwrap();
+    fs::write(
+        project_root.join("package.yaml"),
+        manifest
+            + r#"audit:
+  ignore:
+    - id: CVE-2024-0001
+      package: owner/json
+      reason: Only used to decode our own config files
+      expires: 2999-12-31
+"#,
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["audit", "--offline", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No known vulnerabilities found"));
+    assert!(stdout.contains("Ignored: 1 finding(s)"));
+    assert!(stdout.contains("Only used to decode our own config files"));
+}
+
+#[test]
+fn test_audit_expired_ignore_entry_fails() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+    let db = write_advisory(project_root);
+
+    fs::create_dir_all(project_root.join(".depot")).unwrap();
+    fs::write(
+        project_root.join(".depot/audit.yaml"),
+        r#"ignore:
+  - id: INT-2024-001
+    reason: Waiting on upstream fix
+    expires: 2020-01-01
+"#,
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["audit", "--offline", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("INT-2024-001 expired on 2020-01-01"));
+}
+
+#[test]
+fn test_audit_fix_nothing_to_fix() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+
+    let db = project_root.join("advisories");
+    fs::create_dir_all(&db).unwrap();
+
+    let output = depot_command()
+        .args(["audit", "fix", "--offline", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No vulnerable packages to fix"));
+}
+
+#[test]
+fn test_audit_fix_respects_ignore_list() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+    write_project(project_root);
+    let db = write_advisory(project_root);
+
+    fs::create_dir_all(project_root.join(".depot")).unwrap();
+    fs::write(
+        project_root.join(".depot/audit.yaml"),
+        r#"ignore:
+  - id: INT-2024-001
+    reason: Waiting on upstream fix
+    expires: 2999-01-01
+"#,
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["audit", "fix", "--offline", "--db"])
+        .arg(&db)
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No vulnerable packages to fix"));
+}
+
+#[test]
+fn test_audit_health_conflicts_with_offline() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .args(["audit", "--health", "--offline"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
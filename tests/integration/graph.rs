@@ -0,0 +1,96 @@
+//! Tests for `depot graph` command
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path) {
+    fs::write(
+        project_root.join("package.yaml"),
+        r#"
+name: test-project
+version: 1.0.0
+dependencies:
+  owner/web: ^2.0.0
+dev_dependencies:
+  owner/busted: ^2.0.0
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/web:
+    version: 2.0.0
+    repository: owner/web
+    ref_type: Release
+    ref_value: v2.0.0
+    commit_sha: abc123
+    tarball_url: https://api.github.com/repos/owner/web/tarball/v2.0.0
+    checksum: blake3:abc
+    size: 1024
+    dependencies:
+      owner/cjson: ^2.1.0
+  owner/cjson:
+    version: 2.1.0
+    repository: owner/cjson
+    ref_type: Release
+    ref_value: v2.1.0
+    commit_sha: def456
+    tarball_url: https://api.github.com/repos/owner/cjson/tarball/v2.1.0
+    checksum: blake3:def
+    size: 512
+    native_code:
+      types: [c]
+      build_required: true
+      prebuilt_used: false
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_graph_dot_default() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .arg("graph")
+        .arg("--highlight-native")
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("digraph dependencies {"));
+    assert!(stdout.contains("\"owner/web\" -> \"owner/cjson\""));
+    assert!(stdout.contains("native: c"));
+    assert!(!stdout.contains("owner/busted"));
+}
+
+#[test]
+fn test_graph_json_with_dev_and_depth() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .args(["graph", "--format", "json", "--dev", "--depth", "1"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["test-project", "owner/busted", "owner/web"]);
+}
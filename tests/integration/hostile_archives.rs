@@ -0,0 +1,172 @@
+//! Regression tests for extracting hostile package archives
+//!
+//! The corpus lives in tests/fixtures/hostile-archives; see its README for
+//! what each archive attempts.
+
+use depot::package::extractor::{ArchiveLimits, PackageExtractor};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/hostile-archives")
+        .join(name)
+}
+
+/// Extract a corpus archive into `<temp>/lua_modules`, returning the error message
+fn extract_err(name: &str, limits: ArchiveLimits) -> (TempDir, String) {
+    let temp = TempDir::new().unwrap();
+    let dest = temp.path().join("lua_modules");
+    fs::create_dir_all(&dest).unwrap();
+
+    let archive = temp.path().join(name);
+    fs::copy(fixture(name), &archive).unwrap();
+
+    let err = PackageExtractor::new(dest)
+        .with_limits(limits)
+        .extract(&archive)
+        .expect_err(&format!("{} should be rejected", name))
+        .to_string();
+    (temp, err)
+}
+
+fn assert_rejected(name: &str, expected: &str) {
+    let (temp, err) = extract_err(name, ArchiveLimits::default());
+    assert!(
+        err.contains(expected),
+        "{}: expected error containing '{}', got '{}'",
+        name,
+        expected,
+        err
+    );
+
+    // Nothing may be written next to the destination, and the temp dir is cleaned up
+    let mut leftovers: Vec<String> = fs::read_dir(temp.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n != "lua_modules" && n != name)
+        .collect();
+    leftovers.extend(
+        fs::read_dir(temp.path().join("lua_modules"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned()),
+    );
+    assert!(leftovers.is_empty(), "{}: left {:?}", name, leftovers);
+}
+
+#[test]
+fn test_benign_archive_extracts() {
+    let temp = TempDir::new().unwrap();
+    let archive = temp.path().join("benign.tar.gz");
+    fs::copy(fixture("benign.tar.gz"), &archive).unwrap();
+
+    let root = PackageExtractor::new(temp.path().to_path_buf())
+        .extract(&archive)
+        .unwrap();
+
+    assert!(root.ends_with("owner-pkg-abc123"));
+    assert_eq!(
+        fs::read_to_string(root.join("copy.lua")).unwrap(),
+        "return {}\n"
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        fs::read_to_string(root.join("lib/alias.lua")).unwrap(),
+        "return {}\n"
+    );
+}
+
+#[test]
+fn test_rejects_absolute_paths() {
+    assert_rejected("absolute-path.tar.gz", "has an absolute path");
+    assert_rejected("zip-absolute.zip", "has an absolute path");
+}
+
+#[test]
+fn test_rejects_path_traversal() {
+    assert_rejected("path-traversal.tar.gz", "uses '..'");
+    assert_rejected("zip-traversal.zip", "uses '..'");
+}
+
+#[test]
+fn test_rejects_escaping_links() {
+    assert_rejected(
+        "symlink-escape.tar.gz",
+        "points outside the package directory",
+    );
+    assert_rejected(
+        "symlink-absolute.tar.gz",
+        "points outside the package directory",
+    );
+    assert_rejected(
+        "symlink-write-through.tar.gz",
+        "points outside the package directory",
+    );
+    assert_rejected(
+        "symlink-chain.tar.gz",
+        "Archive symlink 'pkg/lib' points outside the package directory",
+    );
+    assert_rejected("hardlink-escape.tar.gz", "uses '..'");
+    assert_rejected(
+        "zip-symlink-escape.zip",
+        "points outside the package directory",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_symlink_chains_in_zips() {
+    assert_rejected(
+        "zip-symlink-chain.zip",
+        "Archive symlink 'pkg/out' points outside the package directory",
+    );
+    assert_rejected(
+        "zip-dir-through-symlink.zip",
+        "'pkg/out/depot-hostile-dir/init.lua' escapes the package directory",
+    );
+}
+
+#[test]
+fn test_rejects_device_files() {
+    assert_rejected("device-char.tar.gz", "is a device or FIFO");
+    assert_rejected("device-fifo.tar.gz", "is a device or FIFO");
+    assert_rejected("zip-device.zip", "is a device or FIFO");
+}
+
+#[test]
+fn test_rejects_duplicate_entries() {
+    assert_rejected("duplicate-entry.tar.gz", "duplicate entry 'pkg/init.lua'");
+    assert_rejected("zip-duplicate.zip", "duplicate entry 'pkg/init.lua'");
+}
+
+#[test]
+fn test_rejects_multiple_roots() {
+    assert_rejected("multiple-roots.tar.gz", "single root directory");
+}
+
+#[test]
+fn test_enforces_size_limit() {
+    let limits = ArchiveLimits {
+        max_total_size: 1024 * 1024,
+        ..ArchiveLimits::default()
+    };
+    for name in ["size-bomb.tar.gz", "zip-bomb.zip"] {
+        let (_temp, err) = extract_err(name, limits);
+        assert!(
+            err.contains("expands to more than 1048576 bytes"),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn test_enforces_entry_limit() {
+    let limits = ArchiveLimits {
+        max_entries: 100,
+        ..ArchiveLimits::default()
+    };
+    let (_temp, err) = extract_err("count-bomb.tar.gz", limits);
+    assert!(err.contains("more than 100 entries"), "{}", err);
+}
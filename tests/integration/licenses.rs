@@ -0,0 +1,115 @@
+//! Tests for `depot licenses` commands
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+const MIT_TEXT: &str =
+    "MIT License\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\n";
+
+fn write_project(project_root: &std::path::Path, policy: &str) {
+    fs::write(
+        project_root.join("package.yaml"),
+        format!(
+            "name: test-project\nversion: 1.0.0\ndependencies:\n  owner/web: ^2.0.0\n{}",
+            policy
+        ),
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/web:
+    version: v2.0.0
+    repository: owner/web
+    ref_type: Release
+    ref_value: v2.0.0
+    commit_sha: 1111111
+    tarball_url: https://api.github.com/repos/owner/web/tarball/v2.0.0
+    checksum: blake3:aaaa
+    size: 1024
+    license: GPL-3.0-only
+    dependencies:
+      owner/json: ^1.0.0
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:bbbb
+    size: 512
+"#,
+    )
+    .unwrap();
+
+    let json_dir = project_root.join("lua_modules/owner/json");
+    fs::create_dir_all(&json_dir).unwrap();
+    fs::write(json_dir.join("LICENSE"), MIT_TEXT).unwrap();
+}
+
+#[test]
+fn test_licenses_check_denied() {
+    let temp = TempDir::new().unwrap();
+    write_project(
+        temp.path(),
+        "license_policy:\n  allow: [MIT, Apache-2.0]\n  deny: [GPL-3.0-only]\n",
+    );
+
+    let output = depot_command()
+        .args(["licenses", "check"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // The license of owner/json is detected from its LICENSE file
+    assert!(stdout.contains("owner/json v1.0.0  MIT"));
+    assert!(stderr.contains("owner/web (GPL-3.0-only) is denied"));
+}
+
+#[test]
+fn test_licenses_check_exception() {
+    let temp = TempDir::new().unwrap();
+    write_project(
+        temp.path(),
+        "license_policy:\n  allow: [MIT]\n  exceptions:\n    - package: owner/web\n      licenses: [GPL-3.0-only]\n      reason: Only used by build scripts\n",
+    );
+
+    let output = depot_command()
+        .args(["licenses", "check"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("GPL-3.0-only (exception)"));
+    assert!(stdout.contains("All licenses comply"));
+}
+
+#[test]
+fn test_licenses_notice() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path(), "");
+
+    let output = depot_command()
+        .args(["licenses", "notice", "--output", "NOTICES.txt"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let notices = fs::read_to_string(temp.path().join("NOTICES.txt")).unwrap();
+    assert!(notices.contains("owner/json v1.0.0"));
+    assert!(notices.contains("Permission is hereby granted"));
+    assert!(notices.contains("owner/web v2.0.0"));
+    assert!(notices.contains("License: GPL-3.0-only"));
+}
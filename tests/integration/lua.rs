@@ -29,6 +29,20 @@ fn test_lua_list_remote_command() {
     );
 }
 
+#[test]
+fn test_lua_list_remote_shows_source_versions() {
+    let output = depot_command()
+        .arg("lua")
+        .arg("list-remote")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("--from-source"));
+    assert!(stdout.contains("5.2.4"));
+}
+
 #[test]
 fn test_lua_current_command() {
     let output = depot_command().arg("lua").arg("current").output().unwrap();
@@ -143,3 +157,86 @@ fn test_lua_local_command() {
         assert!(project_root.join(".lua-version").exists());
     }
 }
+
+/// Depot home for a command run with `home` as HOME and XDG_CONFIG_HOME
+#[cfg(unix)]
+fn shim_depot_home(home: &std::path::Path) -> std::path::PathBuf {
+    if cfg!(target_os = "macos") {
+        home.join("Library")
+            .join("Application Support")
+            .join("depot")
+    } else {
+        home.join("config").join("depot")
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lua_shim_dispatches_to_project_version() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+    use std::process::Command;
+
+    let home = TempDir::new().unwrap();
+    let depot_home = shim_depot_home(home.path());
+    for version in ["5.1.5", "5.4.8"] {
+        let bin_dir = depot_home.join("versions").join(version).join("bin");
+        fs::create_dir_all(&bin_dir).unwrap();
+        let lua = bin_dir.join("lua");
+        fs::write(
+            &lua,
+            format!(
+                "#!/bin/sh\necho \"{} args:$*\"\necho \"init:$LUA_INIT\"\n",
+                version
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&lua, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    fs::write(depot_home.join("current"), "5.1.5").unwrap();
+
+    let shim = home.path().join("lua");
+    symlink(env!("CARGO_BIN_EXE_depot"), &shim).unwrap();
+
+    let project = TempDir::new().unwrap();
+    fs::write(
+        project.path().join("package.yaml"),
+        "name: test\nversion: 1.0.0\n",
+    )
+    .unwrap();
+    fs::create_dir_all(project.path().join("lua_modules")).unwrap();
+    fs::write(project.path().join(".lua-version"), "5.4\n").unwrap();
+
+    let run = |extra_env: &[(&str, &str)]| {
+        let mut command = Command::new(&shim);
+        command
+            .args(["-e", "print(1)"])
+            .current_dir(project.path())
+            .env("HOME", home.path())
+            .env("XDG_CONFIG_HOME", home.path().join("config"))
+            .env_remove("DEPOT_LUA_VERSION")
+            .env_remove("LUA_INIT");
+        for (key, value) in extra_env {
+            command.env(key, value);
+        }
+        let output = command.output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let stdout = run(&[]);
+    assert!(stdout.contains("5.4.8 args:-e print(1)"));
+    assert!(stdout.contains("require('depot.loader')"));
+    assert!(project
+        .path()
+        .join("lua_modules")
+        .join("depot")
+        .join("loader.lua")
+        .is_file());
+
+    let stdout = run(&[("DEPOT_LUA_VERSION", "5.1.5")]);
+    assert!(stdout.contains("5.1.5 args:-e print(1)"));
+}
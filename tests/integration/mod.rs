@@ -2,19 +2,28 @@
 //!
 //! This module contains all integration tests for Depot CLI commands.
 
+pub mod audit;
 pub mod build;
 pub mod clean;
 pub mod common;
 pub mod error_recovery;
+pub mod graph;
+pub mod hostile_archives;
 pub mod init;
 pub mod install;
 pub mod install_comprehensive;
 pub mod interactive;
 pub mod list;
+pub mod licenses;
 pub mod lua;
 pub mod package;
 pub mod plugin;
+pub mod policy;
 pub mod remove;
 pub mod run;
+pub mod sbom;
+pub mod scan_capabilities;
+pub mod trust;
 pub mod update;
 pub mod verify;
+pub mod why;
@@ -0,0 +1,158 @@
+//! Tests for `depot policy check` and policy enforcement
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path, policy: &str) {
+    fs::write(
+        project_root.join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\ndependencies:\n  owner/json: ^1.0.0\n",
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Branch
+    ref_value: main
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/main
+    checksum: blake3:bbbb
+    size: 512
+"#,
+    )
+    .unwrap();
+
+    fs::create_dir_all(project_root.join(".depot")).unwrap();
+    fs::write(project_root.join(".depot/policy.yaml"), policy).unwrap();
+}
+
+#[test]
+fn test_policy_check_reports_violations() {
+    let temp = TempDir::new().unwrap();
+    write_project(
+        temp.path(),
+        "allowed_owners: [myorg]\nallowed_hosts: [github.example.com]\nforbid_branch_refs: true\n",
+    );
+
+    let output = depot_command()
+        .args(["policy", "check"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("3 dependency policy violation(s)"));
+    assert!(stderr.contains("owner/json [allowed_owners]"));
+    assert!(stderr.contains("owner/json [allowed_hosts]"));
+    assert!(stderr.contains("owner/json [forbid_branch_refs]"));
+}
+
+#[test]
+fn test_policy_check_passes() {
+    let temp = TempDir::new().unwrap();
+    write_project(
+        temp.path(),
+        "allowed_owners: [owner]\nallowed_hosts: [github.com]\nrequire_lockfile: true\n",
+    );
+
+    let output = depot_command()
+        .args(["policy", "check"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("comply with the policy"));
+}
+
+#[test]
+fn test_policy_found_in_parent_directory() {
+    let temp = TempDir::new().unwrap();
+    let project = temp.path().join("app");
+    fs::create_dir_all(&project).unwrap();
+    write_project(&project, "");
+    fs::create_dir_all(temp.path().join(".depot")).unwrap();
+    fs::rename(
+        project.join(".depot/policy.yaml"),
+        temp.path().join(".depot/policy.yaml"),
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join(".depot/policy.yaml"),
+        "allowed_owners: [myorg]\n",
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["policy", "check"])
+        .current_dir(&project)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("owner/json [allowed_owners]"));
+}
+
+#[test]
+fn test_policy_check_requires_lockfile() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path(), "require_lockfile: true\n");
+    fs::remove_file(temp.path().join("depot.lock")).unwrap();
+
+    let output = depot_command()
+        .args(["policy", "check"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("[require_lockfile]: depot.lock is missing"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_global_install_enforces_configured_policy() {
+    let home = TempDir::new().unwrap();
+    let depot_home = if cfg!(target_os = "macos") {
+        home.path()
+            .join("Library")
+            .join("Application Support")
+            .join("depot")
+    } else {
+        home.path().join("config").join("depot")
+    };
+    fs::create_dir_all(&depot_home).unwrap();
+    let policy = home.path().join("policy.yaml");
+    fs::write(&policy, "allowed_owners: [myorg]\n").unwrap();
+    fs::write(
+        depot_home.join("config.yaml"),
+        format!("policy_file: {}\n", policy.display()),
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["install", "-g", "someone/lib"])
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path().join("config"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("owner is not in allowed_owners (myorg)"),
+        "{}",
+        stderr
+    );
+    assert!(!depot_home
+        .join("global")
+        .join("lua_modules")
+        .join("someone")
+        .exists());
+}
@@ -0,0 +1,117 @@
+//! Tests for `depot sbom` command
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path) {
+    fs::write(
+        project_root.join("package.yaml"),
+        r#"
+name: test-project
+version: 1.0.0
+license: MIT
+dependencies:
+  owner/web: ^2.0.0
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/web:
+    version: v2.0.0
+    repository: owner/web
+    ref_type: Release
+    ref_value: v2.0.0
+    commit_sha: 1111111
+    tarball_url: https://api.github.com/repos/owner/web/tarball/v2.0.0
+    checksum: blake3:aaaa
+    size: 1024
+    license: Apache-2.0
+    dependencies:
+      owner/json: ^1.0.0
+    native_code:
+      types: [c]
+      build_required: true
+      prebuilt_used: false
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:bbbb
+    size: 512
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_sbom_cyclonedx() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .arg("sbom")
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let bom: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+
+    let components = bom["components"].as_array().unwrap();
+    let web = components.iter().find(|c| c["name"] == "web").unwrap();
+    assert_eq!(web["group"], "owner");
+    assert_eq!(web["licenses"][0]["license"]["id"], "Apache-2.0");
+    assert_eq!(web["hashes"][0]["content"], "aaaa");
+    assert_eq!(web["pedigree"]["commits"][0]["uid"], "1111111");
+    assert!(web["properties"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|p| p["name"] == "depot:native_code.build_required" && p["value"] == "true"));
+
+    let json = components.iter().find(|c| c["name"] == "json").unwrap();
+    assert!(json.get("licenses").is_none());
+}
+
+#[test]
+fn test_sbom_spdx_to_file() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .args([
+            "sbom",
+            "--format",
+            "spdx-json",
+            "--output",
+            "sbom.spdx.json",
+        ])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let doc: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("sbom.spdx.json")).unwrap())
+            .unwrap();
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+
+    let packages = doc["packages"].as_array().unwrap();
+    let json = packages.iter().find(|p| p["name"] == "owner/json").unwrap();
+    assert_eq!(json["licenseDeclared"], "NOASSERTION");
+    assert!(doc["relationships"].as_array().unwrap().iter().any(|r| {
+        r["spdxElementId"] == "SPDXRef-Package-owner-web"
+            && r["relatedSpdxElement"] == "SPDXRef-Package-owner-json"
+    }));
+}
@@ -0,0 +1,109 @@
+//! Tests for `depot scan-capabilities`
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path) {
+    fs::write(
+        project_root.join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\ndependencies:\n  owner/shell: ^1.0.0\n  owner/json: ^1.0.0\n",
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/shell:
+    version: v1.1.0
+    repository: owner/shell
+    ref_type: Release
+    ref_value: v1.1.0
+    commit_sha: 1111111
+    tarball_url: https://api.github.com/repos/owner/shell/tarball/v1.1.0
+    checksum: blake3:aaaa
+    size: 1024
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:bbbb
+    size: 512
+"#,
+    )
+    .unwrap();
+
+    let shell_dir = project_root.join("lua_modules/owner/shell/src");
+    fs::create_dir_all(&shell_dir).unwrap();
+    fs::write(
+        shell_dir.join("init.lua"),
+        "local M = {}\n\nfunction M.run(cmd)\n  return os.execute(cmd)\nend\n\nreturn M\n",
+    )
+    .unwrap();
+
+    let json_dir = project_root.join("lua_modules/owner/json");
+    fs::create_dir_all(&json_dir).unwrap();
+    fs::write(json_dir.join("json.lua"), "return { encode = tostring }\n").unwrap();
+}
+
+#[test]
+fn test_scan_capabilities_groups_by_package() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .arg("scan-capabilities")
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("owner/shell v1.1.0\n  os.execute — runs shell commands"));
+    assert!(stdout.contains("src/init.lua:4  os.execute"));
+    assert!(stdout.contains("owner/json v1.0.0: none"));
+    assert!(stdout.contains("1 of 2 package(s) use sensitive APIs"));
+}
+
+#[test]
+fn test_scan_capabilities_json_single_package() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .args(["scan-capabilities", "--json", "--package", "owner/json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let packages = json["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0]["name"], "owner/json");
+    assert_eq!(packages[0]["capabilities"], serde_json::json!([]));
+}
+
+#[test]
+fn test_scan_capabilities_requires_lockfile() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\n",
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .arg("scan-capabilities")
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No depot.lock"));
+}
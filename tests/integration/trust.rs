@@ -0,0 +1,65 @@
+//! Tests for `depot trust`
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+fn write_project(project_root: &std::path::Path) {
+    fs::write(
+        project_root.join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\ndependencies:\n  owner/json: ^1.0.0\n",
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:bbbb
+    size: 512
+    repository_id: 123456
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_trust_update_unknown_package() {
+    let temp = TempDir::new().unwrap();
+    write_project(temp.path());
+
+    let output = depot_command()
+        .args(["trust", "update", "owner/other"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'owner/other' not found"));
+}
+
+#[test]
+fn test_trust_update_requires_lockfile() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\n",
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["trust", "update", "owner/json"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No depot.lock"));
+}
@@ -70,3 +70,57 @@ fn test_verify_without_package_yaml() {
 
     assert!(!output.status.success());
 }
+
+#[test]
+fn test_verify_installed_reports_missing_package() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+
+    fs::write(
+        project_root.join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\ndependencies:\n  owner/json: ^1.0.0\n",
+    )
+    .unwrap();
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/json:
+    version: v1.0.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.0.0
+    commit_sha: 2222222
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.0.0
+    checksum: blake3:bbbb
+    size: 512
+"#,
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .args(["verify", "--installed"])
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("owner/json: not installed"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("depot verify --installed --repair"));
+}
+
+#[test]
+fn test_verify_repair_requires_installed() {
+    let temp = TempDir::new().unwrap();
+
+    let output = depot_command()
+        .args(["verify", "--repair"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--installed"));
+}
@@ -0,0 +1,86 @@
+//! Tests for `depot why` command
+
+use super::common::depot_command;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_why_transitive_dependency() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+
+    fs::write(
+        project_root.join("package.yaml"),
+        r#"
+name: test-project
+version: 1.0.0
+dependencies:
+  owner/web: ^2.0.0
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        project_root.join("depot.lock"),
+        r#"
+version: 2
+generated_at: 2024-01-01T00:00:00Z
+packages:
+  owner/web:
+    version: 2.0.0
+    repository: owner/web
+    ref_type: Release
+    ref_value: v2.0.0
+    commit_sha: abc123
+    tarball_url: https://api.github.com/repos/owner/web/tarball/v2.0.0
+    checksum: blake3:abc
+    size: 1024
+    dependencies:
+      owner/json: ^1.0.0
+  owner/json:
+    version: 1.2.0
+    repository: owner/json
+    ref_type: Release
+    ref_value: v1.2.0
+    commit_sha: def456
+    tarball_url: https://api.github.com/repos/owner/json/tarball/v1.2.0
+    checksum: blake3:def
+    size: 512
+"#,
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .arg("why")
+        .arg("json")
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("owner/json@1.2.0"));
+    assert!(stdout.contains("owner/web ^2.0.0"));
+    assert!(stdout.contains("owner/json ^1.0.0"));
+}
+
+#[test]
+fn test_why_unknown_package() {
+    let temp = TempDir::new().unwrap();
+    let project_root = temp.path();
+
+    fs::write(
+        project_root.join("package.yaml"),
+        "name: test-project\nversion: 1.0.0\n",
+    )
+    .unwrap();
+
+    let output = depot_command()
+        .arg("why")
+        .arg("owner/missing")
+        .current_dir(project_root)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+}